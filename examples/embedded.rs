@@ -0,0 +1,51 @@
+use eframe::egui;
+use synth_mod::{settings::Settings, Output, Rack};
+
+/// Another app with the rack as part of its interface.
+struct Host {
+    rack: Rack,
+    output: Output,
+    settings: Settings,
+    notes: String,
+}
+
+impl eframe::App for Host {
+    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        if let Some(instance) = self.output.instance_mut() {
+            let samples = instance.free_len();
+            instance.push_buffer(self.rack.process_amount(instance.sample_rate(), samples));
+        }
+
+        egui::SidePanel::left("host").show(ctx, |ui| {
+            ui.heading("host");
+            ui.horizontal(|ui| self.output.show(ui));
+            ui.text_edit_multiline(&mut self.notes);
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let sample_rate = self.output.sample_rate_or_default();
+            self.rack.show_embedded(ui, sample_rate, &mut self.settings);
+        });
+
+        ctx.request_repaint();
+    }
+}
+
+fn main() {
+    let mut rack = Rack::default();
+    rack.add_panel();
+
+    eframe::run_native(
+        "embedded",
+        eframe::NativeOptions::default(),
+        Box::new(|_| {
+            Box::new(Host {
+                rack,
+                output: Output::new(),
+                settings: Settings::default(),
+                notes: String::new(),
+            })
+        }),
+    )
+    .unwrap();
+}
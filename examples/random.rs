@@ -33,9 +33,9 @@ fn main() {
         app.rack.connect(from, input).ok();
     }
 
-    app.output
-        .instance_mut()
-        .map(|instance| instance.volume = 0.0);
+    if let Some(instance) = app.output.instance_mut() {
+        instance.volume = 0.0;
+    }
 
     app.run()
 }
@@ -0,0 +1,26 @@
+/* C API of synth-mod, build the library with `cargo build --release` and link to
+ * libsynth_mod. */
+#ifndef SYNTH_MOD_H
+#define SYNTH_MOD_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct SynthModEngine SynthModEngine;
+
+/* Loads an exported patch file, returns NULL when it can't be read. */
+SynthModEngine *synth_mod_engine_open(const char *path, uint32_t sample_rate);
+
+/* Processes `frames` frames into `buffer` as interleaved stereo samples, `buffer` must hold
+ * `frames * 2` floats. Returns whether the frames were processed, the buffer is silent otherwise. */
+bool synth_mod_engine_process(SynthModEngine *engine, float *buffer, size_t frames);
+
+/* Sets a parameter by a path like "lead/cutoff", the tag of a module and the name of one of its
+ * parameters. Returns whether the parameter was found. */
+bool synth_mod_engine_set_parameter(SynthModEngine *engine, const char *path, float value);
+
+/* Frees an engine, NULL is ignored. */
+void synth_mod_engine_free(SynthModEngine *engine);
+
+#endif
@@ -5,14 +5,51 @@ use eframe::egui::{self, Context};
 use eframe::epaint::Vec2;
 use wasm_timer::Instant;
 
-use crate::{frame::Frame, output::Output, rack::rack::Rack};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::midi::Midi;
+use crate::{
+    budget::Budget,
+    console::{self, Console},
+    i18n::tr,
+    output::Output,
+    rack::{block, rack::Rack},
+    settings::{self, CableVisibility, Settings},
+    sharing::Sharing,
+    toast::Toasts,
+    tutorial::{self, Tutorial},
+    util::EnumIter,
+};
+#[cfg(target_arch = "wasm32")]
+use crate::{rack::patch::Patch, web};
 
 const SCALE: f32 = 1.5;
 const PROFILING: bool = false;
 
+/// How often to repaint while idle, so results from background threads still show up.
+const IDLE_REPAINT: Duration = Duration::from_millis(250);
+
+/// Most time processed at once without an output, so resuming after being idle doesn't stall.
+const MAX_DELTA: Duration = Duration::from_millis(100);
+
 pub struct App {
     pub rack: Rack,
     pub output: Output,
+    pub settings: Settings,
+    settings_open: bool,
+    timeline_open: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    midi_open: bool,
+    tutorial: Tutorial,
+    /// Name for the next block saved to the library.
+    block_name: String,
+    /// Text the instances are searched for by.
+    search: String,
+    sharing: Sharing,
+    #[cfg(not(target_arch = "wasm32"))]
+    midi: Midi,
+    console: Console,
+    toasts: Toasts,
+    budget: Budget,
     last_instant: Instant,
     last_deltas: VecDeque<Duration>,
 }
@@ -21,9 +58,24 @@ impl Default for App {
     fn default() -> Self {
         #[cfg(target_arch = "wasm32")]
         console_error_panic_hook::set_once();
+        console::init();
         Self {
             rack: Rack::default(),
             output: Output::new(),
+            settings: Settings::default(),
+            settings_open: false,
+            timeline_open: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            midi_open: false,
+            tutorial: Tutorial::default(),
+            block_name: String::new(),
+            search: String::new(),
+            sharing: Sharing::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            midi: Midi::default(),
+            console: Console::default(),
+            toasts: Toasts::default(),
+            budget: Budget::default(),
             last_instant: Instant::now(),
             last_deltas: VecDeque::new(),
         }
@@ -51,7 +103,7 @@ impl App {
                     Box::new(|cc| {
                         cc.egui_ctx.set_pixels_per_point(SCALE);
                         // cc.egui_ctx.set_debug_on_hover(true);
-                        Box::new(self)
+                        Box::new(self.restored(cc))
                     }),
                 )
                 .await
@@ -77,12 +129,41 @@ impl App {
             Box::new(|cc| {
                 cc.egui_ctx.set_pixels_per_point(SCALE);
                 // cc.egui_ctx.set_debug_on_hover(true);
-                Box::new(self)
+                Box::new(self.restored(cc))
             }),
         )
         .unwrap();
     }
 
+    /// Loads the persisted settings, tutorial progress and block library and applies them, and on
+    /// the web the patch of the url or the last patch.
+    fn restored(mut self, cc: &eframe::CreationContext) -> Self {
+        if let Some(storage) = cc.storage {
+            if let Some(settings) = eframe::get_value(storage, settings::STORAGE_KEY) {
+                self.settings = settings;
+            }
+            if let Some(tutorial) = eframe::get_value(storage, tutorial::STORAGE_KEY) {
+                self.tutorial = tutorial;
+            }
+            if let Some(blocks) = eframe::get_value(storage, block::STORAGE_KEY) {
+                self.rack.blocks = blocks;
+            }
+        }
+        self.tutorial.subscribe(&mut self.rack);
+
+        // a shared link takes precedence over the patch the page was left with
+        #[cfg(target_arch = "wasm32")]
+        if let Some(patch) = web::url_patch().or_else(|| {
+            let text = eframe::get_value::<String>(cc.storage?, web::STORAGE_KEY)?;
+            Patch::from_text(&text)
+        }) {
+            self.rack.import_patch(&patch);
+        }
+
+        self.settings.apply(&cc.egui_ctx);
+        self
+    }
+
     /// Draw ui
     fn show(&mut self, ctx: &Context, avg_delta: Duration) {
         puffin::profile_function!();
@@ -95,39 +176,253 @@ impl App {
                 self.output.show(ui);
                 ui.separator();
 
+                self.budget.show(ui);
+
+                self.rack.transport.show(ui);
+                ui.menu_button("♯", |ui| self.rack.tuning.show(ui))
+                    .response
+                    .on_hover_text_at_pointer(tr("master tune and transpose"));
+                ui.separator();
+
+                ui.menu_button("🎲", |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("amount:"));
+                        ui.add(egui::Slider::new(&mut self.rack.mutation, 0.0..=1.0));
+                    });
+                    if ui.button(tr("randomize all")).clicked() {
+                        self.rack.randomize();
+                    }
+                })
+                .response
+                .on_hover_text_at_pointer(tr("randomize parameters"));
+                ui.menu_button("🧩", |ui| self.show_blocks(ui))
+                    .response
+                    .on_hover_text_at_pointer(tr("blocks"));
+                ui.menu_button("🔗", |ui| self.sharing.show_menu(&mut self.rack, ui))
+                    .response
+                    .on_hover_text_at_pointer(tr("share patch"));
+                if ui
+                    .button("▦")
+                    .on_hover_text_at_pointer(tr("arrange the modules in panels by signal flow"))
+                    .clicked()
+                {
+                    self.rack.arrange_tidy();
+                }
+                ui.menu_button("🔎", |ui| self.show_search(ui))
+                    .response
+                    .on_hover_text_at_pointer(tr("find modules by name, tag or handle"));
+                ui.toggle_value(&mut self.timeline_open, "🎞")
+                    .on_hover_text_at_pointer(tr("timeline"));
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.toggle_value(&mut self.midi_open, "🎹")
+                    .on_hover_text_at_pointer(tr("MIDI"));
+                ui.menu_button("〰", |ui| {
+                    for visibility in CableVisibility::iter() {
+                        ui.selectable_value(
+                            &mut self.rack.cables,
+                            visibility,
+                            tr(visibility.as_str()),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text_at_pointer(tr("show or hide cables"));
+                ui.toggle_value(&mut self.rack.probing, "🔍")
+                    .on_hover_text_at_pointer(tr("probe connections by hovering them"));
+
+                let mut touch = self.rack.touch();
+                if ui
+                    .toggle_value(&mut touch, "👆")
+                    .on_hover_text_at_pointer(tr("touch mode"))
+                    .changed()
+                {
+                    self.rack.set_touch(ctx, touch);
+                }
+                ui.separator();
+
                 ui.label(format!("{:.1}ms", avg_delta.as_secs_f32() * 1000.0))
-                    .on_hover_text_at_pointer("average frame time");
+                    .on_hover_text_at_pointer(tr("average frame time"));
                 ui.separator();
+
+                self.console.show_toggle(ui);
+                ui.toggle_value(&mut self.settings_open, "⚙")
+                    .on_hover_text_at_pointer(tr("settings"));
+
+                if ui
+                    .add_enabled(!self.tutorial.is_running(), egui::Button::new("🎓"))
+                    .on_hover_text_at_pointer(tr("tutorial"))
+                    .clicked()
+                {
+                    self.tutorial.start();
+                }
             });
         });
 
-        self.rack.show(ctx, self.output.sample_rate_or_default());
+        let changed = egui::Window::new(tr("settings"))
+            .id(egui::Id::new("settings"))
+            .open(&mut self.settings_open)
+            .resizable(false)
+            .show(ctx, |ui| self.settings.show(ui))
+            .and_then(|response| response.inner)
+            .unwrap_or(false);
+
+        if changed {
+            self.settings.apply(ctx);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+        let render = egui::Window::new(tr("timeline"))
+            .id(egui::Id::new("timeline"))
+            .open(&mut self.timeline_open)
+            .show(ctx, |ui| {
+                self.rack
+                    .timeline
+                    .show(ui, &mut self.rack.instances, &self.rack.transport)
+            })
+            .and_then(|response| response.inner)
+            .unwrap_or(false);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if render {
+            self.sharing.render();
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            for scene in self.midi.update(&self.settings) {
+                self.rack
+                    .timeline
+                    .launch(scene, &mut self.rack.instances, &self.rack.transport);
+            }
+            for (note, velocity) in self.midi.take_notes() {
+                for instance in self.rack.instances.values_mut() {
+                    instance.module.note_on(note, velocity as f32 / 127.0);
+                }
+            }
+
+            egui::Window::new(tr("MIDI"))
+                .id(egui::Id::new("midi"))
+                .open(&mut self.midi_open)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    self.midi
+                        .show(ui, &mut self.settings, &self.rack.timeline.scene_names())
+                });
+        }
+
+        self.rack.show(
+            ctx,
+            self.output.sample_rate_or_default(),
+            &mut self.settings,
+        );
+
+        self.sharing.show(&mut self.rack, ctx);
+        self.console.show(ctx);
+        self.toasts.show(ctx);
+
+        self.tutorial.update(&self.rack);
+        self.tutorial.show(ctx);
+    }
+
+    fn show_search(&mut self, ui: &mut egui::Ui) {
+        ui.text_edit_singleline(&mut self.search);
+
+        let results = self.rack.search(&self.search);
+        if results.is_empty() && !self.search.trim().is_empty() {
+            ui.label(tr("no modules found"));
+        }
+
+        for handle in results {
+            let instance = &self.rack.instances[&handle];
+            let mut text = format!("{} {}", instance.description.title(), handle);
+            for tag in instance.tags.iter() {
+                text += &format!(" #{tag}");
+            }
+            if ui.button(text).clicked() {
+                self.rack.reveal(handle);
+                ui.close_menu();
+            }
+        }
+    }
+
+    fn show_blocks(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("name:"));
+            ui.text_edit_singleline(&mut self.block_name);
+        });
+
+        if ui
+            .add_enabled(
+                self.rack.selected().is_some() && !self.block_name.is_empty(),
+                egui::Button::new(tr("save selection as block")),
+            )
+            .on_disabled_hover_text(tr("select a module and enter a name first"))
+            .clicked()
+        {
+            self.rack.save_block(&self.block_name);
+            self.block_name.clear();
+        }
+
+        if !self.rack.blocks.is_empty() {
+            ui.separator();
+        }
+
+        let mut removed = None;
+        for (i, block) in self.rack.blocks.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("🧩 {}", block.name));
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+
+        if let Some(removed) = removed {
+            self.rack.blocks.remove(removed);
+        }
+    }
+
+    /// Whether modules need to be processed every frame, because they can be heard or draw what they process.
+    fn is_running(&self) -> bool {
+        !self.rack.instances.is_empty()
+            && (self.output.is_audible()
+                || self
+                    .rack
+                    .instances
+                    .values()
+                    .any(|instance| instance.description.visual))
     }
 
     /// Process modules & audio output
     fn process(&mut self, delta: Duration) {
         puffin::profile_function!();
 
-        if let Some(instance) = self.output.instance_mut() {
-            instance.push_iter(
-                self.rack
-                    .process_amount(instance.sample_rate(), instance.free_len())
-                    .into_iter()
-                    .map(|frames| {
-                        let mut mixed = Frame::ZERO;
+        let start = Instant::now();
+        let mut behind = false;
+        let sample_rate = self.output.sample_rate_or_default();
+        let samples;
+        self.rack.thumbnails = self.settings.thumbnails;
 
-                        for frame in frames {
-                            mixed += frame;
-                        }
+        if let Some(instance) = self.output.instance_mut() {
+            behind = instance.behind();
+            samples = instance.free_len();
+            instance.push_buffer(self.rack.process_amount(instance.sample_rate(), samples));
+        } else {
+            samples = (sample_rate as f32 * delta.min(MAX_DELTA).as_secs_f32()) as usize;
+            self.rack.process_amount(sample_rate, samples);
+        }
 
-                        mixed
-                    }),
-            );
+        if self.settings.cpu_guard {
+            let produced = Duration::from_secs_f32(samples as f32 / sample_rate as f32);
+            self.budget.update(start.elapsed(), produced, behind, delta);
         } else {
-            let samples =
-                (self.output.sample_rate_or_default() as f32 * delta.as_secs_f32()) as usize;
-            self.rack
-                .process_amount(self.output.sample_rate_or_default(), samples);
+            self.budget = Budget::default();
+        }
+
+        self.rack.degradation = self.budget.level;
+        if let Some(instance) = self.output.instance_mut() {
+            instance.guarded = self.settings.cpu_guard;
+            instance.set_latency(self.budget.level.latency());
         }
     }
 }
@@ -143,6 +438,10 @@ impl eframe::App for App {
 
         puffin::profile_scope!("app");
 
+        if !self.rack.touch() && ctx.input(|input| input.any_touches()) {
+            self.rack.set_touch(ctx, true);
+        }
+
         let delta = self.last_instant.elapsed();
 
         self.last_deltas.push_front(delta);
@@ -181,6 +480,22 @@ impl eframe::App for App {
             }
         });
 
-        ctx.request_repaint();
+        if self.is_running() {
+            ctx.request_repaint();
+        } else {
+            ctx.request_repaint_after(IDLE_REPAINT);
+        }
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, settings::STORAGE_KEY, &self.settings);
+        eframe::set_value(storage, tutorial::STORAGE_KEY, &self.tutorial);
+        eframe::set_value(storage, block::STORAGE_KEY, &self.rack.blocks);
+        #[cfg(target_arch = "wasm32")]
+        eframe::set_value(
+            storage,
+            web::STORAGE_KEY,
+            &self.rack.export_patch().to_text(),
+        );
     }
 }
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use eframe::{
+    egui::{RichText, Ui},
+    epaint::Color32,
+};
+
+use crate::i18n::tr;
+
+/// Load above which the quality is reduced further.
+const RAISE_LOAD: f32 = 0.8;
+
+/// Load below which the quality is restored again.
+const LOWER_LOAD: f32 = 0.4;
+
+/// Time to wait after a change before reducing the quality further.
+const RAISE_HOLD: Duration = Duration::from_millis(500);
+
+/// Time to wait after a change before restoring the quality, longer to avoid flipping back and forth.
+const LOWER_HOLD: Duration = Duration::from_secs(3);
+
+/// How far quality is reduced to keep up with the audio output.
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd)]
+pub enum Degradation {
+    #[default]
+    None,
+    Reduced,
+    Minimal,
+}
+
+impl Degradation {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Degradation::None => "full quality",
+            Degradation::Reduced => "reduced quality",
+            Degradation::Minimal => "minimal quality",
+        }
+    }
+
+    /// How much audio is buffered ahead, more gives processing more room to catch up.
+    pub fn latency(&self) -> Duration {
+        match self {
+            Degradation::None => Duration::from_millis(150),
+            Degradation::Reduced => Duration::from_millis(250),
+            Degradation::Minimal => Duration::from_millis(400),
+        }
+    }
+
+    /// Factor by which optional work, like updating visuals, is done less often.
+    pub fn decimation(&self) -> usize {
+        match self {
+            Degradation::None => 1,
+            Degradation::Reduced => 4,
+            Degradation::Minimal => 16,
+        }
+    }
+
+    fn raised(self) -> Self {
+        match self {
+            Degradation::None => Degradation::Reduced,
+            _ => Degradation::Minimal,
+        }
+    }
+
+    fn lowered(self) -> Self {
+        match self {
+            Degradation::Minimal => Degradation::Reduced,
+            _ => Degradation::None,
+        }
+    }
+}
+
+/// Picks a [`Degradation`] from how long processing takes compared to the audio it produces.
+#[derive(Default)]
+pub struct Budget {
+    pub level: Degradation,
+    /// Smoothed time spent processing relative to the duration of the produced audio.
+    load: f32,
+    /// Time since the level last changed.
+    since_change: Duration,
+}
+
+impl Budget {
+    /// Updates the level after a frame, `behind` being whether the output was running low on audio.
+    pub fn update(&mut self, spent: Duration, produced: Duration, behind: bool, delta: Duration) {
+        if !produced.is_zero() {
+            let load = spent.as_secs_f32() / produced.as_secs_f32();
+            self.load += (load - self.load) * 0.1;
+        }
+
+        self.since_change += delta;
+
+        if (behind || self.load > RAISE_LOAD)
+            && self.level != Degradation::Minimal
+            && self.since_change > RAISE_HOLD
+        {
+            self.level = self.level.raised();
+            self.since_change = Duration::ZERO;
+        } else if !behind
+            && self.load < LOWER_LOAD
+            && self.level != Degradation::None
+            && self.since_change > LOWER_HOLD
+        {
+            self.level = self.level.lowered();
+            self.since_change = Duration::ZERO;
+        }
+    }
+
+    pub fn show(&self, ui: &mut Ui) {
+        if self.level != Degradation::None {
+            ui.label(RichText::new(format!("⚠ {}", tr(self.level.as_str()))).color(Color32::GOLD))
+                .on_hover_text_at_pointer(format!(
+                    "{} ({:.0}%)",
+                    tr("processing can't keep up, quality is reduced instead of muting"),
+                    self.load * 100.0
+                ));
+            ui.separator();
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::tr, transport::Transport};
+
+#[derive(Clone, Copy, PartialEq, Default)]
+enum ClipMode {
+    #[default]
+    Stopped,
+    /// Waiting for the transport to reach the start of the loop.
+    Armed,
+    Recording,
+    /// Waiting for the transport to reach the quantize boundary to start playing.
+    Queued,
+    Playing,
+}
+
+/// A loop of values recorded in sync with the transport, to replay a performance.
+///
+/// Only changes are stored, the value at a position is the last change before it.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Clip<T> {
+    /// Length of the loop in bars.
+    bars: u32,
+    /// Changes with their position in beats within the loop, sorted by position.
+    events: Vec<(f64, T)>,
+    #[serde(skip)]
+    mode: ClipMode,
+    /// Position within the loop of the last call to [`Self::process`].
+    #[serde(skip)]
+    last: Option<f64>,
+    /// Position of the transport in the last call to [`Self::process`].
+    #[serde(skip)]
+    previous: Option<f64>,
+}
+
+impl<T> Default for Clip<T> {
+    fn default() -> Self {
+        Self {
+            bars: 1,
+            events: Vec::new(),
+            mode: ClipMode::Stopped,
+            last: None,
+            previous: None,
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Clip<T> {
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Starts playing the recording if there is one, recorded clips also play after loading.
+    pub fn play(&mut self) {
+        if !self.events.is_empty() {
+            self.mode = ClipMode::Playing;
+        }
+    }
+
+    /// Starts playing the recording at the next quantize boundary of the transport.
+    fn queue(&mut self) {
+        if !self.events.is_empty() {
+            self.mode = ClipMode::Queued;
+        }
+    }
+
+    /// Stops playing, a recording in progress is left alone.
+    pub fn stop(&mut self) {
+        if matches!(self.mode, ClipMode::Playing | ClipMode::Queued) {
+            self.mode = ClipMode::Stopped;
+        }
+    }
+
+    /// Records `live` while recording and returns the recorded value while playing.
+    pub fn process(&mut self, transport: &Transport, live: &T) -> Option<T> {
+        if !transport.playing {
+            self.last = None;
+            self.previous = None;
+            return None;
+        }
+
+        if self.mode == ClipMode::Queued && transport.at_boundary(self.previous) {
+            self.mode = ClipMode::Playing;
+        }
+        self.previous = Some(transport.position());
+
+        let length = (self.bars.max(1) * transport.beats_per_bar) as f64;
+        let position = transport.position() % length;
+        let wrapped = self.last.is_some_and(|last| position < last);
+        self.last = Some(position);
+
+        match self.mode {
+            ClipMode::Stopped => None,
+            ClipMode::Armed => {
+                if wrapped || position < 1.0 / 64.0 {
+                    self.events.clear();
+                    self.events.push((position, live.clone()));
+                    self.mode = ClipMode::Recording;
+                }
+                None
+            }
+            ClipMode::Recording => {
+                if wrapped {
+                    self.mode = ClipMode::Playing;
+                    return self.value_at(position);
+                }
+                if self.events.last().is_none_or(|(_, last)| last != live) {
+                    self.events.push((position, live.clone()));
+                }
+                None
+            }
+            ClipMode::Queued => None,
+            ClipMode::Playing => self.value_at(position),
+        }
+    }
+
+    /// The last change before `position`, wrapping around to the end of the loop.
+    fn value_at(&self, position: f64) -> Option<T> {
+        let index = self.events.partition_point(|(start, _)| *start <= position);
+        let index = if index == 0 { self.events.len() } else { index };
+        self.events.get(index - 1).map(|(_, value)| value.clone())
+    }
+
+    /// Shows the record, play and clear buttons and the loop length.
+    pub fn show(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let recording = matches!(self.mode, ClipMode::Armed | ClipMode::Recording);
+            let text = if self.mode == ClipMode::Recording {
+                egui::RichText::new("⏺").color(egui::Color32::RED)
+            } else {
+                egui::RichText::new("⏺")
+            };
+            if ui
+                .selectable_label(recording, text)
+                .on_hover_text_at_pointer(tr(
+                    "record a loop, starting at the next loop start while the transport plays",
+                ))
+                .clicked()
+            {
+                self.mode = if recording {
+                    ClipMode::Stopped
+                } else {
+                    ClipMode::Armed
+                };
+            }
+
+            let playing = matches!(self.mode, ClipMode::Playing | ClipMode::Queued);
+            let text = if self.mode == ClipMode::Queued {
+                "⏳"
+            } else {
+                "🔁"
+            };
+            if ui
+                .add_enabled(
+                    !self.events.is_empty(),
+                    egui::SelectableLabel::new(playing, text),
+                )
+                .on_hover_text_at_pointer(tr("play the recorded loop"))
+                .clicked()
+            {
+                if playing {
+                    self.stop()
+                } else {
+                    self.queue()
+                }
+            }
+
+            if ui
+                .add_enabled(!self.events.is_empty(), egui::Button::new("🗑"))
+                .on_hover_text_at_pointer(tr("clear the recorded loop"))
+                .clicked()
+            {
+                self.events.clear();
+                self.mode = ClipMode::Stopped;
+            }
+
+            ui.add_enabled(
+                !recording,
+                egui::DragValue::new(&mut self.bars)
+                    .clamp_range(1..=16)
+                    .suffix(format!(" {}", tr("bars"))),
+            );
+        });
+    }
+}
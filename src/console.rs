@@ -0,0 +1,160 @@
+use std::{collections::VecDeque, fmt::Debug, sync::Mutex};
+
+use eframe::{
+    egui::{self, Context, RichText, Ui},
+    epaint::Color32,
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+use crate::i18n::tr;
+
+/// Most entries kept, older ones are dropped.
+const MAX_ENTRIES: usize = 500;
+
+/// Least severe level that is recorded.
+const MAX_LEVEL: Level = Level::INFO;
+
+static ENTRIES: Mutex<VecDeque<Entry>> = Mutex::new(VecDeque::new());
+
+/// A recorded log event.
+struct Entry {
+    level: Level,
+    target: &'static str,
+    message: String,
+}
+
+/// Records the events of this crate to be shown in the [`Console`], and to stderr.
+struct ConsoleSubscriber;
+
+impl Subscriber for ConsoleSubscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= MAX_LEVEL && metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        eprintln!("{} {}: {}", metadata.level(), metadata.target(), visitor.0);
+
+        let mut entries = ENTRIES.lock().unwrap();
+        if entries.len() >= MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(Entry {
+            level: *metadata.level(),
+            target: metadata.target(),
+            message: visitor.0,
+        });
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+/// Formats the fields of an event, the message first followed by the others as `name=value`.
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+
+        if field.name() == "message" {
+            self.0.push_str(&format!("{value:?}"));
+        } else {
+            self.0.push_str(&format!("{}={value:?}", field.name()));
+        }
+    }
+}
+
+/// Starts recording log events, does nothing when already started.
+pub fn init() {
+    tracing::subscriber::set_global_default(ConsoleSubscriber).ok();
+}
+
+fn level_color(level: Level) -> Color32 {
+    match level {
+        Level::ERROR => Color32::RED,
+        Level::WARN => Color32::GOLD,
+        _ => Color32::GRAY,
+    }
+}
+
+/// Window listing the recorded log events.
+#[derive(Default)]
+pub struct Console {
+    pub open: bool,
+}
+
+impl Console {
+    /// Shows the toggle of the window, with the amount of warnings and errors if there are any.
+    pub fn show_toggle(&mut self, ui: &mut Ui) {
+        let problems = ENTRIES
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.level <= Level::WARN)
+            .count();
+
+        let text = if problems > 0 {
+            RichText::new(format!("📜 {problems}")).color(Color32::GOLD)
+        } else {
+            RichText::new("📜")
+        };
+
+        ui.toggle_value(&mut self.open, text)
+            .on_hover_text_at_pointer(tr("console"));
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        egui::Window::new(tr("console"))
+            .id(egui::Id::new("console"))
+            .open(&mut self.open)
+            .default_size([500.0, 250.0])
+            .show(ctx, |ui| {
+                let mut entries = ENTRIES.lock().unwrap();
+
+                if ui.button(tr("clear")).clicked() {
+                    entries.clear();
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if entries.is_empty() {
+                            ui.label(tr("nothing logged yet"));
+                        }
+
+                        for entry in entries.iter() {
+                            ui.label(
+                                RichText::new(format!(
+                                    "{:5} {}: {}",
+                                    entry.level.as_str(),
+                                    entry.target,
+                                    entry.message
+                                ))
+                                .monospace()
+                                .color(level_color(entry.level)),
+                            );
+                        }
+                    });
+            });
+    }
+}
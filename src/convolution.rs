@@ -0,0 +1,110 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::sync::Arc;
+
+use realfft::{num_complex::Complex, ComplexToReal, RealFftPlanner, RealToComplex};
+
+/// Convolves a signal with an impulse response using uniformly partitioned overlap-save, so
+/// long responses stay cheap enough for real-time use. The output lags the input by one block.
+pub struct Convolver {
+    block: usize,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    /// Spectra of the block sized parts of the impulse response.
+    partitions: Vec<Vec<Complex<f32>>>,
+    /// Spectra of the most recent input blocks, as many as there are partitions.
+    history: Vec<Vec<Complex<f32>>>,
+    /// Index in `history` of the newest spectrum.
+    newest: usize,
+    /// The previous and the current input block.
+    input: Vec<f32>,
+    /// Samples of the current input block received so far.
+    filled: usize,
+    /// The output of the previous block, played while the current block fills.
+    output: Vec<f32>,
+    time: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+}
+
+impl Convolver {
+    pub fn new(impulse: &[f32], block: usize) -> Self {
+        let mut planner = RealFftPlanner::<f32>::new();
+        let forward = planner.plan_fft_forward(block * 2);
+        let inverse = planner.plan_fft_inverse(block * 2);
+
+        let partitions = impulse
+            .chunks(block)
+            .map(|chunk| {
+                let mut time = forward.make_input_vec();
+                time[..chunk.len()].copy_from_slice(chunk);
+                let mut spectrum = forward.make_output_vec();
+                forward
+                    .process(&mut time, &mut spectrum)
+                    .expect("buffers should have the planned length");
+                spectrum
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            block,
+            history: vec![forward.make_output_vec(); partitions.len().max(1)],
+            partitions,
+            newest: 0,
+            input: vec![0.0; block * 2],
+            filled: 0,
+            output: vec![0.0; block],
+            time: forward.make_input_vec(),
+            spectrum: forward.make_output_vec(),
+            forward,
+            inverse,
+        }
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        let output = self.output[self.filled];
+        self.input[self.block + self.filled] = sample;
+        self.filled += 1;
+
+        if self.filled == self.block {
+            self.filled = 0;
+            self.process_block();
+        }
+
+        output
+    }
+
+    fn process_block(&mut self) {
+        let count = self.history.len();
+
+        self.time.copy_from_slice(&self.input);
+        self.newest = (self.newest + 1) % count;
+        self.forward
+            .process(&mut self.time, &mut self.history[self.newest])
+            .expect("buffers should have the planned length");
+        self.input.copy_within(self.block.., 0);
+
+        // the newest input with the first partition, older input with later partitions
+        self.spectrum.fill(Complex::default());
+        for (age, partition) in self.partitions.iter().enumerate() {
+            let input = &self.history[(self.newest + count - age) % count];
+            for ((sum, x), h) in self.spectrum.iter_mut().zip(input).zip(partition) {
+                *sum += x * h;
+            }
+        }
+
+        // rounding leaves tiny imaginary parts that the inverse transform rejects
+        let last = self.spectrum.len() - 1;
+        self.spectrum[0].im = 0.0;
+        self.spectrum[last].im = 0.0;
+
+        self.inverse
+            .process(&mut self.spectrum, &mut self.time)
+            .expect("buffers should have the planned length");
+
+        // overlap-save keeps the second half, the first is wrapped around
+        let scale = 1.0 / self.time.len() as f32;
+        for (output, sample) in self.output.iter_mut().zip(&self.time[self.block..]) {
+            *output = sample * scale;
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::ops::RangeInclusive;
+
+use eframe::{egui::Ui, epaint::Color32};
+use egui_plot::{Line, Plot, PlotPoint, Points, VLine};
+
+/// Distance in points within which a breakpoint can be grabbed.
+const GRAB_RADIUS: f32 = 8.0;
+
+/// A piecewise linear curve defined by breakpoints, editable in a plot.
+///
+/// The x coordinates of the first and last breakpoint are fixed, breakpoints in between can be moved
+/// freely between their neighbours.
+#[derive(Clone)]
+pub struct Curve {
+    points: Vec<[f32; 2]>,
+    dragging: Option<usize>,
+}
+
+impl Curve {
+    pub fn new(points: Vec<[f32; 2]>) -> Self {
+        assert!(points.len() >= 2, "a curve needs at least two points");
+        Self {
+            points,
+            dragging: None,
+        }
+    }
+
+    pub fn points(&self) -> &[[f32; 2]] {
+        &self.points
+    }
+
+    pub fn set_points(&mut self, points: Vec<[f32; 2]>) {
+        assert!(points.len() >= 2, "a curve needs at least two points");
+        self.points = points;
+        self.dragging = None;
+    }
+
+    /// The x range this curve is defined over.
+    pub fn range(&self) -> RangeInclusive<f32> {
+        self.points.first().unwrap()[0]..=self.points.last().unwrap()[0]
+    }
+
+    /// Samples the curve at `x` using linear interpolation, clamped to the curve's range.
+    pub fn sample(&self, x: f32) -> f32 {
+        let index = self.points.partition_point(|point| point[0] <= x);
+
+        if index == 0 {
+            return self.points[0][1];
+        }
+
+        if index >= self.points.len() {
+            return self.points[self.points.len() - 1][1];
+        }
+
+        let [x0, y0] = self.points[index - 1];
+        let [x1, y1] = self.points[index];
+
+        if x1 - x0 <= f32::EPSILON {
+            y1
+        } else {
+            y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+        }
+    }
+
+    fn closest(&self, plot: &egui_plot::PlotUi, pos: eframe::epaint::Pos2) -> Option<usize> {
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let screen = plot.screen_from_plot(PlotPoint::new(point[0], point[1]));
+                (i, screen.distance(pos))
+            })
+            .filter(|(_, distance)| *distance < GRAB_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Draws the curve editor. Drag points to move them, click to add and right click to remove.
+    ///
+    /// Returns whether the curve was changed.
+    pub fn show(
+        &mut self,
+        id: impl std::hash::Hash,
+        ui: &mut Ui,
+        y_range: RangeInclusive<f32>,
+        marker: Option<f32>,
+    ) -> bool {
+        let x_range = self.range();
+
+        let plot = Plot::new(id)
+            .height(100.0)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .allow_drag(false)
+            .allow_double_click_reset(false)
+            .include_x(*x_range.start())
+            .include_x(*x_range.end())
+            .include_y(*y_range.start())
+            .include_y(*y_range.end());
+
+        plot.show(ui, |plot| {
+            let mut changed = false;
+            let response = plot.response().clone();
+            let pointer = response.interact_pointer_pos().or(response.hover_pos());
+
+            if let Some(pos) = pointer {
+                let value = plot.plot_from_screen(pos);
+                let value = [
+                    (value.x as f32).clamp(*x_range.start(), *x_range.end()),
+                    (value.y as f32).clamp(*y_range.start(), *y_range.end()),
+                ];
+
+                if response.drag_started() {
+                    self.dragging = self.closest(plot, pos);
+                }
+
+                if let Some(index) = self.dragging.filter(|_| response.dragged()) {
+                    let last = self.points.len() - 1;
+                    let x = if index == 0 || index == last {
+                        self.points[index][0]
+                    } else {
+                        value[0].clamp(self.points[index - 1][0], self.points[index + 1][0])
+                    };
+                    self.points[index] = [x, value[1]];
+                    changed = true;
+                } else if response.clicked() && self.closest(plot, pos).is_none() {
+                    let index = self.points.partition_point(|point| point[0] <= value[0]);
+                    self.points
+                        .insert(index.clamp(1, self.points.len() - 1), value);
+                    changed = true;
+                } else if response.secondary_clicked() {
+                    if let Some(index) = self
+                        .closest(plot, pos)
+                        .filter(|&index| index != 0 && index != self.points.len() - 1)
+                    {
+                        self.points.remove(index);
+                        changed = true;
+                    }
+                }
+            }
+
+            if response.drag_stopped() {
+                self.dragging = None;
+            }
+
+            let points = self
+                .points
+                .iter()
+                .map(|point| [point[0] as f64, point[1] as f64])
+                .collect::<Vec<_>>();
+
+            plot.line(Line::new(points.clone()).color(Color32::LIGHT_GREEN));
+            plot.points(Points::new(points).radius(3.0f32).color(Color32::WHITE));
+
+            if let Some(marker) = marker {
+                plot.vline(VLine::new(marker).color(Color32::GOLD));
+            }
+
+            changed
+        })
+        .inner
+    }
+}
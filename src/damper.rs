@@ -18,13 +18,31 @@ impl LinearDamper<f32> {
         Self::new(1.0 / (sample_rate as f32 / 20.0), 0.0)
     }
 
+    /// Creates a damper that takes `seconds` to move across `span`, to smooth out jumps of a value.
+    pub fn new_ramp(span: f32, seconds: f32, sample_rate: u32, start: f32) -> Self {
+        Self::new(span / (seconds * sample_rate as f32).max(1.0), start)
+    }
+
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
     pub fn cutoff_samples(&self) -> u32 {
         (1.0 / self.max_dif) as u32
     }
 
+    /// Whether the damper has reached `input`, so [`Self::frame`] would return it unchanged.
+    pub fn is_settled(&self, input: f32) -> bool {
+        (self.current - input).abs() <= f32::EPSILON
+    }
+
     pub fn frame(&mut self, input: f32) -> f32 {
-        let dif = (input - self.current).clamp(-self.max_dif, self.max_dif);
-        self.current += dif;
+        let dif = input - self.current;
+        if dif.abs() <= self.max_dif {
+            self.current = input;
+        } else {
+            self.current += dif.clamp(-self.max_dif, self.max_dif);
+        }
         self.current
     }
 }
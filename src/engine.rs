@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use crate::{
+    instance::instance::Instance,
+    io::PortHandle,
+    module::PortType,
+    rack::{patch::Patch, rack::Rack},
+};
+
+/// Samples processed at once when samples are pulled one by one.
+const BLOCK: usize = 256;
+
+/// Runs a patch without the app, driven by the loop of a host like a game engine.
+///
+/// Audio is pulled as interleaved stereo samples, by iterating or with [`Engine::fill`], which
+/// maps directly onto the audio sources of engines. Values of outputs can be read in between, to
+/// drive the game with the patch, like the envelope of a kick moving a light.
+pub struct Engine {
+    rack: Rack,
+    sample_rate: u32,
+    /// Processed samples not pulled yet.
+    pending: VecDeque<f32>,
+}
+
+impl Engine {
+    pub fn new(patch: &Patch, sample_rate: u32) -> Self {
+        let mut rack = Rack::default();
+        rack.import_patch(patch);
+        // tagged modules can be read with `control` without reaching the output
+        for instance in rack.instances.values_mut() {
+            if !instance.tags.is_empty() {
+                instance.always_process = true;
+            }
+        }
+        Self {
+            rack,
+            sample_rate,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Loads an exported patch file, with the files of the modules looked up next to it.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: &std::path::Path, sample_rate: u32) -> Option<Self> {
+        let mut patch = Patch::from_ron(&std::fs::read_to_string(path).ok()?)?;
+        if let Some(dir) = path.parent() {
+            patch.resolve_assets(dir);
+        }
+        Some(Self::new(&patch, sample_rate))
+    }
+
+    pub fn rack_mut(&mut self) -> &mut Rack {
+        &mut self.rack
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        2
+    }
+
+    /// Fills `buffer` with interleaved stereo samples.
+    pub fn fill(&mut self, buffer: &mut [f32]) {
+        for (sample, next) in buffer.iter_mut().zip(self) {
+            *sample = next;
+        }
+    }
+
+    /// Processes `seconds` without keeping the audio, for hosts only reading values.
+    pub fn advance(&mut self, seconds: f32) {
+        self.pending.clear();
+        let samples = (seconds * self.sample_rate as f32) as usize;
+        self.rack.process_amount(self.sample_rate, samples);
+    }
+
+    /// The first module tagged with `tag`.
+    fn tagged(&self, tag: &str) -> Option<&Instance> {
+        self.rack
+            .instances
+            .values()
+            .find(|instance| instance.tags.iter().any(|other| other == tag))
+    }
+
+    /// The latest value of the output named `port` of the first module tagged with `tag`.
+    ///
+    /// Tagged modules are always processed, so their values are current even when nothing they
+    /// are connected to is heard.
+    pub fn control(&self, tag: &str, port: &str) -> Option<f32> {
+        let instance = self.tagged(tag)?;
+        let description = instance.port_descriptions().find(|description| {
+            description.name == port && description.port_type == PortType::Output
+        })?;
+        let value = self
+            .rack
+            .io
+            .get_output_dyn(PortHandle::new(description.id, instance.handle))?;
+        Some(value.as_value())
+    }
+
+    /// Sets a parameter by a path like `"lead/cutoff"`, the tag of a module and the name of one
+    /// of its parameters, clamped to its range. Returns whether the parameter was found.
+    pub fn set_parameter(&mut self, path: &str, value: f32) -> bool {
+        let Some((tag, name)) = path.rsplit_once('/') else {
+            return false;
+        };
+        let Some(handle) = self.tagged(tag).map(|instance| instance.handle) else {
+            return false;
+        };
+        let instance = self.rack.instances.get_mut(&handle).unwrap();
+        let Some(parameter) = instance
+            .module
+            .parameters()
+            .into_iter()
+            .find(|parameter| parameter.name == name)
+        else {
+            return false;
+        };
+        *parameter.value = value.clamp(*parameter.range.start(), *parameter.range.end());
+        true
+    }
+}
+
+impl Iterator for Engine {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            let processed = self.rack.process_amount(self.sample_rate, BLOCK);
+            self.pending.extend(processed.iter());
+        }
+        self.pending.pop_front()
+    }
+}
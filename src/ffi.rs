@@ -0,0 +1,104 @@
+#![cfg(not(target_arch = "wasm32"))]
+//! A C API around [`Engine`] for hosts not written in Rust, declared in `include/synth_mod.h`.
+
+use std::{
+    ffi::{c_char, CStr},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    ptr,
+};
+
+use crate::engine::Engine;
+
+/// Runs `body`, returning `fallback` when it panics, as unwinding into the host aborts it.
+fn guard<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
+/// Loads an exported patch file, returns null when it can't be read.
+///
+/// # Safety
+///
+/// `path` must be a valid nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn synth_mod_engine_open(
+    path: *const c_char,
+    sample_rate: u32,
+) -> *mut Engine {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(path) = CStr::from_ptr(path).to_str() else {
+        return ptr::null_mut();
+    };
+    guard(ptr::null_mut(), || {
+        match Engine::from_file(Path::new(path), sample_rate) {
+            Some(engine) => Box::into_raw(Box::new(engine)),
+            None => ptr::null_mut(),
+        }
+    })
+}
+
+/// Processes `frames` frames into `buffer` as interleaved stereo samples.
+///
+/// Returns whether the frames were processed, the buffer is silent otherwise.
+///
+/// # Safety
+///
+/// `engine` must come from [`synth_mod_engine_open`] and `buffer` must hold `frames * 2` floats.
+#[no_mangle]
+pub unsafe extern "C" fn synth_mod_engine_process(
+    engine: *mut Engine,
+    buffer: *mut f32,
+    frames: usize,
+) -> bool {
+    let (Some(engine), false, Some(len)) =
+        (engine.as_mut(), buffer.is_null(), frames.checked_mul(2))
+    else {
+        return false;
+    };
+    let buffer = std::slice::from_raw_parts_mut(buffer, len);
+    let processed = guard(false, || {
+        engine.fill(buffer);
+        true
+    });
+    if !processed {
+        buffer.fill(0.0);
+    }
+    processed
+}
+
+/// Sets a parameter by a path like `"lead/cutoff"`, see [`Engine::set_parameter`].
+///
+/// Returns whether the parameter was found.
+///
+/// # Safety
+///
+/// `engine` must come from [`synth_mod_engine_open`] and `path` must be a valid nul terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn synth_mod_engine_set_parameter(
+    engine: *mut Engine,
+    path: *const c_char,
+    value: f32,
+) -> bool {
+    let (Some(engine), false) = (engine.as_mut(), path.is_null()) else {
+        return false;
+    };
+    let Ok(path) = CStr::from_ptr(path).to_str() else {
+        return false;
+    };
+    guard(false, || engine.set_parameter(path, value))
+}
+
+/// Frees an engine, null is ignored.
+///
+/// # Safety
+///
+/// `engine` must come from [`synth_mod_engine_open`] and can't be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn synth_mod_engine_free(engine: *mut Engine) {
+    if !engine.is_null() {
+        guard((), || drop(Box::from_raw(engine)));
+    }
+}
@@ -0,0 +1,1119 @@
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    OnceLock,
+};
+
+use ahash::HashMap;
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+/// Language of the user facing strings.
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Dutch,
+}
+
+impl Language {
+    /// The name of the language in the language itself.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Language::English => "English",
+            Language::Dutch => "Nederlands",
+        }
+    }
+
+    fn table(&self) -> Option<&'static HashMap<&'static str, &'static str>> {
+        static DUTCH_TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+        match self {
+            Language::English => None,
+            Language::Dutch => Some(DUTCH_TABLE.get_or_init(|| DUTCH.iter().copied().collect())),
+        }
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::Dutch,
+        _ => Language::English,
+    }
+}
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed)
+}
+
+/// Translates an english string to the current language, falling back to the english string.
+pub fn tr(text: &str) -> &str {
+    language()
+        .table()
+        .and_then(|table| table.get(text).copied())
+        .unwrap_or(text)
+}
+
+/// Dutch translations keyed by their english source string.
+const DUTCH: &[(&str, &str)] = &[
+    // chrome
+    ("amount:", "hoeveelheid:"),
+    ("randomize all", "alles willekeurig"),
+    ("randomize", "willekeurig"),
+    ("randomize parameters", "parameters willekeurig maken"),
+    ("probe connections by hovering them", "meet verbindingen door er over te zweven"),
+    ("touch mode", "aanraakmodus"),
+    ("average frame time", "gemiddelde frametijd"),
+    ("settings", "instellingen"),
+    ("console", "console"),
+    ("nothing logged yet", "nog niets gelogd"),
+    ("click to dismiss", "klik om te sluiten"),
+    ("could not load:", "kon niet laden:"),
+    ("the audio output was lost", "de audio-uitvoer is weggevallen"),
+    ("volume", "volume"),
+    ("volume:", "volume:"),
+    ("sample rate", "samplefrequentie"),
+    ("fallback sample rate", "standaard samplefrequentie"),
+    ("channels", "kanalen"),
+    ("⚠ cant keep up!", "⚠ kan het niet bijhouden!"),
+    ("⚠ could not initialize audio output!", "⚠ kon audio-uitvoer niet starten!"),
+    ("retry", "opnieuw"),
+    ("bar.beat", "maat.tel"),
+    ("sync to tempo", "synchroniseer met tempo"),
+    ("➕ Module", "➕ Module"),
+    ("➕ Panel", "➕ Paneel"),
+    // tutorial
+    ("tutorial", "rondleiding"),
+    ("finish", "afronden"),
+    ("skip tutorial", "rondleiding overslaan"),
+    (
+        "add an oscillator with the \"➕ Module\" button of a panel",
+        "voeg een oscillator toe met de \"➕ Module\" knop van een paneel",
+    ),
+    (
+        "add an audio output and drag a cable from the sample output of the oscillator to it",
+        "voeg een audio-uitvoer toe en sleep een kabel van de sample uitvoer van de oscillator er naartoe",
+    ),
+    (
+        "change the frequency of the oscillator and listen to the difference",
+        "verander de frequentie van de oscillator en luister naar het verschil",
+    ),
+    (
+        "well done! explore the other modules, the \"?\" of a module explains it",
+        "goed gedaan! ontdek de andere modules, de \"?\" van een module legt het uit",
+    ),
+    // instances and ports
+    ("click to highlight the signal flow", "klik om de signaalstroom te markeren"),
+    ("compare parameter states", "vergelijk parameterstanden"),
+    ("help", "hulp"),
+    ("no description available", "geen beschrijving beschikbaar"),
+    ("insert example", "voorbeeld invoegen"),
+    ("show in a separate window", "in een apart venster tonen"),
+    ("Are you sure?", "Weet je het zeker?"),
+    (
+        "⚠ this module crashed and was disabled:",
+        "⚠ deze module is gecrasht en uitgeschakeld:",
+    ),
+    ("unknown error", "onbekende fout"),
+    ("Input", "Invoer"),
+    ("input port", "invoerpoort"),
+    ("output port", "uitvoerpoort"),
+    ("connect", "verbinden"),
+    ("disconnect", "loskoppelen"),
+    ("replace", "vervangen"),
+    ("conversion", "conversie"),
+    ("same instance", "zelfde instantie"),
+    ("incompatible", "onverenigbaar"),
+    ("cycle", "kringloop"),
+    ("ok", "ok"),
+    ("gain:", "versterking:"),
+    ("reset", "herstellen"),
+    // settings
+    ("language:", "taal:"),
+    ("theme:", "thema:"),
+    ("dark", "donker"),
+    ("light", "licht"),
+    ("accent:", "accent:"),
+    ("cables:", "kabels:"),
+    ("default", "standaard"),
+    ("high contrast", "hoog contrast"),
+    ("colorblind safe", "kleurenblindveilig"),
+    (
+        "the high contrast and colorblind safe themes also use shapes for port types and line patterns for connection validity",
+        "de thema's hoog contrast en kleurenblindveilig gebruiken ook vormen voor poorttypes en lijnpatronen voor de geldigheid van verbindingen",
+    ),
+    ("cable opacity:", "kabeldekking:"),
+    ("rope curvature:", "kabelkromming:"),
+    ("background grid:", "achtergrondraster:"),
+    ("snap to grid:", "uitlijnen op raster:"),
+    // module names
+    ("Audio Output", "Audio-uitvoer"),
+    ("Kick", "Basdrum"),
+    ("Snare", "Snaredrum"),
+    ("Hat", "Hi-hat"),
+    ("Envelope", "Omhullende"),
+    ("File", "Bestand"),
+    ("Filter", "Filter"),
+    ("Function", "Functie"),
+    ("Keyboard", "Toetsenbord"),
+    ("Noise", "Ruis"),
+    ("Oscillator", "Oscillator"),
+    ("Pads", "Pads"),
+    ("PianoRoll", "Pianorol"),
+    ("Scope", "Scoop"),
+    ("Sequencer", "Sequencer"),
+    ("Shaper", "Vormer"),
+    ("TrigTools", "Triggers"),
+    ("Width", "Breedte"),
+    ("PitchTrack", "Toonvolger"),
+    ("Impulse", "Impuls"),
+    ("LFO", "LFO"),
+    ("Stats", "Statistieken"),
+    ("Reference", "Referentie"),
+    ("Mixer", "Mixer"),
+    ("Delay", "Vertraging"),
+    ("PingPong", "Pingpong"),
+    ("Vinyl", "Vinyl"),
+    ("Reverb", "Galm"),
+    ("Shepard", "Shepard"),
+    ("Generative", "Generatief"),
+    ("XY", "XY"),
+    ("Flanger", "Flanger"),
+    ("MultibandComp", "Multibandcompressor"),
+    ("Looper", "Looper"),
+    ("Metronome", "Metronoom"),
+    ("Missing", "Ontbrekend"),
+    ("Speech", "Spraak"),
+    ("Tracker", "Tracker"),
+    ("AutoPan", "AutoPan"),
+    ("Macro", "Macro"),
+    ("Octaver", "Octaver"),
+    ("Granular", "Granulair"),
+    ("Visualizer", "Visualisatie"),
+    ("Sampler", "Sampler"),
+    ("Additive", "Additief"),
+    ("Unison", "Unisono"),
+    // port names
+    ("a", "a"),
+    ("b", "b"),
+    ("out", "uit"),
+    ("input", "invoer"),
+    ("output", "uitvoer"),
+    ("sample", "sample"),
+    ("trigger", "trigger"),
+    ("gate", "gate"),
+    ("attack", "aanzet"),
+    ("decay", "verval"),
+    ("sustain", "aanhouden"),
+    ("release", "loslaten"),
+    ("freq", "freq"),
+    ("slice", "stuk"),
+    ("sub", "sub"),
+    ("out freq", "uit freq"),
+    ("pressed", "ingedrukt"),
+    ("velocity", "aanslagsterkte"),
+    ("delayed", "vertraagd"),
+    ("shaped", "gevormd"),
+    ("toggle", "wissel"),
+    // parameters
+    ("tune", "stemming"),
+    ("sweep", "sweep"),
+    ("sweep time", "sweeptijd"),
+    ("tone", "toon"),
+    ("body decay", "rompverval"),
+    ("snappy", "snaren"),
+    ("cutoff", "afsnijfrequentie"),
+    ("mid", "midden"),
+    ("side", "zijkant"),
+    ("drive", "drive"),
+    ("swing", "swing"),
+    // module controls
+    ("alternating", "afwisselend"),
+    ("Sine", "Sinus"),
+    ("Square", "Blok"),
+    ("Triangle", "Driehoek"),
+    ("Saw", "Zaagtand"),
+    ("lowpass", "laagdoorlaat"),
+    ("highpass", "hoogdoorlaat"),
+    ("Add ➕", "Optellen ➕"),
+    ("Subtract ➖", "Aftrekken ➖"),
+    ("Multiply ✖", "Vermenigvuldigen ✖"),
+    ("Divide ➗", "Delen ➗"),
+    ("duration", "duur"),
+    ("duration:", "duur:"),
+    ("interval", "interval"),
+    ("locked", "vergrendeld"),
+    ("delay:", "vertraging:"),
+    ("length:", "lengte:"),
+    ("reset toggle", "wissel herstellen"),
+    ("loop", "herhalen"),
+    ("repeat while the gate is held", "herhaal zolang de gate hoog is"),
+    (
+        "cycle attack, decay and release while the gate is held",
+        "doorloop aanzet, verval en loslaten zolang de gate hoog is",
+    ),
+    ("linear", "lineair"),
+    ("exp", "exp"),
+    ("log", "log"),
+    ("legato", "legato"),
+    ("ignore", "negeren"),
+    ("drive:", "drive:"),
+    ("load", "laden"),
+    ("soft clip", "zacht begrenzen"),
+    ("hard clip", "hard begrenzen"),
+    ("fold", "vouwen"),
+    ("rectify", "gelijkrichten"),
+    ("mid:", "midden:"),
+    ("side:", "zijkant:"),
+    ("correlation:", "correlatie:"),
+    ("pad", "pad"),
+    ("pitch:", "toonhoogte:"),
+    ("pick", "kies"),
+    ("keys", "toetsen"),
+    (
+        "play the pads with 1-4, Q-R, A-F and Z-V",
+        "speel de pads met 1-4, Q-R, A-F en Z-V",
+    ),
+    ("bars:", "maten:"),
+    (
+        "loop the file over a number of bars, retuning it to fit",
+        "herhaal het bestand over een aantal maten, herstemd zodat het past",
+    ),
+    ("playback speed", "afspeelsnelheid"),
+    ("slices", "stukken"),
+    ("detect", "detecteren"),
+    (
+        "tempo estimated from the detected onsets",
+        "tempo geschat uit de gedetecteerde aanslagen",
+    ),
+    ("lowest note:", "laagste noot:"),
+    ("clear", "wissen"),
+    ("swing:", "swing:"),
+    ("gate:", "gate:"),
+    ("right click to toggle", "rechtsklik om aan of uit te zetten"),
+    ("step", "stap"),
+    ("on", "aan"),
+    ("note:", "noot:"),
+    ("velocity:", "aanslagsterkte:"),
+    ("probability:", "kans:"),
+    ("ratchets:", "herhalingen:"),
+    ("offset:", "verschuiving:"),
+    (
+        "micro-timing as a percentage of a step",
+        "microtiming als percentage van een stap",
+    ),
+    // module and port descriptions
+    (
+        "plays its input on the selected audio device",
+        "speelt de invoer af op het gekozen audioapparaat",
+    ),
+    (
+        "the sound to play, mono signals are sent to both channels",
+        "het af te spelen geluid, mono signalen gaan naar beide kanalen",
+    ),
+    (
+        "an analog style kick drum, a sine with a falling pitch",
+        "een analoge basdrum, een sinus met een dalende toonhoogte",
+    ),
+    (
+        "a snare drum, a tuned body with a burst of filtered noise",
+        "een snaredrum, een gestemde romp met een uitbarsting van gefilterde ruis",
+    ),
+    (
+        "a noise based hi-hat, open or closed depending on its decay",
+        "een hi-hat op basis van ruis, open of gesloten afhankelijk van het verval",
+    ),
+    (
+        "restarts the drum when it goes high",
+        "herstart de drum wanneer deze hoog wordt",
+    ),
+    ("the drum sound", "het drumgeluid"),
+    (
+        "an ADSR envelope, rises while the gate is high and falls after it is released",
+        "een ADSR omhullende, stijgt zolang de gate hoog is en daalt nadat deze wordt losgelaten",
+    ),
+    (
+        "starts the attack when going high and the release when going low",
+        "start de aanzet wanneer hoog en het loslaten wanneer laag",
+    ),
+    ("time to rise to the peak", "tijd om naar de piek te stijgen"),
+    (
+        "time to fall from the peak to the sustain level",
+        "tijd om van de piek naar het aanhoudniveau te dalen",
+    ),
+    (
+        "level held while the gate stays high",
+        "niveau dat wordt aangehouden zolang de gate hoog blijft",
+    ),
+    (
+        "time to fall to zero after the gate is released",
+        "tijd om naar nul te dalen nadat de gate wordt losgelaten",
+    ),
+    ("the envelope level from 0 to 1", "het niveau van de omhullende van 0 tot 1"),
+    (
+        "decodes and plays an audio file, which can be cut into slices that are played by a trigger",
+        "decodeert en speelt een audiobestand af, dat in stukken gesneden kan worden die met een trigger gespeeld worden",
+    ),
+    (
+        "plays the slice chosen by the slice input on each rising edge",
+        "speelt het stuk gekozen door de stuk-invoer bij elke stijgende flank",
+    ),
+    ("index of the slice to play, starting at 0", "nummer van het te spelen stuk, beginnend bij 0"),
+    ("the decoded sound", "het gedecodeerde geluid"),
+    (
+        "a low or high pass filter removing frequencies beyond the cutoff",
+        "een laag- of hoogdoorlaatfilter dat frequenties voorbij de afsnijfrequentie verwijdert",
+    ),
+    ("the sound to filter", "het te filteren geluid"),
+    ("the cutoff frequency in Hz", "de afsnijfrequentie in Hz"),
+    (
+        "normalled: follows the module until connected",
+        "genormaliseerd: volgt de module tot er een kabel is aangesloten",
+    ),
+    ("the filtered sound", "het gefilterde geluid"),
+    (
+        "plays a drawn curve each time its gate goes high",
+        "speelt een getekende curve af elke keer dat de gate hoog wordt",
+    ),
+    (
+        "restarts the curve when it goes high",
+        "herstart de curve wanneer deze hoog wordt",
+    ),
+    ("the current value of the curve", "de huidige waarde van de curve"),
+    (
+        "play notes with the mouse or the computer keyboard",
+        "speel noten met de muis of het toetsenbord van de computer",
+    ),
+    (
+        "frequency in Hz of the last played note",
+        "frequentie in Hz van de laatst gespeelde noot",
+    ),
+    ("high while a key is held", "hoog zolang een toets wordt ingedrukt"),
+    ("white noise", "witte ruis"),
+    ("random samples between -1 and 1, scaled by the level", "willekeurige samples tussen -1 en 1, geschaald met het niveau"),
+    (
+        "combines two values with the selected operator",
+        "combineert twee waarden met de gekozen bewerking",
+    ),
+    ("the left operand", "de linker operand"),
+    ("the right operand", "de rechter operand"),
+    ("the result", "het resultaat"),
+    (
+        "a periodic wave at the given frequency",
+        "een periodieke golf op de gegeven frequentie",
+    ),
+    ("frequency of the wave in Hz", "frequentie van de golf in Hz"),
+    ("the wave", "de golf"),
+    (
+        "the same wave one or two octaves lower, in phase with the main wave",
+        "dezelfde golf een of twee octaven lager, in fase met de hoofdgolf",
+    ),
+    ("detune:", "ontstemming:"),
+    ("sub:", "sub:"),
+    ("one octave lower", "een octaaf lager"),
+    ("two octaves lower", "twee octaven lager"),
+    (
+        "a grid of pads that each play a one-shot sample, triggered by clicking, keys, MIDI notes or the trigger inputs",
+        "een raster van pads die elk een sample afspelen, gestart door te klikken, met toetsen, MIDI-noten of de trigger ingangen",
+    ),
+    ("all playing pads mixed", "alle spelende pads gemengd"),
+    (
+        "plays a clip of notes in sync with the transport",
+        "speelt een clip met noten af in de maat van het transport",
+    ),
+    ("frequency in Hz of the playing note", "frequentie in Hz van de spelende noot"),
+    ("high while a note plays", "hoog zolang een noot speelt"),
+    ("plots its input over time", "tekent de invoer uit over de tijd"),
+    ("the signal to plot", "het uit te tekenen signaal"),
+    (
+        "plays a row of steps in sync with the transport",
+        "speelt een rij stappen af in de maat van het transport",
+    ),
+    ("frequency in Hz of the playing step", "frequentie in Hz van de spelende stap"),
+    ("high while a step plays", "hoog zolang een stap speelt"),
+    (
+        "velocity of the playing step from 0 to 1",
+        "aanslagsterkte van de spelende stap van 0 tot 1",
+    ),
+    (
+        "a waveshaper mapping its input through a drawn transfer function",
+        "een golfvormer die de invoer door een getekende overdrachtsfunctie stuurt",
+    ),
+    ("the sound to shape", "het te vormen geluid"),
+    ("the shaped sound", "het gevormde geluid"),
+    (
+        "small gate utilities: delay, re-shape, edge detection and toggle",
+        "kleine gate hulpmiddelen: vertragen, hervormen, flankdetectie en wisselen",
+    ),
+    ("the gate to process", "de te verwerken gate"),
+    ("the gate delayed by the delay time", "de gate vertraagd met de vertragingstijd"),
+    (
+        "high for the set length after each rising edge",
+        "hoog gedurende de ingestelde lengte na elke stijgende flank",
+    ),
+    (
+        "high for a single sample on each rising edge",
+        "hoog gedurende een enkele sample bij elke stijgende flank",
+    ),
+    ("flips on each rising edge", "wisselt bij elke stijgende flank"),
+    ("outputs a constant value", "geeft een constante waarde"),
+    (
+        "sends its input to a named bus, receive it anywhere without a cable",
+        "stuurt de invoer naar een bus met een naam, ontvang het overal zonder kabel",
+    ),
+    ("the value to send", "de te versturen waarde"),
+    ("outputs what is sent to a named bus", "geeft wat naar een bus met een naam is gestuurd"),
+    ("the value sent to the bus", "de waarde die naar de bus is gestuurd"),
+    ("bus", "bus"),
+    ("blocks", "blokken"),
+    ("share patch", "patch delen"),
+    ("embed sample files", "samplebestanden insluiten"),
+    ("export to file…", "exporteren naar bestand…"),
+    ("import from file…", "importeren uit bestand…"),
+    ("patch", "patch"),
+    ("copy as text", "kopiëren als tekst"),
+    ("paste a shared patch", "plak een gedeelde patch"),
+    ("import", "importeren"),
+    ("invalid patch", "ongeldige patch"),
+    ("could not write the file", "kon het bestand niet schrijven"),
+    ("missing files", "ontbrekende bestanden"),
+    (
+        "these files of the imported patch could not be found:",
+        "deze bestanden van de geïmporteerde patch zijn niet gevonden:",
+    ),
+    ("locate…", "zoeken…"),
+    ("import VCV Rack patch…", "VCV Rack-patch importeren…"),
+    ("could not read the file", "kon het bestand niet lezen"),
+    ("VCV Rack import", "VCV Rack-import"),
+    ("modules were imported", "modules zijn geïmporteerd"),
+    (
+        "these modules have no equivalent and were replaced by placeholders:",
+        "deze modules hebben geen equivalent en zijn vervangen door plaatsvervangers:",
+    ),
+    (
+        "compressed VCV Rack 2 patches are not supported, extract patch.json first",
+        "gecomprimeerde VCV Rack 2-patches worden niet ondersteund, pak eerst patch.json uit",
+    ),
+    ("not a VCV Rack patch", "geen VCV Rack-patch"),
+    (
+        "stands in for a module that is not available",
+        "vervangt een module die niet beschikbaar is",
+    ),
+    ("not available:", "niet beschikbaar:"),
+    (
+        "plays Amiga ProTracker and FastTracker XM modules with an output per channel",
+        "speelt Amiga ProTracker- en FastTracker XM-modules af met een uitvoer per kanaal",
+    ),
+    ("channel 1", "kanaal 1"),
+    ("channel 2", "kanaal 2"),
+    ("channel 3", "kanaal 3"),
+    ("channel 4", "kanaal 4"),
+    (
+        "all channels, ProTracker modules are panned like an Amiga",
+        "alle kanalen, ProTracker-modules worden gepand zoals een Amiga",
+    ),
+    ("not a ProTracker module", "geen ProTracker-module"),
+    ("not a FastTracker XM module", "geen FastTracker XM-module"),
+    (
+        "speaks text with a simple formant synthesizer",
+        "spreekt tekst uit met een eenvoudige formantsynthesizer",
+    ),
+    ("starts speaking", "begint met spreken"),
+    ("pitch of the voice in Hz", "toonhoogte van de stem in Hz"),
+    ("the voice", "de stem"),
+    ("speak", "spreken"),
+    ("rate:", "tempo:"),
+    ("rate", "tempo"),
+    ("pitch", "toonhoogte"),
+    (
+        "clicks on every beat while the transport plays",
+        "tikt op elke tel terwijl het transport speelt",
+    ),
+    ("click", "tik"),
+    ("the click sound", "het tikgeluid"),
+    ("high at the start of every beat", "hoog aan het begin van elke tel"),
+    ("high at the start of every bar", "hoog aan het begin van elke maat"),
+    ("accent", "accent"),
+    (
+        "click higher on the first beat of a bar",
+        "hoger tikken op de eerste tel van een maat",
+    ),
+    (
+        "records its input in sync with the transport and loops it, optionally overdubbing",
+        "neemt de invoer op in de maat van het transport en herhaalt het, eventueel met overdub",
+    ),
+    ("the sound to record", "het geluid om op te nemen"),
+    (
+        "starts or stops recording when going high",
+        "start of stopt het opnemen wanneer het hoog wordt",
+    ),
+    (
+        "the loop, or the input while recording or overdubbing",
+        "de loop, of de invoer tijdens het opnemen of overdubben",
+    ),
+    ("record", "opnemen"),
+    ("empty", "leeg"),
+    ("recording from bar", "opnemen vanaf maat"),
+    ("recording", "neemt op"),
+    ("bars", "maten"),
+    ("count-in:", "aftellen:"),
+    ("bars, 0 records until stopped", "maten, 0 neemt op tot het gestopt wordt"),
+    ("punch", "inprikken"),
+    ("record between two bars", "opnemen tussen twee maten"),
+    (
+        "follows the pitch of a monophonic sound, so an instrument can play the synth",
+        "volgt de toonhoogte van een monofoon geluid, zodat een instrument de synth kan bespelen",
+    ),
+    ("a sound playing one note at a time", "een geluid dat één noot tegelijk speelt"),
+    (
+        "the detected frequency in Hz, held while no pitch is detected",
+        "de gedetecteerde frequentie in Hz, vastgehouden zolang er geen toonhoogte is",
+    ),
+    ("high while a pitch is detected", "hoog zolang er een toonhoogte gedetecteerd wordt"),
+    ("threshold:", "drempel:"),
+    (
+        "higher also accepts pitches it is less certain of",
+        "hoger accepteert ook toonhoogtes waar het minder zeker van is",
+    ),
+    ("floor:", "ondergrens:"),
+    ("quieter input is treated as silence", "zachtere invoer wordt als stilte behandeld"),
+    ("confidence", "zekerheid"),
+    (
+        "emits a single sample impulse or a short burst of filtered noise on each trigger, to excite resonators or test filters",
+        "geeft bij elke trigger een impuls van één sample of een korte stoot gefilterde ruis, om resonatoren aan te slaan of filters te testen",
+    ),
+    ("fires when it goes high", "vuurt wanneer het hoog wordt"),
+    ("the impulse or burst", "de impuls of stoot"),
+    ("impulse", "impuls"),
+    ("burst", "stoot"),
+    ("fire", "vuren"),
+    ("level:", "niveau:"),
+    ("tone:", "toon:"),
+    (
+        "a slow wave to modulate other modules, like the frequency of an oscillator or the cutoff of a filter",
+        "een langzame golf om andere modules te moduleren, zoals de frequentie van een oscillator of de afsnijfrequentie van een filter",
+    ),
+    (
+        "speed of the wave in Hz, unused while synced to the tempo",
+        "snelheid van de golf in Hz, ongebruikt zolang gesynchroniseerd met het tempo",
+    ),
+    (
+        "the wave multiplied by the depth plus the offset",
+        "de golf vermenigvuldigd met de diepte plus de verschuiving",
+    ),
+    ("Random", "Willekeurig"),
+    ("unipolar", "unipolair"),
+    ("from 0 to 1 instead of -1 to 1", "van 0 tot 1 in plaats van -1 tot 1"),
+    ("depth:", "diepte:"),
+    (
+        "measures the minimum, maximum, RMS, DC offset and zero crossings of its input",
+        "meet het minimum, maximum, RMS, DC-offset en nuldoorgangen van de invoer",
+    ),
+    ("the signal to measure", "het signaal om te meten"),
+    ("min:", "min:"),
+    ("max:", "max:"),
+    ("RMS:", "RMS:"),
+    ("DC offset:", "DC-offset:"),
+    ("zero crossings:", "nuldoorgangen:"),
+    (
+        "exactly known signals to calibrate levels and verify the signal chain with",
+        "precies bekende signalen om niveaus te kalibreren en de signaalketen te controleren",
+    ),
+    ("the selected signal", "het gekozen signaal"),
+    ("440 Hz sine, 0 dBFS", "440 Hz sinus, 0 dBFS"),
+    ("1 kHz tone, -18 dBFS", "1 kHz toon, -18 dBFS"),
+    ("pink noise, -18 dBFS RMS", "roze ruis, -18 dBFS RMS"),
+    ("DC 1.0", "DC 1.0"),
+    (
+        "sums its channels into one sound, with a gain and mute for each",
+        "telt de kanalen op tot één geluid, met voor elk een versterking en demping",
+    ),
+    ("the sum of all channels", "de som van alle kanalen"),
+    ("a sound to mix", "een geluid om te mengen"),
+    ("mute", "dempen"),
+    ("➕ channel", "➕ kanaal"),
+    ("➖ channel", "➖ kanaal"),
+    (
+        "removes the last channel and its connection",
+        "verwijdert het laatste kanaal en de verbinding ervan",
+    ),
+    (
+        "repeats its input after a delay, every echo softer than the last",
+        "herhaalt de invoer na een vertraging, elke echo zachter dan de vorige",
+    ),
+    ("the sound to echo", "het geluid om te herhalen"),
+    ("the input mixed with its echoes", "de invoer gemengd met de echo's"),
+    ("time:", "tijd:"),
+    ("time", "tijd"),
+    ("feedback:", "terugkoppeling:"),
+    ("feedback", "terugkoppeling"),
+    ("mix:", "mix:"),
+    ("mix", "mix"),
+    ("0 is only the input, 1 only the echoes", "0 is alleen de invoer, 1 alleen de echo's"),
+    (
+        "compresses the lows, mids and highs of its input separately, to even out a mix without pumping",
+        "comprimeert de lage, middelste en hoge tonen van de invoer apart, om een mix gelijkmatiger te maken zonder pompen",
+    ),
+    ("the sound to compress", "het geluid om te comprimeren"),
+    ("the sum of the compressed bands", "de som van de gecomprimeerde banden"),
+    ("crossovers:", "scheidingsfrequenties:"),
+    ("low", "laag"),
+    ("high", "hoog"),
+    ("threshold", "drempel"),
+    ("ratio", "verhouding"),
+    ("solo", "solo"),
+    ("gain reduction", "versterkingsreductie"),
+    ("attack:", "aanzet:"),
+    ("release:", "loslaten:"),
+    ("makeup:", "compensatie:"),
+    ("makeup", "compensatie"),
+    ("low threshold", "lage drempel"),
+    ("mid threshold", "middelste drempel"),
+    ("high threshold", "hoge drempel"),
+    ("low ratio", "lage verhouding"),
+    ("mid ratio", "middelste verhouding"),
+    ("high ratio", "hoge verhouding"),
+    (
+        "a stereo delay with echoes alternating between left and right",
+        "een stereovertraging met echo's die afwisselen tussen links en rechts",
+    ),
+    (
+        "the sound to echo, the first echo is on the left",
+        "het geluid om te herhalen, de eerste echo is links",
+    ),
+    (
+        "the input mixed with its echoes in stereo",
+        "de invoer gemengd met de echo's in stereo",
+    ),
+    ("left:", "links:"),
+    ("right:", "rechts:"),
+    ("left", "links"),
+    ("right", "rechts"),
+    ("cross:", "kruis:"),
+    ("cross", "kruis"),
+    (
+        "how much of each echo moves to the other side",
+        "hoeveel van elke echo naar de andere kant gaat",
+    ),
+    (
+        "adds crackle, hiss, hum and a slow pitch wobble to its input, like an old record",
+        "voegt geknetter, ruis, brom en een langzame toonhoogteschommeling toe aan de invoer, zoals een oude plaat",
+    ),
+    ("the sound to age", "het geluid om oud te laten klinken"),
+    ("the aged sound", "het oud klinkende geluid"),
+    ("crackle:", "geknetter:"),
+    ("hiss:", "ruis:"),
+    ("hum:", "brom:"),
+    ("wobble:", "schommeling:"),
+    ("crackle", "geknetter"),
+    ("hiss", "ruis"),
+    ("hum", "brom"),
+    ("wobble", "schommeling"),
+    ("hum frequency:", "bromfrequentie:"),
+    (
+        "places its input in the space an impulse response file was recorded in",
+        "plaatst de invoer in de ruimte waarin een impulsresponsbestand is opgenomen",
+    ),
+    ("the sound to place in the space", "het geluid om in de ruimte te plaatsen"),
+    ("the input mixed with its reverb", "de invoer gemengd met de galm"),
+    ("0 is only the input, 1 only the reverb", "0 is alleen de invoer, 1 alleen de galm"),
+    (
+        "a tone or rhythm that seems to rise or fall forever, like a barber pole",
+        "een toon of ritme dat eindeloos lijkt te stijgen of dalen, zoals een kapperspaal",
+    ),
+    ("the sum of all layers", "de som van alle lagen"),
+    ("rhythm", "ritme"),
+    ("rising", "stijgend"),
+    ("falling", "dalend"),
+    ("octaves moved per second", "verschoven octaven per seconde"),
+    ("makes up a melody in a scale and plays it on a clock", "verzint een melodie in een toonladder en speelt die af op een klok"),
+    ("moves to the next step when it goes high", "gaat naar de volgende stap wanneer deze hoog wordt"),
+    ("clock", "klok"),
+    ("major", "majeur"),
+    ("minor", "mineur"),
+    ("dorian", "dorisch"),
+    ("pentatonic", "pentatonisch"),
+    ("blues", "blues"),
+    ("chromatic", "chromatisch"),
+    ("root:", "grondtoon:"),
+    ("range:", "bereik:"),
+    ("density:", "dichtheid:"),
+    ("density", "dichtheid"),
+    ("make up a new pattern", "verzin een nieuw patroon"),
+    ("waveform thumbnails:", "golfvormminiaturen:"),
+    (
+        "show a small waveform of the first output of every module in its header",
+        "toon een kleine golfvorm van de eerste uitvoer van elke module in de kop",
+    ),
+    ("the first output", "de eerste uitvoer"),
+    (
+        "record a loop, starting at the next loop start while the transport plays",
+        "neem een lus op, vanaf het volgende begin van de lus terwijl het transport speelt",
+    ),
+    ("play the recorded loop", "speel de opgenomen lus af"),
+    ("clear the recorded loop", "wis de opgenomen lus"),
+    ("bend:", "buiging:"),
+    (
+        "outputs two values at once by dragging over a pad, and can loop a recorded movement",
+        "geeft twee waarden tegelijk door over een vlak te slepen, en kan een opgenomen beweging herhalen",
+    ),
+    ("horizontal position from 0 to 1", "horizontale positie van 0 tot 1"),
+    ("vertical position from 0 to 1", "verticale positie van 0 tot 1"),
+    ("timeline", "tijdlijn"),
+    ("follow", "volgen"),
+    (
+        "let the transport play through the arrangement",
+        "laat het transport het arrangement doorlopen",
+    ),
+    ("render…", "renderen…"),
+    (
+        "play the arrangement from the start into a wav file",
+        "speel het arrangement vanaf het begin af naar een wav-bestand",
+    ),
+    ("scene", "scène"),
+    ("scenes", "scènes"),
+    ("scenes:", "scènes:"),
+    ("click to paint, right click to remove", "klik om te schilderen, rechtsklik om te verwijderen"),
+    (
+        "store the current parameters as a scene",
+        "sla de huidige parameters op als scène",
+    ),
+    ("➕ clip lane", "➕ clipbaan"),
+    ("right click to remove the lane", "rechtsklik om de baan te verwijderen"),
+    (
+        "when connected, its level decides the compression instead of the input",
+        "wanneer verbonden bepaalt het niveau hiervan de compressie in plaats van de invoer",
+    ),
+    ("sidechain", "sidechain"),
+    ("the sidechain decides the compression", "de sidechain bepaalt de compressie"),
+    ("off", "uit"),
+    ("beat", "tel"),
+    ("bar", "maat"),
+    (
+        "what launched scenes and clips wait for while the transport plays",
+        "waar gestarte scènes en clips op wachten terwijl het transport speelt",
+    ),
+    (
+        "launch the scene, at the next quantize boundary while the transport plays",
+        "start de scène, bij de volgende kwantiseergrens terwijl het transport speelt",
+    ),
+    (
+        "samples of latency, parallel paths are delayed to match",
+        "samples vertraging, parallelle paden worden even veel vertraagd",
+    ),
+    (
+        "drag to resize the panel, double click to fit it",
+        "sleep om het paneel te verbreden, dubbelklik om het passend te maken",
+    ),
+    (
+        "arrange the modules in panels by signal flow",
+        "orden de modules in panelen volgens de signaalstroom",
+    ),
+    ("cable style:", "kabelstijl:"),
+    ("rope", "touw"),
+    ("straight", "recht"),
+    ("orthogonal", "haaks"),
+    ("all cables", "alle kabels"),
+    ("cables of the selected module", "kabels van de geselecteerde module"),
+    ("no cables", "geen kabels"),
+    ("show or hide cables", "kabels tonen of verbergen"),
+    (
+        "mixes its input with a copy delayed by a few sweeping milliseconds",
+        "mengt de invoer met een kopie die een paar verschuivende milliseconden vertraagd is",
+    ),
+    ("the sound to flange", "het geluid om te flangen"),
+    (
+        "moves the sweep instead of the internal wave, from -1 to 1",
+        "beweegt de sweep in plaats van de interne golf, van -1 tot 1",
+    ),
+    ("the input mixed with its delayed copy", "de invoer gemengd met de vertraagde kopie"),
+    ("manual:", "handmatig:"),
+    ("manual", "handmatig"),
+    ("depth", "diepte"),
+    (
+        "position of the sweep, from the shortest to the longest delay",
+        "positie van de sweep, van de kortste tot de langste vertraging",
+    ),
+    (
+        "negative values invert the fed back signal",
+        "negatieve waarden keren het teruggekoppelde signaal om",
+    ),
+    ("triggers", "triggers"),
+    ("stages", "fases"),
+    ("star to list it at the top", "markeer met een ster om het bovenaan te tonen"),
+    ("source", "bron"),
+    ("effect", "effect"),
+    ("modulation", "modulatie"),
+    ("sequencing", "sequencing"),
+    ("analysis", "analyse"),
+    ("utility", "hulpmiddel"),
+    ("stereo", "stereo"),
+    ("mono", "mono"),
+    ("reduced quality", "verlaagde kwaliteit"),
+    ("minimal quality", "minimale kwaliteit"),
+    ("full quality", "volledige kwaliteit"),
+    (
+        "processing can't keep up, quality is reduced instead of muting",
+        "verwerken kan het niet bijhouden, de kwaliteit wordt verlaagd in plaats van te dempen",
+    ),
+    ("cpu guard:", "cpu-bewaking:"),
+    (
+        "always process, even when it can't be heard",
+        "altijd verwerken, ook wanneer het niet te horen is",
+    ),
+    (
+        "not processed because it can't be heard, click to process anyway",
+        "niet verwerkt omdat het niet te horen is, klik om toch te verwerken",
+    ),
+    (
+        "reduce quality instead of muting when processing can't keep up",
+        "kwaliteit verlagen in plaats van dempen wanneer verwerken het niet bijhoudt",
+    ),
+    ("module", "module"),
+    ("order", "volgorde"),
+    ("row", "rij"),
+    ("name:", "naam:"),
+    ("save selection as block", "selectie opslaan als blok"),
+    (
+        "select a module and enter a name first",
+        "selecteer eerst een module en voer een naam in",
+    ),
+    ("the value", "de waarde"),
+    (
+        "scales the mid and side of a stereo signal, widening or narrowing it",
+        "schaalt het midden en de zijkant van een stereosignaal om het breder of smaller te maken",
+    ),
+    ("the stereo sound", "het stereogeluid"),
+    ("the sound with adjusted width", "het geluid met aangepaste breedte"),
+    ("pan", "pan"),
+    ("position:", "positie:"),
+    (
+        "moves its input from left to right and back",
+        "beweegt de invoer van links naar rechts en terug",
+    ),
+    ("the mono or stereo sound to pan", "het mono- of stereogeluid om te pannen"),
+    (
+        "position instead of the internal wave, from -1 (left) to 1 (right)",
+        "positie in plaats van de interne golf, van -1 (links) tot 1 (rechts)",
+    ),
+    ("the panned stereo sound", "het gepande stereogeluid"),
+    ("tags", "labels"),
+    ("add", "toevoegen"),
+    (
+        "find modules by name, tag or handle",
+        "zoek modules op naam, label of handle",
+    ),
+    ("no modules found", "geen modules gevonden"),
+    (
+        "knobs that each set any number of parameters of other modules, click 🎯 and move a parameter to map it",
+        "knoppen die elk een willekeurig aantal parameters van andere modules instellen, klik 🎯 en beweeg een parameter om hem te koppelen",
+    ),
+    (
+        "map to the next parameter moved by hand",
+        "koppel aan de volgende parameter die met de hand bewogen wordt",
+    ),
+    ("value with the knob all the way down", "waarde met de knop helemaal omlaag"),
+    ("value with the knob all the way up", "waarde met de knop helemaal omhoog"),
+    ("➕ knob", "➕ knop"),
+    ("➖ knob", "➖ knop"),
+    (
+        "removes the last knob and its mappings",
+        "verwijdert de laatste knop en zijn koppelingen",
+    ),
+    ("lock to ignore changes:", "vergrendel om wijzigingen te negeren:"),
+    (
+        "locked, right-click the module to unlock:",
+        "vergrendeld, klik met rechts op de module om te ontgrendelen:",
+    ),
+    ("A4:", "A4:"),
+    ("frequency all notes are tuned to", "frequentie waarop alle noten gestemd zijn"),
+    ("transpose:", "transponeren:"),
+    (
+        "semitones the notes of all modules are shifted by",
+        "halve tonen waarmee de noten van alle modules verschoven worden",
+    ),
+    ("master tune and transpose", "hoofdstemming en transponeren"),
+    (
+        "adds an octave below and above a sound playing one note at a time",
+        "voegt een octaaf onder en boven toe aan een geluid dat één noot tegelijk speelt",
+    ),
+    ("the sound, playing one note at a time", "het geluid, één noot tegelijk spelend"),
+    ("the sound with its octaves mixed in", "het geluid met de octaven erbij gemengd"),
+    ("dry:", "droog:"),
+    ("dry", "droog"),
+    ("up:", "op:"),
+    ("up", "op"),
+    ("level of the octave below", "niveau van het octaaf eronder"),
+    ("level of the octave above", "niveau van het octaaf erboven"),
+    ("fixed", "vast"),
+    (
+        "how the force notes are played with maps to their velocity",
+        "hoe de kracht waarmee noten gespeeld worden zich vertaalt naar hun aanslagsterkte",
+    ),
+    ("velocity of the softest note", "aanslagsterkte van de zachtste noot"),
+    ("velocity of the hardest note", "aanslagsterkte van de hardste noot"),
+    ("velocity of every note", "aanslagsterkte van elke noot"),
+    (
+        "velocity of the last played note, from how low the key was pressed",
+        "aanslagsterkte van de laatst gespeelde noot, naar hoe laag de toets ingedrukt werd",
+    ),
+    (
+        "plays an audio file as a cloud of short overlapping grains",
+        "speelt een audiobestand als een wolk van korte overlappende korrels",
+    ),
+    ("grains", "korrels"),
+    (
+        "where grains start in the file, from 0 to 1",
+        "waar korrels beginnen in het bestand, van 0 tot 1",
+    ),
+    ("length of a grain in ms", "lengte van een korrel in ms"),
+    ("grains started per second", "korrels gestart per seconde"),
+    (
+        "how far grains randomly start away from the position, from 0 to 1",
+        "hoe ver korrels willekeurig van de positie beginnen, van 0 tot 1",
+    ),
+    ("pitch of the grains in semitones", "toonhoogte van de korrels in halve tonen"),
+    ("the cloud of grains", "de wolk van korrels"),
+    ("position", "positie"),
+    ("size:", "grootte:"),
+    ("size", "grootte"),
+    ("spray:", "spreiding:"),
+    ("spray", "spreiding"),
+    ("MIDI", "MIDI"),
+    ("could not connect to MIDI input:", "kon niet verbinden met MIDI-ingang:"),
+    ("input:", "ingang:"),
+    ("none", "geen"),
+    ("program changes launch scenes:", "programmawissels starten scènes:"),
+    ("bank", "bank"),
+    ("program", "programma"),
+    ("➕ mapping", "➕ koppeling"),
+    ("last received:", "laatst ontvangen:"),
+    (
+        "new mappings start from the last received program change",
+        "nieuwe koppelingen beginnen bij de laatst ontvangen programmawissel",
+    ),
+    ("overdub", "overdub"),
+    (
+        "mixes the input into the loop while it plays",
+        "mengt de invoer in de loop terwijl die speelt",
+    ),
+    ("speed:", "snelheid:"),
+    ("virtual port", "virtuele poort"),
+    (
+        "opens a port other applications can send MIDI to, named",
+        "opent een poort waar andere programma's MIDI naar kunnen sturen, genaamd",
+    ),
+    ("could not create a virtual MIDI port", "kon geen virtuele MIDI-poort aanmaken"),
+    (
+        "draws its input large as a spectrum, a waveform or particles, for installations and streams",
+        "tekent de invoer groot als een spectrum, een golfvorm of deeltjes, voor installaties en streams",
+    ),
+    ("the sound to draw", "het geluid om te tekenen"),
+    ("waveform", "golfvorm"),
+    ("particles", "deeltjes"),
+    ("width", "breedte"),
+    ("height", "hoogte"),
+    ("show in a separate resizable window", "toon in een apart venster van aanpasbare grootte"),
+    ("spectrum", "spectrum"),
+    (
+        "plays a short audio file from the start each time its trigger goes high",
+        "speelt een kort audiobestand vanaf het begin telkens als de trigger hoog wordt",
+    ),
+    ("restarts the sound when going high", "herstart het geluid wanneer hoog"),
+    ("the sound", "het geluid"),
+    ("play", "afspelen"),
+    ("the patches are the same", "de patches zijn hetzelfde"),
+    ("settings changed", "instellingen gewijzigd"),
+    ("compare with file…", "vergelijk met bestand…"),
+    (
+        "shows what changes from this rack to a patch file",
+        "toont wat er verandert van dit rack naar een patchbestand",
+    ),
+    ("compare", "vergelijk"),
+    (
+        "shows what changes from this rack to the shared patch",
+        "toont wat er verandert van dit rack naar de gedeelde patch",
+    ),
+    ("patch changes", "patchwijzigingen"),
+    (
+        "a wave built from sines at multiples of the frequency, each with its own amplitude",
+        "een golf opgebouwd uit sinussen op veelvouden van de frequentie, elk met een eigen amplitude",
+    ),
+    ("frequency of the fundamental in Hz", "frequentie van de grondtoon in Hz"),
+    ("partials:", "boventonen:"),
+    ("partial", "boventoon"),
+    ("tilt:", "kanteling:"),
+    (
+        "change in amplitude per octave, below 0 for a darker sound",
+        "verandering in amplitude per octaaf, onder 0 voor een donkerder geluid",
+    ),
+    ("tilt", "kanteling"),
+    ("detuned saw waves stacked into one wide sound", "ontstemde zaagtandgolven gestapeld tot één breed geluid"),
+    ("frequency in Hz", "frequentie in Hz"),
+    ("voices:", "stemmen:"),
+    ("width:", "breedte:"),
+    ("cents between the lowest and the highest voice", "cent tussen de laagste en de hoogste stem"),
+    ("how far the voices are spread from left to right", "hoe ver de stemmen van links naar rechts verspreid zijn"),
+    ("copy link", "kopieer link"),
+    ("a link to this page opening the patch", "een link naar deze pagina die de patch opent"),
+    (
+        "these cables form a feedback loop, which isn't supported, and were left out:",
+        "deze kabels vormen een terugkoppeling, wat niet ondersteund wordt, en zijn weggelaten:",
+    ),
+    ("tune:", "stemming:"),
+    ("sweep:", "sweep:"),
+    ("sweep time:", "sweeptijd:"),
+    ("decay:", "verval:"),
+    ("body decay:", "rompverval:"),
+    ("snappy:", "snaren:"),
+    ("first MIDI note:", "eerste MIDI-noot:"),
+    (
+        "note playing the first pad, the next notes play the next pads",
+        "noot die de eerste pad speelt, de volgende noten spelen de volgende pads",
+    ),
+    ("low crossover", "lage scheidingsfrequentie"),
+    ("high crossover", "hoge scheidingsfrequentie"),
+    ("offset", "verschuiving"),
+    ("delay", "vertraging"),
+    ("length", "lengte"),
+    ("value", "waarde"),
+    ("bend", "buiging"),
+    ("level", "niveau"),
+    ("gain", "versterking"),
+    ("transpose", "transponeren"),
+    ("pad 1 gain", "pad 1 versterking"),
+    ("pad 2 gain", "pad 2 versterking"),
+    ("pad 3 gain", "pad 3 versterking"),
+    ("pad 4 gain", "pad 4 versterking"),
+    ("pad 5 gain", "pad 5 versterking"),
+    ("pad 6 gain", "pad 6 versterking"),
+    ("pad 7 gain", "pad 7 versterking"),
+    ("pad 8 gain", "pad 8 versterking"),
+    ("pad 9 gain", "pad 9 versterking"),
+    ("pad 10 gain", "pad 10 versterking"),
+    ("pad 11 gain", "pad 11 versterking"),
+    ("pad 12 gain", "pad 12 versterking"),
+    ("pad 13 gain", "pad 13 versterking"),
+    ("pad 14 gain", "pad 14 versterking"),
+    ("pad 15 gain", "pad 15 versterking"),
+    ("pad 16 gain", "pad 16 versterking"),
+    ("pad 1 pitch", "pad 1 toonhoogte"),
+    ("pad 2 pitch", "pad 2 toonhoogte"),
+    ("pad 3 pitch", "pad 3 toonhoogte"),
+    ("pad 4 pitch", "pad 4 toonhoogte"),
+    ("pad 5 pitch", "pad 5 toonhoogte"),
+    ("pad 6 pitch", "pad 6 toonhoogte"),
+    ("pad 7 pitch", "pad 7 toonhoogte"),
+    ("pad 8 pitch", "pad 8 toonhoogte"),
+    ("pad 9 pitch", "pad 9 toonhoogte"),
+    ("pad 10 pitch", "pad 10 toonhoogte"),
+    ("pad 11 pitch", "pad 11 toonhoogte"),
+    ("pad 12 pitch", "pad 12 toonhoogte"),
+    ("pad 13 pitch", "pad 13 toonhoogte"),
+    ("pad 14 pitch", "pad 14 toonhoogte"),
+    ("pad 15 pitch", "pad 15 toonhoogte"),
+    ("pad 16 pitch", "pad 16 toonhoogte"),
+    ("cutoff:", "afsnijfrequentie:"),
+    ("freq:", "freq:"),
+    ("value:", "waarde:"),
+];
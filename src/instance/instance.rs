@@ -1,21 +1,37 @@
-use std::{any::Any, marker::PhantomData, ops::Index};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    fmt::Display,
+    marker::PhantomData,
+    ops::Index,
+    panic::{self, AssertUnwindSafe},
+};
 
 use ahash::{HashMap, HashMapExt};
 use eframe::{
-    egui::{self, Sense, Ui},
-    epaint::Hsva,
+    egui::{self, RichText, Sense, Ui},
+    epaint::{pos2, Color32, Hsva, Pos2, Shape, Stroke, Vec2},
 };
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::port::{PortInstance, PortResponse};
 use crate::{
-    io::PortHandle,
-    module::{Module, ModuleDescriptionDyn},
+    damper::LinearDamper,
+    i18n::tr,
+    io::{Io, PortHandle},
+    module::{Module, ModuleDescriptionDyn, PortDescriptionDyn, PortType, Snapshot},
     rack::rack::ShowContext,
     util::random_color,
 };
 
+/// Amount of samples between the values recorded for the header thumbnail.
+pub const THUMBNAIL_DECIMATION: usize = 256;
+
+/// Amount of values shown in the header thumbnail.
+const THUMBNAIL_LENGTH: usize = 64;
+
 /// Holds an instance of a [`Module`].
 pub struct Instance {
     pub module: Box<dyn Module>,
@@ -24,42 +40,394 @@ pub struct Instance {
     pub inputs: IndexMap<PortHandle, PortInstance>,
     pub outputs: IndexMap<PortHandle, PortInstance>,
     handle_color: Hsva,
+    /// Parameters held at their value, ignoring edits, scenes, macros and randomizing.
+    locked: HashMap<&'static str, f32>,
+    /// Where the lock menu was opened by right-clicking the module.
+    lock_menu: Option<Pos2>,
+    mutation: f32,
+    /// The stored A and B parameter states, the active one is only updated when switching.
+    states: [Option<Snapshot>; 2],
+    active_state: usize,
+    /// The parameters as they were when last checked for changes.
+    parameters: Snapshot,
+    /// Whether the instance is shown in its own window instead of its panel.
+    pub detached: bool,
+    /// Whether the instance is processed even when nothing it feeds into can be heard.
+    pub always_process: bool,
+    /// Whether the instance was processed, as of the last processed samples.
+    pub processed: bool,
+    /// Message of the panic that disabled the instance, if its module panicked.
+    pub crashed: Option<String>,
+    /// Recent values of the first output, decimated, for the header thumbnail.
+    thumbnail: VecDeque<f32>,
+    /// Parameters that are ramped towards their latest value.
+    smoothers: Vec<Smoother>,
+    /// Labels to find the instance back with, like "bass voice" or "fx".
+    pub tags: Vec<String>,
+    /// Tag being typed in the tag menu.
+    new_tag: String,
+}
+
+/// Ramps a parameter towards the value it was last set to.
+struct Smoother {
+    name: &'static str,
+    damper: LinearDamper<f32>,
+    target: f32,
 }
 
 impl Instance {
     pub fn from_description(description: &ModuleDescriptionDyn) -> Self {
         let handle = InstanceHandle::new();
+        let mut module = (description.instantiate)();
+        let parameters = Snapshot::take(&mut *module);
+
+        let mut instance = Self {
+            module,
+            description: description.clone(),
+            handle,
+            inputs: IndexMap::new(),
+            outputs: IndexMap::new(),
+            handle_color: random_color(),
+            locked: HashMap::new(),
+            lock_menu: None,
+            mutation: 0.2,
+            states: [None, None],
+            active_state: 0,
+            parameters,
+            detached: false,
+            always_process: false,
+            processed: true,
+            crashed: None,
+            thumbnail: VecDeque::with_capacity(THUMBNAIL_LENGTH),
+            smoothers: Vec::new(),
+            tags: Vec::new(),
+            new_tag: String::new(),
+        };
+        instance.sync_ports();
+        instance
+    }
+
+    /// The input and output ports of the description followed by the dynamic ports of the module.
+    fn current_port_descriptions(&self) -> (Vec<PortDescriptionDyn>, Vec<PortDescriptionDyn>) {
+        let dynamic = self.module.dynamic_ports();
+        let of_type = |port_type: PortType| {
+            dynamic
+                .iter()
+                .filter(|description| description.port_type == port_type)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let mut inputs = self.description.inputs.clone();
+        inputs.extend(of_type(PortType::Input));
+        let mut outputs = self.description.outputs.clone();
+        outputs.extend(of_type(PortType::Output));
+        (inputs, outputs)
+    }
+
+    /// Adds and removes ports to match the dynamic ports of the module, returning the removed ones.
+    fn sync_ports(&mut self) -> Vec<PortHandle> {
+        let (inputs, outputs) = self.current_port_descriptions();
+        let handle = self.handle;
+        let mut removed = Vec::new();
+
+        for (ports, descriptions) in [(&mut self.inputs, inputs), (&mut self.outputs, outputs)] {
+            let handles = descriptions
+                .iter()
+                .map(|description| PortHandle::new(description.id, handle))
+                .collect::<Vec<_>>();
+
+            if ports.keys().eq(handles.iter()) {
+                continue;
+            }
+
+            let mut old = std::mem::take(ports);
+            for (description, port) in descriptions.iter().zip(handles) {
+                let instance = old
+                    .swap_remove(&port)
+                    .unwrap_or_else(|| PortInstance::from_description(description, handle));
+                ports.insert(port, instance);
+            }
+            removed.extend(old.into_keys());
+        }
+
+        removed
+    }
+
+    /// Updates the ports after the dynamic ports of the module changed, disconnecting removed ones.
+    pub fn update_ports(&mut self, io: &mut Io) {
+        for removed in self.sync_ports() {
+            io.clear_port(removed)
+        }
+    }
+
+    /// Records the current value of the first output for the header thumbnail.
+    pub fn record_thumbnail(&mut self, io: &Io) {
+        let Some(value) = self
+            .outputs
+            .keys()
+            .next()
+            .and_then(|port| io.get_output_dyn(*port))
+        else {
+            return;
+        };
+
+        if self.thumbnail.len() >= THUMBNAIL_LENGTH {
+            self.thumbnail.pop_front();
+        }
+        self.thumbnail.push_back(value.as_value());
+    }
 
-        let inputs = description
-            .inputs
+    /// Draws the recorded values of the first output, scaled to fit.
+    fn show_thumbnail(&self, ui: &mut Ui) {
+        let (rect, response) = ui.allocate_exact_size(Vec2::new(48.0, 16.0), Sense::hover());
+        let peak = self
+            .thumbnail
             .iter()
-            .map(|description| {
-                (
-                    PortHandle::new(description.id, handle),
-                    PortInstance::from_description(description, handle),
+            .fold(0.0f32, |peak, value| peak.max(value.abs()));
+
+        let color = if peak > 1e-4 {
+            ui.visuals().selection.bg_fill
+        } else {
+            ui.visuals().weak_text_color()
+        };
+
+        let scale = if peak > 1e-4 { 1.0 / peak } else { 0.0 };
+        let step = rect.width() / (THUMBNAIL_LENGTH - 1) as f32;
+        let points = self
+            .thumbnail
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                pos2(
+                    rect.left() + index as f32 * step,
+                    rect.center().y - value * scale * rect.height() / 2.0,
                 )
             })
-            .collect::<IndexMap<_, _>>();
+            .collect::<Vec<_>>();
 
-        let outputs = description
-            .outputs
+        ui.painter()
+            .rect_stroke(rect, 2.0, ui.visuals().widgets.noninteractive.bg_stroke);
+        ui.painter()
+            .add(Shape::line(points, Stroke::new(1.0, color)));
+        response.on_hover_text_at_pointer(tr("the first output"));
+    }
+
+    /// Runs `f` on the instance, disabling it instead of taking down the app if it panics.
+    pub fn guarded(&mut self, f: impl FnOnce(&mut Self)) {
+        if self.crashed.is_some() {
+            return;
+        }
+
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| f(self))) {
+            self.crashed = Some(panic_message(&*payload));
+        }
+    }
+
+    /// Stores the current parameters in the active state and applies the other one.
+    fn switch_state(&mut self, state: usize) {
+        if state == self.active_state {
+            return;
+        }
+
+        let current = Snapshot::take(&mut *self.module);
+        if let Some(snapshot) = &self.states[state] {
+            snapshot.apply(&mut *self.module);
+            self.hold_locked();
+        }
+        self.states[self.active_state] = Some(current);
+        self.active_state = state;
+    }
+
+    /// Copies the current parameters to the inactive state.
+    fn copy_state(&mut self) {
+        self.states[1 - self.active_state] = Some(Snapshot::take(&mut *self.module));
+    }
+
+    fn show_states(&mut self, ui: &mut Ui) {
+        for (state, label) in ["A", "B"].into_iter().enumerate() {
+            if ui
+                .selectable_label(self.active_state == state, label)
+                .on_hover_text_at_pointer(tr("compare parameter states"))
+                .clicked()
+            {
+                self.switch_state(state);
+            }
+        }
+
+        let label = if self.active_state == 0 {
+            "A→B"
+        } else {
+            "B→A"
+        };
+        if ui.small_button(label).clicked() {
+            self.copy_state();
+        }
+    }
+
+    /// Sets the unconnected normalled inputs to the values they follow.
+    pub fn apply_normalled(&self, io: &mut Io) {
+        for port in self.inputs.values() {
+            if let Some(normalled) = &port.description.normalled {
+                if io.input_connection(port.handle).is_none() {
+                    io.set_input_dyn(port.handle, normalled(&*self.module));
+                }
+            }
+        }
+    }
+
+    /// Picks up parameters that were set since the last call, to ramp them towards the new value
+    /// in [`Self::smooth_parameters`] instead of jumping.
+    pub fn prepare_smoothing(&mut self, sample_rate: u32) {
+        for parameter in self.module.parameters() {
+            if parameter.smoothing <= 0.0 {
+                continue;
+            }
+
+            let span = parameter.range.end() - parameter.range.start();
+            let ramp =
+                |start| LinearDamper::new_ramp(span, parameter.smoothing, sample_rate, start);
+            match self
+                .smoothers
+                .iter_mut()
+                .find(|smoother| smoother.name == parameter.name)
+            {
+                Some(smoother) => {
+                    let current = smoother.damper.current();
+                    if *parameter.value != current {
+                        smoother.target = *parameter.value;
+                        smoother.damper = ramp(current);
+                        *parameter.value = current;
+                    }
+                }
+                None => self.smoothers.push(Smoother {
+                    name: parameter.name,
+                    damper: ramp(*parameter.value),
+                    target: *parameter.value,
+                }),
+            }
+        }
+    }
+
+    /// Moves the parameters that are ramping one sample closer to their new value.
+    pub fn smooth_parameters(&mut self) {
+        if self
+            .smoothers
             .iter()
-            .map(|description| {
-                (
-                    PortHandle::new(description.id, handle),
-                    PortInstance::from_description(description, handle),
-                )
+            .all(|smoother| smoother.damper.is_settled(smoother.target))
+        {
+            return;
+        }
+
+        for parameter in self.module.parameters() {
+            if let Some(smoother) = self
+                .smoothers
+                .iter_mut()
+                .find(|smoother| smoother.name == parameter.name)
+            {
+                *parameter.value = smoother.damper.frame(smoother.target);
+            }
+        }
+    }
+
+    /// Returns the parameters that changed since the last call.
+    pub fn take_parameter_changes(&mut self) -> Vec<(&'static str, f32)> {
+        let current = Snapshot::take(&mut *self.module);
+        let changes = current.changes(&self.parameters);
+        self.parameters = current;
+        changes
+    }
+
+    /// Mutates all unlocked parameters of the module by `amount` from 0 to 1.
+    pub fn randomize(&mut self, amount: f32) {
+        for mut parameter in self.module.parameters() {
+            if !self.locked.contains_key(parameter.name) {
+                parameter.mutate(amount);
+            }
+        }
+    }
+
+    /// Sets the locked parameters back to the value they were locked at.
+    pub fn hold_locked(&mut self) {
+        if self.locked.is_empty() {
+            return;
+        }
+
+        for parameter in self.module.parameters() {
+            if let Some(value) = self.locked.get(parameter.name) {
+                *parameter.value = *value;
+            }
+        }
+    }
+
+    /// Shows a checkbox to lock each parameter.
+    fn show_locks(&mut self, ui: &mut Ui) {
+        for parameter in self.module.parameters() {
+            let mut locked = self.locked.contains_key(parameter.name);
+            if ui
+                .checkbox(&mut locked, format!("🔒 {}", tr(parameter.name)))
+                .changed()
+            {
+                if locked {
+                    self.locked.insert(parameter.name, *parameter.value);
+                } else {
+                    self.locked.remove(parameter.name);
+                }
+            }
+        }
+    }
+
+    /// Shows the lock menu where the module was right-clicked.
+    fn show_lock_menu(&mut self, ui: &mut Ui, body: egui::Rect) {
+        let right_clicked = ui.input(|input| {
+            input.pointer.secondary_clicked()
+                && input
+                    .pointer
+                    .interact_pos()
+                    .is_some_and(|position| body.contains(position))
+        });
+        if right_clicked && !self.module.parameters().is_empty() {
+            self.lock_menu = ui.input(|input| input.pointer.interact_pos());
+        }
+
+        let Some(position) = self.lock_menu else {
+            return;
+        };
+        let response = egui::Area::new(egui::Id::new((self.handle, "locks")))
+            .order(egui::Order::Foreground)
+            .fixed_pos(position)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(tr("lock to ignore changes:"));
+                    self.show_locks(ui);
+                })
             })
-            .collect::<IndexMap<_, _>>();
+            .response;
+        if !right_clicked && response.clicked_elsewhere() {
+            self.lock_menu = None;
+        }
+    }
 
-        Self {
-            module: (description.instantiate)(),
-            description: description.clone(),
-            handle,
-            inputs,
-            outputs,
-            handle_color: random_color(),
+    fn show_randomize(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("amount:"));
+            ui.add(egui::Slider::new(&mut self.mutation, 0.0..=1.0));
+        });
+
+        if ui.button(tr("randomize")).clicked() {
+            self.randomize(self.mutation);
         }
+
+        ui.separator();
+        self.show_locks(ui);
+    }
+
+    /// Descriptions of the input ports followed by the output ports.
+    pub fn port_descriptions(&self) -> impl Iterator<Item = &PortDescriptionDyn> {
+        self.inputs
+            .values()
+            .chain(self.outputs.values())
+            .map(|port| &port.description)
     }
 
     #[allow(unused)]
@@ -76,18 +444,92 @@ impl Instance {
             .or_else(|| self.outputs.get_mut(&handle))
     }
 
+    fn show_tags(&mut self, ui: &mut Ui) {
+        let mut removed = None;
+        for (i, tag) in self.tags.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("#{tag}"));
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(removed) = removed {
+            self.tags.remove(removed);
+        }
+
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(&mut self.new_tag);
+            let entered =
+                response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+            let tag = self.new_tag.trim().to_string();
+            if (ui.button(tr("add")).clicked() || entered)
+                && !tag.is_empty()
+                && !self.tags.contains(&tag)
+            {
+                self.tags.push(tag);
+                self.new_tag.clear();
+            }
+        });
+    }
+
     pub fn get_module<M: Module>(&self) -> Option<&M> {
-        (&*self.module as &dyn Any).downcast_ref()
+        (*self.module).as_any().downcast_ref()
     }
 
     pub fn get_module_mut<M: Module>(&mut self) -> Option<&mut M> {
-        (&mut *self.module as &mut dyn Any).downcast_mut()
+        (*self.module).as_any_mut().downcast_mut()
     }
 
     pub fn show(&mut self, ctx: &mut ShowContext, ui: &mut Ui) -> InstanceResponse {
         let mut response = InstanceResponse::new(self);
         ui.horizontal(|ui| {
-            ui.heading(&self.description.name);
+            let mut hover = match &self.description.tooltip {
+                Some(tooltip) => format!(
+                    "{}\n\n{}",
+                    tr(tooltip),
+                    tr("click to highlight the signal flow")
+                ),
+                None => tr("click to highlight the signal flow").to_string(),
+            };
+            if self.description.latency > 0 {
+                hover += &format!(
+                    "\n{} {}",
+                    self.description.latency,
+                    tr("samples of latency, parallel paths are delayed to match")
+                );
+            }
+            if !self.description.icon.is_empty() {
+                ui.label(
+                    RichText::new(&self.description.icon)
+                        .heading()
+                        .color(self.description.category.color()),
+                )
+                .on_hover_text_at_pointer(tr(self.description.category.as_str()));
+            }
+            if ui
+                .add(
+                    egui::Label::new(egui::RichText::new(tr(&self.description.name)).heading())
+                        .sense(Sense::click()),
+                )
+                .on_hover_text_at_pointer(hover)
+                .clicked()
+            {
+                response.select = true;
+            }
+
+            for tag in self.tags.iter() {
+                ui.label(RichText::new(format!("#{tag}")).small().weak());
+            }
+
+            if ctx.settings.thumbnails && !self.outputs.is_empty() {
+                self.show_thumbnail(ui);
+            }
+
+            if let Some(message) = &self.crashed {
+                ui.label(RichText::new("💥").color(Color32::RED))
+                    .on_hover_text_at_pointer(message);
+            }
 
             let handle_response = ui.add(
                 egui::Label::new(
@@ -100,29 +542,124 @@ impl Instance {
                 self.handle_color = random_color()
             }
 
+            if !self.locked.is_empty() {
+                let mut names = self.locked.keys().map(|name| tr(name)).collect::<Vec<_>>();
+                names.sort();
+                ui.label(RichText::new("🔒").small())
+                    .on_hover_text_at_pointer(format!(
+                        "{} {}",
+                        tr("locked, right-click the module to unlock:"),
+                        names.join(", ")
+                    ));
+            }
+
+            if !self.module.parameters().is_empty() {
+                ui.menu_button("🎲", |ui| self.show_randomize(ui));
+                self.show_states(ui);
+            }
+
+            ui.menu_button("?", |ui| {
+                ui.set_max_width(250.0);
+                ui.label(tr(self
+                    .description
+                    .tooltip
+                    .as_deref()
+                    .unwrap_or("no description available")));
+                if self.description.example.is_some() && ui.button(tr("insert example")).clicked() {
+                    response.example = true;
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text_at_pointer(tr("help"));
+
+            ui.menu_button("🏷", |ui| self.show_tags(ui))
+                .response
+                .on_hover_text_at_pointer(tr("tags"));
+
+            let (icon, hover) = if self.processed {
+                ("⚡", "always process, even when it can't be heard")
+            } else {
+                (
+                    "💤",
+                    "not processed because it can't be heard, click to process anyway",
+                )
+            };
+            ui.toggle_value(&mut self.always_process, icon)
+                .on_hover_text_at_pointer(tr(hover));
+
+            ui.toggle_value(&mut self.detached, "⧉")
+                .on_hover_text_at_pointer(tr("show in a separate window"));
+
             ui.menu_button("🗑", |ui| {
-                if ui.button("Are you sure?").clicked() {
+                if ui.button(tr("Are you sure?")).clicked() {
                     response.remove = true;
                     ui.close_menu();
                 }
             });
         });
 
-        self.module.show(ctx, ui);
-
-        ui.horizontal(|ui| {
-            for port in self.inputs.values_mut() {
-                response.ports.insert(port.handle, port.show(ctx, ui));
-            }
+        let body = ui
+            .scope(|ui| self.guarded(|instance| instance.module.show(ctx, ui)))
+            .response
+            .rect;
+        self.hold_locked();
+        self.show_lock_menu(ui, body);
+        if let Some(message) = &self.crashed {
+            ui.label(
+                RichText::new(tr("⚠ this module crashed and was disabled:")).color(Color32::RED),
+            );
+            ui.label(RichText::new(message).monospace());
+        }
 
-            if !self.inputs.is_empty() && !self.outputs.is_empty() {
-                ui.separator();
+        let mut groups = Vec::<Option<&'static str>>::new();
+        for port in self.inputs.values().chain(self.outputs.values()) {
+            if !groups.contains(&port.description.group) {
+                groups.push(port.description.group);
             }
-
-            for port in self.outputs.values_mut() {
-                response.ports.insert(port.handle, port.show(ctx, ui));
+        }
+        // the main row comes first
+        groups.sort_by_key(|group| group.is_some());
+
+        for group in groups {
+            let mut show_row = |ui: &mut Ui, response: &mut InstanceResponse| {
+                ui.horizontal(|ui| {
+                    let in_group = |port: &&mut PortInstance| port.description.group == group;
+                    let mut inputs = self.inputs.values_mut().filter(in_group).peekable();
+                    let has_inputs = inputs.peek().is_some();
+                    for port in inputs {
+                        response.ports.insert(port.handle, port.show(ctx, ui));
+                    }
+
+                    let mut outputs = self.outputs.values_mut().filter(in_group).peekable();
+                    if has_inputs && outputs.peek().is_some() {
+                        ui.separator();
+                    }
+                    for port in outputs {
+                        response.ports.insert(port.handle, port.show(ctx, ui));
+                    }
+                });
+            };
+
+            let Some(label) = group else {
+                show_row(ui, &mut response);
+                continue;
+            };
+
+            let collapsing = egui::CollapsingHeader::new(tr(label))
+                .id_source((self.handle, label))
+                .default_open(true)
+                .show(ui, |ui| show_row(ui, &mut response));
+            if collapsing.body_returned.is_none() {
+                // cables of hidden ports end at the header
+                let position = collapsing.header_response.rect.right_center();
+                for port in self.inputs.values().chain(self.outputs.values()) {
+                    if port.description.group == Some(label) {
+                        response.ports.insert(port.handle, port.collapsed(position));
+                    }
+                }
             }
-        });
+        }
 
         ui.separator();
 
@@ -130,10 +667,24 @@ impl Instance {
     }
 }
 
+/// Gets the message a panic was started with.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        tr("unknown error").to_string()
+    }
+}
+
 /// Contains useful data after drawing an instance's ui.
 pub struct InstanceResponse {
     pub handle: InstanceHandle,
     pub remove: bool,
+    pub select: bool,
+    /// Whether the example patch of the module should be inserted.
+    pub example: bool,
     pub ports: HashMap<PortHandle, PortResponse>,
 }
 
@@ -142,6 +693,8 @@ impl InstanceResponse {
         Self {
             handle: instance.handle,
             remove: false,
+            select: false,
+            example: false,
             ports: HashMap::new(),
         }
     }
@@ -152,7 +705,7 @@ impl InstanceResponse {
 }
 
 /// Identifies an [`Instance`]
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct InstanceHandle {
     id: Uuid,
 }
@@ -167,15 +720,17 @@ impl InstanceHandle {
         Self { id: typed.id }
     }
 
-    pub fn to_string(&self) -> String {
-        self.id.as_simple().to_string().index(..8).to_string()
-    }
-
     pub fn as_typed<T>(&self) -> TypedInstanceHandle<T> {
         TypedInstanceHandle::from_untyped(*self)
     }
 }
 
+impl Display for InstanceHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id.as_simple().to_string().index(..8))
+    }
+}
+
 impl<T> From<TypedInstanceHandle<T>> for InstanceHandle {
     fn from(value: TypedInstanceHandle<T>) -> Self {
         value.as_untyped()
@@ -190,10 +745,7 @@ pub struct TypedInstanceHandle<T> {
 
 impl<T> Clone for TypedInstanceHandle<T> {
     fn clone(&self) -> Self {
-        Self {
-            id: self.id.clone(),
-            phantom: self.phantom.clone(),
-        }
+        *self
     }
 }
 
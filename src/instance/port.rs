@@ -1,11 +1,12 @@
 use eframe::{
-    egui::{self, Button, Layout, Margin, RichText, Sense, Ui},
+    egui::{self, Button, Layout, Margin, RichText, Sense, Ui, WidgetInfo, WidgetType},
     emath::Align,
-    epaint::{Color32, Hsva, Pos2, Rect, Shadow, Stroke, Vec2},
+    epaint::{Color32, Hsva, Pos2, Rect, Shadow, Shape, Stroke, Vec2},
 };
 
 use super::instance::InstanceHandle;
 use crate::{
+    i18n::tr,
     io::PortHandle,
     module::{PortDescriptionDyn, PortType},
     rack::rack::ShowContext,
@@ -32,6 +33,14 @@ impl PortInstance {
         }
     }
 
+    /// The response of a port hidden in a collapsed group, with cables ending at `position`.
+    pub fn collapsed(&self, position: Pos2) -> PortResponse {
+        PortResponse {
+            position,
+            ..PortResponse::new(self)
+        }
+    }
+
     pub fn show(&mut self, ctx: &mut ShowContext, ui: &mut Ui) -> PortResponse {
         let mut response = PortResponse::new(self);
 
@@ -40,11 +49,24 @@ impl PortInstance {
             .outer_margin(Margin::same(2.0))
             .show(ui, |ui| {
                 ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-                    ui.label(self.description.name);
+                    let name = ui.label(tr(self.description.name));
+                    if let Some(tooltip) = &self.description.tooltip {
+                        name.on_hover_text(tr(tooltip));
+                    }
                     ui.label(RichText::new(self.description.type_name).color(Color32::LIGHT_BLUE));
 
                     if let PortType::Input = self.description.port_type {
-                        if !ctx.has_connection(self.handle) {
+                        if self.description.normalled.is_some() {
+                            if !ctx.has_connection(self.handle) {
+                                let value = ctx
+                                    .get_input_boxed(self.handle)
+                                    .map(|value| value.to_string())
+                                    .unwrap_or_default();
+                                ui.label(RichText::new(value).weak()).on_hover_text(tr(
+                                    "normalled: follows the module until connected",
+                                ));
+                            }
+                        } else if !ctx.has_connection(self.handle) {
                             self.description
                                 .closure_edit
                                 .as_ref()
@@ -58,10 +80,15 @@ impl PortInstance {
 
                     self.show_port_visual(&mut response, ctx, ui);
 
-                    if ctx.has_connection(self.handle)
-                        && ui.add(Button::new("❌").small()).clicked()
-                    {
-                        ctx.clear_port(self.handle)
+                    if ctx.has_connection(self.handle) {
+                        let mut button = Button::new("❌");
+                        if !ctx.touch {
+                            button = button.small();
+                        }
+
+                        if ui.add(button).clicked() {
+                            ctx.clear_port(self.handle)
+                        }
                     }
                 });
             });
@@ -72,7 +99,7 @@ impl PortInstance {
             }
         }
 
-        if !ui.ctx().dragged_id().is_some() && self.dragging {
+        if ui.ctx().dragged_id().is_none() && self.dragging {
             self.dragging = false;
             response.released = true;
         }
@@ -89,14 +116,49 @@ impl PortInstance {
         ui: &mut Ui,
     ) {
         let sense = if let PortType::Output = self.description.port_type {
-            Sense::drag()
+            Sense::click_and_drag()
         } else {
-            Sense::hover()
+            Sense::click()
         };
 
         let desired_size = ui.spacing().interact_size.y * Vec2::splat(1.0);
         let (rect, port_response) = ui.allocate_exact_size(desired_size, sense);
 
+        port_response.widget_info(|| {
+            let kind = match self.description.port_type {
+                PortType::Input => tr("input port"),
+                PortType::Output => tr("output port"),
+            };
+            WidgetInfo::labeled(
+                WidgetType::Button,
+                format!(
+                    "{} {} {}",
+                    tr(self.description.name),
+                    kind,
+                    self.description.type_name
+                ),
+            )
+        });
+
+        // clicking or pressing enter on two ports connects them, so patching works without dragging
+        if port_response.clicked() {
+            response.clicked = true;
+        }
+
+        // also opens on a long press on touch screens
+        port_response.context_menu(|ui| {
+            if ui
+                .add_enabled(
+                    ctx.has_connection(self.handle),
+                    Button::new(tr("disconnect")),
+                )
+                .clicked()
+            {
+                ctx.clear_port(self.handle);
+                ui.close_menu();
+            }
+        });
+
         if port_response.drag_started() {
             self.dragging = true;
         }
@@ -109,7 +171,7 @@ impl PortInstance {
 
         if let PortType::Input = self.description.port_type {
             if !ctx.has_connection(self.handle) {
-                port_response.on_hover_text_at_pointer("Input");
+                port_response.on_hover_text_at_pointer(tr("Input"));
             } else {
                 port_response.on_hover_text_at_pointer(self
                     .description
@@ -119,6 +181,8 @@ impl PortInstance {
                     self.handle, ctx
                 ));
             }
+        } else if let Some(value) = ctx.get_output_boxed(self.handle) {
+            port_response.on_hover_text_at_pointer(value.to_string());
         }
     }
 
@@ -129,14 +193,33 @@ impl PortInstance {
         ctx: &mut ShowContext,
         ui: &mut Ui,
     ) {
-        let visuals = ui.style().interact(&response);
+        let visuals = ui.style().interact(response);
         let rect = rect.expand(visuals.expansion);
         let radius = 0.5 * rect.height();
         let inner_radius = 0.5 * radius;
         let stroke = Stroke::new(visuals.fg_stroke.width + 0.5, visuals.fg_stroke.color);
 
-        ui.painter()
-            .circle(rect.center(), radius, visuals.bg_fill, stroke);
+        if ctx.settings.use_shapes() {
+            paint_type_shape(
+                ui,
+                rect.center(),
+                radius,
+                self.description.type_name,
+                visuals.bg_fill,
+                stroke,
+            );
+        } else {
+            ui.painter()
+                .circle(rect.center(), radius, visuals.bg_fill, stroke);
+        }
+
+        if ctx.patching == Some(self.handle) {
+            ui.painter().circle_stroke(
+                rect.center(),
+                radius + 2.0,
+                Stroke::new(2.0f32, Color32::GOLD),
+            );
+        }
 
         match self.description.port_type {
             PortType::Input => {
@@ -181,6 +264,42 @@ impl PortInstance {
     }
 }
 
+/// Paints the outline of a port with a shape depending on its type: a circle for `f32`, a square for `bool` and a diamond for other types.
+fn paint_type_shape(
+    ui: &Ui,
+    center: Pos2,
+    radius: f32,
+    type_name: &str,
+    fill: Color32,
+    stroke: Stroke,
+) {
+    match type_name {
+        "f32" => {
+            ui.painter().circle(center, radius, fill, stroke);
+        }
+        "bool" => {
+            ui.painter().rect(
+                Rect::from_center_size(center, Vec2::splat(radius * 1.8)),
+                0.0,
+                fill,
+                stroke,
+            );
+        }
+        _ => {
+            ui.painter().add(Shape::convex_polygon(
+                vec![
+                    center + Vec2::new(0.0, -radius),
+                    center + Vec2::new(radius, 0.0),
+                    center + Vec2::new(0.0, radius),
+                    center + Vec2::new(-radius, 0.0),
+                ],
+                fill,
+                stroke,
+            ));
+        }
+    }
+}
+
 /// Contains useful data after drawing an instance's ui.
 pub struct PortResponse {
     pub description: PortDescriptionDyn,
@@ -188,6 +307,7 @@ pub struct PortResponse {
     pub dragging: bool,
     pub released: bool,
     pub hovered: bool,
+    pub clicked: bool,
     pub handle: PortHandle,
     pub color: Hsva,
 }
@@ -200,6 +320,7 @@ impl PortResponse {
             dragging: false,
             released: false,
             hovered: false,
+            clicked: false,
             handle: port.handle,
             color: port.color,
         }
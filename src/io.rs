@@ -1,4 +1,7 @@
-use std::any::{Any, TypeId};
+use std::{
+    any::{Any, TypeId},
+    collections::VecDeque,
+};
 
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use topological_sort::TopologicalSort;
@@ -6,6 +9,7 @@ use topological_sort::TopologicalSort;
 use crate::{
     instance::instance::InstanceHandle,
     module::{ConversionClosure, Input, Port, PortId, PortValueBoxed},
+    rack::event::RackEvent,
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -27,6 +31,8 @@ impl ConnectResultWarn {
 pub enum ConnectResultErr {
     SameInstance,
     InCompatible,
+    /// The output depends on the input already, which can't be processed.
+    Cycle,
 }
 
 impl ConnectResultErr {
@@ -34,6 +40,7 @@ impl ConnectResultErr {
         match self {
             ConnectResultErr::SameInstance => "same instance",
             ConnectResultErr::InCompatible => "incompatible",
+            ConnectResultErr::Cycle => "cycle",
         }
     }
 }
@@ -55,13 +62,40 @@ impl ConnectResult {
     }
 }
 
+/// The last value sent to a named bus and the instance that sent it.
+struct Bus {
+    sender: InstanceHandle,
+    value: Box<dyn PortValueBoxed>,
+}
+
+/// Values held back on a connection, to line them up with a slower parallel path.
+struct DelayLine {
+    length: usize,
+    values: VecDeque<Box<dyn PortValueBoxed>>,
+}
+
 /// Facilitates the data interaction between modules.
 #[derive(Default)]
 pub struct Io {
     inputs: HashMap<PortHandle, Box<dyn PortValueBoxed>>,
+    /// The last value written to each output port.
+    outputs: HashMap<PortHandle, Box<dyn PortValueBoxed>>,
     connections: HashMap<PortHandle, HashSet<PortHandle>>,
+    /// The output port each connected input port is connected to.
+    sources: HashMap<PortHandle, PortHandle>,
+    /// Scale factors of connections as `(from, to)`, connections without one pass values unchanged.
+    gains: HashMap<(PortHandle, PortHandle), f32>,
+    /// Delays of connections as `(from, to)` compensating the latency of parallel paths.
+    delays: HashMap<(PortHandle, PortHandle), DelayLine>,
+    /// Latency in samples of the instances that have any.
+    latencies: HashMap<InstanceHandle, usize>,
+    probe: Option<Probe>,
     conversions: HashMap<ConversionId, Box<dyn ConversionClosure>>,
     processing_order: Vec<Vec<InstanceHandle>>,
+    /// Named buses that carry values between modules without cables.
+    buses: HashMap<String, Bus>,
+    /// Connection changes not yet passed on by the rack.
+    events: Vec<RackEvent>,
 }
 
 impl Io {
@@ -75,12 +109,17 @@ impl Io {
         self.inputs.insert(port, value);
     }
 
+    /// Gets the last value written to an output port.
+    pub fn get_output_dyn(&self, port: PortHandle) -> Option<Box<dyn PortValueBoxed>> {
+        self.outputs.get(&port).cloned()
+    }
+
     /// Tries to get the input data in the correct type either directly or by converting it.
-    fn try_get_input<I: Input>(&self, instance: InstanceHandle) -> Option<I::Type> {
-        let boxed = self.get_input_dyn(PortHandle::new(I::id(), instance))?;
+    fn try_get_input<I: Input>(&self, instance: InstanceHandle, index: usize) -> Option<I::Type> {
+        let boxed = self.get_input_dyn(PortHandle::new(I::id().indexed(index), instance))?;
 
         if let Some(result) = {
-            let any = &*boxed as &dyn Any;
+            let any = (*boxed).as_any();
             any.downcast_ref::<I::Type>()
         } {
             Some(result.clone())
@@ -92,7 +131,7 @@ impl Io {
     /// Tries to convert the data if an conversion exists.
     fn try_convert<I: Input>(&self, boxed: Box<dyn PortValueBoxed>) -> Option<I::Type> {
         let conversion = self.get_conversion::<I>((*boxed).type_id())?;
-        let converted: Box<dyn Any> = (conversion)(boxed);
+        let converted: Box<dyn Any> = (conversion)(boxed.into_any());
         let any = &*converted;
         Some(
             any.downcast_ref::<I::Type>()
@@ -101,7 +140,7 @@ impl Io {
         )
     }
 
-    fn get_conversion<I: Input>(&self, from_type: TypeId) -> Option<&Box<dyn ConversionClosure>> {
+    fn get_conversion<I: Input>(&self, from_type: TypeId) -> Option<&dyn ConversionClosure> {
         let id = I::id();
         let conversion_id = ConversionId {
             from_type,
@@ -111,11 +150,17 @@ impl Io {
         self.conversions
             .get(&conversion_id)
             .or_else(|| self.conversions.get(&conversion_id.into_general()))
+            .map(Box::as_ref)
     }
 
     /// Gets input data in correct type either directly, converting it or a default value.
     pub fn get_input<I: Input>(&self, instance: InstanceHandle) -> I::Type {
-        if let Some(value) = self.try_get_input::<I>(instance) {
+        self.get_input_indexed::<I>(instance, 0)
+    }
+
+    /// Gets the input data of one of several inputs of type `I`, see [`PortId::index`].
+    pub fn get_input_indexed<I: Input>(&self, instance: InstanceHandle, index: usize) -> I::Type {
+        if let Some(value) = self.try_get_input::<I>(instance, index) {
             value
         } else {
             I::default()
@@ -126,23 +171,63 @@ impl Io {
     pub fn set_output_dyn(&mut self, port: PortHandle, value: Box<dyn PortValueBoxed>) {
         if let Some(connections) = self.connections.get(&port) {
             for connected in connections.clone().into_iter() {
-                self.set_input_dyn(connected, value.clone())
+                let value = match self.gains.get(&(port, connected)) {
+                    Some(&gain) => value.scale(gain),
+                    None => value.clone(),
+                };
+                let value = match self.delays.get_mut(&(port, connected)) {
+                    Some(line) => {
+                        line.values.push_back(value);
+                        if line.values.len() <= line.length {
+                            continue;
+                        }
+                        line.values
+                            .pop_front()
+                            .expect("should be longer than the delay")
+                    }
+                    None => value,
+                };
+                if let Some(probe) = &mut self.probe {
+                    if probe.from == port && probe.to == connected {
+                        probe.push(value.as_value());
+                    }
+                }
+                self.set_input_dyn(connected, value)
             }
         }
+
+        self.outputs.insert(port, value);
     }
 
     pub fn set_output<P: Port>(&mut self, instance: InstanceHandle, value: P::Type) {
         self.set_output_dyn(PortHandle::new(P::id(), instance), Box::new(value))
     }
 
+    /// Gets the scale factor of a connection.
+    pub fn gain(&self, from: PortHandle, to: PortHandle) -> f32 {
+        self.gains.get(&(from, to)).copied().unwrap_or(1.0)
+    }
+
+    pub fn set_gain(&mut self, from: PortHandle, to: PortHandle, gain: f32) {
+        if gain == 1.0 {
+            self.gains.remove(&(from, to));
+        } else {
+            self.gains.insert((from, to), gain);
+        }
+    }
+
+    pub fn probe(&self) -> Option<&Probe> {
+        self.probe.as_ref()
+    }
+
+    /// Starts recording the values flowing through a connection, replacing the previous probe.
+    pub fn set_probe(&mut self, probe: Option<Probe>) {
+        self.probe = probe;
+    }
+
     ///Verifies whether the provided input port is connected, and if it is, it returns the handle of the output port.
     pub fn input_connection(&self, input: PortHandle) -> Option<PortHandle> {
-        for (from, connections) in self.connections.iter() {
-            if connections.iter().any(|&value| value == input) {
-                return Some(*from);
-            }
-        }
-        None
+        self.sources.get(&input).copied()
     }
 
     /// Connect two ports.
@@ -152,11 +237,31 @@ impl Io {
             return can_connect;
         }
 
-        let connections = self.connections.entry(from).or_insert_with(HashSet::new);
+        let connections = self.connections.entry(from).or_default();
 
-        connections.insert(to);
+        let inserted = connections.insert(to);
+        if inserted {
+            self.sources.insert(to, from);
+            self.events.push(RackEvent::Connected(from, to));
+        }
 
-        self.update_instances_processing_order();
+        // can_connect already rules out cycles, this keeps the order valid regardless
+        match self.compute_instances_processing_order() {
+            Ok(order) => {
+                self.processing_order = order;
+                self.compensate_latency();
+            }
+            Err(_) => {
+                if inserted {
+                    if let Some(connections) = self.connections.get_mut(&from) {
+                        connections.remove(&to);
+                    }
+                    self.sources.remove(&to);
+                    self.events.pop();
+                }
+                return ConnectResult::Err(ConnectResultErr::Cycle);
+            }
+        }
 
         can_connect
     }
@@ -174,7 +279,12 @@ impl Io {
         }
 
         if let ConnectResult::Ok | ConnectResult::Warn(_) = result {
-            if let Some(connection) = self.input_connection(to) {
+            if self
+                .upstream_instances(from.instance)
+                .contains(&to.instance)
+            {
+                ConnectResult::Err(ConnectResultErr::Cycle)
+            } else if let Some(connection) = self.input_connection(to) {
                 ConnectResult::Warn(ConnectResultWarn::Replace(connection, to))
             } else {
                 result
@@ -186,8 +296,13 @@ impl Io {
 
     pub fn disconnect(&mut self, from: PortHandle, to: PortHandle) {
         if let Some(connections) = self.connections.get_mut(&from) {
-            connections.remove(&to);
+            if connections.remove(&to) {
+                self.sources.remove(&to);
+                self.events.push(RackEvent::Disconnected(from, to));
+            }
             self.inputs.remove(&to);
+            self.gains.remove(&(from, to));
+            self.delays.remove(&(from, to));
             self.update_instances_processing_order();
         }
     }
@@ -220,6 +335,51 @@ impl Io {
         for port in self.instance_ports(instance) {
             self.clear_port(port)
         }
+        self.outputs.retain(|port, _| port.instance != instance);
+        self.buses.retain(|_, bus| bus.sender != instance);
+        self.latencies.remove(&instance);
+    }
+
+    /// Sets the latency in samples of an instance, connections are delayed to match it.
+    pub fn set_latency(&mut self, instance: InstanceHandle, latency: usize) {
+        if latency > 0 {
+            self.latencies.insert(instance, latency);
+        } else {
+            self.latencies.remove(&instance);
+        }
+        self.compensate_latency();
+    }
+
+    pub fn send_bus(&mut self, name: &str, sender: InstanceHandle, value: Box<dyn PortValueBoxed>) {
+        if let Some(bus) = self.buses.get_mut(name) {
+            bus.sender = sender;
+            bus.value = value;
+        } else {
+            self.buses.insert(name.to_string(), Bus { sender, value });
+        }
+    }
+
+    pub fn get_bus(&self, name: &str) -> Option<&dyn PortValueBoxed> {
+        self.buses.get(name).map(|bus| &*bus.value)
+    }
+
+    /// Removes a bus if it was last sent to by `sender`.
+    pub fn remove_bus(&mut self, name: &str, sender: InstanceHandle) {
+        if self.buses.get(name).is_some_and(|bus| bus.sender == sender) {
+            self.buses.remove(name);
+        }
+    }
+
+    /// Returns the names of all buses in alphabetical order.
+    pub fn bus_names(&self) -> Vec<String> {
+        let mut names = self.buses.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Takes the connection changes since the last call.
+    pub fn take_events(&mut self) -> Vec<RackEvent> {
+        std::mem::take(&mut self.events)
     }
 
     pub fn add_conversion(&mut self, conversion: Conversion) {
@@ -240,6 +400,58 @@ impl Io {
         map
     }
 
+    /// Returns all instances that feed into the instance, directly or indirectly.
+    pub fn upstream_instances(&self, instance: InstanceHandle) -> HashSet<InstanceHandle> {
+        Self::reachable(&self.get_instances_dependencies(), instance)
+    }
+
+    /// Returns the instances and all instances that feed into them, directly or indirectly.
+    pub fn upstream_of(
+        &self,
+        instances: impl IntoIterator<Item = InstanceHandle>,
+    ) -> HashSet<InstanceHandle> {
+        let dependencies = self.get_instances_dependencies();
+        let mut found = HashSet::new();
+
+        for instance in instances {
+            if found.insert(instance) {
+                found.extend(Self::reachable(&dependencies, instance));
+            }
+        }
+
+        found
+    }
+
+    /// Returns all instances that the instance feeds into, directly or indirectly.
+    pub fn downstream_instances(&self, instance: InstanceHandle) -> HashSet<InstanceHandle> {
+        let mut dependents = HashMap::new();
+        for (to, froms) in self.get_instances_dependencies() {
+            for from in froms {
+                dependents.entry(from).or_insert(HashSet::new()).insert(to);
+            }
+        }
+
+        Self::reachable(&dependents, instance)
+    }
+
+    fn reachable(
+        edges: &HashMap<InstanceHandle, HashSet<InstanceHandle>>,
+        instance: InstanceHandle,
+    ) -> HashSet<InstanceHandle> {
+        let mut found = HashSet::new();
+        let mut stack = vec![instance];
+
+        while let Some(current) = stack.pop() {
+            for &next in edges.get(&current).into_iter().flatten() {
+                if found.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+
+        found
+    }
+
     pub fn compute_instances_processing_order(&self) -> Result<Vec<Vec<InstanceHandle>>, &str> {
         let mut topo = TopologicalSort::<InstanceHandle>::new();
         let mut added = HashSet::new();
@@ -265,8 +477,49 @@ impl Io {
         Ok(list)
     }
 
+    /// Keeps the previous order when the connections form a cycle, which [`Io::connect`] prevents.
     pub fn update_instances_processing_order(&mut self) {
-        self.processing_order = self.compute_instances_processing_order().unwrap();
+        match self.compute_instances_processing_order() {
+            Ok(order) => {
+                self.processing_order = order;
+                self.compensate_latency();
+            }
+            Err(err) => tracing::warn!("could not order instances: {err}"),
+        }
+    }
+
+    /// Delays connections so values arrive at each instance together with those of its slowest
+    /// input path. Only needed when the connections or latencies change.
+    fn compensate_latency(&mut self) {
+        // samples values leaving each instance lag behind the start of the chain
+        let mut lags = HashMap::<InstanceHandle, usize>::new();
+        let mut lengths = HashMap::new();
+        for &instance in self.processing_order.iter().flatten() {
+            let incoming = self
+                .sources
+                .iter()
+                .filter(|(to, _)| to.instance == instance)
+                .map(|(&to, &from)| ((from, to), lags.get(&from.instance).copied().unwrap_or(0)))
+                .collect::<Vec<_>>();
+
+            let arrival = incoming.iter().map(|(_, lag)| *lag).max().unwrap_or(0);
+            for (connection, lag) in incoming {
+                if lag < arrival {
+                    lengths.insert(connection, arrival - lag);
+                }
+            }
+            let latency = self.latencies.get(&instance).copied().unwrap_or(0);
+            lags.insert(instance, arrival + latency);
+        }
+
+        self.delays
+            .retain(|connection, line| lengths.get(connection) == Some(&line.length));
+        for (connection, length) in lengths {
+            self.delays.entry(connection).or_insert_with(|| DelayLine {
+                length,
+                values: VecDeque::with_capacity(length + 1),
+            });
+        }
     }
 
     pub fn connections(&self) -> &HashMap<PortHandle, HashSet<PortHandle>> {
@@ -278,6 +531,52 @@ impl Io {
     }
 }
 
+/// Records the latest values flowing through a connection.
+pub struct Probe {
+    pub from: PortHandle,
+    pub to: PortHandle,
+    values: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl Probe {
+    pub fn new(from: PortHandle, to: PortHandle, capacity: usize) -> Self {
+        Self {
+            from,
+            to,
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        if self.values.len() >= self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    pub fn values(&self) -> &VecDeque<f32> {
+        &self.values
+    }
+
+    /// Returns the minimum, maximum and mean of the recorded values.
+    pub fn stats(&self) -> Option<(f32, f32, f32)> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        let min = self.values.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = self
+            .values
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max);
+        let mean = self.values.iter().sum::<f32>() / self.values.len() as f32;
+        Some((min, max, mean))
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct PortHandle {
     pub id: PortId,
@@ -313,7 +612,8 @@ impl ConversionId {
         Self {
             from_type: from.id.value_type,
             to_type: to.id.value_type,
-            to_port: Some(to.id),
+            // conversions are described once for all ports of a type
+            to_port: Some(to.id.indexed(0)),
         }
     }
 
@@ -348,8 +648,7 @@ impl Conversion {
                 to_port: Some(port),
             },
             closure: Box::new(move |boxed: Box<dyn Any>| {
-                let any = &*boxed as &dyn Any;
-                Box::new(closure(any.downcast_ref::<I>().unwrap().clone()))
+                Box::new(closure(boxed.downcast_ref::<I>().unwrap().clone()))
             }),
         })
     }
@@ -364,9 +663,33 @@ impl Conversion {
                 to_port: None,
             },
             closure: Box::new(move |boxed: Box<dyn Any>| {
-                let any = &*boxed as &dyn Any;
-                Box::new(closure(any.downcast_ref::<I>().unwrap().clone()))
+                Box::new(closure(boxed.downcast_ref::<I>().unwrap().clone()))
             }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::oscillator::{FrameOutput, FrequencyInput};
+
+    #[test]
+    fn connect_refuses_cycles() {
+        let mut io = Io::default();
+        let (a, b) = (InstanceHandle::new(), InstanceHandle::new());
+        let output = |instance| PortHandle::new(FrameOutput::id(), instance);
+        let input = |instance| PortHandle::new(FrequencyInput::id(), instance);
+
+        assert!(matches!(io.connect(output(a), input(b)), ConnectResult::Ok));
+        let order = io.processing_order().clone();
+
+        assert!(matches!(
+            io.connect(output(b), input(a)),
+            ConnectResult::Err(ConnectResultErr::Cycle)
+        ));
+        assert_eq!(io.processing_order(), &order);
+        assert_eq!(io.input_connection(input(a)), None);
+        assert_eq!(io.input_connection(input(b)), Some(output(a)));
+    }
+}
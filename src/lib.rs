@@ -1,14 +1,40 @@
-#![feature(iter_array_chunks)]
-#![feature(trait_upcasting)]
-
 pub mod app;
+mod budget;
+mod clip;
+mod console;
+mod convolution;
+mod curve;
 mod damper;
+pub mod engine;
+mod ffi;
 mod frame;
+mod i18n;
 mod instance;
 pub mod io;
+mod midi;
 pub mod module;
 pub mod modules;
+mod onset;
 mod output;
+mod python;
 mod rack;
+pub mod settings;
+mod sharing;
+mod simd;
+mod toast;
+mod transport;
+mod tuning;
+mod tutorial;
 mod types;
 mod util;
+mod velocity;
+mod web;
+
+pub use output::Output;
+#[cfg(not(target_arch = "wasm32"))]
+pub use rack::check::check_files;
+pub use rack::{
+    diff::{diff, ParameterChange, PatchDiff},
+    patch::Patch,
+    rack::Rack,
+};
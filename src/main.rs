@@ -1,21 +1,42 @@
-#![feature(iter_array_chunks)]
-#![feature(trait_upcasting)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod budget;
+mod clip;
+mod console;
+mod convolution;
+mod curve;
 mod damper;
 mod frame;
+mod i18n;
 mod instance;
 mod io;
+mod midi;
 mod module;
 mod modules;
+mod onset;
 mod output;
 mod rack;
+mod settings;
+mod sharing;
+mod simd;
+mod toast;
+mod transport;
+mod tuning;
+mod tutorial;
 mod types;
 mod util;
+mod velocity;
+mod web;
 
 use app::App;
 
 fn main() {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(index) = std::env::args().position(|arg| arg == "--check") {
+        let paths = std::env::args().skip(index + 1).collect::<Vec<_>>();
+        std::process::exit(rack::check::check_files(&paths));
+    }
+
     App::default().run()
 }
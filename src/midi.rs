@@ -0,0 +1,277 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::sync::mpsc::{Receiver, Sender};
+
+use eframe::egui::{self, Ui};
+#[cfg(unix)]
+use midir::os::unix::VirtualInput;
+use midir::{MidiInputConnection, MidiInputPort};
+
+use crate::{
+    i18n::tr,
+    settings::{ProgramMapping, Settings},
+    toast,
+};
+
+/// A received MIDI message the app responds to.
+enum Message {
+    ProgramChange(u8),
+    /// Most significant part of the bank, selected with controller 0.
+    BankMsb(u8),
+    /// Least significant part of the bank, selected with controller 32.
+    BankLsb(u8),
+    NoteOn {
+        note: u8,
+        velocity: u8,
+    },
+}
+
+impl Message {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [status, program, ..] if status & 0xF0 == 0xC0 => Some(Self::ProgramChange(*program)),
+            [status, 0, value, ..] if status & 0xF0 == 0xB0 => Some(Self::BankMsb(*value)),
+            [status, 32, value, ..] if status & 0xF0 == 0xB0 => Some(Self::BankLsb(*value)),
+            // a note on without velocity is a note off
+            [status, note, velocity, ..] if status & 0xF0 == 0x90 && *velocity > 0 => {
+                Some(Self::NoteOn {
+                    note: *note,
+                    velocity: *velocity,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Listens to a MIDI input port for program changes, which switch scenes through the mappings in
+/// the [`Settings`], and for notes played on modules.
+pub struct Midi {
+    connection: Option<MidiInputConnection<()>>,
+    /// Name of the port connected to, or last tried to connect to.
+    port: Option<String>,
+    /// Whether a virtual port is open instead.
+    virtual_port: bool,
+    bank: u16,
+    /// Bank and program of the last program change, to map it from the editor.
+    last: Option<(u16, u8)>,
+    /// Notes received since the last [`Midi::take_notes`], with their velocity.
+    notes: Vec<(u8, u8)>,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl Default for Midi {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            connection: None,
+            port: None,
+            virtual_port: false,
+            bank: 0,
+            last: None,
+            notes: Vec::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Midi {
+    fn input() -> Option<midir::MidiInput> {
+        midir::MidiInput::new(env!("CARGO_PKG_NAME")).ok()
+    }
+
+    /// Names of the available input ports.
+    fn ports() -> Vec<String> {
+        let Some(input) = Self::input() else {
+            return Vec::new();
+        };
+        input
+            .ports()
+            .iter()
+            .filter_map(|port| input.port_name(port).ok())
+            .collect()
+    }
+
+    /// Forwards the messages the app responds to from a connection to the receiver.
+    fn callback(&self) -> impl FnMut(u64, &[u8], &mut ()) + Send + 'static {
+        let sender = self.sender.clone();
+        move |_, bytes, _| {
+            if let Some(message) = Message::parse(bytes) {
+                sender.send(message).ok();
+            }
+        }
+    }
+
+    /// Opens a port other applications can send to, named after the app.
+    #[cfg(unix)]
+    fn open_virtual(&mut self) {
+        self.port = None;
+        self.virtual_port = true;
+        let Some(input) = Self::input() else {
+            return;
+        };
+        match input.create_virtual(env!("CARGO_PKG_NAME"), self.callback(), ()) {
+            Ok(connection) => self.connection = Some(connection),
+            Err(_) => toast::error(tr("could not create a virtual MIDI port")),
+        }
+    }
+
+    fn connect(&mut self, name: Option<&String>) {
+        self.connection = None;
+        self.port = name.cloned();
+        self.virtual_port = false;
+        let Some(name) = name else {
+            return;
+        };
+        let Some(input) = Self::input() else {
+            return;
+        };
+        let port: Option<MidiInputPort> = input
+            .ports()
+            .into_iter()
+            .find(|port| input.port_name(port).ok().as_ref() == Some(name));
+        let Some(port) = port else {
+            toast::error(format!("{} {name}", tr("could not connect to MIDI input:")));
+            return;
+        };
+
+        match input.connect(&port, env!("CARGO_PKG_NAME"), self.callback(), ()) {
+            Ok(connection) => self.connection = Some(connection),
+            Err(_) => toast::error(format!("{} {name}", tr("could not connect to MIDI input:"))),
+        }
+    }
+
+    /// Connects to the port in the settings if it changed and handles the received messages,
+    /// returning the scenes the program changes are mapped to.
+    pub fn update(&mut self, settings: &Settings) -> Vec<usize> {
+        #[cfg(unix)]
+        if settings.midi_virtual {
+            if !self.virtual_port {
+                self.connection = None;
+                self.open_virtual();
+            }
+        } else if self.virtual_port || self.port != settings.midi_port {
+            self.connect(settings.midi_port.as_ref());
+        }
+
+        #[cfg(not(unix))]
+        if self.port != settings.midi_port {
+            self.connect(settings.midi_port.as_ref());
+        }
+
+        let mut scenes = Vec::new();
+        for message in self.receiver.try_iter() {
+            match message {
+                Message::BankMsb(value) => self.bank = (value as u16) << 7 | self.bank & 0x7F,
+                Message::BankLsb(value) => self.bank = self.bank & !0x7F | value as u16,
+                Message::ProgramChange(program) => {
+                    self.last = Some((self.bank, program));
+                    scenes.extend(
+                        settings
+                            .program_changes
+                            .iter()
+                            .filter(|mapping| {
+                                mapping.bank == self.bank && mapping.program == program
+                            })
+                            .map(|mapping| mapping.scene),
+                    );
+                }
+                Message::NoteOn { note, velocity } => self.notes.push((note, velocity)),
+            }
+        }
+        scenes
+    }
+
+    /// The notes received by the last [`Midi::update`], to play on modules.
+    pub fn take_notes(&mut self) -> Vec<(u8, u8)> {
+        std::mem::take(&mut self.notes)
+    }
+
+    /// Shows the input port and the editor of the program change mappings.
+    pub fn show(&mut self, ui: &mut Ui, settings: &mut Settings, scenes: &[&str]) {
+        #[cfg(unix)]
+        ui.checkbox(&mut settings.midi_virtual, tr("virtual port"))
+            .on_hover_text(format!(
+                "{} {}",
+                tr("opens a port other applications can send MIDI to, named"),
+                env!("CARGO_PKG_NAME")
+            ));
+
+        #[cfg(unix)]
+        let hardware = !settings.midi_virtual;
+        #[cfg(not(unix))]
+        let hardware = true;
+
+        ui.add_enabled_ui(hardware, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("input:"));
+                egui::ComboBox::from_id_source("midi port")
+                    .selected_text(
+                        settings
+                            .midi_port
+                            .clone()
+                            .unwrap_or_else(|| tr("none").to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut settings.midi_port, None, tr("none"));
+                        for port in Self::ports() {
+                            ui.selectable_value(&mut settings.midi_port, Some(port.clone()), port);
+                        }
+                    });
+                if self.port.is_some() && self.connection.is_none() && ui.button("⟲").clicked() {
+                    self.port = None;
+                }
+            })
+        });
+
+        ui.separator();
+        ui.label(tr("program changes launch scenes:"));
+
+        let mut removed = None;
+        egui::Grid::new("program changes").show(ui, |ui| {
+            ui.label(tr("bank"));
+            ui.label(tr("program"));
+            ui.label(tr("scene"));
+            ui.end_row();
+
+            for (index, mapping) in settings.program_changes.iter_mut().enumerate() {
+                ui.add(egui::DragValue::new(&mut mapping.bank).clamp_range(0..=16383));
+                ui.add(egui::DragValue::new(&mut mapping.program).clamp_range(0..=127));
+                egui::ComboBox::from_id_source(("program change scene", index))
+                    .selected_text(scenes.get(mapping.scene).copied().unwrap_or("-"))
+                    .show_ui(ui, |ui| {
+                        for (scene, name) in scenes.iter().enumerate() {
+                            ui.selectable_value(&mut mapping.scene, scene, *name);
+                        }
+                    });
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(removed) = removed {
+            settings.program_changes.remove(removed);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button(tr("➕ mapping")).clicked() {
+                let (bank, program) = self.last.unwrap_or((self.bank, 0));
+                settings.program_changes.push(ProgramMapping {
+                    bank,
+                    program,
+                    scene: 0,
+                });
+            }
+            if let Some((bank, program)) = self.last {
+                ui.label(format!("{} {bank}:{program}", tr("last received:")))
+                    .on_hover_text(tr(
+                        "new mappings start from the last received program change",
+                    ));
+            }
+        });
+    }
+}
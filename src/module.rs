@@ -2,18 +2,50 @@ use std::{
     any::{Any, TypeId},
     fmt::Debug,
     marker::PhantomData,
+    ops::RangeInclusive,
 };
 
 use dyn_clone::DynClone;
-use eframe::{self, egui::Ui};
+use eframe::{self, egui::Ui, epaint::Color32};
+use enum_iterator::Sequence;
+use rand::Rng;
 
 use crate::{
+    i18n::tr,
+    instance::instance::InstanceHandle,
     io::{ConnectResult, ConnectResultErr, Conversion, PortHandle},
-    rack::rack::{ProcessContext, ShowContext},
+    rack::rack::{ProcessContext, Rack, ShowContext},
 };
 
+/// Factor by which the sample rate of visual modules is lowered.
+pub const VISUAL_DECIMATION: usize = 4;
+
+/// Seconds a parameter takes by default to sweep its whole range after it was changed.
+pub const DEFAULT_SMOOTHING: f32 = 0.005;
+
+/// Access to [`Any`] from trait objects, which can't be upcast on stable Rust.
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
 /// Trait all rack modules implement
-pub trait Module: Any + 'static {
+pub trait Module: AsAny + 'static {
     fn describe() -> ModuleDescription<Self>
     where
         Self: Sized;
@@ -22,6 +54,144 @@ pub trait Module: Any + 'static {
 
     #[allow(unused)]
     fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {}
+
+    /// The continuous settings of this module that can be edited generically.
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        Vec::new()
+    }
+
+    /// Path of a file this module depends on, shared along with patches.
+    fn asset(&self) -> Option<String> {
+        None
+    }
+
+    #[allow(unused)]
+    fn load_asset(&mut self, path: &str) {}
+
+    /// Paths of all files this module depends on, for modules depending on several. Empty paths
+    /// keep the place of files that aren't set.
+    fn assets(&self) -> Vec<String> {
+        self.asset().into_iter().collect()
+    }
+
+    /// Loads the files returned by [`Module::assets`], in the same order.
+    fn load_assets(&mut self, paths: &[String]) {
+        if let Some(path) = paths.first() {
+            self.load_asset(path)
+        }
+    }
+
+    /// Ports of this instance on top of those of its description, for modules with a variable
+    /// amount of ports. Give them distinct indices with [`PortDescriptionDyn::indexed`].
+    fn dynamic_ports(&self) -> Vec<PortDescriptionDyn> {
+        Vec::new()
+    }
+
+    /// Settings that aren't parameters but should be stored in patches, like the amount of ports.
+    fn state(&self) -> Option<String> {
+        None
+    }
+
+    #[allow(unused)]
+    fn load_state(&mut self, state: &str) {}
+
+    /// Whether the module records performances into a clip the timeline can start and stop.
+    fn has_clip(&self) -> bool {
+        false
+    }
+
+    /// Starts or stops playing the recorded clip, see [`Self::has_clip`].
+    #[allow(unused)]
+    fn set_clip_playing(&mut self, playing: bool) {}
+
+    /// Plays a note received over MIDI, `velocity` from 0 to 1.
+    #[allow(unused)]
+    fn note_on(&mut self, note: u8, velocity: f32) {}
+
+    /// Values this module sets parameters of other instances to, applied before processing.
+    fn parameter_targets(&self) -> Vec<ParameterTarget> {
+        Vec::new()
+    }
+
+    /// Points references to other instances to their new handles after being inserted from a
+    /// block, `map` gives `None` for instances that weren't inserted along.
+    #[allow(unused)]
+    fn remap_instances(&mut self, map: &dyn Fn(InstanceHandle) -> Option<InstanceHandle>) {}
+}
+
+/// A value for a parameter of another instance, see [`Module::parameter_targets`].
+pub struct ParameterTarget {
+    pub instance: InstanceHandle,
+    pub parameter: String,
+    pub value: f32,
+}
+
+/// A mutable reference to a continuous setting of a [`Module`] together with its range.
+pub struct Parameter<'a> {
+    pub name: &'static str,
+    pub value: &'a mut f32,
+    pub range: RangeInclusive<f32>,
+    /// Seconds to sweep the whole range, changes are ramped towards by the rack to avoid clicks.
+    pub smoothing: f32,
+}
+
+impl<'a> Parameter<'a> {
+    pub fn new(name: &'static str, value: &'a mut f32, range: RangeInclusive<f32>) -> Self {
+        Self {
+            name,
+            value,
+            range,
+            smoothing: DEFAULT_SMOOTHING,
+        }
+    }
+
+    /// Sets the seconds to sweep the whole range, 0 applies changes at once.
+    pub fn smoothing(mut self, seconds: f32) -> Self {
+        self.smoothing = seconds;
+        self
+    }
+
+    /// Moves the value towards a random value in its range, by `amount` from 0 to 1.
+    pub fn mutate(&mut self, amount: f32) {
+        let random = rand::thread_rng().gen_range(self.range.clone());
+        *self.value += (random - *self.value) * amount.clamp(0.0, 1.0);
+    }
+}
+
+/// The values of the parameters of a [`Module`] at some point in time.
+#[derive(Clone, Default)]
+pub struct Snapshot {
+    values: Vec<(&'static str, f32)>,
+}
+
+impl Snapshot {
+    pub fn take(module: &mut dyn Module) -> Self {
+        Self {
+            values: module
+                .parameters()
+                .into_iter()
+                .map(|parameter| (parameter.name, *parameter.value))
+                .collect(),
+        }
+    }
+
+    /// The values that differ from an older snapshot.
+    pub fn changes(&self, old: &Snapshot) -> Vec<(&'static str, f32)> {
+        self.values
+            .iter()
+            .filter(|value| !old.values.contains(value))
+            .copied()
+            .collect()
+    }
+
+    /// Sets the parameters of the module to the stored values, matched by name.
+    pub fn apply(&self, module: &mut dyn Module) {
+        for parameter in module.parameters() {
+            if let Some((_, value)) = self.values.iter().find(|(name, _)| *name == parameter.name) {
+                *parameter.value = *value;
+            }
+        }
+    }
 }
 
 pub trait ModuleClosure: Fn() -> Box<dyn Module> + DynClone + 'static {}
@@ -34,22 +204,110 @@ impl Clone for Box<dyn ModuleClosure> {
     }
 }
 
+/// Builds a minimal patch demonstrating a module in the given panel of a [`Rack`].
+pub type Example = fn(&mut Rack, usize);
+
 /// Non generic module description. Contains all data necessary for creating an instance.
+/// What kind of module it is, coloring its header and grouping the add menu.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Sequence)]
+pub enum Category {
+    /// Makes sound, like oscillators and samples.
+    Source,
+    /// Changes sound, like filters and delays.
+    Effect,
+    /// Slow signals to move parameters with.
+    Modulation,
+    /// Notes and rhythms.
+    Sequencing,
+    /// Shows or measures signals.
+    Analysis,
+    #[default]
+    Utility,
+    /// Where the signal leaves the rack.
+    Output,
+}
+
+impl Category {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Category::Source => "source",
+            Category::Effect => "effect",
+            Category::Modulation => "modulation",
+            Category::Sequencing => "sequencing",
+            Category::Analysis => "analysis",
+            Category::Utility => "utility",
+            Category::Output => "output",
+        }
+    }
+
+    /// Accent color of the icons of modules in the category.
+    pub fn color(&self) -> Color32 {
+        match self {
+            Category::Source => Color32::from_rgb(240, 160, 60),
+            Category::Effect => Color32::from_rgb(90, 170, 240),
+            Category::Modulation => Color32::from_rgb(190, 120, 230),
+            Category::Sequencing => Color32::from_rgb(110, 200, 110),
+            Category::Analysis => Color32::from_rgb(80, 200, 200),
+            Category::Utility => Color32::GRAY,
+            Category::Output => Color32::from_rgb(230, 90, 90),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ModuleDescriptionDyn {
     pub name: String,
+    pub icon: String,
+    pub category: Category,
+    pub tooltip: Option<String>,
+    pub example: Option<Example>,
     pub instantiate: Box<dyn ModuleClosure>,
     pub inputs: Vec<PortDescriptionDyn>,
     pub outputs: Vec<PortDescriptionDyn>,
+    pub visual: bool,
+    pub sink: bool,
+    /// Delay in samples the module adds to its outputs.
+    pub latency: usize,
 }
 
 impl ModuleDescriptionDyn {
     pub fn from_typed<M>(description: ModuleDescription<M>) -> Self {
         Self {
             name: description.name,
+            icon: description.icon,
+            category: description.category,
+            tooltip: description.tooltip,
+            example: description.example,
             instantiate: description.instantiate,
             inputs: description.inputs,
             outputs: description.outputs,
+            visual: description.visual,
+            sink: description.sink,
+            latency: description.latency,
+        }
+    }
+
+    /// The translated name preceded by the icon.
+    pub fn title(&self) -> String {
+        format!("{} {}", self.icon, tr(&self.name))
+            .trim_start()
+            .to_string()
+    }
+
+    /// Whether `name` refers to the module, also by its name from before icons were separate.
+    pub fn matches_name(&self, name: &str) -> bool {
+        name == self.name
+            || name
+                .strip_prefix(self.icon.as_str())
+                .is_some_and(|name| !self.icon.is_empty() && name.trim_start() == self.name)
+    }
+
+    /// The sample rate the module is processed at, lower for visual modules.
+    pub fn process_rate(&self, sample_rate: u32) -> u32 {
+        if self.visual {
+            sample_rate / VISUAL_DECIMATION as u32
+        } else {
+            sample_rate
         }
     }
 
@@ -63,9 +321,16 @@ impl ModuleDescriptionDyn {
 
 pub struct ModuleDescription<M> {
     name: String,
+    icon: String,
+    category: Category,
+    tooltip: Option<String>,
+    example: Option<Example>,
     instantiate: Box<dyn ModuleClosure>,
     inputs: Vec<PortDescriptionDyn>,
     outputs: Vec<PortDescriptionDyn>,
+    visual: bool,
+    sink: bool,
+    latency: usize,
     phantom: PhantomData<M>,
 }
 
@@ -79,9 +344,16 @@ impl<M: Module> ModuleDescription<M> {
     pub fn new(closure: impl Fn() -> M + Clone + 'static) -> Self {
         Self {
             name: std::any::type_name::<M>().to_string(),
+            icon: String::new(),
+            category: Category::default(),
+            tooltip: None,
+            example: None,
             instantiate: Box::new(move || Box::new(closure())),
             inputs: Vec::new(),
             outputs: Vec::new(),
+            visual: false,
+            sink: false,
+            latency: 0,
             phantom: PhantomData,
         }
     }
@@ -91,6 +363,53 @@ impl<M: Module> ModuleDescription<M> {
         self
     }
 
+    /// A symbol shown before the name, colored by the category.
+    pub fn icon(mut self, value: &str) -> Self {
+        self.icon = value.to_string();
+        self
+    }
+
+    pub fn category(mut self, value: Category) -> Self {
+        self.category = value;
+        self
+    }
+
+    /// Text describing the module, shown when hovering its header.
+    pub fn tooltip(mut self, value: &str) -> Self {
+        self.tooltip = Some(value.to_string());
+        self
+    }
+
+    /// A patch that can be inserted from the help of the module.
+    pub fn example(mut self, example: Example) -> Self {
+        self.example = Some(example);
+        self
+    }
+
+    /// Marks the module as only visualizing its inputs, so it is processed at a decimated rate.
+    ///
+    /// Visual modules are processed after all others and should not have outputs.
+    pub fn visual(mut self) -> Self {
+        self.visual = true;
+        self
+    }
+
+    /// Marks the module as an end point of the signal, like an output.
+    ///
+    /// Only sinks, visual modules and whatever feeds into them are processed.
+    pub fn sink(mut self) -> Self {
+        self.sink = true;
+        self
+    }
+
+    /// Sets the delay in samples the module adds to its outputs, like the block of a convolution.
+    ///
+    /// Parallel paths that skip the module are delayed by as much, keeping them aligned.
+    pub fn latency(mut self, samples: usize) -> Self {
+        self.latency = samples;
+        self
+    }
+
     pub fn port<P: Port>(mut self, port: PortDescription<P>) -> Self {
         match port.port_type {
             PortType::Input => self.inputs.push(port.into_dyn()),
@@ -104,12 +423,16 @@ impl<M: Module> ModuleDescription<M> {
     }
 }
 
-pub trait PortValueBoxed: Any + DynClone + 'static {
+pub trait PortValueBoxed: AsAny + DynClone + 'static {
     fn name() -> &'static str
+    where
+        Self: Sized;
+    fn scalable() -> bool
     where
         Self: Sized;
     fn to_string(&self) -> String;
     fn as_value(&self) -> f32;
+    fn scale(&self, factor: f32) -> Box<dyn PortValueBoxed>;
 }
 
 impl Clone for Box<dyn PortValueBoxed> {
@@ -142,7 +465,7 @@ pub trait Input: Port {
     fn show(value: &mut Self::Type, ui: &mut Ui) {}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum PortType {
     Input,
     Output,
@@ -168,6 +491,16 @@ impl Clone for Box<dyn InputClosureValue> {
     }
 }
 
+pub trait NormalledClosure: Fn(&dyn Module) -> Box<dyn PortValueBoxed> + DynClone {}
+
+impl<F: Fn(&dyn Module) -> Box<dyn PortValueBoxed> + DynClone> NormalledClosure for F {}
+
+impl Clone for Box<dyn NormalledClosure> {
+    fn clone(&self) -> Self {
+        dyn_clone::clone_box(&**self)
+    }
+}
+
 pub trait ConversionClosure: Fn(Box<dyn Any>) -> Box<dyn Any> + DynClone + 'static {}
 
 impl<F: Fn(Box<dyn Any>) -> Box<dyn Any> + DynClone + 'static> ConversionClosure for F {}
@@ -182,11 +515,18 @@ impl Clone for Box<dyn ConversionClosure> {
 pub struct PortDescriptionDyn {
     pub name: &'static str,
     pub type_name: &'static str,
+    /// Whether values of the type can be scaled by the gain of a connection.
+    pub scalable: bool,
     pub port_type: PortType,
+    pub tooltip: Option<String>,
     pub id: PortId,
     pub closure_edit: Option<Box<dyn InputClosureEdit>>,
     pub closure_value: Option<Box<dyn InputClosureValue>>,
+    /// Derives the value of an unconnected input from its module.
+    pub normalled: Option<Box<dyn NormalledClosure>>,
     pub conversions: Vec<Conversion>,
+    /// Label of the collapsible row the port is shown in, `None` for the main row.
+    pub group: Option<&'static str>,
 }
 
 impl PortDescriptionDyn {
@@ -194,20 +534,34 @@ impl PortDescriptionDyn {
         Self {
             name: P::name(),
             type_name: P::type_name(),
+            scalable: P::Type::scalable(),
             port_type: description.port_type,
+            tooltip: description.tooltip,
             id: P::id(),
             closure_edit: description.closure_edit,
             closure_value: description.closure_value,
+            normalled: description.normalled,
             conversions: description.conversions,
+            group: description.group,
         }
     }
+
+    /// Makes this one of several ports of the same type on an instance, told apart by `index`.
+    pub fn indexed(mut self, index: usize, name: &'static str) -> Self {
+        self.id = self.id.indexed(index);
+        self.name = name;
+        self
+    }
 }
 
 pub struct PortDescription<P> {
     port_type: PortType,
+    tooltip: Option<String>,
     closure_edit: Option<Box<dyn InputClosureEdit>>,
     closure_value: Option<Box<dyn InputClosureValue>>,
+    normalled: Option<Box<dyn NormalledClosure>>,
     conversions: Vec<Conversion>,
+    group: Option<&'static str>,
     phantom: PhantomData<P>,
 }
 
@@ -218,6 +572,7 @@ impl<P: Port> PortDescription<P> {
     {
         Self {
             port_type: PortType::Input,
+            tooltip: None,
             closure_edit: Some(Box::new(
                 |handle: PortHandle, ctx: &mut ShowContext, ui: &mut Ui| {
                     let mut value = ctx.get_input::<P>(handle);
@@ -231,7 +586,9 @@ impl<P: Port> PortDescription<P> {
                 let value = ctx.get_input::<P>(handle);
                 value.to_string()
             })),
+            normalled: None,
             conversions: Vec::new(),
+            group: None,
             phantom: PhantomData,
         }
     }
@@ -239,13 +596,44 @@ impl<P: Port> PortDescription<P> {
     pub fn output() -> Self {
         Self {
             port_type: PortType::Output,
+            tooltip: None,
             closure_edit: None,
             closure_value: None,
+            normalled: None,
             conversions: Vec::new(),
+            group: None,
             phantom: PhantomData,
         }
     }
 
+    /// Text describing the port, shown when hovering its name.
+    pub fn tooltip(mut self, value: &str) -> Self {
+        self.tooltip = Some(value.to_string());
+        self
+    }
+
+    /// Shows the port in a labeled row that can be collapsed, together with the other ports of
+    /// the group, so modules with many ports stay narrow.
+    pub fn group(mut self, label: &'static str) -> Self {
+        self.group = Some(label);
+        self
+    }
+
+    /// Makes the input follow a value of its module while unconnected, like a normalled jack.
+    pub fn normalled<M: Module>(mut self, closure: impl Fn(&M) -> P::Type + Clone + 'static) -> Self
+    where
+        P: Input,
+    {
+        self.normalled = Some(Box::new(move |module: &dyn Module| {
+            let module = module
+                .as_any()
+                .downcast_ref::<M>()
+                .expect("should be the module of this port");
+            Box::new(closure(module)) as Box<dyn PortValueBoxed>
+        }));
+        self
+    }
+
     pub fn conversion<I: PortValueBoxed + Clone>(
         mut self,
         closure: impl Fn(I) -> P::Type + Clone + 'static,
@@ -264,6 +652,8 @@ impl<P: Port> PortDescription<P> {
 pub struct PortId {
     pub id: TypeId,
     pub value_type: TypeId,
+    /// Tells apart ports of the same type on one instance, 0 for ports of a description.
+    pub index: usize,
 }
 
 impl PortId {
@@ -271,9 +661,14 @@ impl PortId {
         Self {
             id: TypeId::of::<I>(),
             value_type: TypeId::of::<I::Type>(),
+            index: 0,
         }
     }
 
+    pub fn indexed(self, index: usize) -> Self {
+        Self { index, ..self }
+    }
+
     pub fn is_compatible(&self, other: Self) -> ConnectResult {
         if self.value_type == other.value_type {
             ConnectResult::Ok
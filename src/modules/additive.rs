@@ -0,0 +1,234 @@
+use std::f32::consts::TAU;
+
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        keyboard::{Keyboard, KeyboardFreqOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Names of the partials, which also limits the amount of partials.
+const PARTIAL_NAMES: [&str; 32] = [
+    "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+    "18", "19", "20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31", "32",
+];
+
+/// Amounts of partials to choose from.
+const COUNTS: [usize; 3] = [8, 16, 32];
+
+pub struct AdditiveFreqInput;
+
+impl Port for AdditiveFreqInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "freq"
+    }
+}
+
+impl Input for AdditiveFreqInput {
+    fn default() -> Self::Type {
+        70.0
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.add(
+            egui::DragValue::new(value)
+                .clamp_range(0.0..=f32::MAX)
+                .speed(1.0)
+                .suffix(" Hz"),
+        );
+    }
+}
+
+pub struct AdditiveOutput;
+
+impl Port for AdditiveOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "sample"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    count: usize,
+}
+
+/// A [`Module`] building a wave from sines at whole multiples of the frequency, each with its own
+/// amplitude.
+pub struct Additive {
+    /// Amplitude of each partial from 0 to 1, the first being the fundamental.
+    partials: Vec<f32>,
+    /// Change in amplitude per octave in dB, tilting the spectrum towards the low or the high
+    /// partials.
+    tilt: f32,
+    /// Position in the period of the fundamental from 0 to 1.
+    index: f32,
+}
+
+impl Default for Additive {
+    fn default() -> Self {
+        let mut partials = vec![0.0; 16];
+        partials[0] = 1.0;
+        Self {
+            partials,
+            tilt: 0.0,
+            index: 0.0,
+        }
+    }
+}
+
+impl Additive {
+    /// Sets the amplitudes to those of the partials of a classic wave.
+    fn preset(&mut self, amplitude: impl Fn(usize) -> f32) {
+        for (index, partial) in self.partials.iter_mut().enumerate() {
+            *partial = amplitude(index + 1);
+        }
+    }
+}
+
+impl Module for Additive {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Additive")
+            .icon("📶")
+            .category(Category::Source)
+            .tooltip("a wave built from sines at multiples of the frequency, each with its own amplitude")
+            .example(example)
+            .port(
+                PortDescription::<AdditiveFreqInput>::input()
+                    .tooltip("frequency of the fundamental in Hz"),
+            )
+            .port(PortDescription::<AdditiveOutput>::output().tooltip("the wave"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate() as f32;
+        let freq = ctx.get_input::<AdditiveFreqInput>();
+
+        let mut sample = 0.0;
+        let mut total = 0.0;
+        for (index, amplitude) in self.partials.iter().enumerate() {
+            let number = (index + 1) as f32;
+            // partials above the nyquist frequency would alias
+            if freq * number >= sample_rate / 2.0 {
+                break;
+            }
+            let gain = amplitude * 10f32.powf(self.tilt * number.log2() / 20.0);
+            sample += gain * (TAU * number * self.index).sin();
+            total += gain;
+        }
+
+        self.index = (self.index + freq / sample_rate).fract();
+
+        // keep within -1 to 1 however many partials play
+        ctx.set_output::<AdditiveOutput>(sample / total.max(1.0))
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        let label = ui
+            .horizontal(|ui| {
+                let label = ui.label(tr("partials:"));
+                let mut count = self.partials.len();
+                for option in COUNTS {
+                    ui.selectable_value(&mut count, option, option.to_string());
+                }
+                self.partials.resize(count, 0.0);
+                label
+            })
+            .inner;
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().slider_width = 80.0;
+            ui.spacing_mut().interact_size.x = 6.0;
+            for (index, partial) in self.partials.iter_mut().enumerate() {
+                ui.add(
+                    egui::Slider::new(partial, 0.0..=1.0)
+                        .vertical()
+                        .show_value(false),
+                )
+                .labelled_by(label.id)
+                .on_hover_text(format!(
+                    "{} {}: {:.2}",
+                    tr("partial"),
+                    index + 1,
+                    partial
+                ));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("tilt:"));
+            ui.add(egui::Slider::new(&mut self.tilt, -12.0..=12.0).suffix(" dB/oct"))
+                .labelled_by(label.id)
+                .on_hover_text(tr(
+                    "change in amplitude per octave, below 0 for a darker sound",
+                ));
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button(tr("Sine")).clicked() {
+                self.preset(|number| if number == 1 { 1.0 } else { 0.0 });
+            }
+            if ui.button(tr("Saw")).clicked() {
+                self.preset(|number| 1.0 / number as f32);
+            }
+            if ui.button(tr("Square")).clicked() {
+                self.preset(|number| {
+                    if number % 2 == 1 {
+                        1.0 / number as f32
+                    } else {
+                        0.0
+                    }
+                });
+            }
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        let mut parameters = vec![Parameter::new("tilt", &mut self.tilt, -12.0..=12.0)];
+        parameters.extend(
+            self.partials
+                .iter_mut()
+                .zip(PARTIAL_NAMES)
+                .map(|(value, name)| Parameter::new(name, value, 0.0..=1.0)),
+        );
+        parameters
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            count: self.partials.len(),
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.partials
+                .resize(state.count.clamp(1, PARTIAL_NAMES.len()), 0.0);
+        }
+    }
+}
+
+/// Plays the additive oscillator with the keyboard.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let additive = rack.add_module_to::<Additive>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<KeyboardFreqOutput, AdditiveFreqInput>(keyboard, additive)
+        .unwrap();
+    rack.connect_typed::<AdditiveOutput, AudioInput>(additive, audio)
+        .unwrap();
+}
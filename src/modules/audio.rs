@@ -4,8 +4,10 @@ use eframe::egui::{self, Ui};
 
 use crate::{
     frame::Frame,
-    module::{Input, Module, ModuleDescription, Port, PortDescription},
-    rack::rack::{ProcessContext, ShowContext},
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::oscillator::{FrameOutput, Oscillator},
+    rack::rack::{ProcessContext, Rack, ShowContext},
 };
 
 pub struct AudioInput;
@@ -41,19 +43,29 @@ impl Default for Audio {
 
 impl Module for Audio {
     fn describe() -> ModuleDescription<Self> {
-        ModuleDescription::default().name("🔊 Audio Output").port(
-            PortDescription::<AudioInput>::input().conversion(|sample: f32| Frame::Mono(sample)),
-        )
+        ModuleDescription::default()
+            .name("Audio Output")
+            .icon("🔊")
+            .category(Category::Output)
+            .tooltip("plays its input on the selected audio device")
+            .sink()
+            .example(example)
+            .port(
+                PortDescription::<AudioInput>::input()
+                    .tooltip("the sound to play, mono signals are sent to both channels")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
     }
 
     fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            ui.label("volume:");
+            let label = ui.label(tr("volume:"));
             ui.add(
                 egui::DragValue::new(&mut self.volume)
                     .clamp_range(0.0..=2.0)
                     .speed(0.01),
-            );
+            )
+            .labelled_by(label.id);
         });
     }
 
@@ -64,4 +76,16 @@ impl Module for Audio {
                 .unwrap();
         }
     }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("volume", &mut self.volume, 0.0..=2.0)]
+    }
+}
+
+/// Plays an oscillator.
+fn example(rack: &mut Rack, panel: usize) {
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<FrameOutput, AudioInput>(oscillator, audio)
+        .unwrap();
 }
@@ -0,0 +1,178 @@
+use std::f32::consts::{FRAC_PI_4, TAU};
+
+use eframe::egui::{self, Ui};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        noise::{Noise, NoiseOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+pub struct AutoPanInput;
+
+impl Port for AutoPanInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for AutoPanInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct AutoPanPanInput;
+
+impl Port for AutoPanPanInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "pan"
+    }
+}
+
+impl Input for AutoPanPanInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+pub struct AutoPanOutput;
+
+impl Port for AutoPanOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// A [`Module`] moving its input across the stereo field, following an internal wave or the pan input.
+pub struct AutoPan {
+    /// Speed of the internal wave in Hz, used when the pan input isn't connected.
+    rate: f32,
+    /// How far the sound moves away from the center.
+    depth: f32,
+    phase: f32,
+    /// Last position, from -1 (left) to 1 (right), for display.
+    position: f32,
+}
+
+impl Default for AutoPan {
+    fn default() -> Self {
+        Self {
+            rate: 0.5,
+            depth: 1.0,
+            phase: 0.0,
+            position: 0.0,
+        }
+    }
+}
+
+/// Equal power gains of the left and right channel for a position from -1 to 1.
+fn gains(position: f32) -> (f32, f32) {
+    let angle = (position + 1.0) * FRAC_PI_4;
+    (angle.cos(), angle.sin())
+}
+
+impl Module for AutoPan {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("AutoPan")
+            .icon("⇄")
+            .category(Category::Effect)
+            .tooltip("moves its input from left to right and back")
+            .example(example)
+            .port(
+                PortDescription::<AutoPanInput>::input()
+                    .tooltip("the mono or stereo sound to pan")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<AutoPanPanInput>::input()
+                    .tooltip("position instead of the internal wave, from -1 (left) to 1 (right)"),
+            )
+            .port(PortDescription::<AutoPanOutput>::output().tooltip("the panned stereo sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let wave = if ctx.is_connected::<AutoPanPanInput>() {
+            ctx.get_input::<AutoPanPanInput>().clamp(-1.0, 1.0)
+        } else {
+            self.phase = (self.phase + self.rate / ctx.sample_rate() as f32).fract();
+            (TAU * self.phase).sin()
+        };
+        self.position = wave * self.depth;
+
+        let output = match ctx.get_input::<AutoPanInput>() {
+            Frame::Mono(sample) => {
+                let (left, right) = gains(self.position);
+                Frame::Stereo(sample * left, sample * right)
+            }
+            // A stereo sound is balanced instead, so the channels don't collapse into each other.
+            Frame::Stereo(left, right) => {
+                let left_gain = (1.0 - self.position).min(1.0);
+                let right_gain = (1.0 + self.position).min(1.0);
+                Frame::Stereo(left * left_gain, right * right_gain)
+            }
+        };
+
+        ctx.set_output::<AutoPanOutput>(output)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("rate:"));
+            ui.add(
+                egui::Slider::new(&mut self.rate, 0.01..=20.0)
+                    .logarithmic(true)
+                    .suffix(" Hz"),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("depth:"));
+            ui.add(egui::Slider::new(&mut self.depth, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("position:"));
+            ui.add(
+                egui::ProgressBar::new((self.position + 1.0) / 2.0)
+                    .desired_width(150.0)
+                    .text(format!("{:+.2}", self.position)),
+            );
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("rate", &mut self.rate, 0.01..=20.0),
+            Parameter::new("depth", &mut self.depth, 0.0..=1.0),
+        ]
+    }
+}
+
+/// Noise swaying between the speakers.
+fn example(rack: &mut Rack, panel: usize) {
+    let noise = rack.add_module_to::<Noise>(panel);
+    let autopan = rack.add_module_to::<AutoPan>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<NoiseOutput, AutoPanInput>(noise, autopan)
+        .unwrap();
+    rack.connect_typed::<AutoPanOutput, AudioInput>(autopan, audio)
+        .unwrap();
+}
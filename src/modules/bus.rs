@@ -0,0 +1,163 @@
+use std::marker::PhantomData;
+
+use eframe::egui::{self, Ui};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Port, PortDescription, PortValueBoxed},
+    rack::rack::{ProcessContext, ShowContext},
+};
+
+pub struct BusSendInput<T>(PhantomData<T>);
+
+impl<T: PortValueBoxed + Clone> Port for BusSendInput<T> {
+    type Type = T;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl<T: PortValueBoxed + Clone + Default> Input for BusSendInput<T> {
+    fn default() -> Self::Type {
+        T::default()
+    }
+}
+
+pub struct BusReceiveOutput<T>(PhantomData<T>);
+
+impl<T: PortValueBoxed + Clone> Port for BusReceiveOutput<T> {
+    type Type = T;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// A [`Module`] that broadcasts its input to a named bus.
+pub struct BusSend<T> {
+    bus: String,
+    /// The bus that was last sent to, removed when the name changes.
+    sent: Option<String>,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Default for BusSend<T> {
+    fn default() -> Self {
+        Self {
+            bus: "bus".to_string(),
+            sent: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: PortValueBoxed + Clone + Default> Module for BusSend<T> {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name(&format!("Send<{}>", T::name()))
+            .icon("📤")
+            .category(Category::Utility)
+            .tooltip("sends its input to a named bus, receive it anywhere without a cable")
+            .sink()
+            .port(PortDescription::<BusSendInput<T>>::input().tooltip("the value to send"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        if self.sent.as_ref() != Some(&self.bus) {
+            if let Some(sent) = self.sent.take() {
+                ctx.remove_bus(&sent)
+            }
+            self.sent = Some(self.bus.clone());
+        }
+
+        let value = ctx.get_input::<BusSendInput<T>>();
+        ctx.send(&self.bus, value)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("bus"));
+            ui.text_edit_singleline(&mut self.bus);
+        });
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&self.bus).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(bus) = ron::from_str(state) {
+            self.bus = bus;
+        }
+    }
+}
+
+/// A [`Module`] that outputs the value of a named bus.
+///
+/// Buses are not part of the processing order, so the value can arrive a sample late.
+pub struct BusReceive<T> {
+    bus: String,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Default for BusReceive<T> {
+    fn default() -> Self {
+        Self {
+            bus: "bus".to_string(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: PortValueBoxed + Clone + Default> Module for BusReceive<T> {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name(&format!("Receive<{}>", T::name()))
+            .icon("📥")
+            .category(Category::Utility)
+            .tooltip("outputs what is sent to a named bus")
+            .port(
+                PortDescription::<BusReceiveOutput<T>>::output()
+                    .tooltip("the value sent to the bus"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let value = ctx.receive::<T>(&self.bus).unwrap_or_default();
+        ctx.set_output::<BusReceiveOutput<T>>(value)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("bus"));
+            ui.text_edit_singleline(&mut self.bus);
+
+            egui::ComboBox::from_id_source(ctx.instance)
+                .selected_text("")
+                .width(0.0)
+                .show_ui(ui, |ui| {
+                    for name in ctx.bus_names() {
+                        let text = name.clone();
+                        ui.selectable_value(&mut self.bus, name, text);
+                    }
+                });
+        });
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&self.bus).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(bus) = ron::from_str(state) {
+            self.bus = bus;
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        impulse::{Impulse, ImpulseOutput, ImpulseTriggerInput},
+        sequencer::{Sequencer, SequencerGateOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    transport::SyncedTime,
+};
+
+/// Longest delay time in ms, the buffer holds this much audio.
+const MAX_TIME: f32 = 2000.0;
+
+pub struct DelayInput;
+
+impl Port for DelayInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for DelayInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct DelayOutput;
+
+impl Port for DelayOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// A ring buffer of past samples.
+struct DelayLine<T> {
+    buffer: Vec<T>,
+    position: usize,
+}
+
+impl<T: Copy + Default> DelayLine<T> {
+    fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Makes room for [`MAX_TIME`] at the sample rate, clearing the line if it changed.
+    fn prepare(&mut self, sample_rate: u32) {
+        let size = (MAX_TIME / 1000.0 * sample_rate as f32) as usize + 1;
+        if self.buffer.len() != size {
+            self.buffer = vec![T::default(); size];
+            self.position = 0;
+        }
+    }
+
+    /// The sample written `time` ms ago.
+    fn read(&self, time: f32, sample_rate: u32) -> T {
+        let size = self.buffer.len();
+        let delay = ((time / 1000.0 * sample_rate as f32) as usize).clamp(1, size - 1);
+        self.buffer[(self.position + size - delay) % size]
+    }
+
+    fn write(&mut self, value: T) {
+        self.buffer[self.position] = value;
+        self.position = (self.position + 1) % self.buffer.len();
+    }
+
+    fn clear(&mut self) {
+        self.buffer.fill(T::default());
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DelayState {
+    time: SyncedTime,
+}
+
+/// A [`Module`] repeating its input after a delay, fading with every echo.
+pub struct Delay {
+    time: SyncedTime,
+    /// Part of each echo fed back into the delay.
+    feedback: f32,
+    /// Balance between the input and the echoes, from 0 (dry) to 1 (wet).
+    mix: f32,
+    line: DelayLine<Frame>,
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Self {
+            time: SyncedTime::new(300.0),
+            feedback: 0.4,
+            mix: 0.5,
+            line: DelayLine::new(),
+        }
+    }
+}
+
+impl Module for Delay {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Delay")
+            .icon("🔁")
+            .category(Category::Effect)
+            .tooltip("repeats its input after a delay, every echo softer than the last")
+            .example(example)
+            .port(
+                PortDescription::<DelayInput>::input()
+                    .tooltip("the sound to echo")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<DelayOutput>::output().tooltip("the input mixed with its echoes"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        self.line.prepare(ctx.sample_rate());
+        let time = self.time.as_ms(ctx.transport());
+        let delayed = self.line.read(time, ctx.sample_rate());
+
+        let input = ctx.get_input::<DelayInput>();
+        self.line.write(input + delayed * self.feedback);
+
+        ctx.set_output::<DelayOutput>(input * (1.0 - self.mix) + delayed * self.mix)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("time:"));
+            self.time
+                .show_range((ctx.instance, "time"), 1.0..=MAX_TIME, ui)
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("feedback:"));
+            ui.add(egui::Slider::new(&mut self.feedback, 0.0..=0.95))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("mix:"));
+            ui.add(egui::Slider::new(&mut self.mix, 0.0..=1.0))
+                .labelled_by(label.id)
+                .on_hover_text(tr("0 is only the input, 1 only the echoes"));
+        });
+
+        if ui.button(tr("clear")).clicked() {
+            self.line.clear();
+        }
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("time", &mut self.time.ms, 1.0..=MAX_TIME),
+            Parameter::new("feedback", &mut self.feedback, 0.0..=0.95),
+            Parameter::new("mix", &mut self.mix, 0.0..=1.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&DelayState { time: self.time }).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<DelayState>(state) {
+            self.time = state.time;
+        }
+    }
+}
+
+pub struct PingPongInput;
+
+impl Port for PingPongInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for PingPongInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct PingPongOutput;
+
+impl Port for PingPongOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PingPongState {
+    times: [SyncedTime; 2],
+}
+
+/// A stereo delay [`Module`] whose echoes bounce between the left and right channel.
+pub struct PingPong {
+    /// Delay times of the left and right channel.
+    times: [SyncedTime; 2],
+    /// Part of each echo fed back into its own channel.
+    feedback: f32,
+    /// Part of each echo fed into the other channel, which makes the echoes alternate.
+    cross: f32,
+    mix: f32,
+    lines: [DelayLine<f32>; 2],
+}
+
+impl Default for PingPong {
+    fn default() -> Self {
+        Self {
+            times: [SyncedTime::new(250.0); 2],
+            feedback: 0.0,
+            cross: 0.6,
+            mix: 0.4,
+            lines: [DelayLine::new(), DelayLine::new()],
+        }
+    }
+}
+
+impl Module for PingPong {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("PingPong")
+            .icon("🏓")
+            .category(Category::Effect)
+            .tooltip("a stereo delay with echoes alternating between left and right")
+            .example(example_ping_pong)
+            .port(
+                PortDescription::<PingPongInput>::input()
+                    .tooltip("the sound to echo, the first echo is on the left")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<PingPongOutput>::output()
+                    .tooltip("the input mixed with its echoes in stereo"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate();
+        for line in self.lines.iter_mut() {
+            line.prepare(sample_rate);
+        }
+
+        let [left, right] = self.times.map(|time| time.as_ms(ctx.transport()));
+        let left = self.lines[0].read(left, sample_rate);
+        let right = self.lines[1].read(right, sample_rate);
+
+        // keeps the echoes from growing when both amounts are high
+        let total = self.feedback + self.cross;
+        let scale = if total > 0.95 { 0.95 / total } else { 1.0 };
+        let (feedback, cross) = (self.feedback * scale, self.cross * scale);
+
+        let input = ctx.get_input::<PingPongInput>();
+        let (dry_left, dry_right) = input.as_f32_tuple();
+        let mono = (dry_left + dry_right) / 2.0;
+
+        self.lines[0].write(mono + left * feedback + right * cross);
+        self.lines[1].write(right * feedback + left * cross);
+
+        ctx.set_output::<PingPongOutput>(Frame::Stereo(
+            dry_left * (1.0 - self.mix) + left * self.mix,
+            dry_right * (1.0 - self.mix) + right * self.mix,
+        ))
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        for (time, label) in self.times.iter_mut().zip(["left:", "right:"]) {
+            ui.horizontal(|ui| {
+                let name = ui.label(tr(label));
+                time.show_range((ctx.instance, label), 1.0..=MAX_TIME, ui)
+                    .labelled_by(name.id);
+            });
+        }
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("feedback:"));
+            ui.add(egui::Slider::new(&mut self.feedback, 0.0..=0.95))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("cross:"));
+            ui.add(egui::Slider::new(&mut self.cross, 0.0..=0.95))
+                .labelled_by(label.id)
+                .on_hover_text(tr("how much of each echo moves to the other side"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("mix:"));
+            ui.add(egui::Slider::new(&mut self.mix, 0.0..=1.0))
+                .labelled_by(label.id)
+                .on_hover_text(tr("0 is only the input, 1 only the echoes"));
+        });
+
+        if ui.button(tr("clear")).clicked() {
+            for line in self.lines.iter_mut() {
+                line.clear();
+            }
+        }
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        let [left, right] = &mut self.times;
+        vec![
+            Parameter::new("left", &mut left.ms, 1.0..=MAX_TIME),
+            Parameter::new("right", &mut right.ms, 1.0..=MAX_TIME),
+            Parameter::new("feedback", &mut self.feedback, 0.0..=0.95),
+            Parameter::new("cross", &mut self.cross, 0.0..=0.95),
+            Parameter::new("mix", &mut self.mix, 0.0..=1.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&PingPongState { times: self.times }).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<PingPongState>(state) {
+            self.times = state.times;
+        }
+    }
+}
+
+/// Echoes of impulses played by a sequencer.
+fn example(rack: &mut Rack, panel: usize) {
+    let sequencer = rack.add_module_to::<Sequencer>(panel);
+    let impulse = rack.add_module_to::<Impulse>(panel);
+    let delay = rack.add_module_to::<Delay>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<SequencerGateOutput, ImpulseTriggerInput>(sequencer, impulse)
+        .unwrap();
+    rack.connect_typed::<ImpulseOutput, DelayInput>(impulse, delay)
+        .unwrap();
+    rack.connect_typed::<DelayOutput, AudioInput>(delay, audio)
+        .unwrap();
+}
+
+/// Impulses bouncing between left and right.
+fn example_ping_pong(rack: &mut Rack, panel: usize) {
+    let sequencer = rack.add_module_to::<Sequencer>(panel);
+    let impulse = rack.add_module_to::<Impulse>(panel);
+    let delay = rack.add_module_to::<PingPong>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<SequencerGateOutput, ImpulseTriggerInput>(sequencer, impulse)
+        .unwrap();
+    rack.connect_typed::<ImpulseOutput, PingPongInput>(impulse, delay)
+        .unwrap();
+    rack.connect_typed::<PingPongOutput, AudioInput>(delay, audio)
+        .unwrap();
+}
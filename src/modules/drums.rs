@@ -0,0 +1,373 @@
+use std::f32::consts::PI;
+
+use biquad::{Biquad, Coefficients, DirectForm1, ToHertz, Type, Q_BUTTERWORTH_F32};
+use eframe::egui::{self, Ui};
+use rand::Rng;
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        sequencer::{Sequencer, SequencerGateOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+pub struct DrumTriggerInput;
+
+impl Port for DrumTriggerInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "trigger"
+    }
+}
+
+impl Input for DrumTriggerInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct DrumOutput;
+
+impl Port for DrumOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "sample"
+    }
+}
+
+/// An exponentially decaying amplitude, restarted on each trigger.
+#[derive(Default)]
+struct Decay {
+    level: f32,
+    last_trigger: bool,
+}
+
+impl Decay {
+    /// Checks the trigger for a rising edge, returns true when the decay was restarted.
+    fn trigger(&mut self, trigger: bool) -> bool {
+        let rising = trigger && !self.last_trigger;
+        self.last_trigger = trigger;
+        if rising {
+            self.level = 1.0;
+        }
+        rising
+    }
+
+    /// Returns the current level and decays it, `time` being the time in ms to reach -60dB.
+    fn next(&mut self, time: f32, sample_rate: u32) -> f32 {
+        let level = self.level;
+        let samples = (time / 1000.0 * sample_rate as f32).max(1.0);
+        self.level *= 0.001f32.powf(1.0 / samples);
+        level
+    }
+}
+
+fn drag(
+    ui: &mut Ui,
+    label: &str,
+    value: &mut f32,
+    range: std::ops::RangeInclusive<f32>,
+    suffix: &str,
+) {
+    let label = ui.label(tr(label));
+    ui.add(
+        egui::DragValue::new(value)
+            .clamp_range(range)
+            .speed(1.0)
+            .suffix(suffix),
+    )
+    .labelled_by(label.id);
+}
+
+fn filter(filter_type: Type<f32>, cutoff: f32, sample_rate: u32) -> Option<Coefficients<f32>> {
+    Coefficients::<f32>::from_params(
+        filter_type,
+        sample_rate.hz(),
+        cutoff.clamp(1.0, sample_rate as f32 / 2.0 - 1.0).hz(),
+        Q_BUTTERWORTH_F32,
+    )
+    .ok()
+}
+
+/// An analog style kick drum [`Module`], a sine with a falling pitch.
+pub struct Kick {
+    tune: f32,
+    sweep: f32,
+    pitch_decay: f32,
+    decay: f32,
+    amp: Decay,
+    pitch: Decay,
+    phase: f32,
+}
+
+impl Default for Kick {
+    fn default() -> Self {
+        Self {
+            tune: 50.0,
+            sweep: 150.0,
+            pitch_decay: 60.0,
+            decay: 400.0,
+            amp: Decay::default(),
+            pitch: Decay::default(),
+            phase: 0.0,
+        }
+    }
+}
+
+impl Module for Kick {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Kick")
+            .icon("🥁")
+            .category(Category::Source)
+            .tooltip("an analog style kick drum, a sine with a falling pitch")
+            .example(example::<Self>)
+            .port(
+                PortDescription::<DrumTriggerInput>::input()
+                    .tooltip("restarts the drum when it goes high"),
+            )
+            .port(PortDescription::<DrumOutput>::output().tooltip("the drum sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let trigger = ctx.get_input::<DrumTriggerInput>();
+        if self.amp.trigger(trigger) {
+            self.phase = 0.0;
+        }
+        self.pitch.trigger(trigger);
+
+        let sample_rate = ctx.sample_rate();
+        let freq = self.tune + self.sweep * self.pitch.next(self.pitch_decay, sample_rate);
+        self.phase = (self.phase + freq / sample_rate as f32) % 1.0;
+
+        let sample = (self.phase * 2.0 * PI).sin() * self.amp.next(self.decay, sample_rate);
+        ctx.set_output::<DrumOutput>(sample)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            drag(ui, "tune:", &mut self.tune, 20.0..=200.0, " Hz");
+            drag(ui, "sweep:", &mut self.sweep, 0.0..=1000.0, " Hz");
+        });
+        ui.horizontal(|ui| {
+            drag(
+                ui,
+                "sweep time:",
+                &mut self.pitch_decay,
+                1.0..=1000.0,
+                " ms",
+            );
+            drag(ui, "decay:", &mut self.decay, 10.0..=5000.0, " ms");
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("tune", &mut self.tune, 20.0..=200.0),
+            Parameter::new("sweep", &mut self.sweep, 0.0..=1000.0),
+            Parameter::new("sweep time", &mut self.pitch_decay, 1.0..=1000.0),
+            Parameter::new("decay", &mut self.decay, 10.0..=5000.0),
+        ]
+    }
+}
+
+/// Filtered noise with a decaying amplitude, the basis of the noise based drums.
+struct NoiseVoice {
+    filter_type: Type<f32>,
+    cutoff: f32,
+    decay: f32,
+    amp: Decay,
+    filter: Option<DirectForm1<f32>>,
+}
+
+impl NoiseVoice {
+    fn new(filter_type: Type<f32>, cutoff: f32, decay: f32) -> Self {
+        Self {
+            filter_type,
+            cutoff,
+            decay,
+            amp: Decay::default(),
+            filter: None,
+        }
+    }
+
+    fn next(&mut self, trigger: bool, sample_rate: u32) -> f32 {
+        if self.amp.trigger(trigger) || self.filter.is_none() {
+            self.filter =
+                filter(self.filter_type, self.cutoff, sample_rate).map(DirectForm1::<f32>::new);
+        }
+
+        let noise = rand::thread_rng().gen_range(-1.0..=1.0);
+        let noise = self
+            .filter
+            .as_mut()
+            .map(|filter| filter.run(noise))
+            .unwrap_or(noise);
+
+        noise * self.amp.next(self.decay, sample_rate)
+    }
+}
+
+/// A snare drum [`Module`], a tuned body with a burst of filtered noise, each with its own decay.
+pub struct Snare {
+    /// Frequency of the body in Hz.
+    tune: f32,
+    body_decay: f32,
+    /// Level of the noise against the body, from 0 (only body) to 1 (only noise).
+    snappy: f32,
+    body: Decay,
+    phase: f32,
+    noise: NoiseVoice,
+}
+
+impl Default for Snare {
+    fn default() -> Self {
+        Self {
+            tune: 180.0,
+            body_decay: 120.0,
+            snappy: 0.6,
+            body: Decay::default(),
+            phase: 0.0,
+            noise: NoiseVoice::new(Type::BandPass, 1500.0, 200.0),
+        }
+    }
+}
+
+impl Module for Snare {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Snare")
+            .icon("🥁")
+            .category(Category::Source)
+            .tooltip("a snare drum, a tuned body with a burst of filtered noise")
+            .example(example::<Self>)
+            .port(
+                PortDescription::<DrumTriggerInput>::input()
+                    .tooltip("restarts the drum when it goes high"),
+            )
+            .port(PortDescription::<DrumOutput>::output().tooltip("the drum sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let trigger = ctx.get_input::<DrumTriggerInput>();
+        let sample_rate = ctx.sample_rate();
+
+        if self.body.trigger(trigger) {
+            self.phase = 0.0;
+        }
+        self.phase = (self.phase + self.tune / sample_rate as f32) % 1.0;
+        let body = (self.phase * 2.0 * PI).sin() * self.body.next(self.body_decay, sample_rate);
+
+        let noise = self.noise.next(trigger, sample_rate);
+        ctx.set_output::<DrumOutput>(body * (1.0 - self.snappy) + noise * self.snappy)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            drag(ui, "tune:", &mut self.tune, 80.0..=400.0, " Hz");
+            drag(
+                ui,
+                "body decay:",
+                &mut self.body_decay,
+                10.0..=1000.0,
+                " ms",
+            );
+        });
+        ui.horizontal(|ui| {
+            drag(ui, "tone:", &mut self.noise.cutoff, 100.0..=10000.0, " Hz");
+            drag(ui, "decay:", &mut self.noise.decay, 10.0..=2000.0, " ms");
+        });
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("snappy:"));
+            ui.add(egui::Slider::new(&mut self.snappy, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("tune", &mut self.tune, 80.0..=400.0),
+            Parameter::new("body decay", &mut self.body_decay, 10.0..=1000.0),
+            Parameter::new("tone", &mut self.noise.cutoff, 100.0..=10000.0),
+            Parameter::new("decay", &mut self.noise.decay, 10.0..=2000.0),
+            Parameter::new("snappy", &mut self.snappy, 0.0..=1.0),
+        ]
+    }
+}
+
+/// A noise based hi-hat [`Module`], open or closed depending on its decay.
+pub struct Hat {
+    noise: NoiseVoice,
+}
+
+impl Default for Hat {
+    fn default() -> Self {
+        Self {
+            noise: NoiseVoice::new(Type::HighPass, 7000.0, 60.0),
+        }
+    }
+}
+
+impl Module for Hat {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Hat")
+            .icon("🥁")
+            .category(Category::Source)
+            .tooltip("a noise based hi-hat, open or closed depending on its decay")
+            .example(example::<Self>)
+            .port(
+                PortDescription::<DrumTriggerInput>::input()
+                    .tooltip("restarts the drum when it goes high"),
+            )
+            .port(PortDescription::<DrumOutput>::output().tooltip("the drum sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let trigger = ctx.get_input::<DrumTriggerInput>();
+        ctx.set_output::<DrumOutput>(self.noise.next(trigger, ctx.sample_rate()))
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            drag(ui, "tone:", &mut self.noise.cutoff, 1000.0..=15000.0, " Hz");
+            drag(ui, "decay:", &mut self.noise.decay, 5.0..=2000.0, " ms");
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("tone", &mut self.noise.cutoff, 1000.0..=15000.0),
+            Parameter::new("decay", &mut self.noise.decay, 5.0..=2000.0),
+        ]
+    }
+}
+
+/// Plays a drum with a sequencer.
+fn example<D: Module>(rack: &mut Rack, panel: usize) {
+    let sequencer = rack.add_module_to::<Sequencer>(panel);
+    let drum = rack.add_module_to::<D>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<SequencerGateOutput, DrumTriggerInput>(sequencer, drum)
+        .unwrap();
+    rack.connect_typed::<DrumOutput, AudioInput>(drum, audio)
+        .unwrap();
+}
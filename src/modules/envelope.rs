@@ -0,0 +1,449 @@
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        keyboard::{Keyboard, KeyboardPressedOutput},
+        scope::{Scope, ScopeInput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    transport::SyncedTime,
+    util::EnumIter,
+};
+
+/// Longest time of a stage in ms.
+const MAX_TIME: f32 = 60000.0;
+
+pub struct EnvelopeGateInput;
+
+impl Port for EnvelopeGateInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "gate"
+    }
+}
+
+impl Input for EnvelopeGateInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct AttackInput;
+
+impl Port for AttackInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "attack"
+    }
+}
+
+impl Input for AttackInput {
+    fn default() -> Self::Type {
+        10.0
+    }
+}
+
+pub struct DecayInput;
+
+impl Port for DecayInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "decay"
+    }
+}
+
+impl Input for DecayInput {
+    fn default() -> Self::Type {
+        200.0
+    }
+}
+
+pub struct SustainInput;
+
+impl Port for SustainInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "sustain"
+    }
+}
+
+impl Input for SustainInput {
+    fn default() -> Self::Type {
+        0.6
+    }
+}
+
+pub struct ReleaseInput;
+
+impl Port for ReleaseInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "release"
+    }
+}
+
+impl Input for ReleaseInput {
+    fn default() -> Self::Type {
+        300.0
+    }
+}
+
+pub struct EnvelopeOutput;
+
+impl Port for EnvelopeOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+enum Shape {
+    Linear,
+    Exponential,
+    Logarithmic,
+}
+
+impl Shape {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Shape::Linear => "linear",
+            Shape::Exponential => "exp",
+            Shape::Logarithmic => "log",
+        }
+    }
+
+    /// Maps the linear progress of a stage onto this shape.
+    fn apply(&self, t: f32) -> f32 {
+        match self {
+            Shape::Linear => t,
+            Shape::Exponential => t * t * t,
+            Shape::Logarithmic => 1.0 - (1.0 - t).powi(3),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+enum Retrigger {
+    /// Restart the attack from zero.
+    Reset,
+    /// Restart the attack from the current level.
+    Legato,
+    /// Ignore new gates until the release has finished.
+    Ignore,
+}
+
+impl Retrigger {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Retrigger::Reset => "reset",
+            Retrigger::Legato => "legato",
+            Retrigger::Ignore => "ignore",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    shapes: [Shape; 3],
+    times: [SyncedTime; 3],
+    retrigger: Retrigger,
+    looping: bool,
+}
+
+/// An ADSR envelope generator [`Module`] with shaped stages and an optional loop.
+pub struct Envelope {
+    attack_shape: Shape,
+    decay_shape: Shape,
+    release_shape: Shape,
+    /// Attack, decay and release times while their inputs are unconnected, unless synced.
+    times: [SyncedTime; 3],
+    /// Sustain level while the sustain input is unconnected.
+    sustain: f32,
+    retrigger: Retrigger,
+    looping: bool,
+    stage: Stage,
+    /// Progress of the current stage from 0 to 1.
+    progress: f32,
+    /// Level at the start of the current stage.
+    start: f32,
+    level: f32,
+    last_gate: bool,
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self {
+            attack_shape: Shape::Linear,
+            decay_shape: Shape::Logarithmic,
+            release_shape: Shape::Logarithmic,
+            times: [
+                SyncedTime::new(AttackInput::default()),
+                SyncedTime::new(DecayInput::default()),
+                SyncedTime::new(ReleaseInput::default()),
+            ],
+            sustain: SustainInput::default(),
+            retrigger: Retrigger::Reset,
+            looping: false,
+            stage: Stage::Idle,
+            progress: 0.0,
+            start: 0.0,
+            level: 0.0,
+            last_gate: false,
+        }
+    }
+}
+
+impl Envelope {
+    fn enter(&mut self, stage: Stage) {
+        self.stage = stage;
+        self.progress = 0.0;
+        self.start = self.level;
+    }
+
+    /// Advances the current stage, returns true when the stage has finished.
+    fn advance(&mut self, duration: f32, target: f32, shape: Shape, sample_rate: u32) -> bool {
+        let samples = duration / 1000.0 * sample_rate as f32;
+        self.progress = if samples < 1.0 {
+            1.0
+        } else {
+            (self.progress + 1.0 / samples).min(1.0)
+        };
+
+        self.level = self.start + (target - self.start) * shape.apply(self.progress);
+        self.progress >= 1.0
+    }
+}
+
+impl Module for Envelope {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Envelope")
+            .icon("📐")
+            .category(Category::Modulation)
+            .tooltip(
+                "an ADSR envelope, rises while the gate is high and falls after it is released",
+            )
+            .example(example)
+            .port(
+                PortDescription::<EnvelopeGateInput>::input()
+                    .tooltip("starts the attack when going high and the release when going low"),
+            )
+            .port(
+                PortDescription::<AttackInput>::input()
+                    .tooltip("time to rise to the peak")
+                    .normalled(|envelope: &Self| envelope.times[0].ms)
+                    .group("stages"),
+            )
+            .port(
+                PortDescription::<DecayInput>::input()
+                    .tooltip("time to fall from the peak to the sustain level")
+                    .normalled(|envelope: &Self| envelope.times[1].ms)
+                    .group("stages"),
+            )
+            .port(
+                PortDescription::<SustainInput>::input()
+                    .tooltip("level held while the gate stays high")
+                    .normalled(|envelope: &Self| envelope.sustain)
+                    .group("stages"),
+            )
+            .port(
+                PortDescription::<ReleaseInput>::input()
+                    .tooltip("time to fall to zero after the gate is released")
+                    .normalled(|envelope: &Self| envelope.times[2].ms)
+                    .group("stages"),
+            )
+            .port(
+                PortDescription::<EnvelopeOutput>::output()
+                    .tooltip("the envelope level from 0 to 1"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let gate = ctx.get_input::<EnvelopeGateInput>();
+        let sample_rate = ctx.sample_rate();
+        let sustain = ctx.get_input::<SustainInput>().clamp(0.0, 1.0);
+        let inputs = [
+            ctx.get_input::<AttackInput>(),
+            ctx.get_input::<DecayInput>(),
+            ctx.get_input::<ReleaseInput>(),
+        ];
+        let [attack, decay, release] = [0, 1, 2].map(|i| {
+            SyncedTime {
+                ms: inputs[i],
+                ..self.times[i]
+            }
+            .as_ms(ctx.transport())
+        });
+
+        if gate && !self.last_gate {
+            match self.retrigger {
+                Retrigger::Reset => {
+                    self.level = 0.0;
+                    self.enter(Stage::Attack)
+                }
+                Retrigger::Legato => self.enter(Stage::Attack),
+                Retrigger::Ignore => {
+                    if self.stage == Stage::Idle {
+                        self.enter(Stage::Attack)
+                    }
+                }
+            }
+        } else if !gate && self.last_gate && self.stage != Stage::Idle {
+            self.enter(Stage::Release)
+        }
+        self.last_gate = gate;
+
+        match self.stage {
+            Stage::Idle => self.level = 0.0,
+            Stage::Attack => {
+                if self.advance(attack, 1.0, self.attack_shape, sample_rate) {
+                    self.enter(Stage::Decay)
+                }
+            }
+            Stage::Decay => {
+                if self.advance(decay, sustain, self.decay_shape, sample_rate) {
+                    if self.looping {
+                        self.enter(Stage::Release)
+                    } else {
+                        self.enter(Stage::Sustain)
+                    }
+                }
+            }
+            Stage::Sustain => self.level = sustain,
+            Stage::Release => {
+                if self.advance(release, 0.0, self.release_shape, sample_rate) {
+                    if self.looping && gate {
+                        self.enter(Stage::Attack)
+                    } else {
+                        self.enter(Stage::Idle)
+                    }
+                }
+            }
+        }
+
+        ctx.set_output::<EnvelopeOutput>(self.level)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        for ((name, shape), time) in [
+            ("attack", &mut self.attack_shape),
+            ("decay", &mut self.decay_shape),
+            ("release", &mut self.release_shape),
+        ]
+        .into_iter()
+        .zip(&mut self.times)
+        {
+            ui.horizontal(|ui| {
+                let label = ui.label(tr(name));
+                egui::ComboBox::from_id_source((ctx.instance, name))
+                    .selected_text(tr(shape.as_str()))
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for option in Shape::iter() {
+                            ui.selectable_value(shape, option, tr(option.as_str()));
+                        }
+                    });
+                time.show_range((ctx.instance, name, "time"), 0.0..=MAX_TIME, ui)
+                    .labelled_by(label.id);
+            });
+        }
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("sustain"));
+            ui.add(
+                egui::DragValue::new(&mut self.sustain)
+                    .clamp_range(0.0..=1.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::new((ctx.instance, "retrigger"), "retrigger")
+                .selected_text(tr(self.retrigger.as_str()))
+                .show_ui(ui, |ui| {
+                    for option in Retrigger::iter() {
+                        ui.selectable_value(&mut self.retrigger, option, tr(option.as_str()));
+                    }
+                });
+
+            ui.checkbox(&mut self.looping, tr("loop"))
+                .on_hover_text_at_pointer(tr(
+                    "cycle attack, decay and release while the gate is held",
+                ));
+
+            ui.add(
+                egui::ProgressBar::new(self.level.clamp(0.0, 1.0))
+                    .desired_width(60.0)
+                    .text(format!("{:.2}", self.level)),
+            );
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        let [attack, decay, release] = &mut self.times;
+        vec![
+            Parameter::new("attack", &mut attack.ms, 0.0..=MAX_TIME),
+            Parameter::new("decay", &mut decay.ms, 0.0..=MAX_TIME),
+            Parameter::new("sustain", &mut self.sustain, 0.0..=1.0),
+            Parameter::new("release", &mut release.ms, 0.0..=MAX_TIME),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            shapes: [self.attack_shape, self.decay_shape, self.release_shape],
+            times: self.times,
+            retrigger: self.retrigger,
+            looping: self.looping,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            [self.attack_shape, self.decay_shape, self.release_shape] = state.shapes;
+            self.times = state.times;
+            self.retrigger = state.retrigger;
+            self.looping = state.looping;
+        }
+    }
+}
+
+/// Shows the envelope on a scope, triggered by holding keys of a keyboard.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let envelope = rack.add_module_to::<Envelope>(panel);
+    let scope = rack.add_module_to::<Scope>(panel);
+    rack.connect_typed::<KeyboardPressedOutput, EnvelopeGateInput>(keyboard, envelope)
+        .unwrap();
+    rack.connect_typed::<EnvelopeOutput, ScopeInput>(envelope, scope)
+        .unwrap();
+}
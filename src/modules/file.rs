@@ -6,9 +6,14 @@ use std::{
     sync::mpsc::{Receiver, Sender},
 };
 
-use eframe::egui::{Slider, Ui};
+use eframe::{
+    egui::{self, Slider, Ui},
+    epaint::{Color32, Pos2},
+};
+use egui_plot::{Line, Plot, PlotPoint, VLine};
 use rfd::FileDialog;
 use rubato::{FftFixedIn, Resampler};
+use serde::{Deserialize, Serialize};
 use symphonia::core::{
     audio::SampleBuffer,
     codecs::DecoderOptions,
@@ -17,13 +22,65 @@ use symphonia::core::{
     meta::MetadataOptions,
     probe::Hint,
 };
+use tracing::{error, warn};
 
 use crate::{
     frame::Frame,
-    module::{Module, ModuleDescription, Port, PortDescription},
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    onset,
     rack::rack::{ProcessContext, ShowContext},
+    toast,
+    transport::Transport,
 };
 
+/// Distance in points within which a slice marker can be grabbed.
+const GRAB_RADIUS: f32 = 6.0;
+
+pub struct FileTriggerInput;
+
+impl Port for FileTriggerInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "trigger"
+    }
+}
+
+impl Input for FileTriggerInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct FileSliceInput;
+
+impl Port for FileSliceInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "slice"
+    }
+}
+
+impl Input for FileSliceInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.add(
+            egui::DragValue::new(value)
+                .speed(0.1)
+                .clamp_range(0.0..=f32::MAX),
+        );
+    }
+}
+
 pub struct FileOutput;
 
 impl Port for FileOutput {
@@ -35,19 +92,44 @@ impl Port for FileOutput {
 }
 
 enum Message {
-    Decoded(Option<Vec<Frame>>),
+    /// The decoded file together with the onsets detected in it.
+    Decoded(Option<Vec<Frame>>, Vec<usize>),
     PickedFile(PathBuf),
 }
 
+#[derive(Serialize, Deserialize)]
+struct State {
+    sync: bool,
+    bars: u32,
+    markers: Vec<usize>,
+}
+
 /// A [`Module`] that decodes and plays files
 pub struct File {
     pub buffer: Vec<Frame>,
     pub seek: usize,
     pub playing: bool,
+    gain: f32,
     path: String,
     sender: Sender<Message>,
     receiver: Receiver<Message>,
     loading: bool,
+    /// Whether playback is locked to the transport, looping the file over [`Self::bars`].
+    sync: bool,
+    bars: u32,
+    /// Start positions of the slices, in samples and sorted.
+    markers: Vec<usize>,
+    /// Markers loaded from a patch, used instead of the detected onsets once the file is decoded.
+    loaded_markers: Option<Vec<usize>>,
+    /// Tempo estimated from the detected onsets.
+    bpm: Option<f32>,
+    /// Where the playing slice ends, `None` plays until the end of the file.
+    slice_end: Option<usize>,
+    last_trigger: bool,
+    /// The marker being dragged in the slice editor.
+    dragging: Option<usize>,
+    /// Peaks of the buffer at a low resolution, drawn in the slice editor.
+    overview: Vec<[f64; 2]>,
 }
 
 impl Default for File {
@@ -57,17 +139,41 @@ impl Default for File {
             buffer: Vec::new(),
             seek: 0,
             playing: false,
+            gain: 1.0,
             path: String::new(),
             sender,
             receiver,
             loading: false,
+            sync: false,
+            bars: 1,
+            markers: Vec::new(),
+            loaded_markers: None,
+            bpm: None,
+            slice_end: None,
+            last_trigger: false,
+            dragging: None,
+            overview: Vec::new(),
         }
     }
 }
 
 impl File {
+    /// Interpolates between the samples of a buffer around a fractional position, wrapping at the
+    /// end. Also used by the other modules playing decoded files.
+    pub fn sample_at(buffer: &[Frame], position: f64) -> Frame {
+        let index = position as usize % buffer.len();
+        let next = (index + 1) % buffer.len();
+        let fract = position.fract() as f32;
+
+        buffer[index] * (1.0 - fract) + buffer[next] * fract
+    }
+
     pub fn decode(path: impl AsRef<Path>, target_sample_rate: usize) -> Option<Vec<Frame>> {
-        let file = std::fs::File::open(&path).ok()?;
+        let file = std::fs::File::open(&path)
+            .inspect_err(
+                |err| error!(path = %path.as_ref().display(), "could not open file: {err}"),
+            )
+            .ok()?;
 
         let source = MediaSourceStream::new(Box::new(file), MediaSourceStreamOptions::default());
 
@@ -83,6 +189,7 @@ impl File {
                 &FormatOptions::default(),
                 &MetadataOptions::default(),
             )
+            .inspect_err(|err| error!("unsupported format: {err}"))
             .ok()?;
 
         let mut format = probe.format;
@@ -94,6 +201,7 @@ impl File {
 
         let mut decoder = symphonia::default::get_codecs()
             .make(&track.codec_params, &DecoderOptions::default())
+            .inspect_err(|err| error!("unsupported codec: {err}"))
             .ok()?;
 
         let track_id = track.id;
@@ -105,16 +213,17 @@ impl File {
             let packet = match format.next_packet() {
                 Ok(packet) => packet,
                 Err(symphonia::core::errors::Error::ResetRequired) => {
+                    error!("decoding requires a reset, which is not supported");
                     return None;
                 }
                 Err(symphonia::core::errors::Error::IoError(err)) => {
                     if err.kind() != ErrorKind::UnexpectedEof {
-                        dbg!(err);
+                        warn!("could not read packet: {err}");
                     }
                     break;
                 }
                 Err(err) => {
-                    dbg!(err);
+                    warn!("could not read packet: {err}");
                     break;
                 }
             };
@@ -137,22 +246,22 @@ impl File {
                     buffer.extend(sample_buffer.samples());
                 }
                 Err(symphonia::core::errors::Error::IoError(err)) => {
-                    dbg!(err);
+                    warn!("could not read packet: {err}");
                     continue;
                 }
                 Err(symphonia::core::errors::Error::DecodeError(err)) => {
-                    dbg!(err);
+                    warn!("skipped malformed packet: {err}");
                     continue;
                 }
                 Err(err) => {
-                    eprintln!("{}", err);
+                    error!("could not decode: {err}");
                     return None;
                 }
             }
         }
 
         let channels = spec.unwrap().channels.count();
-        let mut separated: Vec<Vec<f32>> = (0..channels).into_iter().map(|_| Vec::new()).collect();
+        let mut separated: Vec<Vec<f32>> = (0..channels).map(|_| Vec::new()).collect();
 
         for (i, sample) in buffer.into_iter().enumerate() {
             separated[i % channels].push(sample)
@@ -185,7 +294,6 @@ impl File {
         Some(buffer)
     }
 
-    #[allow(dead_code)]
     pub fn open_file(&self, path: impl AsRef<Path>) {
         self.sender
             .send(Message::PickedFile(path.as_ref().into()))
@@ -198,9 +306,134 @@ impl File {
             let sender = self.sender.clone();
             let path = self.path.clone();
             move || {
-                sender
-                    .send(Message::Decoded(Self::decode(&path, sample_rate)))
-                    .ok();
+                let buffer = Self::decode(&path, sample_rate);
+                let onsets = buffer
+                    .as_deref()
+                    .map(|buffer| onset::detect(buffer, sample_rate as u32))
+                    .unwrap_or_default();
+                sender.send(Message::Decoded(buffer, onsets)).ok();
+            }
+        });
+    }
+
+    /// Length of the loop in samples when synced to the transport.
+    fn loop_samples(&self, transport: &Transport, sample_rate: u32) -> f64 {
+        (self.bars * transport.beats_per_bar) as f64
+            * transport.samples_per_beat(sample_rate) as f64
+    }
+
+    /// Uses detected onsets as the slice markers and estimates the tempo from them.
+    fn set_onsets(&mut self, onsets: Vec<usize>, sample_rate: u32) {
+        self.bpm = onset::estimate_bpm(&onsets, sample_rate);
+        self.markers = onsets;
+        self.dragging = None;
+    }
+
+    /// Starts playing a slice, the whole file if there are no markers.
+    fn play_slice(&mut self, slice: usize) {
+        if self.markers.is_empty() {
+            self.seek = 0;
+            self.slice_end = None;
+        } else {
+            let slice = slice.min(self.markers.len() - 1);
+            self.seek = self.markers[slice];
+            self.slice_end = self.markers.get(slice + 1).copied();
+        }
+        self.playing = true;
+    }
+
+    fn update_overview(&mut self) {
+        const RESOLUTION: usize = 500;
+        let chunk = (self.buffer.len() / RESOLUTION).max(1);
+
+        self.overview = self
+            .buffer
+            .chunks(chunk)
+            .enumerate()
+            .map(|(i, frames)| {
+                let peak = frames
+                    .iter()
+                    .map(|frame| frame.as_f32_mono().abs())
+                    .fold(0.0, f32::max);
+                [(i * chunk) as f64, peak as f64]
+            })
+            .collect();
+    }
+
+    fn closest_marker(&self, plot: &egui_plot::PlotUi, pos: Pos2) -> Option<usize> {
+        self.markers
+            .iter()
+            .enumerate()
+            .map(|(i, &marker)| {
+                let screen = plot.screen_from_plot(PlotPoint::new(marker as f64, 0.0));
+                (i, (screen.x - pos.x).abs())
+            })
+            .filter(|(_, distance)| *distance < GRAB_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Draws the waveform with the slice markers. Drag markers to move them, click to add and right
+    /// click to remove.
+    fn show_slices(&mut self, ui: &mut Ui) {
+        let len = self.buffer.len() as f64;
+
+        let plot = Plot::new("slices")
+            .height(60.0)
+            .show_axes(false)
+            .show_grid(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .allow_drag(false)
+            .allow_double_click_reset(false)
+            .include_x(0.0)
+            .include_x(len)
+            .include_y(0.0)
+            .include_y(1.0);
+
+        plot.show(ui, |plot| {
+            let response = plot.response().clone();
+
+            if let Some(pos) = response.interact_pointer_pos().or(response.hover_pos()) {
+                let position = plot.plot_from_screen(pos).x.clamp(0.0, len - 1.0) as usize;
+
+                if response.drag_started() {
+                    self.dragging = self.closest_marker(plot, pos);
+                }
+
+                if let Some(index) = self.dragging.filter(|_| response.dragged()) {
+                    let min = index.checked_sub(1).map_or(0, |i| self.markers[i] + 1);
+                    let max = self
+                        .markers
+                        .get(index + 1)
+                        .map_or(len as usize, |&next| next)
+                        - 1;
+                    self.markers[index] = position.clamp(min, max.max(min));
+                } else if response.clicked() && self.closest_marker(plot, pos).is_none() {
+                    let index = self.markers.partition_point(|&marker| marker < position);
+                    if self.markers.get(index) != Some(&position) {
+                        self.markers.insert(index, position);
+                    }
+                } else if response.secondary_clicked() {
+                    if let Some(index) = self.closest_marker(plot, pos) {
+                        self.markers.remove(index);
+                    }
+                }
+            }
+
+            if response.drag_stopped() {
+                self.dragging = None;
+            }
+
+            plot.line(Line::new(self.overview.clone()).color(Color32::LIGHT_GREEN));
+
+            for &marker in self.markers.iter() {
+                plot.vline(VLine::new(marker as f64).color(Color32::GOLD));
+            }
+
+            if self.playing {
+                plot.vline(VLine::new(self.seek as f64).color(Color32::WHITE));
             }
         });
     }
@@ -229,34 +462,84 @@ impl Module for File {
         Self: Sized,
     {
         ModuleDescription::default()
-            .name("📁 File")
-            .port(PortDescription::<FileOutput>::output())
+            .name("File")
+            .icon("📁")
+            .category(Category::Source)
+            .tooltip("decodes and plays an audio file, which can be cut into slices that are played by a trigger")
+            .port(
+                PortDescription::<FileTriggerInput>::input()
+                    .tooltip("plays the slice chosen by the slice input on each rising edge"),
+            )
+            .port(
+                PortDescription::<FileSliceInput>::input()
+                    .tooltip("index of the slice to play, starting at 0"),
+            )
+            .port(PortDescription::<FileOutput>::output().tooltip("the decoded sound"))
     }
 
     fn process(&mut self, ctx: &mut ProcessContext) {
-        let frame = if self.playing {
-            if self.seek < self.buffer.len() {
+        let trigger = ctx.get_input::<FileTriggerInput>();
+        if trigger && !self.last_trigger && !self.sync {
+            self.play_slice(ctx.get_input::<FileSliceInput>().round() as usize);
+        }
+        self.last_trigger = trigger;
+
+        let frame = if self.sync {
+            let transport = ctx.transport();
+            if self.playing && transport.playing && !self.buffer.is_empty() {
+                let beats = (self.bars * transport.beats_per_bar) as f64;
+                let position =
+                    transport.position().rem_euclid(beats) / beats * self.buffer.len() as f64;
+                self.seek = position as usize;
+                Self::sample_at(&self.buffer, position)
+            } else {
+                Frame::default()
+            }
+        } else if self.playing {
+            let end = self
+                .slice_end
+                .unwrap_or(self.buffer.len())
+                .min(self.buffer.len());
+            if self.seek < end {
                 self.seek += 1;
                 self.buffer.get(self.seek - 1).copied().unwrap()
             } else {
                 self.playing = false;
                 self.seek = 0;
+                self.slice_end = None;
                 Frame::default()
             }
         } else {
             Frame::default()
         };
 
-        ctx.set_output::<FileOutput>(frame);
+        ctx.set_output::<FileOutput>(frame * self.gain);
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("gain", &mut self.gain, 0.0..=2.0)]
+    }
+
+    fn asset(&self) -> Option<String> {
+        (!self.path.is_empty()).then(|| self.path.clone())
+    }
+
+    fn load_asset(&mut self, path: &str) {
+        self.open_file(path)
     }
 
     fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
         let messages = self.receiver.try_iter().collect::<Vec<_>>();
         for message in messages {
             match message {
-                Message::Decoded(buffer) => {
+                Message::Decoded(buffer, onsets) => {
                     if let Some(buffer) = buffer {
                         self.buffer = buffer;
+                        self.update_overview();
+                        let onsets = self.loaded_markers.take().unwrap_or(onsets);
+                        self.set_onsets(onsets, ctx.sample_rate);
+                    } else {
+                        toast::error(format!("{} {}", tr("could not load:"), self.path));
                     }
                     self.loading = false
                 }
@@ -277,7 +560,7 @@ impl Module for File {
                 self.update(ctx.sample_rate as usize);
             }
 
-            if ui.button("pick").clicked() {
+            if ui.button(tr("pick")).clicked() {
                 self.open_picker()
             }
 
@@ -286,10 +569,16 @@ impl Module for File {
             }
         });
 
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("gain:"));
+            ui.add(Slider::new(&mut self.gain, 0.0..=2.0))
+                .labelled_by(label.id);
+        });
+
         ui.horizontal(|ui| {
             let progress = self.seek as f32 / ctx.sample_rate as f32;
             let total = self.buffer.len() as f32 / ctx.sample_rate as f32;
-            ui.label(format!(
+            let time = ui.label(format!(
                 "{:02}:{:02}.{:02}/{:02}:{:02}.{:02}",
                 (progress as u32 / 60) % 60,
                 progress as u32 % 60,
@@ -304,10 +593,12 @@ impl Module for File {
 
                 let mut seek = self.seek;
 
-                let response = ui.add_enabled(
-                    !self.buffer.is_empty(),
-                    Slider::new(&mut seek, 0..=self.buffer.len().max(1)).show_value(false),
-                );
+                let response = ui
+                    .add_enabled(
+                        !self.buffer.is_empty(),
+                        Slider::new(&mut seek, 0..=self.buffer.len().max(1)).show_value(false),
+                    )
+                    .labelled_by(time.id);
 
                 if response.drag_stopped() {
                     self.seek = seek;
@@ -315,7 +606,44 @@ impl Module for File {
             });
         });
 
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.sync, tr("sync to tempo"))
+                .on_hover_text(tr(
+                    "loop the file over a number of bars, retuning it to fit",
+                ));
+            ui.add_enabled_ui(self.sync, |ui| {
+                let label = ui.label(tr("bars:"));
+                ui.add(egui::DragValue::new(&mut self.bars).clamp_range(1..=64))
+                    .labelled_by(label.id);
+
+                if !self.buffer.is_empty() {
+                    let speed = self.buffer.len() as f64
+                        / self.loop_samples(&ctx.transport, ctx.sample_rate);
+                    ui.label(format!("{speed:.2}×"))
+                        .on_hover_text(tr("playback speed"));
+                }
+            });
+        });
+
         if !self.buffer.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} {}", self.markers.len(), tr("slices")));
+                if let Some(bpm) = self.bpm {
+                    ui.label(format!("≈ {bpm:.0} bpm"))
+                        .on_hover_text(tr("tempo estimated from the detected onsets"));
+                }
+
+                if ui.button(tr("detect")).clicked() {
+                    let onsets = onset::detect(&self.buffer, ctx.sample_rate);
+                    self.set_onsets(onsets, ctx.sample_rate);
+                }
+                if ui.button(tr("clear")).clicked() {
+                    self.markers.clear();
+                }
+            });
+
+            self.show_slices(ui);
+
             ui.horizontal(|ui| {
                 let size = std::mem::size_of_val(self.buffer.as_slice());
 
@@ -330,4 +658,21 @@ impl Module for File {
             });
         }
     }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            sync: self.sync,
+            bars: self.bars,
+            markers: self.markers.clone(),
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.sync = state.sync;
+            self.bars = state.bars.clamp(1, 64);
+            self.loaded_markers = (!state.markers.is_empty()).then_some(state.markers);
+        }
+    }
 }
@@ -1,11 +1,17 @@
 use biquad::{Biquad, DirectForm1, ToHertz};
 use eframe::egui::{self, Ui};
 use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     frame::Frame,
-    module::{Input, Module, ModuleDescription, Port, PortDescription},
-    rack::rack::{ProcessContext, ShowContext},
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        noise::{Noise, NoiseOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
     util::EnumIter,
 };
 
@@ -25,6 +31,22 @@ impl Input for FilterInput {
     }
 }
 
+pub struct FilterCutoffInput;
+
+impl Port for FilterCutoffInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "cutoff"
+    }
+}
+
+impl Input for FilterCutoffInput {
+    fn default() -> Self::Type {
+        50.0
+    }
+}
+
 pub struct FilterOutput;
 
 impl Port for FilterOutput {
@@ -35,8 +57,8 @@ impl Port for FilterOutput {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Sequence)]
-enum FilterType {
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum FilterType {
     LowPass,
     HighPass,
 }
@@ -50,12 +72,19 @@ impl FilterType {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+struct State {
+    filter_type: FilterType,
+}
+
 /// A low or high pass frequency filter [`Module`]
 pub struct Filter {
     left: Option<DirectForm1<f32>>,
     right: Option<DirectForm1<f32>>,
-    filter_type: FilterType,
-    cutoff: f32,
+    pub filter_type: FilterType,
+    pub cutoff: f32,
+    /// The type and cutoff the coefficients were last calculated for.
+    applied: Option<(FilterType, f32)>,
 }
 
 impl Default for Filter {
@@ -65,23 +94,25 @@ impl Default for Filter {
             right: None,
             filter_type: FilterType::LowPass,
             cutoff: 50.0,
+            applied: None,
         }
     }
 }
 
 impl Filter {
-    fn update_coeffs(&mut self, sample_rate: u32) {
+    fn update_coeffs(&mut self, sample_rate: u32, cutoff: f32) {
+        self.applied = Some((self.filter_type, cutoff));
         let coeffs = match self.filter_type {
             FilterType::LowPass => biquad::Coefficients::<f32>::from_params(
                 biquad::Type::LowPass,
                 sample_rate.hz(),
-                self.cutoff.max(1.0).hz(),
+                cutoff.max(1.0).hz(),
                 biquad::Q_BUTTERWORTH_F32,
             ),
             FilterType::HighPass => biquad::Coefficients::<f32>::from_params(
                 biquad::Type::HighPass,
                 sample_rate.hz(),
-                self.cutoff.max(1.0).hz(),
+                cutoff.max(1.0).hz(),
                 biquad::Q_BUTTERWORTH_F32,
             ),
         };
@@ -108,16 +139,27 @@ impl Module for Filter {
         Self: Sized,
     {
         ModuleDescription::default()
-            .name("🕳 Filter")
-            .port(PortDescription::<FilterInput>::input())
-            .port(PortDescription::<FilterOutput>::output())
+            .name("Filter")
+            .icon("🕳")
+            .category(Category::Effect)
+            .tooltip("a low or high pass filter removing frequencies beyond the cutoff")
+            .example(example)
+            .port(PortDescription::<FilterInput>::input().tooltip("the sound to filter"))
+            .port(
+                PortDescription::<FilterCutoffInput>::input()
+                    .tooltip("the cutoff frequency in Hz")
+                    .normalled(|filter: &Self| filter.cutoff),
+            )
+            .port(PortDescription::<FilterOutput>::output().tooltip("the filtered sound"))
     }
 
     fn process(&mut self, ctx: &mut ProcessContext) {
         let mut frame = ctx.get_input::<FilterInput>();
 
-        if self.left.is_none() {
-            self.update_coeffs(ctx.sample_rate())
+        let cutoff = ctx.get_input::<FilterCutoffInput>();
+
+        if self.applied != Some((self.filter_type, cutoff)) {
+            self.update_coeffs(ctx.sample_rate(), cutoff)
         }
 
         frame = match frame {
@@ -133,30 +175,50 @@ impl Module for Filter {
 
     fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            if ui
-                .add(
-                    egui::DragValue::new(&mut self.cutoff)
-                        .clamp_range(10.0..=f32::MAX)
-                        .speed(1.0)
-                        .suffix(" Hz"),
-                )
-                .changed()
-            {
-                self.update_coeffs(ctx.sample_rate)
-            }
+            let label = ui.label(tr("cutoff:"));
+            ui.add(
+                egui::DragValue::new(&mut self.cutoff)
+                    .clamp_range(10.0..=f32::MAX)
+                    .speed(1.0)
+                    .suffix(" Hz"),
+            )
+            .labelled_by(label.id);
 
             egui::ComboBox::from_id_source(ctx.instance)
-                .selected_text(format!("{:?}", self.filter_type.as_str()))
+                .selected_text(tr(self.filter_type.as_str()))
                 .show_ui(ui, |ui| {
                     for filter in FilterType::iter() {
-                        if ui
-                            .selectable_value(&mut self.filter_type, filter, filter.as_str())
-                            .changed()
-                        {
-                            self.update_coeffs(ctx.sample_rate)
-                        }
+                        ui.selectable_value(&mut self.filter_type, filter, tr(filter.as_str()));
                     }
                 });
         });
     }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("cutoff", &mut self.cutoff, 10.0..=20000.0)]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            filter_type: self.filter_type,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.filter_type = state.filter_type;
+        }
+    }
+}
+
+/// Filters noise, change the cutoff to hear the difference.
+fn example(rack: &mut Rack, panel: usize) {
+    let noise = rack.add_module_to::<Noise>(panel);
+    let filter = rack.add_module_to::<Filter>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<NoiseOutput, FilterInput>(noise, filter)
+        .unwrap();
+    rack.connect_typed::<FilterOutput, AudioInput>(filter, audio)
+        .unwrap();
 }
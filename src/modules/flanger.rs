@@ -0,0 +1,218 @@
+use std::f32::consts::TAU;
+
+use eframe::egui::{self, Ui};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        noise::{Noise, NoiseOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Shortest and longest delay in ms the sweep moves between.
+const MIN_TIME: f32 = 0.1;
+const MAX_TIME: f32 = 10.0;
+
+pub struct FlangerInput;
+
+impl Port for FlangerInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for FlangerInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct FlangerSweepInput;
+
+impl Port for FlangerSweepInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "sweep"
+    }
+}
+
+impl Input for FlangerSweepInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+pub struct FlangerOutput;
+
+impl Port for FlangerOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// A [`Module`] mixing its input with a copy delayed by a few sweeping milliseconds, for the
+/// comb filtered whoosh of a jet plane.
+pub struct Flanger {
+    /// Position of the sweep without modulation, from 0 (shortest delay) to 1 (longest).
+    manual: f32,
+    /// Speed of the internal sweep in Hz, used when the sweep input isn't connected.
+    rate: f32,
+    /// How far the sweep moves away from the manual position.
+    depth: f32,
+    /// Part of the delayed signal fed back, negative values invert it for a hollower sound.
+    feedback: f32,
+    mix: f32,
+    phase: f32,
+    buffer: Vec<Frame>,
+    position: usize,
+}
+
+impl Default for Flanger {
+    fn default() -> Self {
+        Self {
+            manual: 0.3,
+            rate: 0.25,
+            depth: 0.3,
+            feedback: 0.5,
+            mix: 0.5,
+            phase: 0.0,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Flanger {
+    /// The sample written `delay` samples ago, interpolated between whole samples.
+    fn read(&self, delay: f32) -> Frame {
+        let size = self.buffer.len();
+        let delay = delay.clamp(1.0, (size - 2) as f32);
+        let whole = delay as usize;
+        let fraction = delay - whole as f32;
+        let newer = self.buffer[(self.position + size - whole) % size];
+        let older = self.buffer[(self.position + size - whole - 1) % size];
+        newer * (1.0 - fraction) + older * fraction
+    }
+}
+
+impl Module for Flanger {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Flanger")
+            .icon("✈")
+            .category(Category::Effect)
+            .tooltip("mixes its input with a copy delayed by a few sweeping milliseconds")
+            .example(example)
+            .port(
+                PortDescription::<FlangerInput>::input()
+                    .tooltip("the sound to flange")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<FlangerSweepInput>::input()
+                    .tooltip("moves the sweep instead of the internal wave, from -1 to 1"),
+            )
+            .port(
+                PortDescription::<FlangerOutput>::output()
+                    .tooltip("the input mixed with its delayed copy"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate() as f32;
+        let size = (MAX_TIME / 1000.0 * sample_rate) as usize + 3;
+        if self.buffer.len() != size {
+            self.buffer = vec![Frame::ZERO; size];
+            self.position = 0;
+        }
+
+        let wave = if ctx.is_connected::<FlangerSweepInput>() {
+            ctx.get_input::<FlangerSweepInput>().clamp(-1.0, 1.0)
+        } else {
+            self.phase = (self.phase + self.rate / sample_rate).fract();
+            (TAU * self.phase).sin()
+        };
+        let sweep = (self.manual + wave * self.depth).clamp(0.0, 1.0);
+        let time = MIN_TIME + sweep * (MAX_TIME - MIN_TIME);
+        let delayed = self.read(time / 1000.0 * sample_rate);
+
+        let input = ctx.get_input::<FlangerInput>();
+        self.buffer[self.position] = input + delayed * self.feedback;
+        self.position = (self.position + 1) % size;
+
+        ctx.set_output::<FlangerOutput>(input * (1.0 - self.mix) + delayed * self.mix)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("manual:"));
+            ui.add(egui::Slider::new(&mut self.manual, 0.0..=1.0))
+                .labelled_by(label.id)
+                .on_hover_text(tr(
+                    "position of the sweep, from the shortest to the longest delay",
+                ));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("rate:"));
+            ui.add(
+                egui::Slider::new(&mut self.rate, 0.01..=10.0)
+                    .logarithmic(true)
+                    .suffix(" Hz"),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("depth:"));
+            ui.add(egui::Slider::new(&mut self.depth, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("feedback:"));
+            ui.add(egui::Slider::new(&mut self.feedback, -0.95..=0.95))
+                .labelled_by(label.id)
+                .on_hover_text(tr("negative values invert the fed back signal"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("mix:"));
+            ui.add(egui::Slider::new(&mut self.mix, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("manual", &mut self.manual, 0.0..=1.0),
+            Parameter::new("rate", &mut self.rate, 0.01..=10.0),
+            Parameter::new("depth", &mut self.depth, 0.0..=1.0),
+            Parameter::new("feedback", &mut self.feedback, -0.95..=0.95),
+            Parameter::new("mix", &mut self.mix, 0.0..=1.0),
+        ]
+    }
+}
+
+/// The whoosh of flanged noise.
+fn example(rack: &mut Rack, panel: usize) {
+    let noise = rack.add_module_to::<Noise>(panel);
+    let flanger = rack.add_module_to::<Flanger>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<NoiseOutput, FlangerInput>(noise, flanger)
+        .unwrap();
+    rack.connect_typed::<FlangerOutput, AudioInput>(flanger, audio)
+        .unwrap();
+}
@@ -0,0 +1,181 @@
+use eframe::egui::Ui;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    curve::Curve,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        keyboard::{Keyboard, KeyboardPressedOutput},
+        scope::{Scope, ScopeInput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    transport::SyncedTime,
+};
+
+pub struct FunctionGateInput;
+
+impl Port for FunctionGateInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "gate"
+    }
+}
+
+impl Input for FunctionGateInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct FunctionOutput;
+
+impl Port for FunctionOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    points: Vec<[f32; 2]>,
+    duration: SyncedTime,
+    looping: bool,
+}
+
+/// A [`Module`] that plays a user drawn curve each time its gate goes high.
+pub struct Function {
+    curve: Curve,
+    /// Duration of the whole curve.
+    duration: SyncedTime,
+    looping: bool,
+    /// Position in the curve from 0 to 1, `None` when idle.
+    phase: Option<f32>,
+    last_gate: bool,
+}
+
+impl Default for Function {
+    fn default() -> Self {
+        Self {
+            curve: Curve::new(vec![[0.0, 0.0], [0.1, 1.0], [0.4, 0.6], [1.0, 0.0]]),
+            duration: SyncedTime::new(1000.0),
+            looping: false,
+            phase: None,
+            last_gate: false,
+        }
+    }
+}
+
+impl Module for Function {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Function")
+            .icon("〰")
+            .category(Category::Modulation)
+            .tooltip("plays a drawn curve each time its gate goes high")
+            .example(example)
+            .port(
+                PortDescription::<FunctionGateInput>::input()
+                    .tooltip("restarts the curve when it goes high"),
+            )
+            .port(
+                PortDescription::<FunctionOutput>::output()
+                    .tooltip("the current value of the curve"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let gate = ctx.get_input::<FunctionGateInput>();
+
+        if gate && !self.last_gate {
+            self.phase = Some(0.0);
+        }
+        self.last_gate = gate;
+
+        let value = if let Some(phase) = self.phase {
+            let value = self.curve.sample(phase);
+            let duration = self.duration.as_ms(ctx.transport()).max(1.0);
+            let step = 1000.0 / (duration * ctx.sample_rate() as f32);
+            let next = phase + step;
+
+            self.phase = if next < 1.0 {
+                Some(next)
+            } else if self.looping && gate {
+                Some(next % 1.0)
+            } else {
+                None
+            };
+
+            value
+        } else {
+            self.curve.sample(1.0)
+        };
+
+        ctx.set_output::<FunctionOutput>(value)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.set_min_width(300.0);
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("duration:"));
+            self.duration
+                .show((ctx.instance, "duration"), ui)
+                .labelled_by(label.id);
+            ui.checkbox(&mut self.looping, tr("loop"))
+                .on_hover_text_at_pointer(tr("repeat while the gate is held"));
+            if ui.button(tr("trigger")).clicked() {
+                self.phase = Some(0.0);
+            }
+        });
+
+        self.curve.show(ctx.instance, ui, 0.0..=1.0, self.phase);
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new(
+            "duration",
+            &mut self.duration.ms,
+            1.0..=60000.0,
+        )]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            points: self.curve.points().to_vec(),
+            duration: self.duration,
+            looping: self.looping,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            if state.points.len() >= 2 {
+                self.curve.set_points(state.points);
+            }
+            self.duration = state.duration;
+            self.looping = state.looping;
+        }
+    }
+}
+
+/// Shows the curve on a scope, triggered by pressing keys of a keyboard.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let function = rack.add_module_to::<Function>(panel);
+    let scope = rack.add_module_to::<Scope>(panel);
+    rack.connect_typed::<KeyboardPressedOutput, FunctionGateInput>(keyboard, function)
+        .unwrap();
+    rack.connect_typed::<FunctionOutput, ScopeInput>(function, scope)
+        .unwrap();
+}
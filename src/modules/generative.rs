@@ -0,0 +1,357 @@
+use eframe::{
+    egui::{self, Ui},
+    epaint::{Color32, Vec2},
+};
+use enum_iterator::Sequence;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        metronome::{Metronome, MetronomeBeatOutput},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::{note_name, EnumIter},
+};
+
+pub struct GenerativeClockInput;
+
+impl Port for GenerativeClockInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "clock"
+    }
+}
+
+impl Input for GenerativeClockInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct GenerativeFreqOutput;
+
+impl Port for GenerativeFreqOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "freq"
+    }
+}
+
+pub struct GenerativeGateOutput;
+
+impl Port for GenerativeGateOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "gate"
+    }
+}
+
+const MAX_STEPS: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum Scale {
+    Major,
+    Minor,
+    Dorian,
+    Pentatonic,
+    Blues,
+    Chromatic,
+}
+
+impl Scale {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scale::Major => "major",
+            Scale::Minor => "minor",
+            Scale::Dorian => "dorian",
+            Scale::Pentatonic => "pentatonic",
+            Scale::Blues => "blues",
+            Scale::Chromatic => "chromatic",
+        }
+    }
+
+    /// Semitones above the root of the notes in an octave.
+    fn intervals(&self) -> &'static [u8] {
+        match self {
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Scale::Pentatonic => &[0, 2, 4, 7, 9],
+            Scale::Blues => &[0, 3, 5, 6, 7, 10],
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+}
+
+/// The settings a pattern was generated with.
+#[derive(Clone, Copy, PartialEq)]
+struct Settings {
+    scale: Scale,
+    root: u8,
+    range: u8,
+    density: f32,
+    length: usize,
+}
+
+/// The settings that aren't parameters, a new pattern is made from them when loaded.
+#[derive(Serialize, Deserialize)]
+struct State {
+    scale: Scale,
+    root: u8,
+    range: u8,
+    length: usize,
+}
+
+/// A [`Module`] that makes up a melody in a scale and plays it on a clock.
+pub struct Generative {
+    scale: Scale,
+    root: u8,
+    /// Octaves above the root the notes are picked from.
+    range: u8,
+    /// Fraction of the steps that play a note, spread evenly over the pattern.
+    density: f32,
+    length: usize,
+    /// Length of the gate as a fraction of the time between clocks.
+    gate: f32,
+    /// The note of each step, `None` for rests.
+    pattern: [Option<u8>; MAX_STEPS],
+    /// Settings of the current pattern, a new one is made when they change.
+    applied: Option<Settings>,
+    step: Option<usize>,
+    last_clock: bool,
+    /// Samples since the last clock and between the last two clocks.
+    elapsed: usize,
+    interval: usize,
+}
+
+impl Default for Generative {
+    fn default() -> Self {
+        Self {
+            scale: Scale::Pentatonic,
+            root: 57,
+            range: 2,
+            density: 0.6,
+            length: MAX_STEPS,
+            gate: 0.5,
+            pattern: [None; MAX_STEPS],
+            applied: None,
+            step: None,
+            last_clock: false,
+            elapsed: 0,
+            interval: 0,
+        }
+    }
+}
+
+impl Generative {
+    fn settings(&self) -> Settings {
+        Settings {
+            scale: self.scale,
+            root: self.root,
+            range: self.range,
+            density: self.density,
+            length: self.length,
+        }
+    }
+
+    /// Makes up a new pattern, with the notes spread in a Euclidean rhythm.
+    fn generate(&mut self) {
+        let settings = self.settings();
+        let mut rng = rand::thread_rng();
+
+        let notes = (0..settings.range)
+            .flat_map(|octave| {
+                settings
+                    .scale
+                    .intervals()
+                    .iter()
+                    .map(move |interval| octave * 12 + interval)
+            })
+            .chain(std::iter::once(settings.range * 12))
+            .map(|offset| settings.root.saturating_add(offset).min(127))
+            .collect::<Vec<_>>();
+
+        let pulses = (settings.density * settings.length as f32).round() as usize;
+        for (index, step) in self.pattern.iter_mut().enumerate() {
+            let active = index < settings.length && (index * pulses) % settings.length < pulses;
+            *step = active.then(|| notes[rng.gen_range(0..notes.len())]);
+        }
+
+        self.applied = Some(settings);
+    }
+}
+
+impl Module for Generative {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Generative")
+            .icon("🎲")
+            .category(Category::Sequencing)
+            .tooltip("makes up a melody in a scale and plays it on a clock")
+            .example(example)
+            .port(
+                PortDescription::<GenerativeClockInput>::input()
+                    .tooltip("moves to the next step when it goes high"),
+            )
+            .port(
+                PortDescription::<GenerativeFreqOutput>::output()
+                    .tooltip("frequency in Hz of the playing note"),
+            )
+            .port(
+                PortDescription::<GenerativeGateOutput>::output()
+                    .tooltip("high while a note plays"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        if self.applied != Some(self.settings()) {
+            self.generate();
+        }
+
+        let clock = ctx.get_input::<GenerativeClockInput>();
+        self.elapsed += 1;
+        if clock && !self.last_clock {
+            self.step = Some(self.step.map_or(0, |step| (step + 1) % self.length));
+            self.interval = self.elapsed;
+            self.elapsed = 0;
+        }
+        self.last_clock = clock;
+
+        let note = self.step.and_then(|step| self.pattern[step]);
+        if let Some(note) = note {
+            ctx.set_output::<GenerativeFreqOutput>(ctx.tuning().note_to_freq(note as f32));
+        }
+
+        let gate = note.is_some() && (self.elapsed as f32) < self.interval as f32 * self.gate;
+        ctx.set_output::<GenerativeGateOutput>(gate);
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source(ctx.instance)
+                .selected_text(tr(self.scale.as_str()))
+                .show_ui(ui, |ui| {
+                    for scale in Scale::iter() {
+                        ui.selectable_value(&mut self.scale, scale, tr(scale.as_str()));
+                    }
+                });
+            let label = ui.label(tr("root:"));
+            ui.add(
+                egui::DragValue::new(&mut self.root)
+                    .clamp_range(0..=127)
+                    .custom_formatter(|value, _| note_name(value as u8)),
+            )
+            .labelled_by(label.id);
+            let label = ui.label(tr("range:"));
+            ui.add(
+                egui::DragValue::new(&mut self.range)
+                    .clamp_range(1..=4)
+                    .suffix(" oct"),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("density:"));
+            ui.add(egui::Slider::new(&mut self.density, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("length:"));
+            ui.add(egui::DragValue::new(&mut self.length).clamp_range(1..=MAX_STEPS))
+                .labelled_by(label.id);
+            let label = ui.label(tr("gate:"));
+            let mut gate = self.gate * 100.0;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut gate)
+                        .clamp_range(1.0..=100.0)
+                        .suffix("%"),
+                )
+                .labelled_by(label.id)
+                .changed()
+            {
+                self.gate = gate / 100.0;
+            }
+            if ui
+                .button("🎲")
+                .on_hover_text(tr("make up a new pattern"))
+                .clicked()
+            {
+                self.generate();
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing = Vec2::splat(2.0);
+            for (index, note) in self.pattern.iter().enumerate().take(self.length) {
+                let mut button = egui::Button::new(note.map_or("·".to_owned(), note_name))
+                    .min_size(Vec2::new(32.0, 24.0));
+                if note.is_some() {
+                    button = button.fill(Color32::DARK_GREEN);
+                }
+                if self.step == Some(index) {
+                    button = button.stroke((1.0, Color32::GOLD));
+                }
+                ui.add_enabled(false, button);
+            }
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            // a new pattern is made up on every change, ramping would make up many in a row
+            Parameter::new("density", &mut self.density, 0.0..=1.0).smoothing(0.0),
+            Parameter::new("gate", &mut self.gate, 0.01..=1.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            scale: self.scale,
+            root: self.root,
+            range: self.range,
+            length: self.length,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.scale = state.scale;
+            self.root = state.root.min(127);
+            self.range = state.range.clamp(1, 4);
+            self.length = state.length.clamp(1, MAX_STEPS);
+        }
+    }
+}
+
+/// Plays a made up melody on every beat, start the transport to hear it.
+fn example(rack: &mut Rack, panel: usize) {
+    let metronome = rack.add_module_to::<Metronome>(panel);
+    let generative = rack.add_module_to::<Generative>(panel);
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<MetronomeBeatOutput, GenerativeClockInput>(metronome, generative)
+        .unwrap();
+    rack.connect_typed::<GenerativeFreqOutput, FrequencyInput>(generative, oscillator)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, AudioInput>(oscillator, audio)
+        .unwrap();
+}
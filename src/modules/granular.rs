@@ -0,0 +1,388 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    f32::consts::PI,
+    path::PathBuf,
+    sync::mpsc::{Receiver, Sender},
+};
+
+use eframe::egui::{self, Ui};
+use rand::Rng;
+use rfd::FileDialog;
+
+use super::file::File;
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    rack::rack::{ProcessContext, ShowContext},
+    toast,
+};
+
+/// Most grains playing at once, new grains are skipped while this many play.
+const MAX_GRAINS: usize = 64;
+
+pub struct GranularPositionInput;
+
+impl Port for GranularPositionInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "position"
+    }
+}
+
+impl Input for GranularPositionInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+pub struct GranularSizeInput;
+
+impl Port for GranularSizeInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "size"
+    }
+}
+
+impl Input for GranularSizeInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+pub struct GranularDensityInput;
+
+impl Port for GranularDensityInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "density"
+    }
+}
+
+impl Input for GranularDensityInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+pub struct GranularSprayInput;
+
+impl Port for GranularSprayInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "spray"
+    }
+}
+
+impl Input for GranularSprayInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+pub struct GranularPitchInput;
+
+impl Port for GranularPitchInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "pitch"
+    }
+}
+
+impl Input for GranularPitchInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+pub struct GranularOutput;
+
+impl Port for GranularOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+enum Message {
+    Decoded(Option<Vec<Frame>>),
+    PickedFile(PathBuf),
+}
+
+/// A short windowed piece of the buffer being played.
+struct Grain {
+    /// Position in the buffer in samples.
+    position: f32,
+    /// Samples the position moves per output sample.
+    rate: f32,
+    age: usize,
+    length: usize,
+}
+
+/// A [`Module`] playing a sound as a cloud of short overlapping grains.
+pub struct Granular {
+    buffer: Vec<Frame>,
+    path: String,
+    /// Where grains start in the buffer, from 0 to 1.
+    position: f32,
+    /// Length of a grain in ms.
+    size: f32,
+    /// Grains started per second.
+    density: f32,
+    /// How far grains start randomly away from the position, as part of the buffer.
+    spray: f32,
+    /// Pitch of the grains in semitones.
+    pitch: f32,
+    grains: Vec<Grain>,
+    /// Progress towards starting the next grain.
+    phase: f32,
+    loading: bool,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl Default for Granular {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            buffer: Vec::new(),
+            path: String::new(),
+            position: 0.5,
+            size: 100.0,
+            density: 20.0,
+            spray: 0.05,
+            pitch: 0.0,
+            grains: Vec::with_capacity(MAX_GRAINS),
+            phase: 0.0,
+            loading: false,
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Granular {
+    fn load(&mut self, sample_rate: usize) {
+        self.loading = true;
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            let path = self.path.clone();
+            move || {
+                sender
+                    .send(Message::Decoded(File::decode(&path, sample_rate)))
+                    .ok();
+            }
+        });
+    }
+
+    fn open_picker(&self) {
+        let dialog = FileDialog::new().add_filter("audio", &["mp3", "wav", "flac", "ogg"]);
+
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            move || {
+                if let Some(path) = dialog.pick_file() {
+                    sender.send(Message::PickedFile(path)).ok();
+                }
+            }
+        });
+    }
+}
+
+/// The value of an input when connected, otherwise the parameter, kept within its range.
+fn modulated<I: Input<Type = f32>>(
+    ctx: &ProcessContext,
+    parameter: f32,
+    range: std::ops::RangeInclusive<f32>,
+) -> f32 {
+    if ctx.is_connected::<I>() {
+        ctx.get_input::<I>().clamp(*range.start(), *range.end())
+    } else {
+        parameter
+    }
+}
+
+impl Module for Granular {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Granular")
+            .icon("☁")
+            .category(Category::Source)
+            .tooltip("plays an audio file as a cloud of short overlapping grains")
+            .port(
+                PortDescription::<GranularPositionInput>::input()
+                    .group("grains")
+                    .tooltip("where grains start in the file, from 0 to 1"),
+            )
+            .port(
+                PortDescription::<GranularSizeInput>::input()
+                    .group("grains")
+                    .tooltip("length of a grain in ms"),
+            )
+            .port(
+                PortDescription::<GranularDensityInput>::input()
+                    .group("grains")
+                    .tooltip("grains started per second"),
+            )
+            .port(
+                PortDescription::<GranularSprayInput>::input()
+                    .group("grains")
+                    .tooltip("how far grains randomly start away from the position, from 0 to 1"),
+            )
+            .port(
+                PortDescription::<GranularPitchInput>::input()
+                    .group("grains")
+                    .tooltip("pitch of the grains in semitones"),
+            )
+            .port(PortDescription::<GranularOutput>::output().tooltip("the cloud of grains"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        if self.buffer.is_empty() {
+            ctx.set_output::<GranularOutput>(Frame::ZERO);
+            return;
+        }
+
+        let sample_rate = ctx.sample_rate() as f32;
+        let position = modulated::<GranularPositionInput>(ctx, self.position, 0.0..=1.0);
+        let size = modulated::<GranularSizeInput>(ctx, self.size, 5.0..=1000.0);
+        let density = modulated::<GranularDensityInput>(ctx, self.density, 0.5..=200.0);
+        let spray = modulated::<GranularSprayInput>(ctx, self.spray, 0.0..=1.0);
+        let pitch = modulated::<GranularPitchInput>(ctx, self.pitch, -24.0..=24.0);
+
+        self.phase += density / sample_rate;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+            if self.grains.len() < MAX_GRAINS {
+                let len = self.buffer.len() as f32;
+                let offset = rand::thread_rng().gen_range(-0.5..=0.5) * spray;
+                self.grains.push(Grain {
+                    position: (position + offset).rem_euclid(1.0) * len,
+                    rate: 2f32.powf(pitch / 12.0),
+                    age: 0,
+                    length: ((size / 1000.0 * sample_rate) as usize).max(1),
+                });
+            }
+        }
+
+        let mut mixed = Frame::ZERO;
+        for index in 0..self.grains.len() {
+            let grain = &self.grains[index];
+            let window = (PI * grain.age as f32 / grain.length as f32).sin().powi(2);
+            mixed += File::sample_at(&self.buffer, grain.position as f64) * window;
+
+            let grain = &mut self.grains[index];
+            grain.position += grain.rate;
+            grain.age += 1;
+        }
+        self.grains.retain(|grain| grain.age < grain.length);
+
+        // keep the level about the same however many grains overlap
+        let overlap = (density * size / 1000.0).max(1.0);
+        ctx.set_output::<GranularOutput>(mixed * overlap.sqrt().recip())
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        let messages = self.receiver.try_iter().collect::<Vec<_>>();
+        for message in messages {
+            match message {
+                Message::Decoded(buffer) => {
+                    if let Some(buffer) = buffer {
+                        self.buffer = buffer;
+                        self.grains.clear();
+                    } else {
+                        toast::error(format!("{} {}", tr("could not load:"), self.path));
+                    }
+                    self.loading = false;
+                }
+                Message::PickedFile(path) => {
+                    self.path = path.to_string_lossy().to_string();
+                    self.load(ctx.sample_rate as usize);
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.text_edit_singleline(&mut self.path).lost_focus() {
+                self.load(ctx.sample_rate as usize);
+            }
+            if ui.button(tr("pick")).clicked() {
+                self.open_picker();
+            }
+            if self.loading {
+                ui.spinner();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("position:"));
+            ui.add(egui::Slider::new(&mut self.position, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("size:"));
+            ui.add(
+                egui::Slider::new(&mut self.size, 5.0..=1000.0)
+                    .logarithmic(true)
+                    .suffix(" ms"),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("density:"));
+            ui.add(
+                egui::Slider::new(&mut self.density, 0.5..=200.0)
+                    .logarithmic(true)
+                    .suffix(" /s"),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("spray:"));
+            ui.add(egui::Slider::new(&mut self.spray, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("pitch:"));
+            ui.add(egui::Slider::new(&mut self.pitch, -24.0..=24.0).suffix(" st"))
+                .labelled_by(label.id);
+        });
+
+        ui.label(format!("{} {}", self.grains.len(), tr("grains")));
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("position", &mut self.position, 0.0..=1.0),
+            Parameter::new("size", &mut self.size, 5.0..=1000.0),
+            Parameter::new("density", &mut self.density, 0.5..=200.0),
+            Parameter::new("spray", &mut self.spray, 0.0..=1.0),
+            Parameter::new("pitch", &mut self.pitch, -24.0..=24.0),
+        ]
+    }
+
+    fn asset(&self) -> Option<String> {
+        (!self.path.is_empty()).then(|| self.path.clone())
+    }
+
+    fn load_asset(&mut self, path: &str) {
+        self.sender
+            .send(Message::PickedFile(PathBuf::from(path)))
+            .ok();
+    }
+}
@@ -0,0 +1,259 @@
+use std::f32::consts::PI;
+
+use biquad::{Biquad, Coefficients, DirectForm1, ToHertz, Type, Q_BUTTERWORTH_F32};
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        filter::{Filter, FilterInput, FilterOutput},
+        sequencer::{Sequencer, SequencerGateOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::EnumIter,
+};
+
+pub struct ImpulseTriggerInput;
+
+impl Port for ImpulseTriggerInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "trigger"
+    }
+}
+
+impl Input for ImpulseTriggerInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct ImpulseOutput;
+
+impl Port for ImpulseOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum Shape {
+    /// A single sample at full level.
+    Impulse,
+    /// Lowpass filtered noise with a smooth envelope.
+    Burst,
+}
+
+impl Shape {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Shape::Impulse => "impulse",
+            Shape::Burst => "burst",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    shape: Shape,
+}
+
+/// A [`Module`] that emits an impulse or a short noise burst on each trigger.
+pub struct Impulse {
+    shape: Shape,
+    level: f32,
+    /// Length of a burst in ms.
+    length: f32,
+    /// Cutoff of the burst's lowpass filter in Hz.
+    tone: f32,
+    /// Samples since the last trigger, `None` when it has finished.
+    position: Option<usize>,
+    last_trigger: bool,
+    /// Whether the fire button was clicked since the last processed sample.
+    pending: bool,
+    filter: Option<DirectForm1<f32>>,
+}
+
+impl Default for Impulse {
+    fn default() -> Self {
+        Self {
+            shape: Shape::Impulse,
+            level: 1.0,
+            length: 10.0,
+            tone: 5000.0,
+            position: None,
+            last_trigger: false,
+            pending: false,
+            filter: None,
+        }
+    }
+}
+
+impl Impulse {
+    fn start(&mut self, sample_rate: u32) {
+        self.position = Some(0);
+        self.filter = Coefficients::<f32>::from_params(
+            Type::LowPass,
+            sample_rate.hz(),
+            self.tone.clamp(1.0, sample_rate as f32 / 2.0 - 1.0).hz(),
+            Q_BUTTERWORTH_F32,
+        )
+        .ok()
+        .map(DirectForm1::<f32>::new);
+    }
+
+    fn next(&mut self, sample_rate: u32) -> f32 {
+        let Some(position) = self.position else {
+            return 0.0;
+        };
+
+        match self.shape {
+            Shape::Impulse => {
+                self.position = None;
+                self.level
+            }
+            Shape::Burst => {
+                let length = (self.length / 1000.0 * sample_rate as f32).max(1.0) as usize;
+                if position >= length {
+                    self.position = None;
+                    return 0.0;
+                }
+                self.position = Some(position + 1);
+
+                let noise = rand::thread_rng().gen_range(-1.0..=1.0);
+                let noise = self
+                    .filter
+                    .as_mut()
+                    .map(|filter| filter.run(noise))
+                    .unwrap_or(noise);
+
+                // hann window, so the burst starts and ends without clicks
+                let window = 0.5 - 0.5 * (2.0 * PI * position as f32 / length as f32).cos();
+                noise * window * self.level
+            }
+        }
+    }
+}
+
+impl Module for Impulse {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Impulse")
+            .icon("⚡")
+            .category(Category::Source)
+            .tooltip(
+                "emits a single sample impulse or a short burst of filtered noise on each trigger, to excite resonators or test filters",
+            )
+            .example(example)
+            .port(
+                PortDescription::<ImpulseTriggerInput>::input()
+                    .tooltip("fires when it goes high"),
+            )
+            .port(PortDescription::<ImpulseOutput>::output().tooltip("the impulse or burst"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let trigger = ctx.get_input::<ImpulseTriggerInput>();
+        if (trigger && !self.last_trigger) || self.pending {
+            self.pending = false;
+            self.start(ctx.sample_rate());
+        }
+        self.last_trigger = trigger;
+
+        let sample = self.next(ctx.sample_rate());
+        ctx.set_output::<ImpulseOutput>(sample)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            for shape in Shape::iter() {
+                ui.selectable_value(&mut self.shape, shape, tr(shape.as_str()));
+            }
+
+            if ui.button(tr("fire")).clicked() {
+                self.pending = true;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("level:"));
+            ui.add(
+                egui::DragValue::new(&mut self.level)
+                    .clamp_range(0.0..=1.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.add_enabled_ui(self.shape == Shape::Burst, |ui| {
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("length:"));
+                ui.add(
+                    egui::DragValue::new(&mut self.length)
+                        .clamp_range(1.0..=100.0)
+                        .speed(0.5)
+                        .suffix(" ms"),
+                )
+                .labelled_by(label.id);
+                let label = ui.label(tr("tone:"));
+                ui.add(
+                    egui::DragValue::new(&mut self.tone)
+                        .clamp_range(100.0..=20000.0)
+                        .speed(10.0)
+                        .suffix(" Hz"),
+                )
+                .labelled_by(label.id);
+            });
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("level", &mut self.level, 0.0..=1.0),
+            Parameter::new("length", &mut self.length, 1.0..=100.0),
+            Parameter::new("tone", &mut self.tone, 100.0..=20000.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State { shape: self.shape }).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.shape = state.shape;
+        }
+    }
+}
+
+/// Bursts played by a sequencer through a filter.
+fn example(rack: &mut Rack, panel: usize) {
+    let sequencer = rack.add_module_to::<Sequencer>(panel);
+    let impulse = rack.add_module_to::<Impulse>(panel);
+    let filter = rack.add_module_to::<Filter>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    if let Some(impulse) = rack.get_module_mut(impulse) {
+        impulse.shape = Shape::Burst;
+    }
+    rack.connect_typed::<SequencerGateOutput, ImpulseTriggerInput>(sequencer, impulse)
+        .unwrap();
+    rack.connect_typed::<ImpulseOutput, FilterInput>(impulse, filter)
+        .unwrap();
+    rack.connect_typed::<FilterOutput, AudioInput>(filter, audio)
+        .unwrap();
+}
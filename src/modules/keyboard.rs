@@ -5,11 +5,21 @@ use eframe::{
     epaint::{Color32, Hsva, Vec2},
 };
 use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    module::{Module, ModuleDescription, Port, PortDescription},
-    rack::rack::{ProcessContext, ShowContext},
+    clip::Clip,
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        envelope::{Envelope, EnvelopeGateInput, EnvelopeOutput},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator},
+        scope::{Scope, ScopeInput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
     util::EnumIter,
+    velocity::VelocityResponse,
 };
 
 #[derive(Clone, Copy, Sequence)]
@@ -58,13 +68,9 @@ struct Note {
 }
 
 impl Note {
-    ///Relative position of this `Note` to A4 (440Hz)
-    fn offset(&self) -> i32 {
-        self.tone as i32 + ((self.octave.index as i32 - 4) * 12) - 9
-    }
-
-    fn freq(&self) -> f32 {
-        440.0 * 2f32.powf(self.offset() as f32 / 12.0)
+    /// MIDI note number of this `Note`, with A4 at 69.
+    fn number(&self) -> i32 {
+        self.tone as i32 + (self.octave.index as i32 + 1) * 12
     }
 }
 
@@ -100,6 +106,16 @@ impl Port for KeyboardFreqOutput {
     }
 }
 
+pub struct KeyboardVelocityOutput;
+
+impl Port for KeyboardVelocityOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "velocity"
+    }
+}
+
 pub struct KeyboardPressedOutput;
 
 impl Port for KeyboardPressedOutput {
@@ -110,8 +126,37 @@ impl Port for KeyboardPressedOutput {
     }
 }
 
+/// What is being played, as recorded in a clip.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct Performance {
+    /// Frequency of the held note, `None` when no key is held.
+    freq: Option<f32>,
+    /// Pitch bend in semitones.
+    bend: f32,
+    /// How hard the note was played, from 0 to 1.
+    #[serde(default = "full_force")]
+    force: f32,
+}
+
+fn full_force() -> f32 {
+    1.0
+}
+
+/// The clip and velocity response, as stored in patches.
+#[derive(Serialize, Deserialize)]
+struct State {
+    clip: Clip<Performance>,
+    velocity: VelocityResponse,
+}
+
 pub struct Keyboard {
     pressed: Option<Note>,
+    /// Pitch bend in semitones, springing back to 0 when the slider is released.
+    bend: f32,
+    /// How hard the held key was pressed, lower on the key is harder.
+    force: f32,
+    velocity: VelocityResponse,
+    clip: Clip<Performance>,
     key_visuals: Widgets,
     sharp_visuals: Widgets,
 }
@@ -132,6 +177,10 @@ impl Default for Keyboard {
 
         Self {
             pressed: None,
+            bend: 0.0,
+            force: 1.0,
+            velocity: VelocityResponse::default(),
+            clip: Clip::default(),
             key_visuals,
             sharp_visuals,
         }
@@ -144,14 +193,43 @@ impl Module for Keyboard {
         Self: Sized,
     {
         ModuleDescription::default()
-            .name("🎹 Keyboard")
-            .port(PortDescription::<KeyboardFreqOutput>::output())
-            .port(PortDescription::<KeyboardPressedOutput>::output())
+            .name("Keyboard")
+            .icon("🎹")
+            .category(Category::Sequencing)
+            .tooltip("play notes with the mouse or the computer keyboard")
+            .example(example)
+            .port(
+                PortDescription::<KeyboardFreqOutput>::output()
+                    .tooltip("frequency in Hz of the last played note"),
+            )
+            .port(
+                PortDescription::<KeyboardPressedOutput>::output()
+                    .tooltip("high while a key is held"),
+            )
+            .port(
+                PortDescription::<KeyboardVelocityOutput>::output()
+                    .tooltip("velocity of the last played note, from how low the key was pressed"),
+            )
     }
 
     fn process(&mut self, ctx: &mut ProcessContext) {
-        if let Some(pressed) = self.pressed {
-            ctx.set_output::<KeyboardFreqOutput>(pressed.freq());
+        let live = Performance {
+            freq: self
+                .pressed
+                .map(|note| ctx.tuning().note_to_freq(note.number() as f32)),
+            bend: self.bend,
+            force: self.force,
+        };
+
+        // playing live takes over from the clip
+        let performance = match self.clip.process(ctx.transport(), &live) {
+            Some(recorded) if live.freq.is_none() => recorded,
+            _ => live,
+        };
+
+        if let Some(freq) = performance.freq {
+            ctx.set_output::<KeyboardFreqOutput>(freq * 2f32.powf(performance.bend / 12.0));
+            ctx.set_output::<KeyboardVelocityOutput>(self.velocity.apply(performance.force));
             ctx.set_output::<KeyboardPressedOutput>(true)
         } else {
             ctx.set_output::<KeyboardFreqOutput>(0.0);
@@ -185,13 +263,17 @@ impl Module for Keyboard {
                                     format!("{}", note)
                                 };
 
-                                if ui
-                                    .add(
-                                        egui::Button::new(egui::RichText::new(text).monospace())
-                                            .sense(egui::Sense::drag()),
-                                    )
-                                    .dragged()
-                                {
+                                let response = ui.add(
+                                    egui::Button::new(egui::RichText::new(text).monospace())
+                                        .sense(egui::Sense::drag()),
+                                );
+                                if response.drag_started() {
+                                    if let Some(position) = response.interact_pointer_pos() {
+                                        self.force = (position.y - response.rect.top())
+                                            / response.rect.height();
+                                    }
+                                }
+                                if response.dragged() {
                                     self.pressed = Some(note)
                                 }
 
@@ -202,8 +284,82 @@ impl Module for Keyboard {
                 )
             });
 
-        if !ui.ctx().dragged_id().is_some() {
+        if ui.ctx().dragged_id().is_none() {
             self.pressed = None;
         }
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("bend:"));
+            let response = ui
+                .add(egui::Slider::new(&mut self.bend, -2.0..=2.0).suffix(" st"))
+                .labelled_by(label.id);
+            if response.drag_stopped() {
+                self.bend = 0.0;
+            }
+        });
+
+        self.velocity.show(ui, (ctx.instance, "velocity"));
+
+        self.clip.show(ui);
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("bend", &mut self.bend, -2.0..=2.0)]
+    }
+
+    fn state(&self) -> Option<String> {
+        (!self.clip.is_empty() || self.velocity != VelocityResponse::default())
+            .then(|| {
+                ron::to_string(&State {
+                    clip: self.clip.clone(),
+                    velocity: self.velocity,
+                })
+                .ok()
+            })
+            .flatten()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        // patches from before the velocity response only stored the clip
+        let state = ron::from_str::<State>(state).or_else(|_| {
+            ron::from_str(state).map(|clip| State {
+                clip,
+                velocity: VelocityResponse::default(),
+            })
+        });
+        if let Ok(state) = state {
+            self.clip = state.clip;
+            self.velocity = state.velocity;
+            self.clip.play();
+        }
+    }
+
+    fn has_clip(&self) -> bool {
+        true
     }
+
+    fn set_clip_playing(&mut self, playing: bool) {
+        if playing {
+            self.clip.play()
+        } else {
+            self.clip.stop()
+        }
+    }
+}
+
+/// Plays an oscillator and an envelope with the keyboard.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    let envelope = rack.add_module_to::<Envelope>(panel);
+    let scope = rack.add_module_to::<Scope>(panel);
+    rack.connect_typed::<KeyboardFreqOutput, FrequencyInput>(keyboard, oscillator)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, AudioInput>(oscillator, audio)
+        .unwrap();
+    rack.connect_typed::<KeyboardPressedOutput, EnvelopeGateInput>(keyboard, envelope)
+        .unwrap();
+    rack.connect_typed::<EnvelopeOutput, ScopeInput>(envelope, scope)
+        .unwrap();
 }
@@ -0,0 +1,259 @@
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator, Wave},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    transport::Division,
+    util::EnumIter,
+};
+
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Square,
+    Saw,
+    /// A new random value every period.
+    Random,
+}
+
+impl LfoShape {
+    pub fn as_str(&self) -> &str {
+        match self {
+            LfoShape::Sine => "Sine",
+            LfoShape::Triangle => "Triangle",
+            LfoShape::Square => "Square",
+            LfoShape::Saw => "Saw",
+            LfoShape::Random => "Random",
+        }
+    }
+}
+
+pub struct LfoRateInput;
+
+impl Port for LfoRateInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "rate"
+    }
+}
+
+impl Input for LfoRateInput {
+    fn default() -> Self::Type {
+        1.0
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.add(
+            egui::DragValue::new(value)
+                .clamp_range(0.01..=100.0)
+                .speed(0.01)
+                .suffix(" Hz"),
+        );
+    }
+}
+
+pub struct LfoOutput;
+
+impl Port for LfoOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    shape: LfoShape,
+    unipolar: bool,
+    // patches from before the tempo sync lack these
+    #[serde(default)]
+    synced: bool,
+    #[serde(default)]
+    division: Division,
+}
+
+/// A [`Module`] producing a slow wave to modulate other modules with.
+pub struct Lfo {
+    shape: LfoShape,
+    rate: f32,
+    /// Whether the period follows the division at the transport's tempo instead of the rate.
+    synced: bool,
+    division: Division,
+    /// Whether the wave goes from 0 to 1 instead of -1 to 1.
+    unipolar: bool,
+    /// Scale of the wave, so it can drive inputs with larger ranges like frequencies.
+    depth: f32,
+    offset: f32,
+    index: f32,
+    /// The value of the random shape during the current period.
+    random: f32,
+}
+
+impl Default for Lfo {
+    fn default() -> Self {
+        Self {
+            shape: LfoShape::Sine,
+            rate: 1.0,
+            synced: false,
+            division: Division::Quarter,
+            unipolar: false,
+            depth: 1.0,
+            offset: 0.0,
+            index: 0.0,
+            random: 0.0,
+        }
+    }
+}
+
+impl Module for Lfo {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("LFO")
+            .icon("🌊")
+            .category(Category::Modulation)
+            .tooltip("a slow wave to modulate other modules, like the frequency of an oscillator or the cutoff of a filter")
+            .example(example)
+            .port(
+                PortDescription::<LfoRateInput>::input()
+                    .tooltip("speed of the wave in Hz, unused while synced to the tempo")
+                    .normalled(|lfo: &Self| lfo.rate),
+            )
+            .port(
+                PortDescription::<LfoOutput>::output()
+                    .tooltip("the wave multiplied by the depth plus the offset"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let value = match self.shape {
+            LfoShape::Sine => Wave::Sine.sample(self.index),
+            LfoShape::Triangle => Wave::Triangle.sample(self.index),
+            LfoShape::Square => Wave::Square.sample(self.index),
+            LfoShape::Saw => Wave::Saw.sample(self.index),
+            LfoShape::Random => self.random,
+        };
+        let value = if self.unipolar {
+            (value + 1.0) / 2.0
+        } else {
+            value
+        };
+
+        let rate = if self.synced {
+            1000.0 / self.division.ms(ctx.transport().bpm)
+        } else {
+            ctx.get_input::<LfoRateInput>().max(0.0)
+        };
+        self.index += rate / ctx.sample_rate() as f32;
+        if self.index >= 1.0 {
+            self.index %= 1.0;
+            self.random = rand::random::<f32>() * 2.0 - 1.0;
+        }
+
+        ctx.set_output::<LfoOutput>(self.offset + value * self.depth)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::new(ctx.instance, "shape")
+                .selected_text(tr(self.shape.as_str()))
+                .show_ui(ui, |ui| {
+                    for shape in LfoShape::iter() {
+                        ui.selectable_value(&mut self.shape, shape, tr(shape.as_str()));
+                    }
+                });
+
+            ui.checkbox(&mut self.unipolar, tr("unipolar"))
+                .on_hover_text(tr("from 0 to 1 instead of -1 to 1"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("rate:"));
+            if self.synced {
+                egui::ComboBox::from_id_source((ctx.instance, "division"))
+                    .selected_text(self.division.as_str())
+                    .width(50.0)
+                    .show_ui(ui, |ui| {
+                        for division in Division::iter() {
+                            ui.selectable_value(&mut self.division, division, division.as_str());
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id);
+            } else {
+                ui.add(
+                    egui::Slider::new(&mut self.rate, 0.01..=100.0)
+                        .logarithmic(true)
+                        .suffix(" Hz"),
+                )
+                .labelled_by(label.id);
+            }
+            ui.toggle_value(&mut self.synced, "♩")
+                .on_hover_text_at_pointer(tr("sync to tempo"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("depth:"));
+            ui.add(egui::DragValue::new(&mut self.depth).speed(0.01))
+                .labelled_by(label.id);
+            let label = ui.label(tr("offset:"));
+            ui.add(egui::DragValue::new(&mut self.offset).speed(0.01))
+                .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("rate", &mut self.rate, 0.01..=100.0),
+            Parameter::new("depth", &mut self.depth, -20000.0..=20000.0),
+            Parameter::new("offset", &mut self.offset, -20000.0..=20000.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            shape: self.shape,
+            unipolar: self.unipolar,
+            synced: self.synced,
+            division: self.division,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.shape = state.shape;
+            self.unipolar = state.unipolar;
+            self.synced = state.synced;
+            self.division = state.division;
+        }
+    }
+}
+
+/// Vibrato, an oscillator's frequency swept around 220Hz.
+fn example(rack: &mut Rack, panel: usize) {
+    let lfo = rack.add_module_to::<Lfo>(panel);
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    if let Some(lfo) = rack.get_module_mut(lfo) {
+        lfo.rate = 5.0;
+        lfo.depth = 10.0;
+        lfo.offset = 220.0;
+    }
+    rack.connect_typed::<LfoOutput, FrequencyInput>(lfo, oscillator)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, AudioInput>(oscillator, audio)
+        .unwrap();
+}
@@ -0,0 +1,381 @@
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        oscillator::{FrameOutput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    transport::Transport,
+};
+
+/// Longest recording in seconds, to bound the memory a forgotten recording takes.
+const MAX_LENGTH: usize = 300;
+
+/// Playback speeds and their labels, halving and doubling also shift by an octave.
+const SPEEDS: [(f64, &str); 3] = [(0.5, "½×"), (1.0, "1×"), (2.0, "2×")];
+
+pub struct LooperInput;
+
+impl Port for LooperInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for LooperInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct LooperRecordInput;
+
+impl Port for LooperRecordInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "record"
+    }
+}
+
+impl Input for LooperRecordInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct LooperOutput;
+
+impl Port for LooperOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// Positions are in beats of the transport.
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Idle,
+    /// Waiting for the transport to reach `start`.
+    Armed {
+        start: f64,
+        end: Option<f64>,
+    },
+    Recording {
+        start: f64,
+        end: Option<f64>,
+    },
+    /// Playing back the recording, which started at `start`.
+    Looping {
+        start: f64,
+    },
+}
+
+/// The settings stored in patches, the recording is not.
+#[derive(Serialize, Deserialize)]
+struct Settings {
+    count_in: u32,
+    length: u32,
+    punch: bool,
+    punch_in: u32,
+    punch_out: u32,
+    overdub: bool,
+    speed: f64,
+}
+
+/// A [`Module`] that records its input in sync with the transport and loops it.
+pub struct Looper {
+    buffer: Vec<Frame>,
+    state: State,
+    /// Bars to wait before recording starts.
+    count_in: u32,
+    /// Bars to record before stopping automatically, 0 records until stopped.
+    length: u32,
+    /// Whether recording starts and stops at fixed bars instead.
+    punch: bool,
+    /// Bar where recording starts when punching, starting at 1 like the transport display.
+    punch_in: u32,
+    /// Bar where recording stops when punching, starting at 1 like the transport display.
+    punch_out: u32,
+    /// Whether the input is mixed into the loop while it plays.
+    overdub: bool,
+    /// Speed the loop plays at.
+    speed: f64,
+    /// Level of the output.
+    level: f32,
+    last_record: bool,
+}
+
+impl Default for Looper {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            state: State::Idle,
+            count_in: 1,
+            length: 4,
+            punch: false,
+            punch_in: 1,
+            punch_out: 5,
+            overdub: false,
+            speed: 1.0,
+            level: 1.0,
+            last_record: false,
+        }
+    }
+}
+
+impl Looper {
+    /// Waits for the count-in or the punch-in point before recording.
+    fn arm(&mut self, transport: &Transport) {
+        let bar = transport.beats_per_bar as f64;
+
+        self.state = if self.punch {
+            State::Armed {
+                start: (self.punch_in.max(1) - 1) as f64 * bar,
+                end: Some((self.punch_out.max(self.punch_in + 1) - 1) as f64 * bar),
+            }
+        } else {
+            let start = (transport.position() / bar).ceil() * bar + self.count_in as f64 * bar;
+            State::Armed {
+                start,
+                end: (self.length > 0).then_some(start + self.length as f64 * bar),
+            }
+        };
+    }
+
+    /// Stops recording at the next bar, so the loop stays a whole number of bars.
+    fn stop(&mut self, transport: &Transport) {
+        self.state = match self.state {
+            State::Armed { .. } => State::Idle,
+            State::Recording { start, end: None } => {
+                let bar = transport.beats_per_bar as f64;
+                State::Recording {
+                    start,
+                    end: Some((transport.position() / bar).ceil() * bar),
+                }
+            }
+            state => state,
+        };
+    }
+
+    fn finish(&mut self, start: f64) {
+        self.state = if self.buffer.is_empty() {
+            State::Idle
+        } else {
+            State::Looping { start }
+        };
+    }
+
+    fn toggle(&mut self, transport: &Transport) {
+        match self.state {
+            State::Idle | State::Looping { .. } => self.arm(transport),
+            State::Armed { .. } | State::Recording { .. } => self.stop(transport),
+        }
+    }
+}
+
+impl Module for Looper {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Looper")
+            .icon("🔁")
+            .category(Category::Source)
+            .tooltip(
+                "records its input in sync with the transport and loops it, optionally overdubbing",
+            )
+            .sink()
+            .example(example)
+            .port(PortDescription::<LooperInput>::input().tooltip("the sound to record"))
+            .port(
+                PortDescription::<LooperRecordInput>::input()
+                    .tooltip("starts or stops recording when going high"),
+            )
+            .port(
+                PortDescription::<LooperOutput>::output()
+                    .tooltip("the loop, or the input while recording or overdubbing"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let transport = *ctx.transport();
+
+        let record = ctx.get_input::<LooperRecordInput>();
+        if record && !self.last_record {
+            self.toggle(&transport)
+        }
+        self.last_record = record;
+
+        let input = ctx.get_input::<LooperInput>();
+        let position = transport.position();
+
+        if let State::Armed { start, end } = self.state {
+            if transport.playing && position >= start {
+                self.buffer.clear();
+                self.state = State::Recording { start, end };
+            }
+        }
+
+        let output = match self.state {
+            State::Recording { start, end } => {
+                let full = self.buffer.len() >= MAX_LENGTH * ctx.sample_rate() as usize;
+                if !transport.playing || full || end.is_some_and(|end| position >= end) {
+                    self.finish(start);
+                    Frame::ZERO
+                } else {
+                    self.buffer.push(input);
+                    input
+                }
+            }
+            State::Looping { start } if transport.playing => {
+                let samples_per_beat = transport.samples_per_beat(ctx.sample_rate()) as f64;
+                let offset = ((position - start) * samples_per_beat * self.speed) as i64;
+                let index = offset.rem_euclid(self.buffer.len() as i64) as usize;
+                let looped = self.buffer[index];
+                if self.overdub {
+                    self.buffer[index] += input;
+                    looped + input
+                } else {
+                    looped
+                }
+            }
+            _ => Frame::ZERO,
+        };
+
+        ctx.set_output::<LooperOutput>(output * self.level);
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        let transport = ctx.transport;
+
+        ui.horizontal(|ui| {
+            let recording = matches!(self.state, State::Armed { .. } | State::Recording { .. });
+            if ui
+                .selectable_label(recording, "⏺")
+                .on_hover_text(tr("record"))
+                .clicked()
+            {
+                self.toggle(&transport)
+            }
+
+            ui.toggle_value(&mut self.overdub, tr("overdub"))
+                .on_hover_text(tr("mixes the input into the loop while it plays"));
+
+            if ui.button("🗑").on_hover_text(tr("clear")).clicked() {
+                self.buffer.clear();
+                self.state = State::Idle;
+            }
+
+            let bar = transport.beats_per_bar as f64;
+            match self.state {
+                State::Idle => ui.label(tr("empty")),
+                State::Armed { start, .. } => ui.label(format!(
+                    "{} {}",
+                    tr("recording from bar"),
+                    (start / bar) as u64 + 1
+                )),
+                State::Recording { .. } => ui.label(tr("recording")),
+                State::Looping { .. } => {
+                    let beats = self.buffer.len() as f64
+                        / transport.samples_per_beat(ctx.sample_rate) as f64;
+                    ui.label(format!("{:.1} {}", beats / bar, tr("bars")))
+                }
+            };
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("speed:"));
+            for (speed, label) in SPEEDS {
+                ui.selectable_value(&mut self.speed, speed, label);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("level:"));
+            ui.add(egui::Slider::new(&mut self.level, 0.0..=2.0))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!self.punch, |ui| {
+                let label = ui.label(tr("count-in:"));
+                ui.add(egui::DragValue::new(&mut self.count_in).clamp_range(0..=4))
+                    .labelled_by(label.id);
+                let label = ui.label(tr("length:"));
+                ui.add(egui::DragValue::new(&mut self.length).clamp_range(0..=64))
+                    .labelled_by(label.id)
+                    .on_hover_text(tr("bars, 0 records until stopped"));
+            });
+        });
+
+        ui.horizontal(|ui| {
+            let punch = ui
+                .checkbox(&mut self.punch, tr("punch"))
+                .on_hover_text(tr("record between two bars"));
+            ui.add_enabled_ui(self.punch, |ui| {
+                ui.add(egui::DragValue::new(&mut self.punch_in).clamp_range(1..=999))
+                    .labelled_by(punch.id);
+                ui.label("-");
+                ui.add(
+                    egui::DragValue::new(&mut self.punch_out).clamp_range(self.punch_in + 1..=1000),
+                )
+                .labelled_by(punch.id);
+            });
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("level", &mut self.level, 0.0..=2.0)]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&Settings {
+            count_in: self.count_in,
+            length: self.length,
+            punch: self.punch,
+            punch_in: self.punch_in,
+            punch_out: self.punch_out,
+            overdub: self.overdub,
+            speed: self.speed,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(settings) = ron::from_str::<Settings>(state) {
+            self.count_in = settings.count_in;
+            self.length = settings.length;
+            self.punch = settings.punch;
+            self.punch_in = settings.punch_in.max(1);
+            self.punch_out = settings.punch_out.max(self.punch_in + 1);
+            self.overdub = settings.overdub;
+            self.speed = settings.speed;
+        }
+    }
+}
+
+/// Records an oscillator for four bars after a bar of count-in, start the transport and press record.
+fn example(rack: &mut Rack, panel: usize) {
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let looper = rack.add_module_to::<Looper>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<FrameOutput, LooperInput>(oscillator, looper)
+        .unwrap();
+    rack.connect_typed::<LooperOutput, AudioInput>(looper, audio)
+        .unwrap();
+}
@@ -0,0 +1,254 @@
+use std::ops::RangeInclusive;
+
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    instance::instance::InstanceHandle,
+    module::{Category, Module, ModuleDescription, Parameter, ParameterTarget},
+    rack::rack::{ProcessContext, ShowContext},
+    util::EnumIter,
+};
+
+/// Names of the knobs, which also limits the amount of knobs.
+const KNOB_NAMES: [&str; 8] = ["1", "2", "3", "4", "5", "6", "7", "8"];
+
+/// Fewest knobs a macro has.
+const MIN_KNOBS: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Default, Sequence, Serialize, Deserialize)]
+enum Curve {
+    #[default]
+    Linear,
+    Exponential,
+    Logarithmic,
+}
+
+impl Curve {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Curve::Linear => "linear",
+            Curve::Exponential => "exp",
+            Curve::Logarithmic => "log",
+        }
+    }
+
+    /// Maps the position of a knob from 0 to 1 onto this curve.
+    fn apply(&self, t: f32) -> f32 {
+        match self {
+            Curve::Linear => t,
+            Curve::Exponential => t * t,
+            Curve::Logarithmic => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// A parameter of another instance following a knob.
+#[derive(Clone, Serialize, Deserialize)]
+struct Mapping {
+    knob: usize,
+    instance: InstanceHandle,
+    parameter: String,
+    /// Values of the parameter with the knob all the way down and all the way up.
+    from: f32,
+    to: f32,
+    /// Range of the parameter, which `from` and `to` stay within.
+    range: (f32, f32),
+    curve: Curve,
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    knobs: usize,
+    mappings: Vec<Mapping>,
+}
+
+/// A [`Module`] with a few knobs that each set any number of parameters of other modules, to
+/// perform a complex patch with a few controls.
+pub struct Macro {
+    knobs: Vec<f32>,
+    mappings: Vec<Mapping>,
+    /// The knob that gets mapped to the next parameter moved by hand.
+    pub learning: Option<usize>,
+}
+
+impl Default for Macro {
+    fn default() -> Self {
+        Self {
+            knobs: vec![0.0; MIN_KNOBS],
+            mappings: Vec::new(),
+            learning: None,
+        }
+    }
+}
+
+impl Macro {
+    /// Maps the learning knob to a parameter of another instance, over the whole range.
+    pub fn learn(&mut self, instance: InstanceHandle, parameter: &str, range: RangeInclusive<f32>) {
+        let Some(knob) = self.learning.take() else {
+            return;
+        };
+        self.mappings.push(Mapping {
+            knob,
+            instance,
+            parameter: parameter.to_string(),
+            from: *range.start(),
+            to: *range.end(),
+            range: (*range.start(), *range.end()),
+            curve: Curve::default(),
+        })
+    }
+
+    fn show_mapping(mapping: &mut Mapping, id: (InstanceHandle, usize), ui: &mut Ui) -> bool {
+        let mut removed = false;
+        ui.horizontal(|ui| {
+            let label = ui.label(format!("→ {} {}", mapping.instance, tr(&mapping.parameter)));
+            let (start, end) = mapping.range;
+            let speed = (end - start) / 200.0;
+            ui.add(
+                egui::DragValue::new(&mut mapping.from)
+                    .clamp_range(start..=end)
+                    .speed(speed),
+            )
+            .labelled_by(label.id)
+            .on_hover_text(tr("value with the knob all the way down"));
+            ui.add(
+                egui::DragValue::new(&mut mapping.to)
+                    .clamp_range(start..=end)
+                    .speed(speed),
+            )
+            .labelled_by(label.id)
+            .on_hover_text(tr("value with the knob all the way up"));
+            egui::ComboBox::from_id_source(id)
+                .selected_text(tr(mapping.curve.as_str()))
+                .width(60.0)
+                .show_ui(ui, |ui| {
+                    for option in Curve::iter() {
+                        ui.selectable_value(&mut mapping.curve, option, tr(option.as_str()));
+                    }
+                });
+            if ui.small_button("🗑").clicked() {
+                removed = true;
+            }
+        });
+        removed
+    }
+}
+
+impl Module for Macro {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Macro")
+            .icon("🎛")
+            .category(Category::Modulation)
+            .tooltip("knobs that each set any number of parameters of other modules, click 🎯 and move a parameter to map it")
+    }
+
+    fn process(&mut self, _: &mut ProcessContext) {}
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        for (knob, value) in self.knobs.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let label = ui.label(KNOB_NAMES[knob]);
+                ui.add(egui::Slider::new(value, 0.0..=1.0))
+                    .labelled_by(label.id);
+
+                let mut learning = self.learning == Some(knob);
+                if ui
+                    .toggle_value(&mut learning, "🎯")
+                    .on_hover_text(tr("map to the next parameter moved by hand"))
+                    .changed()
+                {
+                    self.learning = learning.then_some(knob);
+                }
+            });
+
+            let mut index = 0;
+            self.mappings.retain_mut(|mapping| {
+                index += 1;
+                mapping.knob != knob || !Self::show_mapping(mapping, (ctx.instance, index), ui)
+            });
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.knobs.len() < KNOB_NAMES.len(),
+                    egui::Button::new(tr("➕ knob")),
+                )
+                .clicked()
+            {
+                self.knobs.push(0.0);
+            }
+
+            if ui
+                .add_enabled(
+                    self.knobs.len() > MIN_KNOBS,
+                    egui::Button::new(tr("➖ knob")),
+                )
+                .on_hover_text(tr("removes the last knob and its mappings"))
+                .clicked()
+            {
+                self.knobs.pop();
+                let knobs = self.knobs.len();
+                self.mappings.retain(|mapping| mapping.knob < knobs);
+                if self.learning >= Some(knobs) {
+                    self.learning = None;
+                }
+            }
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        self.knobs
+            .iter_mut()
+            .zip(KNOB_NAMES)
+            .map(|(value, name)| Parameter::new(name, value, 0.0..=1.0))
+            .collect()
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            knobs: self.knobs.len(),
+            mappings: self.mappings.clone(),
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            let knobs = state.knobs.clamp(MIN_KNOBS, KNOB_NAMES.len());
+            self.knobs.resize(knobs, 0.0);
+            self.mappings = state.mappings;
+            self.mappings.retain(|mapping| mapping.knob < knobs);
+        }
+    }
+
+    fn parameter_targets(&self) -> Vec<ParameterTarget> {
+        self.mappings
+            .iter()
+            .map(|mapping| ParameterTarget {
+                instance: mapping.instance,
+                parameter: mapping.parameter.clone(),
+                value: mapping.from
+                    + (mapping.to - mapping.from) * mapping.curve.apply(self.knobs[mapping.knob]),
+            })
+            .collect()
+    }
+
+    fn remap_instances(&mut self, map: &dyn Fn(InstanceHandle) -> Option<InstanceHandle>) {
+        self.mappings
+            .retain_mut(|mapping| match map(mapping.instance) {
+                Some(instance) => {
+                    mapping.instance = instance;
+                    true
+                }
+                None => false,
+            });
+    }
+}
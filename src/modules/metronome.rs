@@ -0,0 +1,188 @@
+use std::f32::consts::TAU;
+
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::audio::{Audio, AudioInput},
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Time for a click to fade to about a third, in seconds.
+const CLICK_DECAY: f32 = 0.015;
+
+/// Length of a click, after which it is silent.
+const CLICK_LENGTH: f32 = 0.1;
+
+/// Part of a beat the gate outputs are high.
+const PULSE: f64 = 0.1;
+
+pub struct MetronomeClickOutput;
+
+impl Port for MetronomeClickOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "click"
+    }
+}
+
+pub struct MetronomeBeatOutput;
+
+impl Port for MetronomeBeatOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "beat"
+    }
+}
+
+pub struct MetronomeBarOutput;
+
+impl Port for MetronomeBarOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "bar"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    accent: bool,
+}
+
+/// A [`Module`] that clicks on every beat of the transport.
+pub struct Metronome {
+    volume: f32,
+    /// Whether the first beat of a bar clicks higher.
+    accent: bool,
+    /// The beat that clicked last, since the transport was started.
+    last_beat: Option<u64>,
+    /// Seconds since the last click started.
+    time: f32,
+    frequency: f32,
+    /// Beat within the bar of the last click, shown in the ui.
+    counter: u32,
+}
+
+impl Default for Metronome {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            accent: true,
+            last_beat: None,
+            time: CLICK_LENGTH,
+            frequency: 1000.0,
+            counter: 0,
+        }
+    }
+}
+
+impl Module for Metronome {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Metronome")
+            .icon("⏱")
+            .category(Category::Sequencing)
+            .tooltip("clicks on every beat while the transport plays")
+            .example(example)
+            .port(PortDescription::<MetronomeClickOutput>::output().tooltip("the click sound"))
+            .port(
+                PortDescription::<MetronomeBeatOutput>::output()
+                    .tooltip("high at the start of every beat"),
+            )
+            .port(
+                PortDescription::<MetronomeBarOutput>::output()
+                    .tooltip("high at the start of every bar"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let transport = *ctx.transport();
+        let sample_rate = ctx.sample_rate() as f32;
+
+        if !transport.playing {
+            self.last_beat = None;
+            ctx.set_output::<MetronomeBeatOutput>(false);
+            ctx.set_output::<MetronomeBarOutput>(false);
+        } else {
+            let beat = transport.position() as u64;
+            let first = transport.beat() == 0;
+
+            if self.last_beat != Some(beat) {
+                self.last_beat = Some(beat);
+                self.time = 0.0;
+                self.frequency = if first && self.accent { 1500.0 } else { 1000.0 };
+                self.counter = transport.beat();
+            }
+
+            let pulse = transport.position().fract() < PULSE;
+            ctx.set_output::<MetronomeBeatOutput>(pulse);
+            ctx.set_output::<MetronomeBarOutput>(pulse && first);
+        }
+
+        let click = if self.time < CLICK_LENGTH {
+            let value = (self.time * self.frequency * TAU).sin()
+                * (-self.time / CLICK_DECAY).exp()
+                * self.volume;
+            self.time += 1.0 / sample_rate;
+            value
+        } else {
+            0.0
+        };
+
+        ctx.set_output::<MetronomeClickOutput>(Frame::Mono(click));
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("volume:"));
+            ui.add(egui::Slider::new(&mut self.volume, 0.0..=1.0))
+                .labelled_by(label.id);
+            ui.checkbox(&mut self.accent, tr("accent"))
+                .on_hover_text(tr("click higher on the first beat of a bar"));
+        });
+
+        ui.horizontal(|ui| {
+            for beat in 0..ctx.transport.beats_per_bar {
+                let active = ctx.transport.playing && beat == self.counter;
+                ui.add_enabled(
+                    active,
+                    egui::SelectableLabel::new(active, (beat + 1).to_string()),
+                );
+            }
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("volume", &mut self.volume, 0.0..=1.0)]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            accent: self.accent,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.accent = state.accent;
+        }
+    }
+}
+
+/// Clicks through the audio output, start the transport to hear it.
+fn example(rack: &mut Rack, panel: usize) {
+    let metronome = rack.add_module_to::<Metronome>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<MetronomeClickOutput, AudioInput>(metronome, audio)
+        .unwrap();
+}
@@ -0,0 +1,33 @@
+use eframe::egui::{RichText, Ui};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Module, ModuleDescription},
+    rack::rack::{ProcessContext, ShowContext},
+};
+
+/// A placeholder [`Module`] for a module that is not available, e.g. in an imported patch.
+#[derive(Default)]
+pub struct Missing {
+    /// Name of the module this stands in for.
+    pub name: String,
+}
+
+impl Module for Missing {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Missing")
+            .icon("❔")
+            .category(Category::Utility)
+            .tooltip("stands in for a module that is not available")
+    }
+
+    fn process(&mut self, _: &mut ProcessContext) {}
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.label(RichText::new(format!("{} {}", tr("not available:"), self.name)).weak());
+    }
+}
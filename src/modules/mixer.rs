@@ -0,0 +1,188 @@
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    io::PortHandle,
+    module::{
+        Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription,
+        PortDescriptionDyn,
+    },
+    modules::{
+        audio::{Audio, AudioInput},
+        noise::{Noise, NoiseOutput},
+        oscillator::{FrameOutput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Names of the channel inputs, which also limits the amount of channels.
+const CHANNEL_NAMES: [&str; 8] = ["1", "2", "3", "4", "5", "6", "7", "8"];
+
+const GAIN_NAMES: [&str; 8] = [
+    "gain 1", "gain 2", "gain 3", "gain 4", "gain 5", "gain 6", "gain 7", "gain 8",
+];
+
+pub struct MixerInput;
+
+impl Port for MixerInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for MixerInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct MixerOutput;
+
+impl Port for MixerOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Channel {
+    gain: f32,
+    mute: bool,
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            mute: false,
+        }
+    }
+}
+
+/// A [`Module`] summing a variable amount of sounds, each with its own gain.
+pub struct Mixer {
+    channels: Vec<Channel>,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            channels: vec![Channel::default(); 2],
+        }
+    }
+}
+
+impl Module for Mixer {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Mixer")
+            .icon("🎚")
+            .category(Category::Utility)
+            .tooltip("sums its channels into one sound, with a gain and mute for each")
+            .example(example)
+            .port(PortDescription::<MixerOutput>::output().tooltip("the sum of all channels"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let mut mixed = Frame::ZERO;
+        for (index, channel) in self.channels.iter().enumerate() {
+            if !channel.mute {
+                mixed += ctx.get_input_indexed::<MixerInput>(index) * channel.gain;
+            }
+        }
+        ctx.set_output::<MixerOutput>(mixed)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        for (index, channel) in self.channels.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let label = ui.label(CHANNEL_NAMES[index]);
+                ui.add(egui::Slider::new(&mut channel.gain, 0.0..=2.0))
+                    .labelled_by(label.id);
+                ui.toggle_value(&mut channel.mute, "M")
+                    .on_hover_text(tr("mute"));
+            });
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.channels.len() < CHANNEL_NAMES.len(),
+                    egui::Button::new(tr("➕ channel")),
+                )
+                .clicked()
+            {
+                self.channels.push(Channel::default());
+            }
+
+            if ui
+                .add_enabled(self.channels.len() > 1, egui::Button::new(tr("➖ channel")))
+                .on_hover_text(tr("removes the last channel and its connection"))
+                .clicked()
+            {
+                self.channels.pop();
+            }
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        self.channels
+            .iter_mut()
+            .zip(GAIN_NAMES)
+            .map(|(channel, name)| Parameter::new(name, &mut channel.gain, 0.0..=2.0))
+            .collect()
+    }
+
+    fn dynamic_ports(&self) -> Vec<PortDescriptionDyn> {
+        (0..self.channels.len())
+            .map(|index| {
+                PortDescription::<MixerInput>::input()
+                    .tooltip("a sound to mix")
+                    .into_dyn()
+                    .indexed(index, CHANNEL_NAMES[index])
+            })
+            .collect()
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&self.channels).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(mut channels) = ron::from_str::<Vec<Channel>>(state) {
+            channels.truncate(CHANNEL_NAMES.len());
+            if !channels.is_empty() {
+                self.channels = channels;
+            }
+        }
+    }
+}
+
+/// An oscillator with some noise mixed in.
+fn example(rack: &mut Rack, panel: usize) {
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let noise = rack.add_module_to::<Noise>(panel);
+    let mixer = rack.add_module_to::<Mixer>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    if let Some(mixer) = rack.get_module_mut(mixer) {
+        mixer.channels[1].gain = 0.1;
+    }
+    rack.connect_typed::<FrameOutput, MixerInput>(oscillator, mixer)
+        .unwrap();
+    rack.connect(
+        PortHandle::new(NoiseOutput::id(), noise),
+        PortHandle::new(MixerInput::id().indexed(1), mixer),
+    )
+    .unwrap();
+    rack.connect_typed::<MixerOutput, AudioInput>(mixer, audio)
+        .unwrap();
+}
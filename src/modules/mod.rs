@@ -1,9 +1,46 @@
+pub mod additive;
 pub mod audio;
+pub mod autopan;
+pub mod bus;
+pub mod delay;
+pub mod drums;
+pub mod envelope;
 pub mod file;
 pub mod filter;
+pub mod flanger;
+pub mod function;
+pub mod generative;
+pub mod granular;
+pub mod impulse;
 pub mod keyboard;
+pub mod lfo;
+pub mod looper;
+pub mod macros;
+pub mod metronome;
+pub mod missing;
+pub mod mixer;
+pub mod multiband;
 pub mod noise;
+pub mod octaver;
 pub mod ops;
 pub mod oscillator;
+pub mod pads;
+pub mod pianoroll;
+pub mod pitch;
+pub mod reference;
+pub mod reverb;
+pub mod sampler;
 pub mod scope;
+pub mod sequencer;
+pub mod shaper;
+pub mod shepard;
+pub mod speech;
+pub mod stats;
+pub mod tracker;
+pub mod trig;
+pub mod unison;
 pub mod value;
+pub mod vinyl;
+pub mod visualizer;
+pub mod width;
+pub mod xy;
@@ -0,0 +1,419 @@
+use biquad::{Biquad, Coefficients, DirectForm1, ToHertz, Type, Q_BUTTERWORTH_F32};
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    io::PortHandle,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        drums::{DrumOutput, DrumTriggerInput, Kick},
+        noise::{Noise, NoiseOutput},
+        sequencer::{Sequencer, SequencerGateOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+const BAND_NAMES: [&str; 3] = ["low", "mid", "high"];
+
+const THRESHOLD_NAMES: [&str; 3] = ["low threshold", "mid threshold", "high threshold"];
+
+const RATIO_NAMES: [&str; 3] = ["low ratio", "mid ratio", "high ratio"];
+
+const CROSSOVER_NAMES: [&str; 2] = ["low crossover", "high crossover"];
+
+pub struct MultibandCompInput;
+
+impl Port for MultibandCompInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for MultibandCompInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct MultibandCompSidechainInput;
+
+impl Port for MultibandCompSidechainInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "sidechain"
+    }
+}
+
+impl Input for MultibandCompSidechainInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct MultibandCompOutput;
+
+impl Port for MultibandCompOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// A fourth order Linkwitz-Riley filter, two Butterworth filters in series, whose low- and
+/// highpass sum to a flat response.
+#[derive(Clone, Copy)]
+struct LinkwitzRiley {
+    stages: [DirectForm1<f32>; 2],
+}
+
+impl LinkwitzRiley {
+    fn new(filter: Type<f32>, sample_rate: u32, freq: f32) -> Self {
+        let freq = freq.clamp(10.0, sample_rate as f32 / 2.0 - 10.0);
+        let coefficients = Coefficients::<f32>::from_params(
+            filter,
+            sample_rate.hz(),
+            freq.hz(),
+            Q_BUTTERWORTH_F32,
+        )
+        .expect("frequency should be below nyquist");
+        Self {
+            stages: [DirectForm1::<f32>::new(coefficients); 2],
+        }
+    }
+
+    fn run(&mut self, sample: f32) -> f32 {
+        let sample = self.stages[0].run(sample);
+        self.stages[1].run(sample)
+    }
+}
+
+/// Splits one channel into a low, mid and high band that add up to the input.
+#[derive(Clone, Copy)]
+struct Crossover {
+    low: LinkwitzRiley,
+    rest: LinkwitzRiley,
+    mid: LinkwitzRiley,
+    high: LinkwitzRiley,
+    /// Gives the low band the same phase shift as the mid and high bands got from the upper split.
+    allpass: [LinkwitzRiley; 2],
+}
+
+impl Crossover {
+    fn new(sample_rate: u32, lower: f32, upper: f32) -> Self {
+        let lowpass = |freq| LinkwitzRiley::new(Type::LowPass, sample_rate, freq);
+        let highpass = |freq| LinkwitzRiley::new(Type::HighPass, sample_rate, freq);
+        Self {
+            low: lowpass(lower),
+            rest: highpass(lower),
+            mid: lowpass(upper),
+            high: highpass(upper),
+            allpass: [lowpass(upper), highpass(upper)],
+        }
+    }
+
+    fn split(&mut self, sample: f32) -> [f32; 3] {
+        let low = self.low.run(sample);
+        let low = self.allpass[0].run(low) + self.allpass[1].run(low);
+        let rest = self.rest.run(sample);
+        [low, self.mid.run(rest), self.high.run(rest)]
+    }
+}
+
+/// Turns down a signal by `ratio` above a threshold, following its level with an envelope.
+struct Compressor {
+    /// Threshold in dB.
+    threshold: f32,
+    ratio: f32,
+    /// Followed level in dB.
+    envelope: f32,
+    /// Reduction of the last sample in dB, for display.
+    reduction: f32,
+}
+
+impl Compressor {
+    fn new(threshold: f32, ratio: f32) -> Self {
+        Self {
+            threshold,
+            ratio,
+            envelope: -120.0,
+            reduction: 0.0,
+        }
+    }
+
+    /// The gain to apply for a sample with the given peak, smoothed by the attack and release
+    /// coefficients.
+    fn gain(&mut self, peak: f32, attack: f32, release: f32) -> f32 {
+        let level = 20.0 * (peak + 1e-6).log10();
+        let coefficient = if level > self.envelope {
+            attack
+        } else {
+            release
+        };
+        self.envelope = level + coefficient * (self.envelope - level);
+
+        let over = (self.envelope - self.threshold).max(0.0);
+        self.reduction = over * (1.0 - 1.0 / self.ratio.max(1.0));
+        10f32.powf(-self.reduction / 20.0)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    solo: [bool; 3],
+}
+
+/// A [`Module`] compressing a low, mid and high band of its input separately.
+pub struct MultibandComp {
+    /// Crossover frequencies between the low and mid and the mid and high band in Hz.
+    crossovers: [f32; 2],
+    bands: [Compressor; 3],
+    solo: [bool; 3],
+    /// Attack and release time in ms, shared by the bands.
+    attack: f32,
+    release: f32,
+    /// Gain applied after compression in dB.
+    makeup: f32,
+    channels: Option<[Crossover; 2]>,
+    /// Splits the sidechain into the same bands, only used while it is connected.
+    sidechain: Option<[Crossover; 2]>,
+    /// The crossover frequencies and sample rate the filters were made for.
+    applied: Option<([f32; 2], u32)>,
+}
+
+impl Default for MultibandComp {
+    fn default() -> Self {
+        Self {
+            crossovers: [200.0, 2500.0],
+            bands: [
+                Compressor::new(-20.0, 4.0),
+                Compressor::new(-20.0, 3.0),
+                Compressor::new(-20.0, 3.0),
+            ],
+            solo: [false; 3],
+            attack: 10.0,
+            release: 150.0,
+            makeup: 0.0,
+            channels: None,
+            sidechain: None,
+            applied: None,
+        }
+    }
+}
+
+impl Module for MultibandComp {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("MultibandComp")
+            .icon("🗜")
+            .category(Category::Effect)
+            .tooltip("compresses the lows, mids and highs of its input separately, to even out a mix without pumping")
+            .example(example)
+            .port(
+                PortDescription::<MultibandCompInput>::input()
+                    .tooltip("the sound to compress")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<MultibandCompSidechainInput>::input()
+                    .tooltip("when connected, its level decides the compression instead of the input")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<MultibandCompOutput>::output()
+                    .tooltip("the sum of the compressed bands"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate();
+        let lower = self.crossovers[0].min(self.crossovers[1]);
+        let upper = self.crossovers[0].max(self.crossovers[1]);
+        if self.applied != Some(([lower, upper], sample_rate)) {
+            self.applied = Some(([lower, upper], sample_rate));
+            self.channels = Some([Crossover::new(sample_rate, lower, upper); 2]);
+            self.sidechain = Some([Crossover::new(sample_rate, lower, upper); 2]);
+        }
+        let channels = self.channels.as_mut().unwrap();
+
+        let input = ctx.get_input::<MultibandCompInput>();
+        let (left, right) = input.as_f32_tuple();
+        let left = channels[0].split(left);
+        let right = channels[1].split(right);
+
+        let detector = if ctx.is_connected::<MultibandCompSidechainInput>() {
+            let sidechain = self.sidechain.as_mut().unwrap();
+            let (side_left, side_right) = ctx
+                .get_input::<MultibandCompSidechainInput>()
+                .as_f32_tuple();
+            (
+                sidechain[0].split(side_left),
+                sidechain[1].split(side_right),
+            )
+        } else {
+            (left, right)
+        };
+
+        let time = |ms: f32| (-1.0 / (ms.max(0.1) / 1000.0 * sample_rate as f32)).exp();
+        let (attack, release) = (time(self.attack), time(self.release));
+        let soloing = self.solo.contains(&true);
+        let makeup = 10f32.powf(self.makeup / 20.0);
+
+        let mut output = (0.0, 0.0);
+        for (band, compressor) in self.bands.iter_mut().enumerate() {
+            // linked, so the stereo image doesn't shift
+            let peak = detector.0[band].abs().max(detector.1[band].abs());
+            let gain = compressor.gain(peak, attack, release) * makeup;
+            if !soloing || self.solo[band] {
+                output.0 += left[band] * gain;
+                output.1 += right[band] * gain;
+            }
+        }
+
+        let output = match input {
+            Frame::Mono(_) => Frame::Mono(output.0),
+            Frame::Stereo(..) => Frame::Stereo(output.0, output.1),
+        };
+        ctx.set_output::<MultibandCompOutput>(output)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("crossovers:"));
+            for freq in self.crossovers.iter_mut() {
+                ui.add(
+                    egui::DragValue::new(freq)
+                        .clamp_range(20.0..=16000.0)
+                        .speed(5.0)
+                        .suffix(" Hz"),
+                )
+                .labelled_by(label.id);
+            }
+
+            let sidechain = PortHandle::new(MultibandCompSidechainInput::id(), ctx.instance);
+            if ctx.has_connection(sidechain) {
+                ui.label(format!("🔗 {}", tr("sidechain")))
+                    .on_hover_text(tr("the sidechain decides the compression"));
+            }
+        });
+
+        egui::Grid::new(ctx.instance).num_columns(5).show(ui, |ui| {
+            for (band, compressor) in self.bands.iter_mut().enumerate() {
+                let label = ui.label(tr(BAND_NAMES[band]));
+                ui.add(
+                    egui::DragValue::new(&mut compressor.threshold)
+                        .clamp_range(-60.0..=0.0)
+                        .speed(0.2)
+                        .suffix(" dB"),
+                )
+                .labelled_by(label.id)
+                .on_hover_text(tr("threshold"));
+                ui.add(
+                    egui::DragValue::new(&mut compressor.ratio)
+                        .clamp_range(1.0..=20.0)
+                        .speed(0.05)
+                        .suffix(":1"),
+                )
+                .labelled_by(label.id)
+                .on_hover_text(tr("ratio"));
+                ui.toggle_value(&mut self.solo[band], "S")
+                    .on_hover_text(tr("solo"));
+                ui.add(
+                    egui::ProgressBar::new(compressor.reduction / 24.0)
+                        .desired_width(80.0)
+                        .text(format!("-{:.1} dB", compressor.reduction)),
+                )
+                .on_hover_text(tr("gain reduction"));
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("attack:"));
+            ui.add(
+                egui::DragValue::new(&mut self.attack)
+                    .clamp_range(0.1..=200.0)
+                    .speed(0.2)
+                    .suffix(" ms"),
+            )
+            .labelled_by(label.id);
+            let label = ui.label(tr("release:"));
+            ui.add(
+                egui::DragValue::new(&mut self.release)
+                    .clamp_range(5.0..=2000.0)
+                    .speed(2.0)
+                    .suffix(" ms"),
+            )
+            .labelled_by(label.id);
+            let label = ui.label(tr("makeup:"));
+            ui.add(
+                egui::DragValue::new(&mut self.makeup)
+                    .clamp_range(0.0..=24.0)
+                    .speed(0.1)
+                    .suffix(" dB"),
+            )
+            .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        let mut parameters = self
+            .crossovers
+            .iter_mut()
+            .zip(CROSSOVER_NAMES)
+            .map(|(freq, name)| Parameter::new(name, freq, 20.0..=16000.0))
+            .collect::<Vec<_>>();
+        for (band, compressor) in self.bands.iter_mut().enumerate() {
+            parameters.push(Parameter::new(
+                THRESHOLD_NAMES[band],
+                &mut compressor.threshold,
+                -60.0..=0.0,
+            ));
+            parameters.push(Parameter::new(
+                RATIO_NAMES[band],
+                &mut compressor.ratio,
+                1.0..=20.0,
+            ));
+        }
+        parameters.push(Parameter::new("attack", &mut self.attack, 0.1..=200.0));
+        parameters.push(Parameter::new("release", &mut self.release, 5.0..=2000.0));
+        parameters.push(Parameter::new("makeup", &mut self.makeup, 0.0..=24.0));
+        parameters
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State { solo: self.solo }).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.solo = state.solo;
+        }
+    }
+}
+
+/// Noise ducked by a kick through the sidechain, start the transport to hear it.
+fn example(rack: &mut Rack, panel: usize) {
+    let noise = rack.add_module_to::<Noise>(panel);
+    let sequencer = rack.add_module_to::<Sequencer>(panel);
+    let kick = rack.add_module_to::<Kick>(panel);
+    let compressor = rack.add_module_to::<MultibandComp>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<NoiseOutput, MultibandCompInput>(noise, compressor)
+        .unwrap();
+    rack.connect_typed::<SequencerGateOutput, DrumTriggerInput>(sequencer, kick)
+        .unwrap();
+    rack.connect_typed::<DrumOutput, MultibandCompSidechainInput>(kick, compressor)
+        .unwrap();
+    rack.connect_typed::<MultibandCompOutput, AudioInput>(compressor, audio)
+        .unwrap();
+}
@@ -1,9 +1,11 @@
-use eframe::egui::Ui;
+use eframe::egui::{self, Ui};
 use rand::Rng;
 
 use crate::{
-    module::{Module, ModuleDescription, Port, PortDescription},
-    rack::rack::{ProcessContext, ShowContext},
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::scope::{Scope, ScopeInput},
+    rack::rack::{ProcessContext, Rack, ShowContext},
 };
 
 pub struct NoiseOutput;
@@ -16,8 +18,15 @@ impl Port for NoiseOutput {
     }
 }
 
-#[derive(Default)]
-pub struct Noise {}
+pub struct Noise {
+    level: f32,
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Self { level: 1.0 }
+    }
+}
 
 impl Module for Noise {
     fn describe() -> ModuleDescription<Self>
@@ -25,13 +34,38 @@ impl Module for Noise {
         Self: Sized,
     {
         ModuleDescription::default()
-            .name("✨ Noise")
-            .port(PortDescription::<NoiseOutput>::output())
+            .name("Noise")
+            .icon("✨")
+            .category(Category::Source)
+            .tooltip("white noise")
+            .example(example)
+            .port(
+                PortDescription::<NoiseOutput>::output()
+                    .tooltip("random samples between -1 and 1, scaled by the level"),
+            )
     }
 
     fn process(&mut self, ctx: &mut ProcessContext) {
-        ctx.set_output::<NoiseOutput>(rand::thread_rng().gen_range(-1.0..=1.0))
+        ctx.set_output::<NoiseOutput>(rand::thread_rng().gen_range(-1.0..=1.0) * self.level)
     }
 
-    fn show(&mut self, _: &ShowContext, _: &mut Ui) {}
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("level:"));
+            ui.add(egui::Slider::new(&mut self.level, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("level", &mut self.level, 0.0..=1.0)]
+    }
+}
+
+/// Shows noise on a scope.
+fn example(rack: &mut Rack, panel: usize) {
+    let noise = rack.add_module_to::<Noise>(panel);
+    let scope = rack.add_module_to::<Scope>(panel);
+    rack.connect_typed::<NoiseOutput, ScopeInput>(noise, scope)
+        .unwrap();
 }
@@ -0,0 +1,164 @@
+use std::f32::consts::TAU;
+
+use eframe::egui::{self, Ui};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        keyboard::{Keyboard, KeyboardFreqOutput},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Cutoff in Hz of the filter cleaning up the input before its zero crossings are tracked.
+const TRACKING_CUTOFF: f32 = 800.0;
+
+/// Cutoff in Hz below which the rectified up octave is removed, as it is all above zero.
+const DC_CUTOFF: f32 = 10.0;
+
+pub struct OctaverInput;
+
+impl Port for OctaverInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for OctaverInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct OctaverOutput;
+
+impl Port for OctaverOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// A [`Module`] adding an octave below and above a monophonic sound, the way analog guitar
+/// pedals do: a flip-flop toggled by the zero crossings for the sub and rectification for the up.
+pub struct Octaver {
+    dry: f32,
+    sub: f32,
+    up: f32,
+    /// The input after the tracking filter.
+    tracked: f32,
+    /// Sign of the sub octave, flipped on every other zero crossing.
+    flip: f32,
+    /// Average of the rectified input, subtracted from it.
+    dc: f32,
+}
+
+impl Default for Octaver {
+    fn default() -> Self {
+        Self {
+            dry: 1.0,
+            sub: 0.5,
+            up: 0.0,
+            tracked: 0.0,
+            flip: 1.0,
+            dc: 0.0,
+        }
+    }
+}
+
+/// Coefficient of a one pole lowpass filter.
+fn coefficient(cutoff: f32, sample_rate: f32) -> f32 {
+    1.0 - (-TAU * cutoff / sample_rate).exp()
+}
+
+impl Module for Octaver {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Octaver")
+            .icon("8")
+            .category(Category::Effect)
+            .tooltip("adds an octave below and above a sound playing one note at a time")
+            .example(example)
+            .port(
+                PortDescription::<OctaverInput>::input()
+                    .tooltip("the sound, playing one note at a time")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<OctaverOutput>::output()
+                    .tooltip("the sound with its octaves mixed in"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate() as f32;
+        let input = ctx.get_input::<OctaverInput>().as_f32_mono();
+
+        let previous = self.tracked;
+        self.tracked += (input - self.tracked) * coefficient(TRACKING_CUTOFF, sample_rate);
+        if previous < 0.0 && self.tracked >= 0.0 {
+            self.flip = -self.flip;
+        }
+
+        let rectified = input.abs();
+        self.dc += (rectified - self.dc) * coefficient(DC_CUTOFF, sample_rate);
+
+        let output =
+            input * self.dry + input * self.flip * self.sub + (rectified - self.dc) * self.up;
+        ctx.set_output::<OctaverOutput>(Frame::Mono(output))
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("dry:"));
+            ui.add(egui::Slider::new(&mut self.dry, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("sub:"));
+            ui.add(egui::Slider::new(&mut self.sub, 0.0..=1.0))
+                .labelled_by(label.id)
+                .on_hover_text(tr("level of the octave below"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("up:"));
+            ui.add(egui::Slider::new(&mut self.up, 0.0..=1.0))
+                .labelled_by(label.id)
+                .on_hover_text(tr("level of the octave above"));
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("dry", &mut self.dry, 0.0..=1.0),
+            Parameter::new("sub", &mut self.sub, 0.0..=1.0),
+            Parameter::new("up", &mut self.up, 0.0..=1.0),
+        ]
+    }
+}
+
+/// Notes of the keyboard with an octave below.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let octaver = rack.add_module_to::<Octaver>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<KeyboardFreqOutput, FrequencyInput>(keyboard, oscillator)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, OctaverInput>(oscillator, octaver)
+        .unwrap();
+    rack.connect_typed::<OctaverOutput, AudioInput>(octaver, audio)
+        .unwrap();
+}
@@ -1,13 +1,19 @@
 use std::{
+    any::Any,
     marker::PhantomData,
     ops::{Add, Div, Mul, Sub},
 };
 
 use eframe::egui::{self, Ui};
 use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    module::{Input, Module, ModuleDescription, Port, PortDescription, PortValueBoxed},
+    i18n::tr,
+    module::{
+        Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription,
+        PortValueBoxed,
+    },
     rack::rack::{ProcessContext, ShowContext},
     util::EnumIter,
 };
@@ -26,14 +32,6 @@ impl Input for InValueA<f32> {
     fn default() -> Self::Type {
         Self::Type::default()
     }
-
-    fn show(value: &mut Self::Type, ui: &mut Ui) {
-        ui.add(
-            egui::DragValue::new(value)
-                .clamp_range(0.0..=f32::MAX)
-                .speed(1.0),
-        );
-    }
 }
 
 pub struct InValueB<T>(PhantomData<T>);
@@ -50,14 +48,6 @@ impl Input for InValueB<f32> {
     fn default() -> Self::Type {
         Self::Type::default()
     }
-
-    fn show(value: &mut Self::Type, ui: &mut Ui) {
-        ui.add(
-            egui::DragValue::new(value)
-                .clamp_range(0.0..=f32::MAX)
-                .speed(1.0),
-        );
-    }
 }
 
 pub struct OutValue<T>(PhantomData<T>);
@@ -70,8 +60,8 @@ impl<T: PortValueBoxed + Clone> Port for OutValue<T> {
     }
 }
 
-#[derive(Clone, Copy, Default, PartialEq, Sequence)]
-enum Operator {
+#[derive(Clone, Copy, Default, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum Operator {
     #[default]
     Add,
     Sub,
@@ -91,24 +81,27 @@ impl Operator {
 }
 
 pub struct Operation<T> {
-    operator: Operator,
-    phantom: PhantomData<T>,
+    pub operator: Operator,
+    /// Operands while their inputs are unconnected.
+    a: T,
+    b: T,
 }
 
-impl<T> Default for Operation<T> {
+impl<T: Default> Default for Operation<T> {
     fn default() -> Self {
         Self {
             operator: Operator::default(),
-            phantom: PhantomData,
+            a: T::default(),
+            b: T::default(),
         }
     }
 }
 
 impl<T> Module for Operation<T>
 where
-    T: PortValueBoxed + Clone,
-    InValueA<T>: Input,
-    InValueB<T>: Input,
+    T: PortValueBoxed + Clone + Default,
+    InValueA<T>: Input<Type = T>,
+    InValueB<T>: Input<Type = T>,
     <InValueA<T> as Port>::Type: Add<<InValueB<T> as Port>::Type, Output = T>,
     <InValueA<T> as Port>::Type: Sub<<InValueB<T> as Port>::Type, Output = T>,
     <InValueA<T> as Port>::Type: Mul<<InValueB<T> as Port>::Type, Output = T>,
@@ -116,22 +109,53 @@ where
 {
     fn describe() -> ModuleDescription<Self> {
         ModuleDescription::default()
-            .name(&format!("➕✖Operation<{}>", T::name()))
-            .port(PortDescription::<InValueA<T>>::input())
-            .port(PortDescription::<InValueB<T>>::input())
-            .port(PortDescription::<OutValue<T>>::output())
+            .name(&format!("Operation<{}>", T::name()))
+            .icon("➕✖")
+            .category(Category::Utility)
+            .tooltip("combines two values with the selected operator")
+            .port(
+                PortDescription::<InValueA<T>>::input()
+                    .tooltip("the left operand")
+                    .normalled(|operation: &Self| operation.a.clone()),
+            )
+            .port(
+                PortDescription::<InValueB<T>>::input()
+                    .tooltip("the right operand")
+                    .normalled(|operation: &Self| operation.b.clone()),
+            )
+            .port(PortDescription::<OutValue<T>>::output().tooltip("the result"))
     }
 
     fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
         ui.horizontal(|ui| {
             egui::ComboBox::from_id_source(ctx.instance)
-                .selected_text(format!("{:?}", self.operator.as_str()))
+                .selected_text(tr(self.operator.as_str()))
                 .show_ui(ui, |ui| {
                     for operator in Operator::iter() {
-                        ui.selectable_value(&mut self.operator, operator, operator.as_str());
+                        ui.selectable_value(&mut self.operator, operator, tr(operator.as_str()));
                     }
                 });
         });
+
+        for (name, value) in [("a:", &mut self.a), ("b:", &mut self.b)] {
+            if let Some(value) = (value as &mut dyn Any).downcast_mut::<f32>() {
+                ui.horizontal(|ui| {
+                    let label = ui.label(name);
+                    ui.add(egui::DragValue::new(value).speed(1.0))
+                        .labelled_by(label.id);
+                });
+            }
+        }
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        [("a", &mut self.a), ("b", &mut self.b)]
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let value = (value as &mut dyn Any).downcast_mut::<f32>()?;
+                Some(Parameter::new(name, value, -20000.0..=20000.0))
+            })
+            .collect()
     }
 
     fn process(&mut self, ctx: &mut ProcessContext) {
@@ -145,4 +169,14 @@ where
             Operator::Div => a / b,
         })
     }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&self.operator).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(operator) = ron::from_str(state) {
+            self.operator = operator;
+        }
+    }
 }
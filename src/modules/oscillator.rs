@@ -2,14 +2,20 @@ use std::f32::consts::PI;
 
 use eframe::egui::{self, Ui};
 use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    module::{Input, Module, ModuleDescription, Port, PortDescription},
-    rack::rack::{ProcessContext, ShowContext},
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        keyboard::{Keyboard, KeyboardFreqOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
     util::EnumIter,
 };
 
-#[derive(Clone, Copy, PartialEq, Sequence)]
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
 pub enum Wave {
     Sine,
     Square,
@@ -26,6 +32,16 @@ impl Wave {
             Wave::Saw => "Saw",
         }
     }
+
+    /// Value of the wave from -1 to 1 at `index`, a position in its period from 0 to 1.
+    pub fn sample(&self, index: f32) -> f32 {
+        match self {
+            Wave::Sine => (index * 2.0 * PI).sin(),
+            Wave::Square => index.round() * 2.0 - 1.0,
+            Wave::Triangle => ((1.0 - index) * 4.0 - 2.0).abs() - 1.0,
+            Wave::Saw => (index * 2.0) - 1.0,
+        }
+    }
 }
 
 pub struct FrequencyInput;
@@ -42,15 +58,6 @@ impl Input for FrequencyInput {
     fn default() -> Self::Type {
         70.0
     }
-
-    fn show(value: &mut Self::Type, ui: &mut Ui) {
-        ui.add(
-            egui::DragValue::new(value)
-                .clamp_range(0.0..=f32::MAX)
-                .speed(1.0)
-                .suffix(" Hz"),
-        );
-    }
 }
 
 pub struct FrameOutput;
@@ -63,18 +70,47 @@ impl Port for FrameOutput {
     }
 }
 
+pub struct SubOutput;
+
+impl Port for SubOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "sub"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    wave: Wave,
+    alternating: bool,
+    sub_octaves: u32,
+}
+
 pub struct Oscillator {
     pub wave: Wave,
+    /// Frequency in Hz while the frequency input is unconnected.
+    pub freq: f32,
     index: f32,
     alternating: bool,
+    /// Offset from the input frequency in cents.
+    detune: f32,
+    /// How many octaves the sub output is below the main wave.
+    sub_octaves: u32,
+    /// Periods of the main wave completed within the current period of the sub wave.
+    cycles: u32,
 }
 
 impl Default for Oscillator {
     fn default() -> Self {
         Self {
             wave: Wave::Sine,
+            freq: FrequencyInput::default(),
             index: 0.0,
             alternating: true,
+            detune: 0.0,
+            sub_octaves: 1,
+            cycles: 0,
         }
     }
 }
@@ -82,41 +118,119 @@ impl Default for Oscillator {
 impl Module for Oscillator {
     fn describe() -> ModuleDescription<Self> {
         ModuleDescription::default()
-            .name("📉 Oscillator")
-            .port(PortDescription::<FrequencyInput>::input())
-            .port(PortDescription::<FrameOutput>::output())
+            .name("Oscillator")
+            .icon("📉")
+            .category(Category::Source)
+            .tooltip("a periodic wave at the given frequency")
+            .example(example)
+            .port(
+                PortDescription::<FrequencyInput>::input()
+                    .tooltip("frequency of the wave in Hz")
+                    .normalled(|oscillator: &Self| oscillator.freq),
+            )
+            .port(PortDescription::<FrameOutput>::output().tooltip("the wave"))
+            .port(
+                PortDescription::<SubOutput>::output()
+                    .tooltip("the same wave one or two octaves lower, in phase with the main wave"),
+            )
     }
 
     fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
         ui.horizontal(|ui| {
+            let label = ui.label(tr("freq:"));
+            ui.add(
+                egui::DragValue::new(&mut self.freq)
+                    .clamp_range(0.0..=f32::MAX)
+                    .speed(1.0)
+                    .suffix(" Hz"),
+            )
+            .labelled_by(label.id);
+
             egui::ComboBox::new(ctx.instance, "wave")
-                .selected_text(format!("{:?}", self.wave.as_str()))
+                .selected_text(tr(self.wave.as_str()))
                 .show_ui(ui, |ui| {
                     for wave in Wave::iter() {
-                        ui.selectable_value(&mut self.wave, wave, wave.as_str());
+                        ui.selectable_value(&mut self.wave, wave, tr(wave.as_str()));
                     }
                 });
 
-            ui.checkbox(&mut self.alternating, "alternating");
+            ui.checkbox(&mut self.alternating, tr("alternating"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("detune:"));
+            ui.add(
+                egui::DragValue::new(&mut self.detune)
+                    .clamp_range(-100.0..=100.0)
+                    .speed(0.5)
+                    .suffix(" ct"),
+            )
+            .labelled_by(label.id);
+
+            ui.label(tr("sub:"));
+            ui.selectable_value(&mut self.sub_octaves, 1, "-1")
+                .on_hover_text(tr("one octave lower"));
+            ui.selectable_value(&mut self.sub_octaves, 2, "-2")
+                .on_hover_text(tr("two octaves lower"));
         });
     }
 
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("freq", &mut self.freq, 20.0..=20000.0),
+            Parameter::new("detune", &mut self.detune, -100.0..=100.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            wave: self.wave,
+            alternating: self.alternating,
+            sub_octaves: self.sub_octaves,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.wave = state.wave;
+            self.alternating = state.alternating;
+            self.sub_octaves = state.sub_octaves.clamp(1, 2);
+        }
+    }
+
     fn process(&mut self, ctx: &mut ProcessContext) {
-        let mut ampl = match self.wave {
-            Wave::Sine => (self.index * 2.0 * PI).sin(),
-            Wave::Square => self.index.round() * 2.0 - 1.0,
-            Wave::Triangle => ((1.0 - self.index) * 4.0 - 2.0).abs() - 1.0,
-            Wave::Saw => (self.index * 2.0) - 1.0,
-        };
+        let periods = 1 << self.sub_octaves;
+        self.cycles %= periods;
+        let sub_index = (self.cycles as f32 + self.index) / periods as f32;
+
+        let (mut ampl, mut sub) = (self.wave.sample(self.index), self.wave.sample(sub_index));
 
         if !self.alternating {
             ampl = (ampl + 1.0) / 2.0;
+            sub = (sub + 1.0) / 2.0;
         }
 
         let len = 1.0 / ctx.sample_rate() as f32;
-        self.index += len * ctx.get_input::<FrequencyInput>();
+        let freq = ctx.get_input::<FrequencyInput>() * 2f32.powf(self.detune / 1200.0);
+        self.index += len * freq;
+        if self.index >= 1.0 {
+            self.cycles += self.index as u32;
+        }
         self.index %= 1.0;
 
-        ctx.set_output::<FrameOutput>(ampl)
+        ctx.set_output::<FrameOutput>(ampl);
+        ctx.set_output::<SubOutput>(sub)
     }
 }
+
+/// Plays the oscillator with the keyboard.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<KeyboardFreqOutput, FrequencyInput>(keyboard, oscillator)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, AudioInput>(oscillator, audio)
+        .unwrap();
+}
@@ -0,0 +1,508 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    path::PathBuf,
+    sync::mpsc::{Receiver, Sender},
+};
+
+use eframe::{
+    egui::{self, Key, Ui},
+    epaint::{Color32, Vec2},
+};
+use rfd::FileDialog;
+use serde::{Deserialize, Serialize};
+
+use super::file::File;
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    rack::rack::{ProcessContext, ShowContext},
+    toast,
+    velocity::VelocityResponse,
+};
+
+const PAD_COUNT: usize = 16;
+
+/// MIDI note of the first pad by default, the bass drum in General MIDI.
+const FIRST_NOTE: u8 = 36;
+
+const PAD_NAMES: [&str; PAD_COUNT] = [
+    "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+];
+
+const GAIN_NAMES: [&str; PAD_COUNT] = [
+    "pad 1 gain",
+    "pad 2 gain",
+    "pad 3 gain",
+    "pad 4 gain",
+    "pad 5 gain",
+    "pad 6 gain",
+    "pad 7 gain",
+    "pad 8 gain",
+    "pad 9 gain",
+    "pad 10 gain",
+    "pad 11 gain",
+    "pad 12 gain",
+    "pad 13 gain",
+    "pad 14 gain",
+    "pad 15 gain",
+    "pad 16 gain",
+];
+
+const PITCH_NAMES: [&str; PAD_COUNT] = [
+    "pad 1 pitch",
+    "pad 2 pitch",
+    "pad 3 pitch",
+    "pad 4 pitch",
+    "pad 5 pitch",
+    "pad 6 pitch",
+    "pad 7 pitch",
+    "pad 8 pitch",
+    "pad 9 pitch",
+    "pad 10 pitch",
+    "pad 11 pitch",
+    "pad 12 pitch",
+    "pad 13 pitch",
+    "pad 14 pitch",
+    "pad 15 pitch",
+    "pad 16 pitch",
+];
+
+/// Keys playing the pads, laid out like the grid.
+const PAD_KEYS: [Key; PAD_COUNT] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Q,
+    Key::W,
+    Key::E,
+    Key::R,
+    Key::A,
+    Key::S,
+    Key::D,
+    Key::F,
+    Key::Z,
+    Key::X,
+    Key::C,
+    Key::V,
+];
+
+pub struct PadTriggerInput<const N: usize>;
+
+impl<const N: usize> Port for PadTriggerInput<N> {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        PAD_NAMES[N]
+    }
+}
+
+impl<const N: usize> Input for PadTriggerInput<N> {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+fn get_trigger<const N: usize>(ctx: &ProcessContext) -> bool {
+    ctx.get_input::<PadTriggerInput<N>>()
+}
+
+const TRIGGERS: [fn(&ProcessContext) -> bool; PAD_COUNT] = [
+    get_trigger::<0>,
+    get_trigger::<1>,
+    get_trigger::<2>,
+    get_trigger::<3>,
+    get_trigger::<4>,
+    get_trigger::<5>,
+    get_trigger::<6>,
+    get_trigger::<7>,
+    get_trigger::<8>,
+    get_trigger::<9>,
+    get_trigger::<10>,
+    get_trigger::<11>,
+    get_trigger::<12>,
+    get_trigger::<13>,
+    get_trigger::<14>,
+    get_trigger::<15>,
+];
+
+pub struct PadsOutput;
+
+impl Port for PadsOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+enum Message {
+    Decoded(usize, Option<Vec<Frame>>),
+    PickedFile(usize, PathBuf),
+}
+
+/// A single pad holding a one-shot sample.
+struct Pad {
+    buffer: Vec<Frame>,
+    path: String,
+    gain: f32,
+    /// Pitch in semitones.
+    pitch: f32,
+    /// Playback position in samples, `None` when not playing.
+    position: Option<f32>,
+    last_trigger: bool,
+    pending: bool,
+    /// How hard the pending hit was, from 0 to 1.
+    force: f32,
+    /// Velocity of the playing hit.
+    velocity: f32,
+    loading: bool,
+}
+
+impl Default for Pad {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            path: String::new(),
+            gain: 1.0,
+            pitch: 0.0,
+            position: None,
+            last_trigger: false,
+            pending: false,
+            force: 1.0,
+            velocity: 1.0,
+            loading: false,
+        }
+    }
+}
+
+impl Pad {
+    fn label(&self, index: usize) -> String {
+        PathBuf::from(&self.path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().chars().take(8).collect())
+            .unwrap_or_else(|| PAD_NAMES[index].to_string())
+    }
+
+    /// Hits from the trigger input are played with full force.
+    fn next(&mut self, trigger: bool, response: &VelocityResponse) -> Frame {
+        if trigger && !self.last_trigger && !self.pending {
+            self.force = 1.0;
+            self.pending = true;
+        }
+        if self.pending {
+            self.position = Some(0.0);
+            self.velocity = response.apply(self.force);
+            self.pending = false;
+        }
+        self.last_trigger = trigger;
+
+        let Some(position) = self.position else {
+            return Frame::ZERO;
+        };
+
+        let index = position as usize;
+        if index + 1 >= self.buffer.len() {
+            self.position = None;
+            return Frame::ZERO;
+        }
+
+        let fraction = position - index as f32;
+        let frame = self.buffer[index] * (1.0 - fraction) + self.buffer[index + 1] * fraction;
+
+        self.position = Some(position + 2f32.powf(self.pitch / 12.0));
+
+        frame * self.gain * self.velocity
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    keys: bool,
+    first_note: u8,
+    velocity: VelocityResponse,
+}
+
+/// A [`Module`] with a grid of pads that each play a one-shot sample.
+pub struct Pads {
+    pads: Vec<Pad>,
+    selected: usize,
+    keys: bool,
+    /// MIDI note playing the first pad, the following notes play the following pads.
+    first_note: u8,
+    velocity: VelocityResponse,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl Default for Pads {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            pads: (0..PAD_COUNT).map(|_| Pad::default()).collect(),
+            selected: 0,
+            keys: false,
+            first_note: FIRST_NOTE,
+            velocity: VelocityResponse::default(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Pads {
+    fn load(&mut self, index: usize, sample_rate: usize) {
+        let pad = &mut self.pads[index];
+        pad.loading = true;
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            let path = pad.path.clone();
+            move || {
+                sender
+                    .send(Message::Decoded(index, File::decode(&path, sample_rate)))
+                    .ok();
+            }
+        });
+    }
+
+    fn open_picker(&self, index: usize) {
+        let dialog = FileDialog::new().add_filter("audio", &["mp3", "wav", "flac", "ogg"]);
+
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            move || {
+                if let Some(path) = dialog.pick_file() {
+                    sender.send(Message::PickedFile(index, path)).ok();
+                }
+            }
+        });
+    }
+}
+
+impl Module for Pads {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Pads")
+            .icon("🎛")
+            .category(Category::Source)
+            .tooltip(
+                "a grid of pads that each play a one-shot sample, triggered by clicking, keys, MIDI notes or the trigger inputs",
+            )
+            .port(PortDescription::<PadTriggerInput<0>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<1>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<2>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<3>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<4>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<5>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<6>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<7>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<8>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<9>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<10>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<11>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<12>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<13>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<14>>::input().group("triggers"))
+            .port(PortDescription::<PadTriggerInput<15>>::input().group("triggers"))
+            .port(PortDescription::<PadsOutput>::output().tooltip("all playing pads mixed"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let mut mixed = Frame::ZERO;
+
+        for (pad, trigger) in self.pads.iter_mut().zip(TRIGGERS) {
+            mixed += pad.next(trigger(ctx), &self.velocity);
+        }
+
+        ctx.set_output::<PadsOutput>(mixed)
+    }
+
+    fn note_on(&mut self, note: u8, velocity: f32) {
+        let Some(index) = note.checked_sub(self.first_note) else {
+            return;
+        };
+        if let Some(pad) = self.pads.get_mut(index as usize) {
+            pad.force = velocity;
+            pad.pending = true;
+        }
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        let messages = self.receiver.try_iter().collect::<Vec<_>>();
+        for message in messages {
+            match message {
+                Message::Decoded(index, buffer) => {
+                    let pad = &mut self.pads[index];
+                    if let Some(buffer) = buffer {
+                        pad.buffer = buffer;
+                        pad.position = None;
+                    } else {
+                        toast::error(format!("{} {}", tr("could not load:"), pad.path));
+                    }
+                    pad.loading = false;
+                }
+                Message::PickedFile(index, path) => {
+                    self.pads[index].path = path.to_string_lossy().to_string();
+                    self.load(index, ctx.sample_rate as usize);
+                }
+            }
+        }
+
+        if self.keys && !ui.ctx().wants_keyboard_input() {
+            ui.input(|input| {
+                for (pad, key) in self.pads.iter_mut().zip(PAD_KEYS) {
+                    if input.key_pressed(key) {
+                        pad.force = 1.0;
+                        pad.pending = true;
+                    }
+                }
+            });
+        }
+
+        egui::Grid::new(ctx.instance)
+            .spacing(Vec2::splat(4.0))
+            .show(ui, |ui| {
+                for index in 0..PAD_COUNT {
+                    let pad = &mut self.pads[index];
+                    let mut button =
+                        egui::Button::new(pad.label(index)).min_size(Vec2::new(60.0, 40.0));
+
+                    if pad.position.is_some() {
+                        button = button.fill(Color32::DARK_GREEN);
+                    }
+
+                    if index == self.selected {
+                        button = button.stroke((1.0, Color32::WHITE));
+                    }
+
+                    let response = ui.add(button);
+                    if response.contains_pointer()
+                        && ui.input(|input| input.pointer.primary_pressed())
+                    {
+                        // lower on the pad hits harder
+                        if let Some(position) = ui.input(|input| input.pointer.interact_pos()) {
+                            pad.force = (position.y - response.rect.top()) / response.rect.height();
+                        }
+                        pad.pending = true;
+                        self.selected = index;
+                    }
+
+                    if index % 4 == 3 {
+                        ui.end_row();
+                    }
+                }
+            });
+
+        let pad = &mut self.pads[self.selected];
+        ui.horizontal(|ui| {
+            ui.label(format!("{} {}:", tr("pad"), PAD_NAMES[self.selected]));
+            let label = ui.label(tr("gain:"));
+            ui.add(
+                egui::DragValue::new(&mut pad.gain)
+                    .clamp_range(0.0..=2.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id);
+            let label = ui.label(tr("pitch:"));
+            ui.add(
+                egui::DragValue::new(&mut pad.pitch)
+                    .clamp_range(-24.0..=24.0)
+                    .speed(0.1)
+                    .suffix(" st"),
+            )
+            .labelled_by(label.id);
+        });
+
+        let mut load = false;
+        let mut pick = false;
+        ui.horizontal(|ui| {
+            let pad = &mut self.pads[self.selected];
+            load = ui.text_edit_singleline(&mut pad.path).lost_focus();
+            pick = ui.button(tr("pick")).clicked();
+
+            if pad.loading {
+                ui.spinner();
+            }
+
+            ui.checkbox(&mut self.keys, tr("keys"))
+                .on_hover_text_at_pointer(tr("play the pads with 1-4, Q-R, A-F and Z-V"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("first MIDI note:"));
+            ui.add(
+                egui::DragValue::new(&mut self.first_note).clamp_range(0..=127 - PAD_COUNT as u8),
+            )
+            .labelled_by(label.id)
+            .on_hover_text(tr(
+                "note playing the first pad, the next notes play the next pads",
+            ));
+        });
+
+        self.velocity.show(ui, (ctx.instance, "velocity"));
+
+        if load {
+            self.load(self.selected, ctx.sample_rate as usize);
+        }
+
+        if pick {
+            self.open_picker(self.selected)
+        }
+    }
+
+    fn assets(&self) -> Vec<String> {
+        if self.pads.iter().all(|pad| pad.path.is_empty()) {
+            return Vec::new();
+        }
+        self.pads.iter().map(|pad| pad.path.clone()).collect()
+    }
+
+    fn load_assets(&mut self, paths: &[String]) {
+        for (index, path) in paths.iter().enumerate().take(PAD_COUNT) {
+            if !path.is_empty() {
+                self.sender
+                    .send(Message::PickedFile(index, PathBuf::from(path)))
+                    .ok();
+            }
+        }
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        let mut parameters = Vec::new();
+        for (index, pad) in self.pads.iter_mut().enumerate() {
+            parameters.push(Parameter::new(GAIN_NAMES[index], &mut pad.gain, 0.0..=2.0));
+            parameters.push(Parameter::new(
+                PITCH_NAMES[index],
+                &mut pad.pitch,
+                -24.0..=24.0,
+            ));
+        }
+        parameters
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            keys: self.keys,
+            first_note: self.first_note,
+            velocity: self.velocity,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.keys = state.keys;
+            self.first_note = state.first_note.min(127 - PAD_COUNT as u8);
+            self.velocity = state.velocity;
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use eframe::{
+    egui::{self, Sense, Ui},
+    epaint::{Color32, Pos2, Rect, Stroke, Vec2},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::note_name,
+};
+
+pub struct PianoRollFreqOutput;
+
+impl Port for PianoRollFreqOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "freq"
+    }
+}
+
+pub struct PianoRollGateOutput;
+
+impl Port for PianoRollGateOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "gate"
+    }
+}
+
+/// Grid resolution in beats, a sixteenth note.
+const STEP: f64 = 0.25;
+/// Amount of visible note rows.
+const ROWS: u8 = 24;
+const CELL: Vec2 = Vec2::new(12.0, 8.0);
+/// Time in beats before the end of a note where the gate is released, so repeated notes retrigger.
+const GAP: f64 = 0.02;
+
+/// A note in a clip, with its start and length in steps.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct ClipNote {
+    start: u32,
+    length: u32,
+    pitch: u8,
+}
+
+impl ClipNote {
+    fn contains(&self, step: u32, pitch: u8) -> bool {
+        self.pitch == pitch && step >= self.start && step < self.start + self.length
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    notes: Vec<ClipNote>,
+    bars: u32,
+    looping: bool,
+    low: u8,
+}
+
+/// A [`Module`] that plays a clip of notes in sync with the transport.
+pub struct PianoRoll {
+    notes: Vec<ClipNote>,
+    bars: u32,
+    looping: bool,
+    /// The lowest visible note.
+    low: u8,
+    /// Semitones added to the played notes.
+    transpose: f32,
+    /// The note currently being drawn.
+    drawing: Option<ClipNote>,
+    /// Playback position within the clip in beats, `None` when past the end.
+    position: Option<f64>,
+}
+
+impl Default for PianoRoll {
+    fn default() -> Self {
+        Self {
+            notes: Vec::new(),
+            bars: 2,
+            looping: true,
+            low: 48,
+            transpose: 0.0,
+            drawing: None,
+            position: None,
+        }
+    }
+}
+
+impl PianoRoll {
+    fn steps(&self, beats_per_bar: u32) -> u32 {
+        self.bars * beats_per_bar * (1.0 / STEP) as u32
+    }
+
+    /// The note sounding at a position in beats.
+    fn note_at(&self, position: f64) -> Option<&ClipNote> {
+        self.notes.iter().rev().find(|note| {
+            let start = note.start as f64 * STEP;
+            let end = (note.start + note.length) as f64 * STEP - GAP;
+            position >= start && position < end
+        })
+    }
+}
+
+impl Module for PianoRoll {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("PianoRoll")
+            .icon("🎼")
+            .category(Category::Sequencing)
+            .tooltip("plays a clip of notes in sync with the transport")
+            .example(example)
+            .port(
+                PortDescription::<PianoRollFreqOutput>::output()
+                    .tooltip("frequency in Hz of the playing note"),
+            )
+            .port(
+                PortDescription::<PianoRollGateOutput>::output().tooltip("high while a note plays"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let transport = ctx.transport();
+        let length = self.steps(transport.beats_per_bar) as f64 * STEP;
+
+        self.position = if !transport.playing {
+            None
+        } else if self.looping {
+            Some(transport.position() % length)
+        } else {
+            Some(transport.position()).filter(|&position| position < length)
+        };
+
+        let note = self.position.and_then(|position| self.note_at(position));
+
+        if let Some(note) = note {
+            ctx.set_output::<PianoRollFreqOutput>(
+                ctx.tuning()
+                    .note_to_freq(note.pitch as f32 + self.transpose),
+            );
+            ctx.set_output::<PianoRollGateOutput>(true);
+        } else {
+            ctx.set_output::<PianoRollGateOutput>(false);
+        }
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("bars:"));
+            ui.add(egui::DragValue::new(&mut self.bars).clamp_range(1..=16))
+                .labelled_by(label.id);
+            ui.checkbox(&mut self.looping, tr("loop"));
+            let label = ui.label(tr("lowest note:"));
+            ui.add(
+                egui::DragValue::new(&mut self.low)
+                    .clamp_range(0..=127 - ROWS)
+                    .custom_formatter(|value, _| note_name(value as u8)),
+            )
+            .labelled_by(label.id);
+            if ui.button(tr("clear")).clicked() {
+                self.notes.clear();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("transpose:"));
+            ui.add(
+                egui::DragValue::new(&mut self.transpose)
+                    .clamp_range(-24.0..=24.0)
+                    .speed(0.1)
+                    .suffix(" st"),
+            )
+            .labelled_by(label.id);
+        });
+
+        let steps = self.steps(ctx.transport.beats_per_bar);
+        let steps_per_beat = (1.0 / STEP) as u32;
+        let steps_per_bar = steps_per_beat * ctx.transport.beats_per_bar;
+        let low = self.low;
+
+        egui::ScrollArea::horizontal()
+            .id_source(ctx.instance)
+            .show(ui, |ui| {
+                let size = Vec2::new(steps as f32 * CELL.x, ROWS as f32 * CELL.y);
+                let (response, painter) = ui.allocate_painter(size, Sense::click_and_drag());
+                let rect = response.rect;
+
+                let cell_at = |pos: Pos2| -> (u32, u8) {
+                    let relative = pos - rect.min;
+                    let step = (relative.x / CELL.x).clamp(0.0, steps as f32 - 1.0) as u32;
+                    let row = (relative.y / CELL.y).clamp(0.0, ROWS as f32 - 1.0) as u8;
+                    (step, low + ROWS - 1 - row)
+                };
+
+                let cell_rect = |step: u32, pitch: u8, length: u32| -> Rect {
+                    let row = (low + ROWS - 1 - pitch) as f32;
+                    Rect::from_min_size(
+                        rect.min + Vec2::new(step as f32 * CELL.x, row * CELL.y),
+                        Vec2::new(length as f32 * CELL.x, CELL.y),
+                    )
+                };
+
+                if let Some(pos) = response.interact_pointer_pos() {
+                    let (step, pitch) = cell_at(pos);
+
+                    if response.clicked() {
+                        if let Some(index) = self
+                            .notes
+                            .iter()
+                            .position(|note| note.contains(step, pitch))
+                        {
+                            self.notes.remove(index);
+                        } else {
+                            self.notes.push(ClipNote {
+                                start: step,
+                                length: 1,
+                                pitch,
+                            });
+                        }
+                    } else if response.drag_started() {
+                        self.drawing = Some(ClipNote {
+                            start: step,
+                            length: 1,
+                            pitch,
+                        });
+                    } else if let Some(drawing) = &mut self.drawing {
+                        drawing.length = (step + 1).saturating_sub(drawing.start).max(1);
+                    }
+                }
+
+                if response.drag_stopped() {
+                    if let Some(drawing) = self.drawing.take() {
+                        self.notes.push(drawing);
+                    }
+                }
+
+                for row in 0..ROWS {
+                    let pitch = low + ROWS - 1 - row;
+                    let color = if matches!(pitch % 12, 1 | 3 | 6 | 8 | 10) {
+                        Color32::from_gray(20)
+                    } else {
+                        Color32::from_gray(35)
+                    };
+                    painter.rect_filled(cell_rect(0, pitch, steps), 0.0, color);
+                }
+
+                for step in 0..=steps {
+                    let x = rect.min.x + step as f32 * CELL.x;
+                    let color = if step % steps_per_bar == 0 {
+                        Color32::GRAY
+                    } else if step % steps_per_beat == 0 {
+                        Color32::from_gray(70)
+                    } else {
+                        Color32::from_gray(45)
+                    };
+                    painter.vline(x, rect.y_range(), Stroke::new(1.0f32, color));
+                }
+
+                let visible = low..low + ROWS;
+                for note in self.notes.iter().chain(self.drawing.iter()) {
+                    if visible.contains(&note.pitch) && note.start < steps {
+                        let length = note.length.min(steps - note.start);
+                        painter.rect(
+                            cell_rect(note.start, note.pitch, length).shrink(0.5),
+                            1.0,
+                            Color32::LIGHT_GREEN,
+                            Stroke::new(1.0f32, Color32::DARK_GREEN),
+                        );
+                    }
+                }
+
+                if let Some(position) = self.position {
+                    let x = rect.min.x + (position / STEP) as f32 * CELL.x;
+                    painter.vline(x, rect.y_range(), Stroke::new(1.0f32, Color32::GOLD));
+                }
+
+                if let Some(pos) = response.hover_pos() {
+                    let (_, pitch) = cell_at(pos);
+                    response.on_hover_text_at_pointer(note_name(pitch));
+                }
+            });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new(
+            "transpose",
+            &mut self.transpose,
+            -24.0..=24.0,
+        )]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            notes: self.notes.clone(),
+            bars: self.bars,
+            looping: self.looping,
+            low: self.low,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.notes = state.notes;
+            self.bars = state.bars.clamp(1, 16);
+            self.looping = state.looping;
+            self.low = state.low.min(127 - ROWS);
+        }
+    }
+}
+
+/// Plays an oscillator with the piano roll, start the transport to hear it.
+fn example(rack: &mut Rack, panel: usize) {
+    let roll = rack.add_module_to::<PianoRoll>(panel);
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<PianoRollFreqOutput, FrequencyInput>(roll, oscillator)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, AudioInput>(oscillator, audio)
+        .unwrap();
+}
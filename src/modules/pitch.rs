@@ -0,0 +1,262 @@
+use std::collections::VecDeque;
+
+use eframe::egui::{self, Ui};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::note_name,
+};
+
+/// Rate the input is lowered to before analysis, high enough for the fundamental of most instruments.
+const ANALYSIS_RATE: u32 = 11025;
+
+/// Amount of analysed samples compared against their delayed copy, limits the lowest pitch to
+/// about 43Hz.
+const WINDOW: usize = 256;
+
+/// Amount of new analysed samples between two analyses.
+const HOP: usize = 64;
+
+pub struct PitchTrackInput;
+
+impl Port for PitchTrackInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for PitchTrackInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct PitchTrackFreqOutput;
+
+impl Port for PitchTrackFreqOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "freq"
+    }
+}
+
+pub struct PitchTrackGateOutput;
+
+impl Port for PitchTrackGateOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "gate"
+    }
+}
+
+/// A [`Module`] that follows the pitch of a monophonic input, using the YIN algorithm.
+pub struct PitchTrack {
+    /// Highest normalized difference accepted as a pitch, lower is stricter.
+    threshold: f32,
+    /// Level in dB below which the input is considered silent.
+    floor: f32,
+    /// The last `2 * WINDOW` analysed samples.
+    history: VecDeque<f32>,
+    /// Sum and count of the input samples being averaged into the next analysed sample.
+    accumulated: (f32, u32),
+    /// Analysed samples since the last analysis.
+    pending: usize,
+    freq: f32,
+    gate: bool,
+    /// How certain the last analysis was of its pitch, from 0 to 1.
+    confidence: f32,
+}
+
+impl Default for PitchTrack {
+    fn default() -> Self {
+        Self {
+            threshold: 0.15,
+            floor: -45.0,
+            history: VecDeque::from(vec![0.0; WINDOW * 2]),
+            accumulated: (0.0, 0),
+            pending: 0,
+            freq: 0.0,
+            gate: false,
+            confidence: 0.0,
+        }
+    }
+}
+
+impl PitchTrack {
+    /// Finds the period of `samples` in samples together with its normalized difference, if that
+    /// difference is below `threshold`.
+    fn period(samples: &[f32], threshold: f32) -> Option<(f32, f32)> {
+        let mut difference = [0.0f32; WINDOW];
+        for (tau, difference) in difference.iter_mut().enumerate().skip(1) {
+            *difference = (0..WINDOW)
+                .map(|i| (samples[i] - samples[i + tau]).powi(2))
+                .sum();
+        }
+
+        // cumulative mean normalized difference
+        let mut normalized = [1.0f32; WINDOW];
+        let mut sum = 0.0;
+        for tau in 1..WINDOW {
+            sum += difference[tau];
+            normalized[tau] = if sum > 0.0 {
+                difference[tau] * tau as f32 / sum
+            } else {
+                1.0
+            };
+        }
+
+        let mut tau = (2..WINDOW - 1).find(|&tau| normalized[tau] < threshold)?;
+        while tau + 1 < WINDOW - 1 && normalized[tau + 1] < normalized[tau] {
+            tau += 1;
+        }
+
+        // parabolic interpolation between the neighbours for a period between samples
+        let (a, b, c) = (normalized[tau - 1], normalized[tau], normalized[tau + 1]);
+        let curvature = a - 2.0 * b + c;
+        let offset = if curvature.abs() > f32::EPSILON {
+            (a - c) / (2.0 * curvature)
+        } else {
+            0.0
+        };
+
+        Some((tau as f32 + offset, normalized[tau]))
+    }
+
+    fn analyse(&mut self) {
+        let threshold = self.threshold;
+        let samples = self.history.make_contiguous();
+
+        let energy =
+            samples.iter().map(|sample| sample.powi(2)).sum::<f32>() / samples.len() as f32;
+        let level = 10.0 * (energy + 1e-10).log10();
+
+        match Self::period(samples, threshold).filter(|_| level > self.floor) {
+            Some((period, difference)) => {
+                self.freq = ANALYSIS_RATE as f32 / period;
+                self.confidence = 1.0 - difference;
+                self.gate = true;
+            }
+            None => {
+                self.confidence = 0.0;
+                self.gate = false;
+            }
+        }
+    }
+}
+
+impl Module for PitchTrack {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("PitchTrack")
+            .icon("🎤")
+            .category(Category::Analysis)
+            .tooltip("follows the pitch of a monophonic sound, so an instrument can play the synth")
+            .example(example)
+            .port(
+                PortDescription::<PitchTrackInput>::input()
+                    .tooltip("a sound playing one note at a time")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<PitchTrackFreqOutput>::output()
+                    .tooltip("the detected frequency in Hz, held while no pitch is detected"),
+            )
+            .port(
+                PortDescription::<PitchTrackGateOutput>::output()
+                    .tooltip("high while a pitch is detected"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let factor = (ctx.sample_rate() / ANALYSIS_RATE).max(1);
+
+        self.accumulated.0 += ctx.get_input::<PitchTrackInput>().as_f32_mono();
+        self.accumulated.1 += 1;
+
+        if self.accumulated.1 >= factor {
+            self.history.pop_front();
+            self.history
+                .push_back(self.accumulated.0 / self.accumulated.1 as f32);
+            self.accumulated = (0.0, 0);
+
+            self.pending += 1;
+            if self.pending >= HOP {
+                self.pending = 0;
+                self.analyse();
+            }
+        }
+
+        ctx.set_output::<PitchTrackFreqOutput>(self.freq);
+        ctx.set_output::<PitchTrackGateOutput>(self.gate);
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("threshold:"));
+            ui.add(egui::Slider::new(&mut self.threshold, 0.05..=0.5))
+                .labelled_by(label.id)
+                .on_hover_text(tr("higher also accepts pitches it is less certain of"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("floor:"));
+            ui.add(egui::Slider::new(&mut self.floor, -80.0..=0.0).suffix(" dB"))
+                .labelled_by(label.id)
+                .on_hover_text(tr("quieter input is treated as silence"));
+        });
+
+        ui.horizontal(|ui| {
+            let note = 69.0 + 12.0 * (self.freq / 440.0).log2();
+            let text = if self.freq > 0.0 {
+                format!(
+                    "{:.1} Hz {}",
+                    self.freq,
+                    note_name(note.round().clamp(0.0, 127.0) as u8)
+                )
+            } else {
+                "-".to_string()
+            };
+            ui.add_enabled(self.gate, egui::Label::new(text));
+            ui.add(
+                egui::ProgressBar::new(self.confidence)
+                    .desired_width(100.0)
+                    .text(tr("confidence")),
+            );
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("threshold", &mut self.threshold, 0.05..=0.5),
+            Parameter::new("floor", &mut self.floor, -80.0..=0.0),
+        ]
+    }
+}
+
+/// A second oscillator follows the pitch of the first.
+fn example(rack: &mut Rack, panel: usize) {
+    let source = rack.add_module_to::<Oscillator>(panel);
+    let pitch = rack.add_module_to::<PitchTrack>(panel);
+    let follower = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<FrameOutput, PitchTrackInput>(source, pitch)
+        .unwrap();
+    rack.connect_typed::<PitchTrackFreqOutput, FrequencyInput>(pitch, follower)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, AudioInput>(follower, audio)
+        .unwrap();
+}
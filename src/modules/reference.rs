@@ -0,0 +1,181 @@
+use std::f64::consts::TAU;
+
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Port, PortDescription},
+    modules::stats::{Stats, StatsInput},
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::EnumIter,
+};
+
+/// Amplitude of -18 dBFS, the usual alignment level.
+const ALIGNMENT: f32 = 0.125_892_54;
+
+pub struct ReferenceOutput;
+
+impl Port for ReferenceOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum Signal {
+    /// A 440 Hz sine with a peak of 0 dBFS.
+    A440,
+    /// A 1 kHz sine with a peak of -18 dBFS.
+    Tone,
+    /// Pink noise with an RMS of -18 dBFS.
+    Pink,
+    /// A constant 1.0.
+    Dc,
+}
+
+impl Signal {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Signal::A440 => "440 Hz sine, 0 dBFS",
+            Signal::Tone => "1 kHz tone, -18 dBFS",
+            Signal::Pink => "pink noise, -18 dBFS RMS",
+            Signal::Dc => "DC 1.0",
+        }
+    }
+}
+
+/// Paul Kellet's filter turning white noise into pink noise, accurate above 9Hz at 44.1kHz.
+#[derive(Default)]
+struct PinkFilter {
+    state: [f32; 7],
+}
+
+impl PinkFilter {
+    fn run(&mut self, white: f32) -> f32 {
+        let b = &mut self.state;
+        b[0] = 0.99886 * b[0] + white * 0.0555179;
+        b[1] = 0.99332 * b[1] + white * 0.0750759;
+        b[2] = 0.96900 * b[2] + white * 0.153852;
+        b[3] = 0.86650 * b[3] + white * 0.3104856;
+        b[4] = 0.55000 * b[4] + white * 0.5329522;
+        b[5] = -0.7616 * b[5] - white * 0.0168980;
+        let pink = b.iter().sum::<f32>() + white * 0.5362;
+        b[6] = white * 0.115926;
+        pink
+    }
+
+    /// The factor that brings the output to an RMS of [`ALIGNMENT`], for uniform white noise
+    /// between -1 and 1.
+    ///
+    /// The filter is linear, so the output's variance is the input's variance times the energy of
+    /// its impulse response.
+    fn gain() -> f32 {
+        let mut filter = Self::default();
+        let energy = (0..100_000)
+            .map(|i| filter.run(if i == 0 { 1.0 } else { 0.0 }) as f64)
+            .map(|sample| sample.powi(2))
+            .sum::<f64>();
+        let rms = (energy / 3.0).sqrt();
+        (ALIGNMENT as f64 / rms) as f32
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    signal: Signal,
+}
+
+/// A [`Module`] producing exactly known signals, to calibrate levels and check the signal chain.
+pub struct Reference {
+    signal: Signal,
+    /// Phase of the sines from 0 to 1, in `f64` so it doesn't drift over long sessions.
+    phase: f64,
+    pink: PinkFilter,
+    pink_gain: f32,
+}
+
+impl Default for Reference {
+    fn default() -> Self {
+        Self {
+            signal: Signal::A440,
+            phase: 0.0,
+            pink: PinkFilter::default(),
+            pink_gain: PinkFilter::gain(),
+        }
+    }
+}
+
+impl Reference {
+    fn sine(&mut self, freq: f64, sample_rate: u32) -> f32 {
+        let sample = (self.phase * TAU).sin() as f32;
+        self.phase = (self.phase + freq / sample_rate as f64).fract();
+        sample
+    }
+}
+
+impl Module for Reference {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Reference")
+            .icon("📏")
+            .category(Category::Analysis)
+            .tooltip("exactly known signals to calibrate levels and verify the signal chain with")
+            .example(example)
+            .port(PortDescription::<ReferenceOutput>::output().tooltip("the selected signal"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample = match self.signal {
+            Signal::A440 => self.sine(440.0, ctx.sample_rate()),
+            Signal::Tone => self.sine(1000.0, ctx.sample_rate()) * ALIGNMENT,
+            Signal::Pink => {
+                let white = rand::thread_rng().gen_range(-1.0..=1.0);
+                self.pink.run(white) * self.pink_gain
+            }
+            Signal::Dc => 1.0,
+        };
+        ctx.set_output::<ReferenceOutput>(sample)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        egui::ComboBox::new(ctx.instance, "")
+            .selected_text(tr(self.signal.as_str()))
+            .show_ui(ui, |ui| {
+                for signal in Signal::iter() {
+                    ui.selectable_value(&mut self.signal, signal, tr(signal.as_str()));
+                }
+            });
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            signal: self.signal,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.signal = state.signal;
+        }
+    }
+}
+
+/// Checks the level of pink noise.
+fn example(rack: &mut Rack, panel: usize) {
+    let reference = rack.add_module_to::<Reference>(panel);
+    let stats = rack.add_module_to::<Stats>(panel);
+    if let Some(reference) = rack.get_module_mut(reference) {
+        reference.signal = Signal::Pink;
+    }
+    rack.connect_typed::<ReferenceOutput, StatsInput>(reference, stats)
+        .unwrap();
+}
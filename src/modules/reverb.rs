@@ -0,0 +1,280 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, Sender},
+};
+
+use eframe::egui::{self, Ui};
+use rfd::FileDialog;
+
+use crate::{
+    convolution::Convolver,
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::file::File,
+    rack::rack::{ProcessContext, ShowContext},
+    toast,
+};
+
+/// Size of the blocks the impulse response is split into, also the latency in samples.
+const BLOCK: usize = 256;
+
+/// Longest impulse response used in seconds, the rest is cut off.
+const MAX_LENGTH: f32 = 10.0;
+
+pub struct ReverbInput;
+
+impl Port for ReverbInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for ReverbInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct ReverbOutput;
+
+impl Port for ReverbOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+enum Message {
+    /// A convolver for each channel of the impulse response and its length in samples.
+    Loaded(Option<(Vec<Convolver>, usize)>),
+    PickedFile(PathBuf),
+}
+
+/// A [`Module`] that places its input in a recorded space by convolving it with an impulse
+/// response.
+pub struct Reverb {
+    path: String,
+    /// Balance between the input and the reverb, from 0 (dry) to 1 (wet).
+    mix: f32,
+    /// One convolver for a mono impulse response, two for a stereo one.
+    convolvers: Vec<Convolver>,
+    /// Length of the loaded impulse response in samples.
+    length: usize,
+    /// The input delayed by the latency of the convolution, to stay aligned with the reverb.
+    dry: VecDeque<Frame>,
+    loading: bool,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            path: String::new(),
+            mix: 0.3,
+            convolvers: Vec::new(),
+            length: 0,
+            dry: VecDeque::from(vec![Frame::ZERO; BLOCK]),
+            loading: false,
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Reverb {
+    pub fn open_file(&self, path: impl AsRef<Path>) {
+        self.sender
+            .send(Message::PickedFile(path.as_ref().into()))
+            .ok();
+    }
+
+    /// Decodes the impulse response and prepares its convolvers in the background.
+    fn load(&mut self, sample_rate: u32) {
+        self.loading = true;
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            let path = self.path.clone();
+            move || {
+                let loaded = File::decode(&path, sample_rate as usize).map(|mut impulse| {
+                    impulse.truncate((MAX_LENGTH * sample_rate as f32) as usize);
+                    (Self::convolvers(&impulse), impulse.len())
+                });
+                sender.send(Message::Loaded(loaded)).ok();
+            }
+        });
+    }
+
+    /// Makes a convolver for each channel, normalized so the reverb is about as loud as the input.
+    fn convolvers(impulse: &[Frame]) -> Vec<Convolver> {
+        let stereo = impulse
+            .first()
+            .is_some_and(|frame| matches!(frame, Frame::Stereo(..)));
+
+        let channels: Vec<Vec<f32>> = if stereo {
+            let (left, right) = impulse.iter().map(|frame| frame.as_f32_tuple()).unzip();
+            vec![left, right]
+        } else {
+            vec![impulse.iter().map(|frame| frame.as_f32_mono()).collect()]
+        };
+
+        let energy = channels
+            .iter()
+            .flatten()
+            .map(|sample| sample.powi(2))
+            .sum::<f32>()
+            / channels.len() as f32;
+        let scale = 1.0 / energy.sqrt().max(f32::EPSILON);
+
+        channels
+            .iter()
+            .map(|channel| {
+                let normalized = channel
+                    .iter()
+                    .map(|sample| sample * scale)
+                    .collect::<Vec<_>>();
+                Convolver::new(&normalized, BLOCK)
+            })
+            .collect()
+    }
+
+    fn open_picker(&self) {
+        let mut dialog =
+            FileDialog::new().add_filter("impulse response", &["wav", "flac", "ogg", "mp3"]);
+
+        if !self.path.is_empty() {
+            dialog = dialog.set_directory(&self.path);
+        }
+
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            move || {
+                if let Some(path) = dialog.pick_file() {
+                    sender.send(Message::PickedFile(path)).ok();
+                }
+            }
+        });
+    }
+}
+
+impl Module for Reverb {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Reverb")
+            .icon("⛪")
+            .category(Category::Effect)
+            .tooltip("places its input in the space an impulse response file was recorded in")
+            .latency(BLOCK)
+            .port(
+                PortDescription::<ReverbInput>::input()
+                    .tooltip("the sound to place in the space")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(
+                PortDescription::<ReverbOutput>::output()
+                    .tooltip("the input mixed with its reverb"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let input = ctx.get_input::<ReverbInput>();
+
+        self.dry.push_back(input);
+        let dry = self.dry.pop_front().unwrap_or(Frame::ZERO);
+
+        let output = match (self.convolvers.as_mut_slice(), input) {
+            ([], _) => dry,
+            ([mono], Frame::Mono(sample)) => {
+                dry * (1.0 - self.mix) + Frame::Mono(mono.process(sample)) * self.mix
+            }
+            (convolvers, input) => {
+                let (left, right) = input.as_f32_tuple();
+                let wet_left = convolvers[0].process(left);
+                let wet_right = match convolvers.get_mut(1) {
+                    Some(convolver) => convolver.process(right),
+                    None => wet_left,
+                };
+                dry * (1.0 - self.mix) + Frame::Stereo(wet_left, wet_right) * self.mix
+            }
+        };
+
+        ctx.set_output::<ReverbOutput>(output)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        let messages = self.receiver.try_iter().collect::<Vec<_>>();
+        for message in messages {
+            match message {
+                Message::Loaded(loaded) => {
+                    if let Some((convolvers, length)) = loaded {
+                        self.convolvers = convolvers;
+                        self.length = length;
+                    } else {
+                        toast::error(format!("{} {}", tr("could not load:"), self.path));
+                    }
+                    self.loading = false
+                }
+                Message::PickedFile(path) => {
+                    self.path = path.to_string_lossy().to_string();
+                    self.load(ctx.sample_rate);
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.text_edit_singleline(&mut self.path).lost_focus() {
+                self.load(ctx.sample_rate);
+            }
+
+            if ui.button(tr("pick")).clicked() {
+                self.open_picker()
+            }
+
+            if self.loading {
+                ui.spinner();
+            }
+        });
+
+        if !self.convolvers.is_empty() {
+            ui.label(format!(
+                "{:.2} s, {}",
+                self.length as f32 / ctx.sample_rate as f32,
+                tr(if self.convolvers.len() == 2 {
+                    "stereo"
+                } else {
+                    "mono"
+                })
+            ));
+        }
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("mix:"));
+            ui.add(egui::Slider::new(&mut self.mix, 0.0..=1.0))
+                .labelled_by(label.id)
+                .on_hover_text(tr("0 is only the input, 1 only the reverb"));
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("mix", &mut self.mix, 0.0..=1.0)]
+    }
+
+    fn asset(&self) -> Option<String> {
+        (!self.path.is_empty()).then(|| self.path.clone())
+    }
+
+    fn load_asset(&mut self, path: &str) {
+        self.open_file(path)
+    }
+}
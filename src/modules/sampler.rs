@@ -0,0 +1,226 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    path::PathBuf,
+    sync::mpsc::{Receiver, Sender},
+};
+
+use eframe::egui::{self, Ui};
+use rfd::FileDialog;
+
+use super::file::File;
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    rack::rack::{ProcessContext, ShowContext},
+    toast,
+};
+
+pub struct SamplerTriggerInput;
+
+impl Port for SamplerTriggerInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "trigger"
+    }
+}
+
+impl Input for SamplerTriggerInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct SamplerOutput;
+
+impl Port for SamplerOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+enum Message {
+    Decoded(Option<Vec<Frame>>),
+    PickedFile(PathBuf),
+}
+
+/// A [`Module`] playing a short sound from the start each time its trigger goes high, to build
+/// drum patches driven by sequencers.
+pub struct Sampler {
+    buffer: Vec<Frame>,
+    path: String,
+    /// Pitch in semitones, which also changes the length.
+    pitch: f32,
+    gain: f32,
+    /// Position in the buffer in samples, past the end when not playing.
+    position: f32,
+    last_trigger: bool,
+    loading: bool,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            buffer: Vec::new(),
+            path: String::new(),
+            pitch: 0.0,
+            gain: 1.0,
+            position: f32::INFINITY,
+            last_trigger: false,
+            loading: false,
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Sampler {
+    fn load(&mut self, sample_rate: usize) {
+        self.loading = true;
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            let path = self.path.clone();
+            move || {
+                sender
+                    .send(Message::Decoded(File::decode(&path, sample_rate)))
+                    .ok();
+            }
+        });
+    }
+
+    fn open_picker(&self) {
+        let dialog = FileDialog::new().add_filter("audio", &["mp3", "wav", "flac", "ogg"]);
+
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            move || {
+                if let Some(path) = dialog.pick_file() {
+                    sender.send(Message::PickedFile(path)).ok();
+                }
+            }
+        });
+    }
+}
+
+impl Module for Sampler {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Sampler")
+            .icon("🥁")
+            .category(Category::Source)
+            .tooltip("plays a short audio file from the start each time its trigger goes high")
+            .port(
+                PortDescription::<SamplerTriggerInput>::input()
+                    .tooltip("restarts the sound when going high"),
+            )
+            .port(PortDescription::<SamplerOutput>::output().tooltip("the sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let trigger = ctx.get_input::<SamplerTriggerInput>();
+        if trigger && !self.last_trigger {
+            self.position = 0.0;
+        }
+        self.last_trigger = trigger;
+
+        // silent past the end, the sound plays once
+        let mut output = Frame::ZERO;
+        if self.position < self.buffer.len() as f32 {
+            output = File::sample_at(&self.buffer, self.position as f64) * self.gain;
+            self.position += 2f32.powf(self.pitch / 12.0);
+        }
+        ctx.set_output::<SamplerOutput>(output)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        let messages = self.receiver.try_iter().collect::<Vec<_>>();
+        for message in messages {
+            match message {
+                Message::Decoded(buffer) => {
+                    if let Some(buffer) = buffer {
+                        self.buffer = buffer;
+                        self.position = f32::INFINITY;
+                    } else {
+                        toast::error(format!("{} {}", tr("could not load:"), self.path));
+                    }
+                    self.loading = false;
+                }
+                Message::PickedFile(path) => {
+                    self.path = path.to_string_lossy().to_string();
+                    self.load(ctx.sample_rate as usize);
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.text_edit_singleline(&mut self.path).lost_focus() {
+                self.load(ctx.sample_rate as usize);
+            }
+            if ui.button(tr("pick")).clicked() {
+                self.open_picker();
+            }
+            if self.loading {
+                ui.spinner();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.buffer.is_empty(), egui::Button::new("▶"))
+                .on_hover_text(tr("play"))
+                .clicked()
+            {
+                self.position = 0.0;
+            }
+
+            let length = self.buffer.len() as f32;
+            ui.add(
+                egui::ProgressBar::new((self.position / length.max(1.0)).min(1.0))
+                    .desired_width(150.0),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("pitch:"));
+            ui.add(egui::Slider::new(&mut self.pitch, -24.0..=24.0).suffix(" st"))
+                .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("gain:"));
+            ui.add(egui::Slider::new(&mut self.gain, 0.0..=2.0))
+                .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("pitch", &mut self.pitch, -24.0..=24.0),
+            Parameter::new("gain", &mut self.gain, 0.0..=2.0),
+        ]
+    }
+
+    fn asset(&self) -> Option<String> {
+        (!self.path.is_empty()).then(|| self.path.clone())
+    }
+
+    fn load_asset(&mut self, path: &str) {
+        self.sender
+            .send(Message::PickedFile(PathBuf::from(path)))
+            .ok();
+    }
+}
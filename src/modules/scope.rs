@@ -3,13 +3,19 @@ use eframe::{
     epaint::Color32,
 };
 use egui_plot::{Legend, Line, Plot};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     frame::Frame,
-    module::{Input, Module, ModuleDescription, Port, PortDescription},
-    rack::rack::{ProcessContext, ShowContext},
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::oscillator::{FrameOutput, Oscillator},
+    rack::rack::{ProcessContext, Rack, ShowContext},
 };
 
+/// Longest duration and interval in ms.
+const MAX_TIME: f32 = 10000.0;
+
 pub struct ScopeInput;
 
 impl Port for ScopeInput {
@@ -31,10 +37,21 @@ enum State {
     Waiting { waited: usize },
 }
 
+#[derive(Serialize, Deserialize)]
+struct Settings {
+    duration: f32,
+    interval: f32,
+    lock_range: bool,
+}
+
 pub struct Scope {
     buffer: Vec<f32>,
+    /// Time plotted in ms.
+    duration: f32,
+    /// Time between plots in ms.
+    interval: f32,
+    /// Samples plotted, the duration at the current sample rate.
     size: usize,
-    interval: usize,
     state: State,
     lock_range: bool,
 }
@@ -43,8 +60,9 @@ impl Default for Scope {
     fn default() -> Self {
         Self {
             buffer: Default::default(),
-            size: 10000,
-            interval: 50000,
+            duration: 50.0,
+            interval: 250.0,
+            size: 2500,
             state: State::Updating { pos: 0 },
             lock_range: true,
         }
@@ -81,15 +99,25 @@ impl Scope {
 impl Module for Scope {
     fn describe() -> ModuleDescription<Self> {
         ModuleDescription::default()
-            .name("📈 Scope")
+            .name("Scope")
+            .icon("📈")
+            .category(Category::Analysis)
+            .tooltip("plots its input over time")
+            .visual()
+            .example(example)
             .port(
                 PortDescription::<ScopeInput>::input()
+                    .tooltip("the signal to plot")
                     .conversion(|frame: Frame| frame.as_f32_mono()),
             )
             .port(PortDescription::<ScopeInput>::input())
     }
 
     fn process(&mut self, ctx: &mut ProcessContext) {
+        let samples_per_ms = ctx.sample_rate() as f32 / 1000.0;
+        self.size = ((self.duration * samples_per_ms) as usize).max(1);
+        let interval = (self.interval * samples_per_ms) as usize;
+
         match self.state {
             State::Updating { pos } => {
                 if pos >= self.size {
@@ -108,7 +136,8 @@ impl Module for Scope {
                 }
             }
             State::Waiting { waited } => {
-                if self.interval > waited {
+                // waiting longer when degraded refreshes the plot less often
+                if interval * ctx.degradation().decimation() > waited {
                     self.state = State::Waiting { waited: waited + 1 }
                 } else {
                     self.state = State::Updating { pos: 0 }
@@ -120,34 +149,25 @@ impl Module for Scope {
     fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
         ui.set_min_width(400.0);
         ui.horizontal(|ui| {
-            ui.label("duration");
-            {
-                let mut seconds = self.size / (ctx.sample_rate as usize / 1000);
-                if ui
-                    .add(
-                        egui::DragValue::new(&mut seconds)
-                            .suffix(" ms")
-                            .speed(5)
-                            .clamp_range(1..=usize::MAX),
-                    )
-                    .changed()
-                {
-                    self.size = seconds * (ctx.sample_rate as usize / 1000)
-                }
-            }
-
-            ui.label("interval");
-            {
-                let mut interval = self.interval / (ctx.sample_rate as usize / 1000);
-                if ui
-                    .add(egui::DragValue::new(&mut interval).suffix(" ms").speed(10))
-                    .changed()
-                {
-                    self.interval = interval * (ctx.sample_rate as usize / 1000)
-                }
-            }
+            let label = ui.label(tr("duration"));
+            ui.add(
+                egui::DragValue::new(&mut self.duration)
+                    .suffix(" ms")
+                    .speed(5)
+                    .clamp_range(1.0..=MAX_TIME),
+            )
+            .labelled_by(label.id);
+
+            let label = ui.label(tr("interval"));
+            ui.add(
+                egui::DragValue::new(&mut self.interval)
+                    .suffix(" ms")
+                    .speed(10)
+                    .clamp_range(0.0..=MAX_TIME),
+            )
+            .labelled_by(label.id);
 
-            ui.checkbox(&mut self.lock_range, "locked")
+            ui.checkbox(&mut self.lock_range, tr("locked"))
         });
 
         let mut plot = Plot::new(ctx.instance)
@@ -171,4 +191,36 @@ impl Module for Scope {
             }
         });
     }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("duration", &mut self.duration, 1.0..=MAX_TIME),
+            Parameter::new("interval", &mut self.interval, 0.0..=MAX_TIME),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&Settings {
+            duration: self.duration,
+            interval: self.interval,
+            lock_range: self.lock_range,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(settings) = ron::from_str::<Settings>(state) {
+            self.duration = settings.duration.clamp(1.0, MAX_TIME);
+            self.interval = settings.interval.clamp(0.0, MAX_TIME);
+            self.lock_range = settings.lock_range;
+        }
+    }
+}
+
+/// Shows the wave of an oscillator.
+fn example(rack: &mut Rack, panel: usize) {
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let scope = rack.add_module_to::<Scope>(panel);
+    rack.connect_typed::<FrameOutput, ScopeInput>(oscillator, scope)
+        .unwrap();
 }
@@ -0,0 +1,391 @@
+use eframe::{
+    egui::{self, Ui},
+    epaint::{Color32, Vec2},
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    transport::{Division, SyncedTime},
+    util::note_name,
+};
+
+pub struct SequencerFreqOutput;
+
+impl Port for SequencerFreqOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "freq"
+    }
+}
+
+pub struct SequencerGateOutput;
+
+impl Port for SequencerGateOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "gate"
+    }
+}
+
+pub struct SequencerVelocityOutput;
+
+impl Port for SequencerVelocityOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "velocity"
+    }
+}
+
+const MAX_STEPS: usize = 16;
+/// Shortest step length in ms.
+const MIN_STEP: f32 = 10.0;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct Step {
+    active: bool,
+    note: u8,
+    velocity: f32,
+    /// Chance from 0 to 1 that the step plays.
+    probability: f32,
+    /// Amount of times the step is repeated within its length.
+    ratchets: u32,
+    /// Timing offset as a fraction of a step, from -0.5 to 0.5.
+    offset: f32,
+}
+
+impl Default for Step {
+    fn default() -> Self {
+        Self {
+            active: false,
+            note: 60,
+            velocity: 1.0,
+            probability: 1.0,
+            ratchets: 1,
+            offset: 0.0,
+        }
+    }
+}
+
+/// A step that was rolled to play, with its start in steps since the transport started.
+#[derive(Clone, Copy)]
+struct Hit {
+    start: f64,
+    index: usize,
+    step: Step,
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    steps: Vec<Step>,
+    length: usize,
+    rate: SyncedTime,
+}
+
+/// A [`Module`] that plays a row of steps in sync with the transport.
+pub struct Sequencer {
+    steps: [Step; MAX_STEPS],
+    length: usize,
+    /// Length of a step, a sixteenth note by default.
+    rate: SyncedTime,
+    /// Delay of every second step as a fraction of a step, from 0 to 0.5.
+    swing: f32,
+    /// Length of the gate as a fraction of a (ratcheted) step.
+    gate: f32,
+    selected: usize,
+    /// The last step index since the transport started, `None` while stopped.
+    last: Option<u64>,
+    current: Option<Hit>,
+    /// Hits that were rolled but have not started yet.
+    pending: Vec<Hit>,
+}
+
+impl Default for Sequencer {
+    fn default() -> Self {
+        let mut steps = [Step::default(); MAX_STEPS];
+        for step in steps.iter_mut().step_by(4) {
+            step.active = true;
+        }
+
+        Self {
+            steps,
+            length: MAX_STEPS,
+            rate: SyncedTime {
+                division: Division::Sixteenth,
+                synced: true,
+                ..SyncedTime::new(125.0)
+            },
+            swing: 0.0,
+            gate: 0.5,
+            selected: 0,
+            last: None,
+            current: None,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl Sequencer {
+    /// Decides whether the step at an absolute index plays, and when.
+    fn roll(&self, index: u64) -> Option<Hit> {
+        let step_index = index as usize % self.length;
+        let step = self.steps[step_index];
+        if !step.active || rand::thread_rng().gen::<f32>() >= step.probability {
+            return None;
+        }
+
+        let swing = if index % 2 == 1 { self.swing } else { 0.0 };
+        Some(Hit {
+            start: (index as f64 + (swing + step.offset) as f64).max(0.0),
+            index: step_index,
+            step,
+        })
+    }
+}
+
+impl Module for Sequencer {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Sequencer")
+            .icon("🎹")
+            .category(Category::Sequencing)
+            .tooltip("plays a row of steps in sync with the transport")
+            .example(example)
+            .port(
+                PortDescription::<SequencerFreqOutput>::output()
+                    .tooltip("frequency in Hz of the playing step"),
+            )
+            .port(
+                PortDescription::<SequencerGateOutput>::output().tooltip("high while a step plays"),
+            )
+            .port(
+                PortDescription::<SequencerVelocityOutput>::output()
+                    .tooltip("velocity of the playing step from 0 to 1"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let transport = ctx.transport();
+        if !transport.playing {
+            self.last = None;
+            self.current = None;
+            self.pending.clear();
+            ctx.set_output::<SequencerGateOutput>(false);
+            return;
+        }
+
+        // in beats, so steps keep following the position of the transport when not synced
+        let step = self.rate.as_ms(transport).max(MIN_STEP) / Division::Quarter.ms(transport.bpm);
+        let position = transport.position() / step as f64;
+        let index = position as u64;
+
+        if self.last != Some(index) {
+            if self.last.is_none() {
+                self.pending.extend(self.roll(index));
+            }
+            // Steps are rolled one step ahead so negative offsets can start early.
+            self.pending.extend(self.roll(index + 1));
+            self.last = Some(index);
+        }
+
+        let mut started = None;
+        self.pending.retain(|hit| {
+            if hit.start > position {
+                return true;
+            }
+            if started.is_none_or(|started: Hit| hit.start >= started.start) {
+                started = Some(*hit);
+            }
+            false
+        });
+        if started.is_some() {
+            self.current = started;
+        }
+
+        let gate = self.current.is_some_and(|hit| {
+            let local = position - hit.start;
+            let ratchet = local * hit.step.ratchets as f64;
+            local < 1.0 && ratchet.fract() < self.gate as f64
+        });
+
+        if let Some(hit) = self.current {
+            ctx.set_output::<SequencerFreqOutput>(ctx.tuning().note_to_freq(hit.step.note as f32));
+            ctx.set_output::<SequencerVelocityOutput>(hit.step.velocity);
+        }
+        ctx.set_output::<SequencerGateOutput>(gate);
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("length:"));
+            ui.add(egui::DragValue::new(&mut self.length).clamp_range(1..=MAX_STEPS))
+                .labelled_by(label.id);
+            let label = ui.label(tr("rate:"));
+            self.rate
+                .show_range((ctx.instance, "rate"), MIN_STEP..=4000.0, ui)
+                .labelled_by(label.id);
+            let label = ui.label(tr("swing:"));
+            let mut swing = self.swing * 100.0;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut swing)
+                        .clamp_range(0.0..=50.0)
+                        .suffix("%"),
+                )
+                .labelled_by(label.id)
+                .changed()
+            {
+                self.swing = swing / 100.0;
+            }
+            let label = ui.label(tr("gate:"));
+            let mut gate = self.gate * 100.0;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut gate)
+                        .clamp_range(1.0..=100.0)
+                        .suffix("%"),
+                )
+                .labelled_by(label.id)
+                .changed()
+            {
+                self.gate = gate / 100.0;
+            }
+        });
+
+        let playing = self.current.map(|hit| hit.index);
+        egui::Grid::new(ctx.instance)
+            .spacing(Vec2::splat(2.0))
+            .show(ui, |ui| {
+                for (index, step) in self.steps.iter_mut().enumerate().take(self.length) {
+                    let mut button = egui::Button::new(format!("{:>2}", index + 1))
+                        .min_size(Vec2::new(24.0, 24.0));
+
+                    if step.active {
+                        button = button.fill(Color32::DARK_GREEN);
+                    }
+
+                    if playing == Some(index) {
+                        button = button.stroke((1.0, Color32::GOLD));
+                    } else if index == self.selected {
+                        button = button.stroke((1.0, Color32::WHITE));
+                    }
+
+                    let response = ui
+                        .add(button)
+                        .on_hover_text_at_pointer(tr("right click to toggle"));
+                    if response.clicked() {
+                        self.selected = index;
+                    }
+                    if response.secondary_clicked() {
+                        step.active = !step.active;
+                    }
+                }
+            });
+
+        self.selected = self.selected.min(self.length - 1);
+        let step = &mut self.steps[self.selected];
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} {}:", tr("step"), self.selected + 1));
+            ui.checkbox(&mut step.active, tr("on"));
+            let label = ui.label(tr("note:"));
+            ui.add(
+                egui::DragValue::new(&mut step.note)
+                    .clamp_range(0..=127)
+                    .custom_formatter(|value, _| note_name(value as u8)),
+            )
+            .labelled_by(label.id);
+            let label = ui.label(tr("velocity:"));
+            ui.add(
+                egui::DragValue::new(&mut step.velocity)
+                    .clamp_range(0.0..=1.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("probability:"));
+            let mut probability = step.probability * 100.0;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut probability)
+                        .clamp_range(0.0..=100.0)
+                        .suffix("%"),
+                )
+                .labelled_by(label.id)
+                .changed()
+            {
+                step.probability = probability / 100.0;
+            }
+            let label = ui.label(tr("ratchets:"));
+            ui.add(egui::DragValue::new(&mut step.ratchets).clamp_range(1..=8))
+                .labelled_by(label.id);
+            let label = ui.label(tr("offset:"));
+            let mut offset = step.offset * 100.0;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut offset)
+                        .clamp_range(-50.0..=50.0)
+                        .suffix("%"),
+                )
+                .labelled_by(label.id)
+                .on_hover_text_at_pointer(tr("micro-timing as a percentage of a step"))
+                .changed()
+            {
+                step.offset = offset / 100.0;
+            }
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("swing", &mut self.swing, 0.0..=0.5),
+            Parameter::new("gate", &mut self.gate, 0.01..=1.0),
+            Parameter::new("rate", &mut self.rate.ms, MIN_STEP..=4000.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            steps: self.steps.to_vec(),
+            length: self.length,
+            rate: self.rate,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            for (step, saved) in self.steps.iter_mut().zip(state.steps) {
+                *step = saved;
+            }
+            self.length = state.length.clamp(1, MAX_STEPS);
+            self.rate = state.rate;
+        }
+    }
+}
+
+/// Plays an oscillator with the sequencer, start the transport to hear it.
+fn example(rack: &mut Rack, panel: usize) {
+    let sequencer = rack.add_module_to::<Sequencer>(panel);
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<SequencerFreqOutput, FrequencyInput>(sequencer, oscillator)
+        .unwrap();
+    rack.connect_typed::<FrameOutput, AudioInput>(oscillator, audio)
+        .unwrap();
+}
@@ -0,0 +1,221 @@
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    curve::Curve,
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        oscillator::{FrameOutput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::EnumIter,
+};
+
+pub struct ShaperInput;
+
+impl Port for ShaperInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for ShaperInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct ShaperOutput;
+
+impl Port for ShaperOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// Amount of entries in the lookup table.
+const TABLE_SIZE: usize = 1024;
+
+/// Amount of breakpoints used when generating a preset.
+const PRESET_POINTS: usize = 17;
+
+#[derive(Clone, Copy, PartialEq, Sequence)]
+enum Preset {
+    Linear,
+    SoftClip,
+    HardClip,
+    Fold,
+    Rectify,
+}
+
+impl Preset {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Preset::Linear => "linear",
+            Preset::SoftClip => "soft clip",
+            Preset::HardClip => "hard clip",
+            Preset::Fold => "fold",
+            Preset::Rectify => "rectify",
+        }
+    }
+
+    fn apply(&self, x: f32) -> f32 {
+        match self {
+            Preset::Linear => x,
+            Preset::SoftClip => (x * 2.5).tanh(),
+            Preset::HardClip => (x * 2.0).clamp(-1.0, 1.0),
+            Preset::Fold => (x * std::f32::consts::PI).sin(),
+            Preset::Rectify => x.abs() * 2.0 - 1.0,
+        }
+    }
+
+    fn points(&self) -> Vec<[f32; 2]> {
+        (0..PRESET_POINTS)
+            .map(|i| {
+                let x = i as f32 / (PRESET_POINTS - 1) as f32 * 2.0 - 1.0;
+                [x, self.apply(x)]
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    points: Vec<[f32; 2]>,
+}
+
+/// A waveshaper [`Module`] with a user drawn transfer function.
+pub struct Shaper {
+    curve: Curve,
+    table: Vec<f32>,
+    drive: f32,
+}
+
+impl Default for Shaper {
+    fn default() -> Self {
+        let mut new = Self {
+            curve: Curve::new(Preset::SoftClip.points()),
+            table: Vec::new(),
+            drive: 1.0,
+        };
+        new.update_table();
+        new
+    }
+}
+
+impl Shaper {
+    fn update_table(&mut self) {
+        self.table = (0..TABLE_SIZE)
+            .map(|i| {
+                self.curve
+                    .sample(i as f32 / (TABLE_SIZE - 1) as f32 * 2.0 - 1.0)
+            })
+            .collect();
+    }
+
+    /// Looks up a sample in the table, interpolating between the two closest entries.
+    fn shape(&self, sample: f32) -> f32 {
+        let position = ((sample * self.drive).clamp(-1.0, 1.0) + 1.0) / 2.0;
+        let position = position * (TABLE_SIZE - 1) as f32;
+        let index = (position as usize).min(TABLE_SIZE - 2);
+        let fraction = position - index as f32;
+        self.table[index] + (self.table[index + 1] - self.table[index]) * fraction
+    }
+}
+
+impl Module for Shaper {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Shaper")
+            .icon("⛰")
+            .category(Category::Effect)
+            .tooltip("a waveshaper mapping its input through a drawn transfer function")
+            .example(example)
+            .port(
+                PortDescription::<ShaperInput>::input()
+                    .tooltip("the sound to shape")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(PortDescription::<ShaperOutput>::output().tooltip("the shaped sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let frame = match ctx.get_input::<ShaperInput>() {
+            Frame::Mono(sample) => Frame::Mono(self.shape(sample)),
+            Frame::Stereo(left, right) => Frame::Stereo(self.shape(left), self.shape(right)),
+        };
+
+        ctx.set_output::<ShaperOutput>(frame)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.set_min_width(300.0);
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("drive:"));
+            ui.add(
+                egui::DragValue::new(&mut self.drive)
+                    .clamp_range(0.0..=10.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id);
+
+            egui::ComboBox::new(ctx.instance, "preset")
+                .selected_text(tr("load"))
+                .show_ui(ui, |ui| {
+                    for preset in Preset::iter() {
+                        if ui.button(tr(preset.as_str())).clicked() {
+                            self.curve.set_points(preset.points());
+                            self.update_table();
+                            ui.close_menu();
+                        }
+                    }
+                });
+        });
+
+        if self.curve.show(ctx.instance, ui, -1.0..=1.0, None) {
+            self.update_table();
+        }
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("drive", &mut self.drive, 0.0..=10.0)]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            points: self.curve.points().to_vec(),
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            if state.points.len() >= 2 {
+                self.curve.set_points(state.points);
+                self.update_table();
+            }
+        }
+    }
+}
+
+/// Shapes the wave of an oscillator.
+fn example(rack: &mut Rack, panel: usize) {
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let shaper = rack.add_module_to::<Shaper>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<FrameOutput, ShaperInput>(oscillator, shaper)
+        .unwrap();
+    rack.connect_typed::<ShaperOutput, AudioInput>(shaper, audio)
+        .unwrap();
+}
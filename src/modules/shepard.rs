@@ -0,0 +1,232 @@
+use std::f32::consts::TAU;
+
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::audio::{Audio, AudioInput},
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::EnumIter,
+};
+
+/// Amount of layers, each an octave apart.
+const LAYERS: usize = 8;
+
+/// Frequency of the lowest layer at the start of a cycle in Hz.
+const LOWEST_FREQ: f32 = 27.5;
+
+/// Tempo of the slowest layer at the start of a cycle in pulses per second.
+const LOWEST_TEMPO: f32 = 0.5;
+
+/// Spread of the loudness curve over the layers in octaves.
+const SPREAD: f32 = 1.4;
+
+pub struct ShepardOutput;
+
+impl Port for ShepardOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum ShepardMode {
+    /// Octaves of a sine that seem to rise or fall forever.
+    Tone,
+    /// Pulses at doubling tempos that seem to speed up or slow down forever.
+    Rhythm,
+}
+
+impl ShepardMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ShepardMode::Tone => "tone",
+            ShepardMode::Rhythm => "rhythm",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    mode: ShepardMode,
+    rising: bool,
+}
+
+/// A [`Module`] producing the illusion of an endlessly rising or falling tone or rhythm, by fading
+/// octave spaced layers in at one end and out at the other.
+pub struct Shepard {
+    mode: ShepardMode,
+    /// Octaves moved per second.
+    rate: f32,
+    rising: bool,
+    level: f32,
+    /// Position within the current octave, from 0 to 1.
+    position: f32,
+    /// Phase of each layer, from 0 to 1.
+    phases: [f32; LAYERS],
+    /// Level of the click of each layer in rhythm mode, decaying after every pulse.
+    clicks: [f32; LAYERS],
+}
+
+impl Default for Shepard {
+    fn default() -> Self {
+        Self {
+            mode: ShepardMode::Tone,
+            rate: 0.1,
+            rising: true,
+            level: 0.5,
+            position: 0.0,
+            phases: [0.0; LAYERS],
+            clicks: [0.0; LAYERS],
+        }
+    }
+}
+
+impl Shepard {
+    /// Loudness of a layer at an octave from the lowest, loudest in the middle and silent at the
+    /// ends so layers can wrap around unnoticed.
+    fn weight(octave: f32) -> f32 {
+        let distance = octave - LAYERS as f32 / 2.0;
+        (-distance.powi(2) / (2.0 * SPREAD.powi(2))).exp()
+    }
+
+    /// Moves the position, handing the phases to the neighbouring layers when it wraps.
+    fn advance(&mut self, sample_rate: u32) {
+        let step = self.rate / sample_rate as f32;
+        if self.rising {
+            self.position += step;
+            if self.position >= 1.0 {
+                self.position -= 1.0;
+                self.phases.rotate_left(1);
+                self.clicks.rotate_left(1);
+                self.phases[LAYERS - 1] = 0.0;
+                self.clicks[LAYERS - 1] = 0.0;
+            }
+        } else {
+            self.position -= step;
+            if self.position < 0.0 {
+                self.position += 1.0;
+                self.phases.rotate_right(1);
+                self.clicks.rotate_right(1);
+                self.phases[0] = 0.0;
+                self.clicks[0] = 0.0;
+            }
+        }
+    }
+}
+
+impl Module for Shepard {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Shepard")
+            .icon("💈")
+            .category(Category::Source)
+            .tooltip("a tone or rhythm that seems to rise or fall forever, like a barber pole")
+            .example(example)
+            .port(PortDescription::<ShepardOutput>::output().tooltip("the sum of all layers"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate() as f32;
+        let mut sum = 0.0;
+        let mut total = 0.0;
+
+        for layer in 0..LAYERS {
+            let octave = layer as f32 + self.position;
+            let weight = Self::weight(octave);
+            total += weight;
+
+            match self.mode {
+                ShepardMode::Tone => {
+                    let freq = LOWEST_FREQ * 2f32.powf(octave);
+                    self.phases[layer] = (self.phases[layer] + freq / sample_rate).fract();
+                    sum += (TAU * self.phases[layer]).sin() * weight;
+                }
+                ShepardMode::Rhythm => {
+                    let tempo = LOWEST_TEMPO * 2f32.powf(octave);
+                    self.phases[layer] += tempo / sample_rate;
+                    if self.phases[layer] >= 1.0 {
+                        self.phases[layer] = self.phases[layer].fract();
+                        self.clicks[layer] = 1.0;
+                    }
+                    // a short blip, higher for faster layers to tell them apart
+                    let pitch = 220.0 * 2f32.powf(layer as f32 / 2.0);
+                    let blip = (TAU * pitch * self.phases[layer] / tempo).sin();
+                    sum += blip * self.clicks[layer] * weight;
+                    self.clicks[layer] *= (-1.0 / (0.02 * sample_rate)).exp();
+                }
+            }
+        }
+
+        self.advance(ctx.sample_rate());
+        ctx.set_output::<ShepardOutput>(sum / total * self.level)
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            for mode in ShepardMode::iter() {
+                ui.selectable_value(&mut self.mode, mode, tr(mode.as_str()));
+            }
+            ui.separator();
+            ui.selectable_value(&mut self.rising, true, "⬆")
+                .on_hover_text(tr("rising"));
+            ui.selectable_value(&mut self.rising, false, "⬇")
+                .on_hover_text(tr("falling"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("rate:"));
+            ui.add(
+                egui::Slider::new(&mut self.rate, 0.01..=1.0)
+                    .logarithmic(true)
+                    .suffix(" oct/s"),
+            )
+            .labelled_by(label.id)
+            .on_hover_text(tr("octaves moved per second"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("level:"));
+            ui.add(egui::Slider::new(&mut self.level, 0.0..=1.0))
+                .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("rate", &mut self.rate, 0.01..=1.0),
+            Parameter::new("level", &mut self.level, 0.0..=1.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            mode: self.mode,
+            rising: self.rising,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.mode = state.mode;
+            self.rising = state.rising;
+        }
+    }
+}
+
+/// A tone rising forever.
+fn example(rack: &mut Rack, panel: usize) {
+    let shepard = rack.add_module_to::<Shepard>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<ShepardOutput, AudioInput>(shepard, audio)
+        .unwrap();
+}
@@ -0,0 +1,350 @@
+use biquad::{Biquad, Coefficients, DirectForm1, ToHertz};
+use eframe::egui::{self, Ui};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::audio::{Audio, AudioInput},
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Duration of a phoneme with a relative duration of 1 at a rate of 1, in seconds.
+const PHONEME_DURATION: f32 = 0.08;
+
+/// Bandwidths of the formant filters in Hz.
+const BANDWIDTHS: [f32; 3] = [60.0, 90.0, 120.0];
+
+const GAINS: [f32; 3] = [1.0, 0.5, 0.25];
+
+/// Samples between recalculating the formant filters while they glide.
+const UPDATE_INTERVAL: usize = 32;
+
+pub struct SpeechTriggerInput;
+
+impl Port for SpeechTriggerInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "trigger"
+    }
+}
+
+impl Input for SpeechTriggerInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct SpeechPitchInput;
+
+impl Port for SpeechPitchInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "pitch"
+    }
+}
+
+impl Input for SpeechPitchInput {
+    fn default() -> Self::Type {
+        110.0
+    }
+}
+
+pub struct SpeechOutput;
+
+impl Port for SpeechOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// A speech sound, shaped by three formants.
+#[derive(Clone, Copy)]
+struct Phoneme {
+    formants: [f32; 3],
+    /// Amplitude of the pitched source.
+    voicing: f32,
+    /// Amplitude of the noise source.
+    noise: f32,
+    /// Duration relative to other phonemes.
+    duration: f32,
+}
+
+impl Phoneme {
+    const fn new(formants: [f32; 3], voicing: f32, noise: f32, duration: f32) -> Self {
+        Self {
+            formants,
+            voicing,
+            noise,
+            duration,
+        }
+    }
+
+    /// A rough approximation of how a letter sounds, `None` for letters without a sound.
+    fn from_char(char: char) -> Option<Self> {
+        let phoneme = match char.to_ascii_lowercase() {
+            'a' => Self::new([730.0, 1090.0, 2440.0], 1.0, 0.0, 1.2),
+            'e' => Self::new([530.0, 1840.0, 2480.0], 1.0, 0.0, 1.0),
+            'i' | 'y' => Self::new([270.0, 2290.0, 3010.0], 1.0, 0.0, 1.0),
+            'o' => Self::new([570.0, 840.0, 2410.0], 1.0, 0.0, 1.2),
+            'u' => Self::new([300.0, 870.0, 2240.0], 1.0, 0.0, 1.0),
+            'w' => Self::new([300.0, 610.0, 2200.0], 0.8, 0.0, 0.6),
+            'r' => Self::new([490.0, 1350.0, 1690.0], 0.8, 0.0, 0.7),
+            'l' => Self::new([360.0, 1300.0, 2700.0], 0.8, 0.0, 0.7),
+            'm' => Self::new([280.0, 900.0, 2200.0], 0.6, 0.0, 0.8),
+            'n' => Self::new([280.0, 1700.0, 2600.0], 0.6, 0.0, 0.8),
+            'b' => Self::new([200.0, 1100.0, 2150.0], 0.5, 0.1, 0.5),
+            'd' => Self::new([200.0, 1600.0, 2600.0], 0.5, 0.1, 0.5),
+            'g' => Self::new([200.0, 1990.0, 2850.0], 0.5, 0.1, 0.5),
+            'j' => Self::new([300.0, 2000.0, 2800.0], 0.5, 0.3, 0.7),
+            'p' => Self::new([400.0, 1100.0, 2150.0], 0.0, 0.6, 0.4),
+            't' => Self::new([400.0, 1600.0, 2600.0], 0.0, 0.6, 0.4),
+            'k' | 'c' | 'q' | 'x' => Self::new([300.0, 1990.0, 2850.0], 0.0, 0.6, 0.4),
+            's' => Self::new([2600.0, 4500.0, 6000.0], 0.0, 0.7, 0.9),
+            'z' => Self::new([2600.0, 4500.0, 6000.0], 0.4, 0.5, 0.9),
+            'f' => Self::new([1000.0, 2000.0, 3500.0], 0.0, 0.4, 0.8),
+            'v' => Self::new([1000.0, 2000.0, 3500.0], 0.4, 0.3, 0.8),
+            'h' => Self::new([500.0, 1500.0, 2500.0], 0.0, 0.3, 0.6),
+            ' ' => Self::silence(1.0),
+            ',' | '.' | '!' | '?' | ';' | ':' => Self::silence(2.5),
+            _ => return None,
+        };
+        Some(phoneme)
+    }
+
+    const fn silence(duration: f32) -> Self {
+        Self::new([500.0, 1500.0, 2500.0], 0.0, 0.0, duration)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    text: String,
+    looping: bool,
+}
+
+/// A [`Module`] that speaks text with a simple formant synthesizer.
+pub struct Speech {
+    text: String,
+    phonemes: Vec<Phoneme>,
+    pitch: f32,
+    /// Speed of speaking relative to normal.
+    rate: f32,
+    looping: bool,
+    /// The phoneme being spoken, `None` when silent.
+    index: Option<usize>,
+    /// Progress through the current phoneme from 0 to 1.
+    progress: f32,
+    phase: f32,
+    formants: [f32; 3],
+    voicing: f32,
+    noise: f32,
+    filters: [Option<DirectForm1<f32>>; 3],
+    /// Samples since the filters were last updated.
+    counter: usize,
+    last_trigger: bool,
+}
+
+impl Default for Speech {
+    fn default() -> Self {
+        let text = "hello world".to_string();
+        Self {
+            phonemes: Self::parse(&text),
+            text,
+            pitch: 110.0,
+            rate: 1.0,
+            looping: false,
+            index: None,
+            progress: 0.0,
+            phase: 0.0,
+            formants: Phoneme::silence(1.0).formants,
+            voicing: 0.0,
+            noise: 0.0,
+            filters: [None; 3],
+            counter: 0,
+            last_trigger: false,
+        }
+    }
+}
+
+impl Speech {
+    fn parse(text: &str) -> Vec<Phoneme> {
+        text.chars().filter_map(Phoneme::from_char).collect()
+    }
+
+    fn speak(&mut self) {
+        self.index = (!self.phonemes.is_empty()).then_some(0);
+        self.progress = 0.0;
+    }
+
+    fn update_filters(&mut self, sample_rate: u32) {
+        let nyquist = sample_rate as f32 * 0.45;
+
+        for (i, filter) in self.filters.iter_mut().enumerate() {
+            let frequency = self.formants[i].min(nyquist);
+            let Ok(coeffs) = Coefficients::<f32>::from_params(
+                biquad::Type::BandPass,
+                sample_rate.hz(),
+                frequency.hz(),
+                frequency / BANDWIDTHS[i],
+            ) else {
+                continue;
+            };
+
+            match filter {
+                Some(filter) => filter.update_coefficients(coeffs),
+                None => *filter = Some(DirectForm1::<f32>::new(coeffs)),
+            }
+        }
+    }
+}
+
+impl Module for Speech {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Speech")
+            .icon("🗣")
+            .category(Category::Source)
+            .tooltip("speaks text with a simple formant synthesizer")
+            .example(example)
+            .port(PortDescription::<SpeechTriggerInput>::input().tooltip("starts speaking"))
+            .port(
+                PortDescription::<SpeechPitchInput>::input()
+                    .tooltip("pitch of the voice in Hz")
+                    .normalled(|speech: &Self| speech.pitch),
+            )
+            .port(PortDescription::<SpeechOutput>::output().tooltip("the voice"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let trigger = ctx.get_input::<SpeechTriggerInput>();
+        if trigger && !self.last_trigger {
+            self.speak()
+        }
+        self.last_trigger = trigger;
+
+        let sample_rate = ctx.sample_rate();
+
+        let target = match self.index.and_then(|index| self.phonemes.get(index)) {
+            Some(&phoneme) => {
+                let duration = PHONEME_DURATION * phoneme.duration / self.rate.max(0.01);
+                self.progress += 1.0 / (duration * sample_rate as f32);
+
+                if self.progress >= 1.0 {
+                    self.progress = 0.0;
+                    let next = self.index.unwrap_or_default() + 1;
+                    self.index = if next < self.phonemes.len() {
+                        Some(next)
+                    } else if self.looping {
+                        Some(0)
+                    } else {
+                        None
+                    };
+                }
+
+                phoneme
+            }
+            None => Phoneme::silence(1.0),
+        };
+
+        // glide towards the target to avoid clicks between phonemes
+        for (formant, target) in self.formants.iter_mut().zip(target.formants) {
+            *formant += (target - *formant) * 0.002;
+        }
+        self.voicing += (target.voicing - self.voicing) * 0.005;
+        self.noise += (target.noise - self.noise) * 0.005;
+
+        if self.counter == 0 {
+            self.update_filters(sample_rate)
+        }
+        self.counter = (self.counter + 1) % (UPDATE_INTERVAL * ctx.degradation().decimation());
+
+        let pitch = ctx.get_input::<SpeechPitchInput>();
+        self.phase = (self.phase + pitch / sample_rate as f32) % 1.0;
+
+        let source = (1.0 - 2.0 * self.phase) * self.voicing
+            + rand::thread_rng().gen_range(-1.0..=1.0) * self.noise;
+
+        let mut sample = 0.0;
+        for (filter, gain) in self.filters.iter_mut().zip(GAINS) {
+            if let Some(filter) = filter {
+                sample += filter.run(source) * gain;
+            }
+        }
+
+        ctx.set_output::<SpeechOutput>(Frame::Mono(sample.clamp(-1.0, 1.0)))
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.text_edit_singleline(&mut self.text).changed() {
+                self.phonemes = Self::parse(&self.text);
+                self.index = None;
+            }
+
+            if ui.button("▶").on_hover_text(tr("speak")).clicked() {
+                self.speak()
+            }
+
+            ui.checkbox(&mut self.looping, tr("loop"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("rate:"));
+            ui.add(egui::Slider::new(&mut self.rate, 0.25..=4.0).logarithmic(true))
+                .labelled_by(label.id);
+            let label = ui.label(tr("pitch:"));
+            ui.add(
+                egui::DragValue::new(&mut self.pitch)
+                    .clamp_range(20.0..=1000.0)
+                    .speed(1.0)
+                    .suffix(" Hz"),
+            )
+            .labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("rate", &mut self.rate, 0.25..=4.0),
+            Parameter::new("pitch", &mut self.pitch, 20.0..=1000.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            text: self.text.clone(),
+            looping: self.looping,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.phonemes = Self::parse(&state.text);
+            self.text = state.text;
+            self.looping = state.looping;
+        }
+    }
+}
+
+/// Speaks through the audio output, press the play button to hear it.
+fn example(rack: &mut Rack, panel: usize) {
+    let speech = rack.add_module_to::<Speech>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<SpeechOutput, AudioInput>(speech, audio)
+        .unwrap();
+}
@@ -0,0 +1,167 @@
+use eframe::egui::{self, Ui};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Port, PortDescription},
+    modules::oscillator::{FrameOutput, Oscillator},
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+pub struct StatsInput;
+
+impl Port for StatsInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for StatsInput {
+    fn default() -> Self::Type {
+        0.0
+    }
+}
+
+/// A [`Module`] measuring its input since the last reset, to debug other modules with.
+pub struct Stats {
+    min: f32,
+    max: f32,
+    sum: f64,
+    /// Sum of the squared samples, for the RMS.
+    squared: f64,
+    count: u64,
+    crossings: u64,
+    last: f32,
+    sample_rate: u32,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            sum: 0.0,
+            squared: 0.0,
+            count: 0,
+            crossings: 0,
+            last: 0.0,
+            sample_rate: 44100,
+        }
+    }
+}
+
+impl Stats {
+    fn reset(&mut self) {
+        *self = Self {
+            sample_rate: self.sample_rate,
+            ..Default::default()
+        };
+    }
+
+    fn rms(&self) -> f32 {
+        (self.squared / self.count.max(1) as f64).sqrt() as f32
+    }
+
+    fn dc(&self) -> f32 {
+        (self.sum / self.count.max(1) as f64) as f32
+    }
+
+    /// Zero crossings per second.
+    fn crossing_rate(&self) -> f32 {
+        let seconds = self.count as f32 / self.sample_rate as f32;
+        if seconds > 0.0 {
+            self.crossings as f32 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Module for Stats {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Stats")
+            .icon("🔬")
+            .category(Category::Analysis)
+            .tooltip(
+                "measures the minimum, maximum, RMS, DC offset and zero crossings of its input",
+            )
+            .sink()
+            .example(example)
+            .port(
+                PortDescription::<StatsInput>::input()
+                    .tooltip("the signal to measure")
+                    .conversion(|frame: Frame| frame.as_f32_mono()),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample = ctx.get_input::<StatsInput>();
+        self.sample_rate = ctx.sample_rate();
+
+        if sample.is_finite() {
+            self.min = self.min.min(sample);
+            self.max = self.max.max(sample);
+            self.sum += sample as f64;
+            self.squared += (sample as f64).powi(2);
+
+            let crossed = (self.last < 0.0) != (sample < 0.0);
+            if crossed && self.count > 0 {
+                self.crossings += 1;
+            }
+            self.last = sample;
+            self.count += 1;
+        }
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        let db = |value: f32| 20.0 * (value.abs() + 1e-10).log10();
+
+        egui::Grid::new(ctx.instance).num_columns(2).show(ui, |ui| {
+            if self.count > 0 {
+                ui.label(tr("min:"));
+                ui.label(format!("{:.4}", self.min));
+                ui.end_row();
+
+                ui.label(tr("max:"));
+                ui.label(format!("{:.4}", self.max));
+                ui.end_row();
+            }
+
+            ui.label(tr("RMS:"));
+            ui.label(format!("{:.4} ({:.1} dB)", self.rms(), db(self.rms())));
+            ui.end_row();
+
+            ui.label(tr("DC offset:"));
+            ui.label(format!("{:.4}", self.dc()));
+            ui.end_row();
+
+            ui.label(tr("zero crossings:"));
+            ui.label(format!("{:.1} /s", self.crossing_rate()));
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button(tr("reset")).clicked() {
+                self.reset();
+            }
+            ui.label(format!(
+                "{:.1} s",
+                self.count as f32 / self.sample_rate as f32
+            ));
+        });
+    }
+}
+
+/// Measures an oscillator.
+fn example(rack: &mut Rack, panel: usize) {
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let stats = rack.add_module_to::<Stats>(panel);
+    rack.connect_typed::<FrameOutput, StatsInput>(oscillator, stats)
+        .unwrap();
+}
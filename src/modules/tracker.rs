@@ -0,0 +1,798 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, Sender},
+};
+
+use eframe::egui::{self, RichText, Ui};
+use rfd::FileDialog;
+use xmrsplayer::prelude::XmrsPlayer;
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    rack::rack::{ProcessContext, ShowContext},
+};
+
+/// Amount of channels with their own output, further channels are only part of the mix.
+const CHANNEL_OUTPUTS: usize = 4;
+
+const CHANNEL_NAMES: [&str; CHANNEL_OUTPUTS] = ["1", "2", "3", "4"];
+
+/// Half the clock of a PAL Amiga, divided by a period gives the playback rate of a sample.
+const PAL_RATE: f32 = 3546894.6;
+
+const ROWS: usize = 64;
+
+pub struct TrackerChannelOutput<const N: usize>;
+
+impl<const N: usize> Port for TrackerChannelOutput<N> {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        CHANNEL_NAMES[N]
+    }
+}
+
+pub struct TrackerMixOutput;
+
+impl Port for TrackerMixOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "mix"
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Note {
+    /// Starting from 1, 0 keeps the current sample.
+    sample: usize,
+    period: u16,
+    effect: u8,
+    param: u8,
+}
+
+#[derive(Default)]
+struct Sample {
+    data: Vec<i8>,
+    volume: u8,
+    loop_start: usize,
+    /// Loops shorter than 3 bytes are not played.
+    loop_length: usize,
+}
+
+/// A ProTracker module, the most common format of Amiga tracker music.
+struct Song {
+    title: String,
+    channels: usize,
+    samples: Vec<Sample>,
+    orders: Vec<usize>,
+    restart: usize,
+    /// Rows of notes for all channels.
+    patterns: Vec<Vec<Note>>,
+}
+
+impl Song {
+    fn parse(bytes: &[u8]) -> Result<Self, &'static str> {
+        const INVALID: &str = "not a ProTracker module";
+
+        let get = |range: std::ops::Range<usize>| bytes.get(range).ok_or(INVALID);
+        let word = |offset: usize| -> Result<usize, &'static str> {
+            let bytes = get(offset..offset + 2)?;
+            Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize * 2)
+        };
+
+        let channels = match get(1080..1084)? {
+            b"M.K." | b"M!K!" | b"FLT4" | b"4CHN" => 4,
+            b"6CHN" => 6,
+            b"8CHN" | b"OCTA" | b"CD81" => 8,
+            [a, b, b'C', b'H'] if a.is_ascii_digit() && b.is_ascii_digit() => {
+                ((a - b'0') * 10 + (b - b'0')) as usize
+            }
+            _ => return Err(INVALID),
+        };
+
+        if channels == 0 {
+            return Err(INVALID);
+        }
+
+        let title = String::from_utf8_lossy(get(0..20)?)
+            .trim_end_matches('\0')
+            .to_string();
+
+        let mut samples = Vec::with_capacity(31);
+        let mut lengths = Vec::with_capacity(31);
+        for i in 0..31 {
+            let offset = 20 + i * 30;
+            lengths.push(word(offset + 22)?);
+            samples.push(Sample {
+                data: Vec::new(),
+                volume: get(offset + 25..offset + 26)?[0].min(64),
+                loop_start: word(offset + 26)?,
+                loop_length: word(offset + 28)?,
+            });
+        }
+
+        let length = (get(950..951)?[0] as usize).clamp(1, 128);
+        let restart = get(951..952)?[0] as usize;
+        let orders = get(952..952 + length)?
+            .iter()
+            .map(|&order| order as usize)
+            .collect::<Vec<_>>();
+        let pattern_count = get(952..1080)?.iter().max().copied().unwrap_or(0) as usize + 1;
+
+        let mut offset = 1084;
+        let mut patterns = Vec::with_capacity(pattern_count);
+        for _ in 0..pattern_count {
+            let data = get(offset..offset + ROWS * channels * 4)?;
+            patterns.push(
+                data.chunks_exact(4)
+                    .map(|note| Note {
+                        sample: ((note[0] & 0xF0) | (note[2] >> 4)) as usize,
+                        period: (((note[0] & 0x0F) as u16) << 8) | note[1] as u16,
+                        effect: note[2] & 0x0F,
+                        param: note[3],
+                    })
+                    .collect(),
+            );
+            offset += data.len();
+        }
+
+        for (sample, length) in samples.iter_mut().zip(lengths) {
+            // the last sample is often truncated
+            let end = (offset + length).min(bytes.len());
+            sample.data = bytes[offset.min(end)..end]
+                .iter()
+                .map(|&byte| byte as i8)
+                .collect();
+            offset = end;
+        }
+
+        Ok(Self {
+            title,
+            channels,
+            samples,
+            orders,
+            restart,
+            patterns,
+        })
+    }
+
+    fn note(&self, order: usize, row: usize, channel: usize) -> Note {
+        self.orders
+            .get(order)
+            .and_then(|&pattern| self.patterns.get(pattern))
+            .and_then(|notes| notes.get(row * self.channels + channel))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Default)]
+struct Channel {
+    sample: usize,
+    position: f32,
+    playing: bool,
+    period: f32,
+    /// Period without the offset of an arpeggio.
+    base: f32,
+    /// Period the tone portamento slides to.
+    target: f32,
+    porta_speed: f32,
+    volume: f32,
+    effect: u8,
+    param: u8,
+}
+
+impl Channel {
+    fn render(&mut self, song: &Song, sample_rate: f32) -> f32 {
+        let Some(sample) = song.samples.get(self.sample.wrapping_sub(1)) else {
+            return 0.0;
+        };
+
+        if !self.playing || self.period <= 0.0 || sample.data.is_empty() {
+            return 0.0;
+        }
+
+        let Some(&value) = sample.data.get(self.position as usize) else {
+            self.playing = false;
+            return 0.0;
+        };
+        let value = value as f32 / 128.0 * self.volume / 64.0;
+
+        self.position += PAL_RATE / self.period / sample_rate;
+
+        if sample.loop_length > 2 {
+            let end = (sample.loop_start + sample.loop_length).min(sample.data.len());
+            while self.position >= end as f32 && end > sample.loop_start {
+                self.position -= (end - sample.loop_start) as f32;
+            }
+        }
+
+        if self.position >= sample.data.len() as f32 {
+            self.playing = false;
+        }
+
+        value
+    }
+}
+
+/// The playback state of a [`Song`].
+struct Player {
+    order: usize,
+    row: usize,
+    tick: usize,
+    /// Ticks per row.
+    speed: usize,
+    /// Beats per minute, a tick takes 2.5 / tempo seconds.
+    tempo: usize,
+    /// Samples until the next tick.
+    remaining: f32,
+    /// Order and row to continue at after the current row.
+    jump: Option<(usize, usize)>,
+    channels: Vec<Channel>,
+    /// The last rendered value of each channel.
+    values: Vec<f32>,
+}
+
+impl Player {
+    fn new(song: &Song) -> Self {
+        Self {
+            order: 0,
+            row: 0,
+            tick: 0,
+            speed: 6,
+            tempo: 125,
+            remaining: 0.0,
+            jump: None,
+            channels: vec![Channel::default(); song.channels],
+            values: vec![0.0; song.channels],
+        }
+    }
+
+    fn render(&mut self, song: &Song, sample_rate: f32) -> &[f32] {
+        if self.remaining <= 0.0 {
+            self.update(song);
+            self.remaining += sample_rate * 2.5 / self.tempo as f32;
+        }
+        self.remaining -= 1.0;
+
+        for (channel, value) in self.channels.iter_mut().zip(self.values.iter_mut()) {
+            *value = channel.render(song, sample_rate);
+        }
+
+        &self.values
+    }
+
+    fn update(&mut self, song: &Song) {
+        if self.tick == 0 {
+            self.play_row(song);
+        } else {
+            for channel in self.channels.iter_mut() {
+                Self::apply_tick_effect(channel, self.tick);
+            }
+        }
+
+        self.tick += 1;
+        if self.tick >= self.speed {
+            self.tick = 0;
+            self.advance(song);
+        }
+    }
+
+    fn play_row(&mut self, song: &Song) {
+        for (i, channel) in self.channels.iter_mut().enumerate() {
+            let note = song.note(self.order, self.row, i);
+
+            if note.sample > 0 {
+                channel.sample = note.sample;
+                if let Some(sample) = song.samples.get(note.sample - 1) {
+                    channel.volume = sample.volume as f32;
+                }
+            }
+
+            if note.period > 0 {
+                if note.effect == 0x3 || note.effect == 0x5 {
+                    channel.target = note.period as f32;
+                } else {
+                    channel.period = note.period as f32;
+                    channel.base = channel.period;
+                    channel.position = 0.0;
+                    channel.playing = true;
+                }
+            }
+
+            channel.effect = note.effect;
+            channel.param = note.param;
+
+            match note.effect {
+                0x3 if note.param > 0 => channel.porta_speed = note.param as f32,
+                0x9 if note.period > 0 => channel.position = note.param as f32 * 256.0,
+                0xB => self.jump = Some((note.param as usize, 0)),
+                0xC => channel.volume = note.param.min(64) as f32,
+                0xD => {
+                    let row = (note.param >> 4) as usize * 10 + (note.param & 0x0F) as usize;
+                    let order = self.jump.map_or(self.order + 1, |(order, _)| order);
+                    self.jump = Some((order, row.min(ROWS - 1)));
+                }
+                0xF if note.param > 0 => {
+                    if note.param < 32 {
+                        self.speed = note.param as usize;
+                    } else {
+                        self.tempo = note.param as usize;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_tick_effect(channel: &mut Channel, tick: usize) {
+        let up = (channel.param >> 4) as f32;
+        let down = (channel.param & 0x0F) as f32;
+
+        let volume_slide = |channel: &mut Channel| {
+            channel.volume = (channel.volume + up - down).clamp(0.0, 64.0);
+        };
+
+        let tone_portamento = |channel: &mut Channel| {
+            if channel.target > 0.0 {
+                if channel.period < channel.target {
+                    channel.period = (channel.period + channel.porta_speed).min(channel.target);
+                } else {
+                    channel.period = (channel.period - channel.porta_speed).max(channel.target);
+                }
+            }
+        };
+
+        match channel.effect {
+            0x0 if channel.param > 0 => {
+                // arpeggio, cycles between the note and two semitone offsets
+                let semitones = [0.0, up, down][tick % 3];
+                channel.period = channel.base * 2f32.powf(-semitones / 12.0);
+                return;
+            }
+            0x1 => channel.period = (channel.period - channel.param as f32).max(113.0),
+            0x2 => channel.period = (channel.period + channel.param as f32).min(856.0),
+            0x3 => tone_portamento(channel),
+            0x5 => {
+                tone_portamento(channel);
+                volume_slide(channel);
+            }
+            0xA => volume_slide(channel),
+            _ => {}
+        }
+
+        channel.base = channel.period;
+    }
+
+    fn advance(&mut self, song: &Song) {
+        let (order, row) = self.jump.take().unwrap_or_else(|| {
+            if self.row + 1 >= ROWS {
+                (self.order + 1, 0)
+            } else {
+                (self.order, self.row + 1)
+            }
+        });
+
+        self.row = row;
+        self.order = if order < song.orders.len() {
+            order
+        } else if song.restart < song.orders.len() {
+            song.restart
+        } else {
+            0
+        };
+
+        // the arpeggio changes the period, so it is restored at the start of each row
+        for channel in self.channels.iter_mut() {
+            if channel.effect == 0x0 && channel.param > 0 {
+                channel.period = channel.base;
+            }
+        }
+    }
+}
+
+type ExtendedPlayer<'a> = XmrsPlayer<'a>;
+
+self_cell::self_cell!(
+    /// A FastTracker XM module with the player borrowing it, these are played by xmrsplayer as
+    /// their instruments and envelopes go well beyond what [`Player`] does.
+    struct Extended {
+        owner: xmrs::prelude::Module,
+
+        #[not_covariant]
+        dependent: ExtendedPlayer,
+    }
+);
+
+impl Extended {
+    fn load(bytes: &[u8]) -> Result<Self, &'static str> {
+        let module =
+            xmrs::prelude::Module::load_xm(bytes).map_err(|_| "not a FastTracker XM module")?;
+        Ok(Self::restart(module, 48000))
+    }
+
+    fn restart(module: xmrs::prelude::Module, sample_rate: u32) -> Self {
+        Self::new(module, |module| XmrsPlayer::new(module, sample_rate, 0))
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.with_dependent(|_, player| player.get_sample_rate())
+    }
+}
+
+enum Decoded {
+    ProTracker(Song),
+    Extended(Extended),
+}
+
+impl Decoded {
+    fn parse(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.starts_with(b"Extended Module: ") {
+            Extended::load(bytes).map(Self::Extended)
+        } else {
+            Song::parse(bytes).map(Self::ProTracker)
+        }
+    }
+}
+
+enum Message {
+    Decoded(Result<Decoded, &'static str>),
+    PickedFile(PathBuf),
+}
+
+/// A [`Module`] that plays ProTracker and FastTracker XM modules with an output per channel.
+///
+/// ProTracker modules are decoded and played here, keeping the Amiga panning of the mix.
+pub struct Tracker {
+    song: Option<Song>,
+    player: Option<Player>,
+    extended: Option<Extended>,
+    pub playing: bool,
+    gain: f32,
+    path: String,
+    error: Option<&'static str>,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+    loading: bool,
+}
+
+impl Default for Tracker {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            song: None,
+            player: None,
+            extended: None,
+            playing: false,
+            gain: 1.0,
+            path: String::new(),
+            error: None,
+            sender,
+            receiver,
+            loading: false,
+        }
+    }
+}
+
+impl Tracker {
+    fn decode(path: impl AsRef<Path>) -> Result<Decoded, &'static str> {
+        let bytes = std::fs::read(path).map_err(|_| "could not read the file")?;
+        Decoded::parse(&bytes)
+    }
+
+    fn update(&mut self) {
+        self.loading = true;
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            let path = self.path.clone();
+            move || {
+                sender.send(Message::Decoded(Self::decode(&path))).ok();
+            }
+        });
+    }
+
+    fn open_picker(&self) {
+        let mut dialog = FileDialog::new().add_filter(tr("module"), &["mod", "xm"]);
+
+        if !self.path.is_empty() {
+            dialog = dialog.set_directory(&self.path);
+        }
+
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            move || {
+                if let Some(path) = dialog.pick_file() {
+                    sender.send(Message::PickedFile(path)).ok();
+                }
+            }
+        });
+    }
+}
+
+impl Tracker {
+    fn process_extended(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate();
+        self.extended = self.extended.take().map(|extended| {
+            if extended.sample_rate() == sample_rate {
+                extended
+            } else {
+                Extended::restart(extended.into_owner(), sample_rate)
+            }
+        });
+
+        let Some(extended) = &mut self.extended else {
+            return;
+        };
+
+        let mut values = Vec::new();
+        let mut mix = (0, 0);
+        if self.playing {
+            extended.with_dependent_mut(|_, player| {
+                // the player stops at the end of the song, after that it plays from the start again
+                let samples = player.samples_from_channels().or_else(|| {
+                    player.goto(0, 0, 0);
+                    player.samples_from_channels()
+                });
+                if let Some(samples) = samples {
+                    mix = player.samples_apply_volume(&samples);
+                    values = samples;
+                }
+            });
+        }
+
+        let to_frame = |(left, right): (i16, i16)| {
+            Frame::Stereo(left as f32, right as f32) * (self.gain / i16::MAX as f32)
+        };
+        let channel = |i: usize| to_frame(values.get(i).copied().unwrap_or_default());
+        ctx.set_output::<TrackerChannelOutput<0>>(channel(0));
+        ctx.set_output::<TrackerChannelOutput<1>>(channel(1));
+        ctx.set_output::<TrackerChannelOutput<2>>(channel(2));
+        ctx.set_output::<TrackerChannelOutput<3>>(channel(3));
+        ctx.set_output::<TrackerMixOutput>(to_frame(mix));
+    }
+}
+
+impl Module for Tracker {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Tracker")
+            .icon("🎼")
+            .category(Category::Sequencing)
+            .tooltip("plays Amiga ProTracker and FastTracker XM modules with an output per channel")
+            .port(PortDescription::<TrackerChannelOutput<0>>::output().tooltip("channel 1"))
+            .port(PortDescription::<TrackerChannelOutput<1>>::output().tooltip("channel 2"))
+            .port(PortDescription::<TrackerChannelOutput<2>>::output().tooltip("channel 3"))
+            .port(PortDescription::<TrackerChannelOutput<3>>::output().tooltip("channel 4"))
+            .port(
+                PortDescription::<TrackerMixOutput>::output()
+                    .tooltip("all channels, ProTracker modules are panned like an Amiga"),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        if self.extended.is_some() {
+            self.process_extended(ctx);
+            return;
+        }
+
+        let mut values: &[f32] = &[];
+
+        if self.playing {
+            if let (Some(song), Some(player)) = (&self.song, &mut self.player) {
+                values = player.render(song, ctx.sample_rate() as f32);
+            }
+        }
+
+        let gain = self.gain;
+        let channel = |i: usize| Frame::Mono(values.get(i).copied().unwrap_or_default() * gain);
+        ctx.set_output::<TrackerChannelOutput<0>>(channel(0));
+        ctx.set_output::<TrackerChannelOutput<1>>(channel(1));
+        ctx.set_output::<TrackerChannelOutput<2>>(channel(2));
+        ctx.set_output::<TrackerChannelOutput<3>>(channel(3));
+
+        // the Amiga plays channels 1 and 4 on the left and 2 and 3 on the right
+        let (mut left, mut right) = (0.0, 0.0);
+        for (i, value) in values.iter().enumerate() {
+            if i % 4 == 0 || i % 4 == 3 {
+                left += value;
+            } else {
+                right += value;
+            }
+        }
+        ctx.set_output::<TrackerMixOutput>(Frame::Stereo(left, right) * (0.5 * gain));
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        let messages = self.receiver.try_iter().collect::<Vec<_>>();
+        for message in messages {
+            match message {
+                Message::Decoded(decoded) => {
+                    match decoded {
+                        Ok(Decoded::ProTracker(song)) => {
+                            self.player = Some(Player::new(&song));
+                            self.song = Some(song);
+                            self.extended = None;
+                            self.error = None;
+                        }
+                        Ok(Decoded::Extended(extended)) => {
+                            self.song = None;
+                            self.player = None;
+                            self.extended = Some(extended);
+                            self.error = None;
+                        }
+                        Err(error) => self.error = Some(error),
+                    }
+                    self.loading = false
+                }
+                Message::PickedFile(path) => {
+                    self.path = path.to_string_lossy().to_string();
+                    self.update();
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(self.song.is_some() || self.extended.is_some(), |ui| {
+                ui.selectable_value(&mut self.playing, true, "▶");
+                ui.selectable_value(&mut self.playing, false, "⏸");
+                if ui.button("⏮").clicked() {
+                    self.player = self.song.as_ref().map(Player::new);
+                    self.extended = self.extended.take().map(|extended| {
+                        let sample_rate = extended.sample_rate();
+                        Extended::restart(extended.into_owner(), sample_rate)
+                    });
+                }
+            });
+
+            if ui.text_edit_singleline(&mut self.path).changed() {
+                self.update();
+            }
+
+            if ui.button(tr("pick")).clicked() {
+                self.open_picker()
+            }
+
+            if self.loading {
+                ui.spinner();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("gain:"));
+            ui.add(egui::Slider::new(&mut self.gain, 0.0..=2.0))
+                .labelled_by(label.id);
+        });
+
+        if let Some(error) = self.error {
+            ui.colored_label(ui.visuals().error_fg_color, tr(error));
+        }
+
+        if let (Some(song), Some(player)) = (&self.song, &self.player) {
+            ui.label(RichText::new(&song.title).strong());
+            ui.label(format!(
+                "{} {}/{}, {} {:02}, {} {}",
+                tr("order"),
+                player.order + 1,
+                song.orders.len(),
+                tr("row"),
+                player.row,
+                song.channels,
+                tr("channels"),
+            ));
+        }
+
+        if let Some(extended) = &self.extended {
+            let module = extended.borrow_owner();
+            ui.label(RichText::new(&module.name).strong());
+            let (order, row) = extended.with_dependent(|_, player| {
+                (player.get_current_table_index(), player.playing_row())
+            });
+            ui.label(format!(
+                "{} {}/{}, {} {:02}, {} {}",
+                tr("order"),
+                order + 1,
+                module.song_length(0),
+                tr("row"),
+                row,
+                module.get_num_channels(),
+                tr("channels"),
+            ));
+        }
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![Parameter::new("gain", &mut self.gain, 0.0..=2.0)]
+    }
+
+    fn asset(&self) -> Option<String> {
+        (!self.path.is_empty()).then(|| self.path.clone())
+    }
+
+    fn load_asset(&mut self, path: &str) {
+        self.sender.send(Message::PickedFile(path.into())).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module with a single empty pattern of the given channel signature.
+    fn protracker(signature: &[u8; 4], channels: usize) -> Vec<u8> {
+        let mut bytes = vec![0; 1084 + ROWS * channels * 4];
+        bytes[..4].copy_from_slice(b"test");
+        bytes[950] = 1;
+        bytes[1080..1084].copy_from_slice(signature);
+        bytes
+    }
+
+    /// A FastTracker XM module with a single empty pattern.
+    fn extended() -> Vec<u8> {
+        let mut bytes = b"Extended Module: test                ".to_vec();
+        bytes.push(0x1A);
+        bytes.extend([b' '; 20]);
+        bytes.extend(0x0104u16.to_le_bytes());
+        bytes.extend(276u32.to_le_bytes());
+        // song length, restart, channels, patterns, instruments, flags, speed and tempo
+        for value in [1u16, 0, 8, 1, 0, 1, 6, 125] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend([0; 256]);
+        bytes.extend(9u32.to_le_bytes());
+        bytes.push(0);
+        bytes.extend((ROWS as u16).to_le_bytes());
+        bytes.extend(0u16.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn parses_protracker() {
+        let song = Song::parse(&protracker(b"M.K.", 4)).unwrap();
+        assert_eq!(song.title, "test");
+        assert_eq!(song.channels, 4);
+        assert_eq!(song.samples.len(), 31);
+        assert_eq!(song.orders, [0]);
+        assert_eq!(song.patterns.len(), 1);
+        assert_eq!(song.patterns[0].len(), ROWS * 4);
+    }
+
+    #[test]
+    fn parses_channel_counts() {
+        for (signature, channels) in [(b"6CHN", 6), (b"OCTA", 8), (b"10CH", 10), (b"32CH", 32)] {
+            let song = Song::parse(&protracker(signature, channels)).unwrap();
+            assert_eq!(song.channels, channels);
+        }
+
+        assert!(Song::parse(&protracker(b"00CH", 0)).is_err());
+        assert!(Song::parse(&protracker(b"XYZW", 4)).is_err());
+    }
+
+    #[test]
+    fn refuses_truncated() {
+        let bytes = protracker(b"M.K.", 4);
+        assert!(Song::parse(&bytes[..1000]).is_err());
+        assert!(Song::parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Decoded::parse(&extended()[..100]).is_err());
+    }
+
+    #[test]
+    fn decodes_extended() {
+        assert!(Song::parse(&extended()).is_err());
+        assert!(matches!(
+            Decoded::parse(&extended()),
+            Ok(Decoded::Extended(_))
+        ));
+        assert!(matches!(
+            Decoded::parse(&protracker(b"M.K.", 4)),
+            Ok(Decoded::ProTracker(_))
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::VecDeque;
+
+use eframe::egui::Ui;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        drums::{DrumOutput, DrumTriggerInput, Kick},
+        keyboard::{Keyboard, KeyboardPressedOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    transport::SyncedTime,
+};
+
+pub struct GateInput;
+
+impl Port for GateInput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "gate"
+    }
+}
+
+impl Input for GateInput {
+    fn default() -> Self::Type {
+        false
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.checkbox(value, "");
+    }
+}
+
+pub struct DelayedOutput;
+
+impl Port for DelayedOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "delayed"
+    }
+}
+
+pub struct ShapedOutput;
+
+impl Port for ShapedOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "shaped"
+    }
+}
+
+pub struct TriggerOutput;
+
+impl Port for TriggerOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "trigger"
+    }
+}
+
+pub struct ToggleOutput;
+
+impl Port for ToggleOutput {
+    type Type = bool;
+
+    fn name() -> &'static str {
+        "toggle"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    delay: SyncedTime,
+    length: SyncedTime,
+}
+
+/// A [`Module`] with a set of small gate utilities: delay, re-shape, edge detection and toggle.
+pub struct TrigTools {
+    delay: SyncedTime,
+    length: SyncedTime,
+    time: u64,
+    last: bool,
+    /// Pending state changes of the delayed gate as `(time, state)`.
+    changes: VecDeque<(u64, bool)>,
+    delayed: bool,
+    shaped_until: u64,
+    toggle: bool,
+}
+
+impl Default for TrigTools {
+    fn default() -> Self {
+        Self {
+            delay: SyncedTime::new(100.0),
+            length: SyncedTime::new(50.0),
+            time: 0,
+            last: false,
+            changes: VecDeque::new(),
+            delayed: false,
+            shaped_until: 0,
+            toggle: false,
+        }
+    }
+}
+
+impl TrigTools {
+    fn ms_to_samples(ms: f32, sample_rate: u32) -> u64 {
+        (ms / 1000.0 * sample_rate as f32) as u64
+    }
+}
+
+impl Module for TrigTools {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("TrigTools")
+            .icon("⏱")
+            .category(Category::Utility)
+            .tooltip("small gate utilities: delay, re-shape, edge detection and toggle")
+            .example(example)
+            .port(PortDescription::<GateInput>::input().tooltip("the gate to process"))
+            .port(
+                PortDescription::<DelayedOutput>::output()
+                    .tooltip("the gate delayed by the delay time"),
+            )
+            .port(
+                PortDescription::<ShapedOutput>::output()
+                    .tooltip("high for the set length after each rising edge"),
+            )
+            .port(
+                PortDescription::<TriggerOutput>::output()
+                    .tooltip("high for a single sample on each rising edge"),
+            )
+            .port(PortDescription::<ToggleOutput>::output().tooltip("flips on each rising edge"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let gate = ctx.get_input::<GateInput>();
+        let rising = gate && !self.last;
+
+        if gate != self.last {
+            let delay = Self::ms_to_samples(self.delay.as_ms(ctx.transport()), ctx.sample_rate());
+            self.changes.push_back((self.time + delay, gate));
+        }
+        self.last = gate;
+
+        while let Some(&(time, state)) = self.changes.front() {
+            if time > self.time {
+                break;
+            }
+            self.delayed = state;
+            self.changes.pop_front();
+        }
+
+        if rising {
+            let length = self.length.as_ms(ctx.transport());
+            self.shaped_until = self.time + Self::ms_to_samples(length, ctx.sample_rate());
+            self.toggle = !self.toggle;
+        }
+
+        ctx.set_output::<DelayedOutput>(self.delayed);
+        ctx.set_output::<ShapedOutput>(self.time < self.shaped_until);
+        ctx.set_output::<TriggerOutput>(rising);
+        ctx.set_output::<ToggleOutput>(self.toggle);
+
+        self.time += 1;
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("delay:"));
+            self.delay
+                .show((ctx.instance, "delay"), ui)
+                .labelled_by(label.id);
+            let label = ui.label(tr("length:"));
+            self.length
+                .show((ctx.instance, "length"), ui)
+                .labelled_by(label.id);
+            if ui.button(tr("reset toggle")).clicked() {
+                self.toggle = false;
+            }
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("delay", &mut self.delay.ms, 0.0..=60000.0),
+            Parameter::new("length", &mut self.length.ms, 0.0..=60000.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            delay: self.delay,
+            length: self.length,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.delay = state.delay;
+            self.length = state.length;
+        }
+    }
+}
+
+/// Plays a kick delayed after pressing keys of a keyboard.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let trig = rack.add_module_to::<TrigTools>(panel);
+    let kick = rack.add_module_to::<Kick>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<KeyboardPressedOutput, GateInput>(keyboard, trig)
+        .unwrap();
+    rack.connect_typed::<DelayedOutput, DrumTriggerInput>(trig, kick)
+        .unwrap();
+    rack.connect_typed::<DrumOutput, AudioInput>(kick, audio)
+        .unwrap();
+}
@@ -0,0 +1,193 @@
+use std::f32::consts::FRAC_PI_4;
+
+use eframe::egui::{self, Ui};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        keyboard::{Keyboard, KeyboardFreqOutput},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Most voices that can be stacked.
+const MAX_VOICES: usize = 16;
+
+pub struct UnisonFreqInput;
+
+impl Port for UnisonFreqInput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "freq"
+    }
+}
+
+impl Input for UnisonFreqInput {
+    fn default() -> Self::Type {
+        70.0
+    }
+
+    fn show(value: &mut Self::Type, ui: &mut Ui) {
+        ui.add(
+            egui::DragValue::new(value)
+                .clamp_range(0.0..=f32::MAX)
+                .speed(1.0)
+                .suffix(" Hz"),
+        );
+    }
+}
+
+pub struct UnisonOutput;
+
+impl Port for UnisonOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    voices: usize,
+}
+
+/// A [`Module`] stacking detuned saw waves spread across the stereo field, like a supersaw.
+pub struct Unison {
+    voices: usize,
+    /// Cents between the lowest and the highest voice.
+    detune: f32,
+    /// How far the outer voices are panned, 0 keeps all voices in the center.
+    width: f32,
+    /// Position in the period of each voice from 0 to 1, starting at random so the voices don't
+    /// line up.
+    phases: Vec<f32>,
+}
+
+impl Default for Unison {
+    fn default() -> Self {
+        let mut new = Self {
+            voices: 7,
+            detune: 30.0,
+            width: 0.8,
+            phases: Vec::new(),
+        };
+        new.update_phases();
+        new
+    }
+}
+
+impl Unison {
+    /// Adds a phase for each new voice and removes those of removed voices.
+    fn update_phases(&mut self) {
+        let mut rng = rand::thread_rng();
+        self.phases.truncate(self.voices);
+        while self.phases.len() < self.voices {
+            self.phases.push(rng.gen());
+        }
+    }
+}
+
+impl Module for Unison {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Unison")
+            .icon("🎶")
+            .category(Category::Source)
+            .tooltip("detuned saw waves stacked into one wide sound")
+            .example(example)
+            .port(PortDescription::<UnisonFreqInput>::input().tooltip("frequency in Hz"))
+            .port(PortDescription::<UnisonOutput>::output().tooltip("the stereo sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate() as f32;
+        let freq = ctx.get_input::<UnisonFreqInput>();
+
+        let (mut left, mut right) = (0.0, 0.0);
+        for (index, phase) in self.phases.iter_mut().enumerate() {
+            // from -1 for the lowest voice to 1 for the highest
+            let spread = if self.voices > 1 {
+                index as f32 / (self.voices - 1) as f32 * 2.0 - 1.0
+            } else {
+                0.0
+            };
+
+            let sample = *phase * 2.0 - 1.0;
+            let angle = (spread * self.width + 1.0) * FRAC_PI_4;
+            left += sample * angle.cos();
+            right += sample * angle.sin();
+
+            let voice_freq = freq * 2f32.powf(spread * self.detune / 2.0 / 1200.0);
+            *phase = (*phase + voice_freq / sample_rate).fract();
+        }
+
+        // voices aren't in phase so they add up in power rather than amplitude
+        let gain = 1.0 / (self.voices as f32).sqrt();
+        ctx.set_output::<UnisonOutput>(Frame::Stereo(left * gain, right * gain))
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("voices:"));
+            ui.add(egui::Slider::new(&mut self.voices, 1..=MAX_VOICES))
+                .labelled_by(label.id);
+        });
+        self.update_phases();
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("detune:"));
+            ui.add(egui::Slider::new(&mut self.detune, 0.0..=100.0).suffix(" ct"))
+                .labelled_by(label.id)
+                .on_hover_text(tr("cents between the lowest and the highest voice"));
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("width:"));
+            ui.add(egui::Slider::new(&mut self.width, 0.0..=1.0))
+                .labelled_by(label.id)
+                .on_hover_text(tr("how far the voices are spread from left to right"));
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("detune", &mut self.detune, 0.0..=100.0),
+            Parameter::new("width", &mut self.width, 0.0..=1.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            voices: self.voices,
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.voices = state.voices.clamp(1, MAX_VOICES);
+            self.update_phases();
+        }
+    }
+}
+
+/// Plays the unison oscillator with the keyboard.
+fn example(rack: &mut Rack, panel: usize) {
+    let keyboard = rack.add_module_to::<Keyboard>(panel);
+    let unison = rack.add_module_to::<Unison>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<KeyboardFreqOutput, UnisonFreqInput>(keyboard, unison)
+        .unwrap();
+    rack.connect_typed::<UnisonOutput, AudioInput>(unison, audio)
+        .unwrap();
+}
@@ -1,9 +1,13 @@
-use std::marker::PhantomData;
+use std::{any::Any, marker::PhantomData};
 
-use eframe::egui::{self, Ui};
+use eframe::egui::{self, Response, Ui};
+use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    module::{Module, ModuleDescription, Port, PortDescription, PortValueBoxed},
+    i18n::tr,
+    module::{
+        Category, Module, ModuleDescription, Parameter, Port, PortDescription, PortValueBoxed,
+    },
     rack::rack::{ProcessContext, ShowContext},
 };
 
@@ -24,27 +28,33 @@ pub struct Value<T> {
 }
 
 pub trait Edit {
-    fn edit(&mut self, ui: &mut Ui);
+    fn edit(&mut self, ui: &mut Ui) -> Response;
 }
 
 impl Edit for f32 {
-    fn edit(&mut self, ui: &mut Ui) {
+    fn edit(&mut self, ui: &mut Ui) -> Response {
         ui.add(
             egui::DragValue::new(self)
                 .clamp_range(0.0..=f32::MAX)
                 .speed(1.0),
-        );
+        )
     }
 }
 
-impl<T: Edit + PortValueBoxed + Clone + Default> Module for Value<T> {
+impl<T> Module for Value<T>
+where
+    T: Edit + PortValueBoxed + Clone + Default + Serialize + DeserializeOwned,
+{
     fn describe() -> ModuleDescription<Self>
     where
         Self: Sized,
     {
         ModuleDescription::default()
-            .name(&format!("⎙ Value<{}>", T::name()))
-            .port(PortDescription::<ValueOutput<T>>::output())
+            .name(&format!("Value<{}>", T::name()))
+            .icon("⎙")
+            .category(Category::Utility)
+            .tooltip("outputs a constant value")
+            .port(PortDescription::<ValueOutput<T>>::output().tooltip("the value"))
     }
 
     fn process(&mut self, ctx: &mut ProcessContext) {
@@ -52,6 +62,28 @@ impl<T: Edit + PortValueBoxed + Clone + Default> Module for Value<T> {
     }
 
     fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
-        self.value.edit(ui)
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("value:"));
+            self.value.edit(ui).labelled_by(label.id);
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        // only continuous values can be automated
+        (&mut self.value as &mut dyn Any)
+            .downcast_mut::<f32>()
+            .map(|value| Parameter::new("value", value, 0.0..=20000.0))
+            .into_iter()
+            .collect()
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&self.value).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(value) = ron::from_str(state) {
+            self.value = value;
+        }
     }
 }
@@ -0,0 +1,228 @@
+use std::f32::consts::TAU;
+
+use eframe::egui::{self, Ui};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::{
+        audio::{Audio, AudioInput},
+        oscillator::{FrameOutput, Oscillator},
+    },
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+/// Average amount of crackles per second.
+const CRACKLE_RATE: f32 = 6.0;
+
+/// Speed of the pitch wobble in Hz, about a record turning at 33 rpm.
+const WOBBLE_RATE: f32 = 0.55;
+
+/// Deepest the wobble moves the playback position in ms.
+const WOBBLE_DEPTH: f32 = 2.0;
+
+pub struct VinylInput;
+
+impl Port for VinylInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for VinylInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct VinylOutput;
+
+impl Port for VinylOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    sixty: bool,
+}
+
+/// A [`Module`] that makes its input sound like an old record or tape.
+pub struct Vinyl {
+    crackle: f32,
+    hiss: f32,
+    hum: f32,
+    wobble: f32,
+    /// Whether the hum is at 60Hz instead of 50Hz.
+    sixty: bool,
+    /// Level of the current crackle, decaying to silence.
+    click: f32,
+    /// Lowpass state softening the hiss.
+    hiss_state: f32,
+    hum_phase: f32,
+    wobble_phase: f32,
+    /// Past input, read at a moving position for the wobble.
+    buffer: Vec<Frame>,
+    position: usize,
+}
+
+impl Default for Vinyl {
+    fn default() -> Self {
+        Self {
+            crackle: 0.3,
+            hiss: 0.2,
+            hum: 0.1,
+            wobble: 0.3,
+            sixty: false,
+            click: 0.0,
+            hiss_state: 0.0,
+            hum_phase: 0.0,
+            wobble_phase: 0.0,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Vinyl {
+    /// Reads the input from `delay` samples ago, interpolating between samples.
+    fn read(&self, delay: f32) -> Frame {
+        let size = self.buffer.len();
+        let delay = delay.clamp(1.0, size as f32 - 2.0);
+        let whole = delay as usize;
+        let fraction = delay - whole as f32;
+        let a = self.buffer[(self.position + size - whole) % size];
+        let b = self.buffer[(self.position + size - whole - 1) % size];
+        a * (1.0 - fraction) + b * fraction
+    }
+
+    /// The crackle, hiss and hum added on top of the input.
+    fn noise(&mut self, sample_rate: u32) -> f32 {
+        let mut rng = rand::thread_rng();
+
+        if rng.gen::<f32>() < CRACKLE_RATE / sample_rate as f32 {
+            self.click = rng.gen_range(0.2..=1.0) * if rng.gen() { 1.0 } else { -1.0 };
+        }
+        // each crackle dies out in about a millisecond
+        let crackle = self.click * rng.gen_range(0.5..=1.0);
+        self.click *= (-1.0 / (0.001 * sample_rate as f32)).exp();
+
+        let white = rng.gen_range(-1.0..=1.0);
+        self.hiss_state += (white - self.hiss_state) * 0.5;
+
+        let base = if self.sixty { 60.0 } else { 50.0 };
+        self.hum_phase = (self.hum_phase + base / sample_rate as f32).fract();
+        let hum = (TAU * self.hum_phase).sin()
+            + 0.5 * (2.0 * TAU * self.hum_phase).sin()
+            + 0.25 * (3.0 * TAU * self.hum_phase).sin();
+
+        crackle * self.crackle * 0.5 + self.hiss_state * self.hiss * 0.05 + hum * self.hum * 0.03
+    }
+}
+
+impl Module for Vinyl {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Vinyl")
+            .icon("📀")
+            .category(Category::Effect)
+            .tooltip(
+                "adds crackle, hiss, hum and a slow pitch wobble to its input, like an old record",
+            )
+            .example(example)
+            .port(
+                PortDescription::<VinylInput>::input()
+                    .tooltip("the sound to age")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(PortDescription::<VinylOutput>::output().tooltip("the aged sound"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let sample_rate = ctx.sample_rate();
+        let size = (WOBBLE_DEPTH * 2.0 / 1000.0 * sample_rate as f32) as usize + 4;
+        if self.buffer.len() != size {
+            self.buffer = vec![Frame::ZERO; size];
+            self.position = 0;
+        }
+
+        self.buffer[self.position] = ctx.get_input::<VinylInput>();
+        self.position = (self.position + 1) % size;
+
+        // the position swings around the middle of the buffer, changing the pitch slightly
+        self.wobble_phase = (self.wobble_phase + WOBBLE_RATE / sample_rate as f32).fract();
+        let swing = (TAU * self.wobble_phase).sin() * self.wobble;
+        let delay = (1.0 + swing) * WOBBLE_DEPTH / 1000.0 * sample_rate as f32;
+        let wobbled = self.read(delay);
+
+        let noise = self.noise(sample_rate);
+        let output = match wobbled {
+            Frame::Mono(sample) => Frame::Mono(sample + noise),
+            Frame::Stereo(left, right) => Frame::Stereo(left + noise, right + noise),
+        };
+        ctx.set_output::<VinylOutput>(output)
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        egui::Grid::new(ctx.instance).num_columns(2).show(ui, |ui| {
+            for (label, value) in [
+                ("crackle:", &mut self.crackle),
+                ("hiss:", &mut self.hiss),
+                ("hum:", &mut self.hum),
+                ("wobble:", &mut self.wobble),
+            ] {
+                let label = ui.label(tr(label));
+                ui.add(egui::Slider::new(value, 0.0..=1.0))
+                    .labelled_by(label.id);
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("hum frequency:"));
+            ui.selectable_value(&mut self.sixty, false, "50 Hz");
+            ui.selectable_value(&mut self.sixty, true, "60 Hz");
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("crackle", &mut self.crackle, 0.0..=1.0),
+            Parameter::new("hiss", &mut self.hiss, 0.0..=1.0),
+            Parameter::new("hum", &mut self.hum, 0.0..=1.0),
+            Parameter::new("wobble", &mut self.wobble, 0.0..=1.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State { sixty: self.sixty }).ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.sixty = state.sixty;
+        }
+    }
+}
+
+/// An aged oscillator.
+fn example(rack: &mut Rack, panel: usize) {
+    let oscillator = rack.add_module_to::<Oscillator>(panel);
+    let vinyl = rack.add_module_to::<Vinyl>(panel);
+    let audio = rack.add_module_to::<Audio>(panel);
+    rack.connect_typed::<FrameOutput, VinylInput>(oscillator, vinyl)
+        .unwrap();
+    rack.connect_typed::<VinylOutput, AudioInput>(vinyl, audio)
+        .unwrap();
+}
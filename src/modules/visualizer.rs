@@ -0,0 +1,340 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use eframe::{
+    egui::{self, Sense, Ui},
+    epaint::{Color32, Pos2, Rect, Stroke, Vec2},
+};
+use enum_iterator::Sequence;
+use rand::Rng;
+use realfft::{num_complex::Complex, RealFftPlanner, RealToComplex};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Port, PortDescription},
+    modules::noise::{Noise, NoiseOutput},
+    rack::rack::{ProcessContext, Rack, ShowContext},
+    util::EnumIter,
+};
+
+/// Samples of the input kept to draw, also the length of the spectrum analysis.
+const WINDOW: usize = 1024;
+
+/// Amount of bars the spectrum is divided into.
+const BANDS: usize = 32;
+
+/// Lowest frequency in Hz shown by the bars.
+const LOWEST: f32 = 40.0;
+
+/// Most particles alive at once.
+const MAX_PARTICLES: usize = 500;
+
+/// Part of their height the bars fall back per second.
+const FALLOFF: f32 = 2.0;
+
+pub struct VisualizerInput;
+
+impl Port for VisualizerInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for VisualizerInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Sequence, Serialize, Deserialize)]
+enum Mode {
+    #[default]
+    Bars,
+    Waveform,
+    Particles,
+}
+
+impl Mode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Mode::Bars => "spectrum",
+            Mode::Waveform => "waveform",
+            Mode::Particles => "particles",
+        }
+    }
+}
+
+/// Positions are relative to the drawing area, from 0 to 1.
+struct Particle {
+    position: Pos2,
+    velocity: Vec2,
+    /// Seconds left to live.
+    life: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    mode: Mode,
+    size: (f32, f32),
+}
+
+/// A [`Module`] drawing its input large as a spectrum, a waveform or particles, to show along with
+/// performances and streams.
+pub struct Visualizer {
+    mode: Mode,
+    /// Size of the drawing in the rack.
+    size: Vec2,
+    /// Whether the drawing is shown in its own window, filling it.
+    detached: bool,
+    samples: VecDeque<f32>,
+    /// Sample rate the input is received at, which is decimated for visual modules.
+    sample_rate: u32,
+    fft: Arc<dyn RealToComplex<f32>>,
+    spectrum: Vec<Complex<f32>>,
+    /// Smoothed heights of the bars, from 0 to 1.
+    bars: [f32; BANDS],
+    particles: Vec<Particle>,
+}
+
+impl Default for Visualizer {
+    fn default() -> Self {
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(WINDOW);
+        Self {
+            mode: Mode::default(),
+            size: Vec2::new(400.0, 200.0),
+            detached: false,
+            samples: VecDeque::from(vec![0.0; WINDOW]),
+            sample_rate: 48000,
+            spectrum: fft.make_output_vec(),
+            fft,
+            bars: [0.0; BANDS],
+            particles: Vec::new(),
+        }
+    }
+}
+
+impl Visualizer {
+    /// Level of the input, from its root mean square.
+    fn level(&self) -> f32 {
+        (self
+            .samples
+            .iter()
+            .map(|sample| sample * sample)
+            .sum::<f32>()
+            / WINDOW as f32)
+            .sqrt()
+    }
+
+    /// Moves the bars towards the magnitudes of logarithmically spaced bands of the spectrum.
+    fn update_bars(&mut self, dt: f32) {
+        let window = self.samples.iter().enumerate().map(|(index, sample)| {
+            let hann = 0.5 - 0.5 * (std::f32::consts::TAU * index as f32 / WINDOW as f32).cos();
+            sample * hann
+        });
+        let mut time = window.collect::<Vec<_>>();
+        if self.fft.process(&mut time, &mut self.spectrum).is_err() {
+            return;
+        }
+
+        let nyquist = self.sample_rate as f32 / 2.0;
+        let bin_width = nyquist / (self.spectrum.len() - 1) as f32;
+        for (band, bar) in self.bars.iter_mut().enumerate() {
+            let from = LOWEST * (nyquist / LOWEST).powf(band as f32 / BANDS as f32);
+            let to = LOWEST * (nyquist / LOWEST).powf((band + 1) as f32 / BANDS as f32);
+            let bins = (from / bin_width) as usize..=((to / bin_width) as usize);
+            let magnitude = self.spectrum[bins]
+                .iter()
+                .map(|bin| bin.norm())
+                .fold(0.0, f32::max);
+
+            // from -60 dB to 0 dB of a full scale sine
+            let db = 20.0 * (magnitude / (WINDOW as f32 / 4.0)).max(1e-6).log10();
+            let height = ((db + 60.0) / 60.0).clamp(0.0, 1.0);
+            *bar = if height > *bar {
+                height
+            } else {
+                (*bar - FALLOFF * dt).max(height)
+            };
+        }
+    }
+
+    /// Spawns particles in proportion to the level and moves the living ones.
+    fn update_particles(&mut self, dt: f32) {
+        let level = self.level();
+        let mut rng = rand::thread_rng();
+        let spawned = ((level * 200.0 * dt * 60.0) as usize)
+            .min(MAX_PARTICLES.saturating_sub(self.particles.len()));
+        for _ in 0..spawned {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.gen_range(0.1..0.5) * (0.5 + level * 4.0);
+            self.particles.push(Particle {
+                position: Pos2::new(0.5, 0.5),
+                velocity: Vec2::angled(angle) * speed,
+                life: rng.gen_range(0.5..1.5),
+            });
+        }
+
+        for particle in self.particles.iter_mut() {
+            particle.position += particle.velocity * dt;
+            particle.life -= dt;
+        }
+        self.particles.retain(|particle| {
+            particle.life > 0.0
+                && Rect::from_x_y_ranges(0.0..=1.0, 0.0..=1.0).contains(particle.position)
+        });
+    }
+
+    fn draw(&mut self, ui: &mut Ui, size: Vec2) {
+        let dt = ui.input(|input| input.stable_dt).min(0.1);
+        let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, Color32::BLACK);
+        let color = ui.visuals().selection.bg_fill;
+
+        match self.mode {
+            Mode::Bars => {
+                self.update_bars(dt);
+                let width = rect.width() / BANDS as f32;
+                for (band, bar) in self.bars.iter().enumerate() {
+                    let left = rect.left() + band as f32 * width;
+                    let top = rect.bottom() - bar * rect.height();
+                    painter.rect_filled(
+                        Rect::from_min_max(
+                            Pos2::new(left + 1.0, top),
+                            Pos2::new(left + width - 1.0, rect.bottom()),
+                        ),
+                        0.0,
+                        color,
+                    );
+                }
+            }
+            Mode::Waveform => {
+                let step = WINDOW as f32 / rect.width().max(1.0);
+                let points = (0..rect.width() as usize)
+                    .map(|x| {
+                        let sample = self.samples[((x as f32 * step) as usize).min(WINDOW - 1)];
+                        Pos2::new(
+                            rect.left() + x as f32,
+                            rect.center().y - sample.clamp(-1.0, 1.0) * rect.height() / 2.0,
+                        )
+                    })
+                    .collect();
+                painter.add(egui::Shape::line(points, Stroke::new(2.0, color)));
+            }
+            Mode::Particles => {
+                self.update_particles(dt);
+                for particle in self.particles.iter() {
+                    let position = rect.min + particle.position.to_vec2() * rect.size();
+                    painter.circle_filled(
+                        position,
+                        2.0 + particle.life * 2.0,
+                        color.gamma_multiply(particle.life.min(1.0)),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Module for Visualizer {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Visualizer")
+            .icon("🌈")
+            .category(Category::Analysis)
+            .tooltip("draws its input large as a spectrum, a waveform or particles, for installations and streams")
+            .visual()
+            .example(example)
+            .port(
+                PortDescription::<VisualizerInput>::input()
+                    .tooltip("the sound to draw")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        self.sample_rate = ctx.sample_rate();
+        self.samples.pop_front();
+        self.samples
+            .push_back(ctx.get_input::<VisualizerInput>().as_f32_mono());
+    }
+
+    fn show(&mut self, ctx: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source(ctx.instance)
+                .selected_text(tr(self.mode.as_str()))
+                .show_ui(ui, |ui| {
+                    for mode in Mode::iter() {
+                        ui.selectable_value(&mut self.mode, mode, tr(mode.as_str()));
+                    }
+                });
+
+            ui.add_enabled_ui(!self.detached, |ui| {
+                let label = ui.label(tr("size:"));
+                ui.add(
+                    egui::DragValue::new(&mut self.size.x)
+                        .clamp_range(100.0..=2000.0)
+                        .suffix(" px"),
+                )
+                .labelled_by(label.id)
+                .on_hover_text(tr("width"));
+                ui.add(
+                    egui::DragValue::new(&mut self.size.y)
+                        .clamp_range(50.0..=2000.0)
+                        .suffix(" px"),
+                )
+                .labelled_by(label.id)
+                .on_hover_text(tr("height"));
+            });
+
+            ui.toggle_value(&mut self.detached, "🗗")
+                .on_hover_text(tr("show in a separate resizable window"));
+        });
+
+        if self.detached {
+            let mut open = true;
+            egui::Window::new(tr("Visualizer"))
+                .id(egui::Id::new(("visualizer", ctx.instance)))
+                .default_size(self.size)
+                .open(&mut open)
+                .show(ui.ctx(), |ui| {
+                    let size = ui.available_size().max(Vec2::splat(50.0));
+                    self.draw(ui, size)
+                });
+            self.detached = open;
+        } else {
+            self.draw(ui, self.size);
+        }
+
+        ui.ctx().request_repaint();
+    }
+
+    fn state(&self) -> Option<String> {
+        ron::to_string(&State {
+            mode: self.mode,
+            size: (self.size.x, self.size.y),
+        })
+        .ok()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(state) = ron::from_str::<State>(state) {
+            self.mode = state.mode;
+            self.size = Vec2::new(state.size.0, state.size.1);
+        }
+    }
+}
+
+/// Draws the spectrum of noise as bars.
+fn example(rack: &mut Rack, panel: usize) {
+    let noise = rack.add_module_to::<Noise>(panel);
+    let visualizer = rack.add_module_to::<Visualizer>(panel);
+    rack.connect_typed::<NoiseOutput, VisualizerInput>(noise, visualizer)
+        .unwrap();
+}
@@ -0,0 +1,144 @@
+use eframe::egui::{self, Ui};
+
+use crate::{
+    frame::Frame,
+    i18n::tr,
+    module::{Category, Input, Module, ModuleDescription, Parameter, Port, PortDescription},
+    rack::rack::{ProcessContext, ShowContext},
+};
+
+pub struct WidthInput;
+
+impl Port for WidthInput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "input"
+    }
+}
+
+impl Input for WidthInput {
+    fn default() -> Self::Type {
+        Frame::ZERO
+    }
+}
+
+pub struct WidthOutput;
+
+impl Port for WidthOutput {
+    type Type = Frame;
+
+    fn name() -> &'static str {
+        "output"
+    }
+}
+
+/// Time constant of the correlation meter in seconds.
+const METER_TIME: f32 = 0.3;
+
+/// A [`Module`] that scales the mid and side components of a stereo signal independently.
+pub struct Width {
+    mid: f32,
+    side: f32,
+    lr: f32,
+    ll: f32,
+    rr: f32,
+}
+
+impl Default for Width {
+    fn default() -> Self {
+        Self {
+            mid: 1.0,
+            side: 1.0,
+            lr: 0.0,
+            ll: 0.0,
+            rr: 0.0,
+        }
+    }
+}
+
+impl Width {
+    /// Correlation between the left and right channel of the output, from -1 (out of phase) to 1 (mono).
+    pub fn correlation(&self) -> f32 {
+        let energy = (self.ll * self.rr).sqrt();
+        if energy > f32::EPSILON {
+            (self.lr / energy).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn meter(&mut self, left: f32, right: f32, sample_rate: u32) {
+        let k = 1.0 / (METER_TIME * sample_rate as f32);
+        self.lr += (left * right - self.lr) * k;
+        self.ll += (left * left - self.ll) * k;
+        self.rr += (right * right - self.rr) * k;
+    }
+}
+
+impl Module for Width {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("Width")
+            .icon("↔")
+            .category(Category::Effect)
+            .tooltip("scales the mid and side of a stereo signal, widening or narrowing it")
+            .port(
+                PortDescription::<WidthInput>::input()
+                    .tooltip("the stereo sound")
+                    .conversion(|sample: f32| Frame::Mono(sample)),
+            )
+            .port(PortDescription::<WidthOutput>::output().tooltip("the sound with adjusted width"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let (left, right) = ctx.get_input::<WidthInput>().as_f32_tuple();
+
+        let mid = (left + right) / 2.0 * self.mid;
+        let side = (left - right) / 2.0 * self.side;
+
+        let (left, right) = (mid + side, mid - side);
+        self.meter(left, right, ctx.sample_rate());
+
+        ctx.set_output::<WidthOutput>(Frame::Stereo(left, right))
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("mid:"));
+            ui.add(
+                egui::DragValue::new(&mut self.mid)
+                    .clamp_range(0.0..=2.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id);
+            let label = ui.label(tr("side:"));
+            ui.add(
+                egui::DragValue::new(&mut self.side)
+                    .clamp_range(0.0..=2.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id);
+        });
+
+        ui.horizontal(|ui| {
+            let correlation = self.correlation();
+            ui.label(tr("correlation:"));
+            ui.add(
+                egui::ProgressBar::new((correlation + 1.0) / 2.0)
+                    .desired_width(150.0)
+                    .text(format!("{:+.2}", correlation)),
+            );
+        });
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("mid", &mut self.mid, 0.0..=2.0),
+            Parameter::new("side", &mut self.side, 0.0..=2.0),
+        ]
+    }
+}
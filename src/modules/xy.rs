@@ -0,0 +1,156 @@
+use eframe::{
+    egui::{self, Sense, Ui},
+    epaint::{Color32, Stroke, Vec2},
+};
+
+use crate::{
+    clip::Clip,
+    module::{Category, Module, ModuleDescription, Parameter, Port, PortDescription},
+    modules::scope::{Scope, ScopeInput},
+    rack::rack::{ProcessContext, Rack, ShowContext},
+};
+
+pub struct XyXOutput;
+
+impl Port for XyXOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "x"
+    }
+}
+
+pub struct XyYOutput;
+
+impl Port for XyYOutput {
+    type Type = f32;
+
+    fn name() -> &'static str {
+        "y"
+    }
+}
+
+/// A [`Module`] with a pad that outputs the position of the pointer dragging over it.
+pub struct Xy {
+    x: f32,
+    y: f32,
+    /// Whether the pad is being dragged, which takes over from the clip.
+    dragging: bool,
+    clip: Clip<(f32, f32)>,
+    /// The position that was output last, live or from the clip.
+    output: (f32, f32),
+}
+
+impl Default for Xy {
+    fn default() -> Self {
+        Self {
+            x: 0.5,
+            y: 0.5,
+            dragging: false,
+            clip: Clip::default(),
+            output: (0.5, 0.5),
+        }
+    }
+}
+
+impl Module for Xy {
+    fn describe() -> ModuleDescription<Self>
+    where
+        Self: Sized,
+    {
+        ModuleDescription::default()
+            .name("XY")
+            .icon("✥")
+            .category(Category::Modulation)
+            .tooltip("outputs two values at once by dragging over a pad, and can loop a recorded movement")
+            .example(example)
+            .port(PortDescription::<XyXOutput>::output().tooltip("horizontal position from 0 to 1"))
+            .port(PortDescription::<XyYOutput>::output().tooltip("vertical position from 0 to 1"))
+    }
+
+    fn process(&mut self, ctx: &mut ProcessContext) {
+        let live = (self.x, self.y);
+        self.output = match self.clip.process(ctx.transport(), &live) {
+            Some(recorded) if !self.dragging => recorded,
+            _ => live,
+        };
+
+        ctx.set_output::<XyXOutput>(self.output.0);
+        ctx.set_output::<XyYOutput>(self.output.1);
+    }
+
+    fn show(&mut self, _: &ShowContext, ui: &mut Ui) {
+        let (rect, response) = ui.allocate_exact_size(Vec2::splat(120.0), Sense::drag());
+
+        self.dragging = response.dragged();
+        if let Some(pointer) = response.interact_pointer_pos().filter(|_| self.dragging) {
+            self.x = ((pointer.x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+            self.y = ((rect.bottom() - pointer.y) / rect.height()).clamp(0.0, 1.0);
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+        painter.rect_stroke(rect, 4.0, ui.visuals().widgets.noninteractive.bg_stroke);
+
+        let (x, y) = self.output;
+        let point = egui::pos2(
+            rect.left() + x * rect.width(),
+            rect.bottom() - y * rect.height(),
+        );
+        let faint = Stroke::new(1.0, ui.visuals().weak_text_color());
+        painter.hline(rect.x_range(), point.y, faint);
+        painter.vline(point.x, rect.y_range(), faint);
+        painter.circle_filled(point, 5.0, ui.visuals().selection.bg_fill);
+        if self.output != (self.x, self.y) {
+            // the live position while the clip plays
+            let live = egui::pos2(
+                rect.left() + self.x * rect.width(),
+                rect.bottom() - self.y * rect.height(),
+            );
+            painter.circle_stroke(live, 5.0, Stroke::new(1.0, Color32::GRAY));
+        }
+
+        ui.label(format!("x {x:.2}  y {y:.2}"));
+        self.clip.show(ui);
+    }
+
+    fn parameters(&mut self) -> Vec<Parameter<'_>> {
+        vec![
+            Parameter::new("x", &mut self.x, 0.0..=1.0),
+            Parameter::new("y", &mut self.y, 0.0..=1.0),
+        ]
+    }
+
+    fn state(&self) -> Option<String> {
+        (!self.clip.is_empty())
+            .then(|| ron::to_string(&self.clip).ok())
+            .flatten()
+    }
+
+    fn load_state(&mut self, state: &str) {
+        if let Ok(clip) = ron::from_str(state) {
+            self.clip = clip;
+            self.clip.play();
+        }
+    }
+
+    fn has_clip(&self) -> bool {
+        true
+    }
+
+    fn set_clip_playing(&mut self, playing: bool) {
+        if playing {
+            self.clip.play()
+        } else {
+            self.clip.stop()
+        }
+    }
+}
+
+/// Draws the horizontal movement of the pad, record it while the transport plays to loop it.
+fn example(rack: &mut Rack, panel: usize) {
+    let xy = rack.add_module_to::<Xy>(panel);
+    let scope = rack.add_module_to::<Scope>(panel);
+    rack.connect_typed::<XyXOutput, ScopeInput>(xy, scope)
+        .unwrap();
+}
@@ -0,0 +1,97 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use crate::frame::Frame;
+
+/// Amount of samples the energy is measured over at 44.1kHz, scaled for other sample rates.
+const HOP: f32 = 512.0 / 44100.0;
+
+/// Amount of hops on either side the flux is averaged over for the adaptive threshold.
+const THRESHOLD_RADIUS: usize = 8;
+
+/// Rise in dB above the average flux needed for an onset.
+const THRESHOLD_DB: f32 = 4.0;
+
+/// Hops quieter than this in dB are never onsets.
+const SILENCE_DB: f32 = -50.0;
+
+/// Shortest time between two onsets in seconds.
+const MIN_GAP: f32 = 0.05;
+
+const MIN_BPM: f32 = 70.0;
+const MAX_BPM: f32 = 180.0;
+
+/// Finds the positions in samples where notes or hits start, by looking for sudden rises in energy.
+pub fn detect(buffer: &[Frame], sample_rate: u32) -> Vec<usize> {
+    let hop = ((HOP * sample_rate as f32) as usize).max(1);
+
+    let energies = buffer
+        .chunks(hop)
+        .map(|chunk| {
+            let energy = chunk
+                .iter()
+                .map(|frame| frame.as_f32_mono().powi(2))
+                .sum::<f32>()
+                / chunk.len() as f32;
+            10.0 * (energy + 1e-10).log10()
+        })
+        .collect::<Vec<_>>();
+
+    let flux = energies
+        .iter()
+        .enumerate()
+        .map(|(i, &energy)| {
+            let previous = if i == 0 { SILENCE_DB } else { energies[i - 1] };
+            (energy - previous.max(SILENCE_DB)).max(0.0)
+        })
+        .collect::<Vec<_>>();
+
+    let min_gap = (MIN_GAP * sample_rate as f32) as usize;
+    let mut onsets: Vec<usize> = Vec::new();
+
+    for (i, &value) in flux.iter().enumerate() {
+        let around =
+            &flux[i.saturating_sub(THRESHOLD_RADIUS)..(i + THRESHOLD_RADIUS + 1).min(flux.len())];
+        let mean = around.iter().sum::<f32>() / around.len() as f32;
+
+        let peak = around.iter().all(|&other| other <= value);
+        let loud = energies[i] > SILENCE_DB;
+        let position = i * hop;
+        let spaced = onsets.last().is_none_or(|&last| position - last >= min_gap);
+
+        if peak && loud && spaced && value > mean + THRESHOLD_DB {
+            onsets.push(position);
+        }
+    }
+
+    onsets
+}
+
+/// Estimates the tempo from the intervals between onsets, folded into a common range of tempos.
+pub fn estimate_bpm(onsets: &[usize], sample_rate: u32) -> Option<f32> {
+    let mut histogram = [0.0f32; (MAX_BPM - MIN_BPM) as usize + 1];
+
+    for (i, &from) in onsets.iter().enumerate() {
+        // only nearby onsets, farther ones are rarely a whole number of beats apart
+        for &to in onsets[i + 1..].iter().take(4) {
+            let seconds = (to - from) as f32 / sample_rate as f32;
+            let mut bpm = 60.0 / seconds;
+
+            while bpm < MIN_BPM {
+                bpm *= 2.0;
+            }
+            while bpm > MAX_BPM {
+                bpm /= 2.0;
+            }
+
+            let bin = (bpm - MIN_BPM).round() as usize;
+            histogram[bin.min(histogram.len() - 1)] += 1.0;
+        }
+    }
+
+    let (bin, count) = histogram
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+
+    (*count > 0.0).then_some(MIN_BPM + bin as f32)
+}
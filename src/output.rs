@@ -17,10 +17,13 @@ use ringbuf::{
     CachingProd, HeapRb, SharedRb,
 };
 
-use crate::{damper::LinearDamper, frame::Frame};
+use crate::{damper::LinearDamper, frame::Frame, i18n::tr, simd, toast};
 
 type RingProducer = CachingProd<Arc<SharedRb<Heap<Frame>>>>;
 
+/// Most audio that can be buffered ahead, the ring buffer is allocated at this size.
+const MAX_LATENCY: Duration = Duration::from_millis(400);
+
 /// Instance of the application's audio output.
 pub struct StreamInstance {
     _stream: Stream,
@@ -31,6 +34,10 @@ pub struct StreamInstance {
     pub volume: f32,
     muted: bool,
     protection: bool,
+    /// How much audio is buffered ahead.
+    latency: Duration,
+    /// Whether running behind is handled elsewhere instead of muting.
+    pub guarded: bool,
 }
 
 fn ringbuf_size(config: &StreamConfig, duration: Duration) -> usize {
@@ -40,8 +47,7 @@ fn ringbuf_size(config: &StreamConfig, duration: Duration) -> usize {
 impl StreamInstance {
     fn new(device: Device, config: StreamConfig) -> Option<Self> {
         let (producer, mut consumer) = {
-            let duration = Duration::from_secs_f32(0.15);
-            let rb = HeapRb::<Frame>::new(ringbuf_size(&config, duration));
+            let rb = HeapRb::<Frame>::new(ringbuf_size(&config, MAX_LATENCY));
             rb.split()
         };
 
@@ -51,15 +57,16 @@ impl StreamInstance {
             .build_output_stream(
                 &config,
                 move |data: &mut [f32], _| {
-                    for chunk in data.iter_mut().array_chunks::<2>() {
+                    for chunk in data.chunks_exact_mut(2) {
                         let (a, b) = consumer.try_pop().unwrap_or_default().as_f32_tuple();
-                        *chunk[0] = a;
-                        *chunk[1] = b;
+                        chunk[0] = a;
+                        chunk[1] = b;
                     }
                 },
                 {
                     let is_err = is_err.clone();
-                    move |_| {
+                    move |err| {
+                        tracing::error!("audio output stream failed: {err}");
                         is_err.store(true, std::sync::atomic::Ordering::Relaxed);
                     }
                 },
@@ -78,6 +85,8 @@ impl StreamInstance {
             volume: 0.5,
             muted: false,
             protection: false,
+            latency: Duration::from_millis(150),
+            guarded: false,
         })
     }
 
@@ -85,8 +94,18 @@ impl StreamInstance {
         !self.is_err.load(std::sync::atomic::Ordering::Relaxed)
     }
 
+    /// Amount of frames that can be pushed before reaching the latency.
     pub fn free_len(&self) -> usize {
-        self.producer.vacant_len()
+        ringbuf_size(&self.config, self.latency).saturating_sub(self.producer.occupied_len())
+    }
+
+    pub fn set_latency(&mut self, latency: Duration) {
+        self.latency = latency.min(MAX_LATENCY);
+    }
+
+    /// Whether the buffered audio ran low since it was last filled.
+    pub fn behind(&self) -> bool {
+        self.free_len() > self.damper.cutoff_samples() as usize
     }
 
     pub fn sample_rate(&self) -> u32 {
@@ -97,16 +116,29 @@ impl StreamInstance {
         self.config.channels
     }
 
-    pub fn push_iter(&mut self, iter: impl Iterator<Item = Frame>) {
-        let mut map = iter.map(|frame| {
-            let ampl = if self.muted || self.protection {
-                self.damper.frame(0.0)
-            } else {
-                self.damper.frame(self.volume)
-            };
-            frame * ampl
-        });
-        self.producer.push_iter(&mut map);
+    /// Applies the volume to interleaved stereo samples and queues them for playback.
+    pub fn push_buffer(&mut self, buffer: &mut [f32]) {
+        let ampl = if self.muted || self.protection {
+            0.0
+        } else {
+            self.volume
+        };
+
+        if self.damper.is_settled(ampl) {
+            simd::scale(buffer, ampl);
+        } else {
+            for chunk in buffer.chunks_exact_mut(2) {
+                let ampl = self.damper.frame(ampl);
+                chunk[0] *= ampl;
+                chunk[1] *= ampl;
+            }
+        }
+
+        self.producer.push_iter(
+            buffer
+                .chunks_exact(2)
+                .map(|chunk| Frame::Stereo(chunk[0], chunk[1])),
+        );
     }
 
     fn show(&mut self, ui: &mut Ui) {
@@ -123,19 +155,19 @@ impl StreamInstance {
                 .speed(0.01)
                 .clamp_range(0.0..=1.0),
         )
-        .on_hover_text_at_pointer("volume");
+        .on_hover_text_at_pointer(tr("volume"));
         ui.separator();
         ui.label(RichText::new(format!("{}", self.sample_rate())).monospace())
-            .on_hover_text_at_pointer("sample rate");
+            .on_hover_text_at_pointer(tr("sample rate"));
         ui.separator();
 
         ui.label(RichText::new(format!("{}", self.channels())).monospace())
-            .on_hover_text_at_pointer("channels");
+            .on_hover_text_at_pointer(tr("channels"));
 
-        if self.producer.vacant_len() > self.damper.cutoff_samples() as usize {
+        if self.behind() && !self.guarded {
             self.protection = true;
             ui.separator();
-            ui.label(RichText::new("⚠ cant keep up!").color(Color32::GOLD));
+            ui.label(RichText::new(tr("⚠ cant keep up!")).color(Color32::GOLD));
         } else {
             self.protection = false;
         }
@@ -163,6 +195,12 @@ fn fetch_stream_config(device: &Device) -> Option<StreamConfig> {
     )
 }
 
+impl Default for Output {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Output {
     pub fn new() -> Self {
         let mut new = Self { instance: None };
@@ -173,10 +211,19 @@ impl Output {
     }
 
     fn init_instance(&mut self) -> Option<&mut StreamInstance> {
-        let device = fetch_device()?;
-        let config = fetch_stream_config(&device)?;
+        let Some(device) = fetch_device() else {
+            tracing::warn!("no audio output device found");
+            return None;
+        };
+        let Some(config) = fetch_stream_config(&device) else {
+            tracing::warn!("no supported configuration for the audio output device");
+            return None;
+        };
 
         self.instance = StreamInstance::new(device, config);
+        if self.instance.is_none() {
+            tracing::error!("could not start the audio output stream");
+        }
 
         self.instance.as_mut()
     }
@@ -187,7 +234,8 @@ impl Output {
             .as_ref()
             .is_some_and(|instance| !instance.is_valid())
         {
-            self.instance = None
+            self.instance = None;
+            toast::error(tr("the audio output was lost"));
         }
     }
 
@@ -197,7 +245,7 @@ impl Output {
     }
 
     pub fn instance_mut_or_init(&mut self) -> Option<&mut StreamInstance> {
-        if !self.instance_mut().is_some() {
+        if self.instance_mut().is_none() {
             self.init_instance();
         }
 
@@ -211,17 +259,26 @@ impl Output {
             .unwrap_or(44100)
     }
 
+    /// Whether there is an output that is not muted.
+    pub fn is_audible(&self) -> bool {
+        self.instance
+            .as_ref()
+            .is_some_and(|instance| !instance.muted)
+    }
+
     pub fn show(&mut self, ui: &mut Ui) {
         if let Some(instance) = &mut self.instance_mut_or_init() {
             instance.show(ui)
         } else {
-            ui.label(RichText::new("⚠ could not initialize audio output!").color(Color32::GOLD));
-            if ui.button("retry").clicked() {
+            ui.label(
+                RichText::new(tr("⚠ could not initialize audio output!")).color(Color32::GOLD),
+            );
+            if ui.button(tr("retry")).clicked() {
                 self.init_instance();
             }
             ui.separator();
             ui.label(RichText::new(format!("({})", self.sample_rate_or_default())).monospace())
-                .on_hover_text_at_pointer("fallback sample rate");
+                .on_hover_text_at_pointer(tr("fallback sample rate"));
         }
     }
 }
@@ -0,0 +1,121 @@
+#![cfg(feature = "python")]
+//! Python bindings to build patches and render them offline, for notebooks.
+//!
+//! Built with `maturin develop`, which enables the `python` feature, after which:
+//!
+//! ```python
+//! import synth_mod
+//! rack = synth_mod.Rack()
+//! osc = rack.add("Oscillator")
+//! out = rack.add("Audio Output")
+//! rack.connect(osc, "sample", out, "output")
+//! audio = rack.render(1.0)  # shape (48000, 2)
+//! ```
+
+use numpy::{PyArray1, PyArray2, PyArrayMethods};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{instance::instance::InstanceHandle, module::PortType, rack::rack::Rack};
+
+/// A rack without the app, with modules referred to by the order they were added in.
+#[pyclass(unsendable, name = "Rack")]
+struct PyRack {
+    rack: Rack,
+    handles: Vec<InstanceHandle>,
+}
+
+impl PyRack {
+    fn handle(&self, module: usize) -> PyResult<InstanceHandle> {
+        self.handles
+            .get(module)
+            .copied()
+            .ok_or_else(|| PyValueError::new_err(format!("no module {module}")))
+    }
+}
+
+#[pymethods]
+impl PyRack {
+    #[new]
+    fn new() -> Self {
+        let mut rack = Rack::default();
+        rack.add_panel();
+        Self {
+            rack,
+            handles: Vec::new(),
+        }
+    }
+
+    /// Names of the modules that can be added.
+    #[staticmethod]
+    fn modules() -> Vec<String> {
+        Rack::default()
+            .modules
+            .iter()
+            .map(|description| description.name.to_string())
+            .collect()
+    }
+
+    /// Adds a module by its name, returning its index.
+    fn add(&mut self, name: &str) -> PyResult<usize> {
+        let Some(description) = self
+            .rack
+            .modules
+            .iter()
+            .find(|description| description.matches_name(name))
+            .cloned()
+        else {
+            return Err(PyValueError::new_err(format!("unknown module \"{name}\"")));
+        };
+        self.handles.push(self.rack.add_module(&description, 0));
+        Ok(self.handles.len() - 1)
+    }
+
+    /// Connects an output of one module to an input of another, by the names of the ports.
+    fn connect(&mut self, from: usize, output: &str, to: usize, input: &str) -> PyResult<()> {
+        let from = self
+            .rack
+            .find_port(self.handle(from)?, PortType::Output, output)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown output \"{output}\"")))?;
+        let to = self
+            .rack
+            .find_port(self.handle(to)?, PortType::Input, input)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown input \"{input}\"")))?;
+        self.rack.connect(from, to).map_err(PyValueError::new_err)
+    }
+
+    /// Sets a parameter of a module, clamped to its range.
+    fn set_parameter(&mut self, module: usize, name: &str, value: f32) -> PyResult<()> {
+        let handle = self.handle(module)?;
+        let instance = self.rack.instances.get_mut(&handle).unwrap();
+        let Some(parameter) = instance
+            .module
+            .parameters()
+            .into_iter()
+            .find(|parameter| parameter.name == name)
+        else {
+            return Err(PyValueError::new_err(format!(
+                "unknown parameter \"{name}\""
+            )));
+        };
+        *parameter.value = value.clamp(*parameter.range.start(), *parameter.range.end());
+        Ok(())
+    }
+
+    /// Processes `seconds` of audio, returned as an array of left and right samples.
+    #[pyo3(signature = (seconds, sample_rate = 48000))]
+    fn render<'py>(
+        &mut self,
+        py: Python<'py>,
+        seconds: f32,
+        sample_rate: u32,
+    ) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let frames = (seconds * sample_rate as f32) as usize;
+        let samples = self.rack.process_amount(sample_rate, frames).to_vec();
+        PyArray1::from_vec(py, samples).reshape([frames, 2])
+    }
+}
+
+#[pymodule]
+fn synth_mod(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyRack>()
+}
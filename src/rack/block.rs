@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::instance::instance::InstanceHandle;
+
+/// Key the block library is persisted under.
+pub const STORAGE_KEY: &str = "blocks";
+
+/// A named group of modules with their connections and parameter values, inserted as a whole.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Block {
+    pub name: String,
+    pub modules: Vec<BlockModule>,
+    pub connections: Vec<BlockConnection>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BlockModule {
+    /// Name of the module description to instantiate.
+    pub name: String,
+    pub parameters: Vec<(String, f32)>,
+    /// Values of the unconnected inputs that are numbers or switches, as set on the module.
+    #[serde(default)]
+    pub inputs: Vec<(String, f32)>,
+    /// Path of the file the module depends on, only read from patches saved before modules could
+    /// depend on several files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+    /// Paths of the files the module depends on, see [`crate::module::Module::assets`].
+    #[serde(default)]
+    pub assets: Vec<String>,
+    /// Settings of the module that aren't parameters, see [`crate::module::Module::state`].
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Labels the instance was tagged with, to find it back.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Handle the instance had when captured, to point modules referring to it to its new handle.
+    #[serde(default)]
+    pub handle: Option<InstanceHandle>,
+}
+
+impl BlockModule {
+    /// The paths of the files the module depends on, also for older patches.
+    pub fn asset_paths(&self) -> impl Iterator<Item = &String> {
+        self.asset.iter().chain(self.assets.iter())
+    }
+}
+
+/// A connection between two modules of a block, referred to by their index.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BlockConnection {
+    pub from: usize,
+    pub output: String,
+    pub to: usize,
+    pub input: String,
+    pub gain: f32,
+}
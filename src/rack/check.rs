@@ -0,0 +1,252 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{fmt::Display, path::Path};
+
+use super::{patch::Patch, rack::Rack};
+use crate::{
+    instance::instance::InstanceHandle,
+    io::{ConnectResult, ConnectResultErr, PortHandle},
+    module::PortType,
+};
+
+/// Something wrong with a patch found by [`check`].
+pub enum Problem {
+    UnknownModule {
+        module: usize,
+        name: String,
+    },
+    UnknownPort {
+        module: usize,
+        port: String,
+        port_type: PortType,
+    },
+    /// A connection between ports of types without a conversion.
+    Incompatible {
+        connection: usize,
+        from: String,
+        to: String,
+    },
+    /// Modules depending on each other through their connections, in order.
+    Cycle(Vec<usize>),
+    /// A file a module depends on that exists nowhere and isn't embedded.
+    MissingFile(String),
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::UnknownModule { module, name } => {
+                write!(f, "module {module}: unknown module \"{name}\"")
+            }
+            Problem::UnknownPort {
+                module,
+                port,
+                port_type,
+            } => {
+                let kind = match port_type {
+                    PortType::Input => "input",
+                    PortType::Output => "output",
+                };
+                write!(f, "module {module}: unknown {kind} \"{port}\"")
+            }
+            Problem::Incompatible {
+                connection,
+                from,
+                to,
+            } => write!(
+                f,
+                "connection {connection}: \"{from}\" can't be connected to \"{to}\""
+            ),
+            Problem::Cycle(modules) => {
+                let modules = modules
+                    .iter()
+                    .map(|module| module.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "cycle between modules {}", modules.join(" → "))
+            }
+            Problem::MissingFile(path) => write!(f, "missing file \"{path}\""),
+        }
+    }
+}
+
+/// Finds the problems of a patch without running it, with files looked up relative to `dir` too.
+///
+/// The patch is built in `rack`, which should be empty, so ports can depend on the state of their
+/// modules. Connections closing a cycle are refused by the rack and reported as a whole instead.
+pub fn check(rack: &mut Rack, patch: &Patch, dir: Option<&Path>) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    rack.add_panel();
+
+    let handles = patch
+        .block
+        .modules
+        .iter()
+        .enumerate()
+        .map(|(index, module)| {
+            let Some(description) = rack
+                .modules
+                .iter()
+                .find(|description| description.matches_name(&module.name))
+                .cloned()
+            else {
+                problems.push(Problem::UnknownModule {
+                    module: index,
+                    name: module.name.clone(),
+                });
+                return None;
+            };
+            let handle = rack.add_module(&description, 0);
+            if let Some(state) = &module.state {
+                let instance = rack.instances.get_mut(&handle).unwrap();
+                instance.module.load_state(state);
+                instance.update_ports(&mut rack.io);
+            }
+            Some(handle)
+        })
+        .collect::<Vec<_>>();
+
+    for (index, connection) in patch.block.connections.iter().enumerate() {
+        let from = find_port(
+            rack,
+            &handles,
+            connection.from,
+            PortType::Output,
+            &connection.output,
+            &mut problems,
+        );
+        let to = find_port(
+            rack,
+            &handles,
+            connection.to,
+            PortType::Input,
+            &connection.input,
+            &mut problems,
+        );
+        let (Some(from), Some(to)) = (from, to) else {
+            continue;
+        };
+        if let ConnectResult::Err(ConnectResultErr::InCompatible) = rack.io.connect(from, to) {
+            problems.push(Problem::Incompatible {
+                connection: index,
+                from: connection.output.clone(),
+                to: connection.input.clone(),
+            });
+        }
+    }
+
+    if let Some(cycle) = find_cycle(patch) {
+        problems.push(Problem::Cycle(cycle));
+    }
+
+    for path in patch.assets() {
+        let exists = Path::new(&path).exists()
+            || dir.is_some_and(|dir| {
+                Path::new(&path)
+                    .file_name()
+                    .is_some_and(|name| dir.join(name).exists())
+            })
+            || patch.embedded.iter().any(|(embedded, _)| *embedded == path);
+        if !exists {
+            problems.push(Problem::MissingFile(path))
+        }
+    }
+
+    problems
+}
+
+/// Finds a port of a module of the patch by its index, noting it as a problem when the module
+/// exists but the port doesn't.
+fn find_port(
+    rack: &Rack,
+    handles: &[Option<InstanceHandle>],
+    module: usize,
+    port_type: PortType,
+    name: &str,
+    problems: &mut Vec<Problem>,
+) -> Option<PortHandle> {
+    let handle = handles.get(module).copied().flatten()?;
+    let found = rack.find_port(handle, port_type, name);
+    if found.is_none() {
+        problems.push(Problem::UnknownPort {
+            module,
+            port: name.to_string(),
+            port_type,
+        });
+    }
+    found
+}
+
+/// Finds modules depending on each other through the connections of a patch, by index.
+fn find_cycle(patch: &Patch) -> Option<Vec<usize>> {
+    let count = patch.block.modules.len();
+    let mut edges = vec![Vec::new(); count];
+    for connection in patch.block.connections.iter() {
+        if connection.from < count && connection.to < count {
+            edges[connection.from].push(connection.to);
+        }
+    }
+
+    /// Depth first search keeping the modules on the current path, `done` marks modules that
+    /// were fully searched.
+    fn visit(
+        module: usize,
+        edges: &[Vec<usize>],
+        done: &mut [bool],
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<usize>> {
+        if let Some(start) = path.iter().position(|&other| other == module) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(module);
+            return Some(cycle);
+        }
+        if done[module] {
+            return None;
+        }
+
+        path.push(module);
+        for &to in edges[module].iter() {
+            if let Some(cycle) = visit(to, edges, done, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done[module] = true;
+        None
+    }
+
+    let mut done = vec![false; count];
+    (0..count).find_map(|module| visit(module, &edges, &mut done, &mut Vec::new()))
+}
+
+/// Checks the patch files at `paths` for `--check`, printing their problems.
+///
+/// Returns the exit code, 1 when a patch has problems or can't be read.
+pub fn check_files(paths: &[String]) -> i32 {
+    if paths.is_empty() {
+        eprintln!("usage: --check <patch>...");
+        return 2;
+    }
+
+    let mut code = 0;
+    for path in paths {
+        let Some(patch) = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| Patch::from_ron(&text).or_else(|| Patch::from_text(&text)))
+        else {
+            println!("{path}: invalid patch");
+            code = 1;
+            continue;
+        };
+
+        let problems = check(&mut Rack::default(), &patch, Path::new(path).parent());
+        if problems.is_empty() {
+            println!("{path}: ok");
+        } else {
+            code = 1;
+            for problem in problems {
+                println!("{path}: {problem}");
+            }
+        }
+    }
+    code
+}
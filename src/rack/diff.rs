@@ -0,0 +1,286 @@
+use eframe::egui::{self, Color32, Ui};
+
+use super::patch::Patch;
+use crate::i18n::tr;
+
+/// A parameter of a module in both patches with a different value.
+pub struct ParameterChange {
+    pub module: String,
+    pub parameter: String,
+    pub from: f32,
+    pub to: f32,
+}
+
+/// Differences between two versions of a patch, to review changes to a shared patch.
+///
+/// Modules are described by their name and their index in the patch they are in.
+#[derive(Default)]
+pub struct PatchDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub connected: Vec<String>,
+    pub disconnected: Vec<String>,
+    pub parameters: Vec<ParameterChange>,
+    /// Modules of which settings that aren't parameters changed.
+    pub states: Vec<String>,
+}
+
+impl PatchDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.connected.is_empty()
+            && self.disconnected.is_empty()
+            && self.parameters.is_empty()
+            && self.states.is_empty()
+    }
+
+    pub fn show(&self, ui: &mut Ui) {
+        if self.is_empty() {
+            ui.label(tr("the patches are the same"));
+            return;
+        }
+
+        let green = Color32::from_rgb(0, 158, 115);
+        let red = ui.visuals().error_fg_color;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for module in self.added.iter() {
+                ui.colored_label(green, format!("+ {module}"));
+            }
+            for module in self.removed.iter() {
+                ui.colored_label(red, format!("- {module}"));
+            }
+            for connection in self.connected.iter() {
+                ui.colored_label(green, format!("+ {connection}"));
+            }
+            for connection in self.disconnected.iter() {
+                ui.colored_label(red, format!("- {connection}"));
+            }
+            for change in self.parameters.iter() {
+                ui.label(format!(
+                    "{} {}: {:.3} → {:.3}",
+                    change.module,
+                    tr(&change.parameter),
+                    change.from,
+                    change.to
+                ));
+            }
+            for module in self.states.iter() {
+                ui.label(format!("{module}: {}", tr("settings changed")));
+            }
+        });
+    }
+}
+
+fn label(patch: &Patch, index: usize) -> String {
+    match patch.block.modules.get(index) {
+        Some(module) => format!("{} #{index}", module.name),
+        None => format!("#{index}"),
+    }
+}
+
+/// Pairs each module of `old` with the module it became in `new`, by the handle it was exported
+/// with or otherwise by name in order.
+fn match_modules(old: &Patch, new: &Patch) -> Vec<Option<usize>> {
+    let mut taken = vec![false; new.block.modules.len()];
+    let mut matches = vec![None; old.block.modules.len()];
+
+    for (index, module) in old.block.modules.iter().enumerate() {
+        if module.handle.is_none() {
+            continue;
+        }
+        let found = new
+            .block
+            .modules
+            .iter()
+            .position(|other| other.handle == module.handle);
+        if let Some(found) = found {
+            taken[found] = true;
+            matches[index] = Some(found);
+        }
+    }
+
+    for (index, module) in old.block.modules.iter().enumerate() {
+        if matches[index].is_some() {
+            continue;
+        }
+        let found = new
+            .block
+            .modules
+            .iter()
+            .enumerate()
+            .position(|(other_index, other)| !taken[other_index] && other.name == module.name);
+        if let Some(found) = found {
+            taken[found] = true;
+            matches[index] = Some(found);
+        }
+    }
+
+    matches
+}
+
+/// Finds the modules, connections and parameters that changed from `old` to `new`.
+pub fn diff(old: &Patch, new: &Patch) -> PatchDiff {
+    let matches = match_modules(old, new);
+    let mut diff = PatchDiff::default();
+
+    for (index, matched) in matches.iter().enumerate() {
+        let Some(matched) = *matched else {
+            diff.removed.push(label(old, index));
+            continue;
+        };
+
+        let (before, after) = (&old.block.modules[index], &new.block.modules[matched]);
+        for (parameter, from) in before.parameters.iter() {
+            let Some((_, to)) = after.parameters.iter().find(|(name, _)| name == parameter) else {
+                continue;
+            };
+            if (from - to).abs() > f32::EPSILON {
+                diff.parameters.push(ParameterChange {
+                    module: label(new, matched),
+                    parameter: parameter.clone(),
+                    from: *from,
+                    to: *to,
+                });
+            }
+        }
+        if before.state != after.state {
+            diff.states.push(label(new, matched));
+        }
+    }
+
+    for index in 0..new.block.modules.len() {
+        if !matches.contains(&Some(index)) {
+            diff.added.push(label(new, index));
+        }
+    }
+
+    let describe = |patch: &Patch, from: usize, output: &str, to: usize, input: &str| {
+        format!(
+            "{}.{output} → {}.{input}",
+            label(patch, from),
+            label(patch, to)
+        )
+    };
+
+    // the connections of the old patch in the indices of the new one, `None` for removed modules
+    let moved = old
+        .block
+        .connections
+        .iter()
+        .map(|connection| {
+            let from = matches.get(connection.from).copied().flatten()?;
+            let to = matches.get(connection.to).copied().flatten()?;
+            Some((
+                from,
+                connection.output.as_str(),
+                to,
+                connection.input.as_str(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    for (connection, moved) in old.block.connections.iter().zip(moved.iter()) {
+        let kept = moved.is_some_and(|(from, output, to, input)| {
+            new.block.connections.iter().any(|other| {
+                other.from == from
+                    && other.output == output
+                    && other.to == to
+                    && other.input == input
+            })
+        });
+        if !kept {
+            diff.disconnected.push(describe(
+                old,
+                connection.from,
+                &connection.output,
+                connection.to,
+                &connection.input,
+            ));
+        }
+    }
+
+    for connection in new.block.connections.iter() {
+        let existed = moved.iter().flatten().any(|&(from, output, to, input)| {
+            connection.from == from
+                && connection.output == output
+                && connection.to == to
+                && connection.input == input
+        });
+        if !existed {
+            diff.connected.push(describe(
+                new,
+                connection.from,
+                &connection.output,
+                connection.to,
+                &connection.input,
+            ));
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        modules::{
+            filter::{Filter, FilterInput},
+            noise::Noise,
+            oscillator::{FrameOutput, Oscillator},
+        },
+        rack::rack::Rack,
+    };
+
+    #[test]
+    fn finds_changes() {
+        let mut rack = Rack::default();
+        rack.add_panel();
+        let oscillator = rack.add_module_to::<Oscillator>(0);
+        let filter = rack.add_module_to::<Filter>(0);
+        let old = rack.export_patch();
+
+        rack.connect_typed::<FrameOutput, FilterInput>(oscillator, filter)
+            .unwrap();
+        rack.get_module_mut(oscillator).unwrap().freq = 220.0;
+        rack.add_module_to::<Noise>(0);
+        let new = rack.export_patch();
+
+        assert!(diff(&old, &old).is_empty());
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.added.len(), 1);
+        assert!(changes.removed.is_empty());
+        assert_eq!(changes.connected.len(), 1);
+        assert!(changes.disconnected.is_empty());
+        assert_eq!(changes.parameters.len(), 1);
+        assert_eq!(changes.parameters[0].parameter, "freq");
+        assert_eq!(changes.parameters[0].to, 220.0);
+
+        let changes = diff(&new, &old);
+        assert_eq!(changes.removed.len(), 1);
+        assert_eq!(changes.disconnected.len(), 1);
+        assert_eq!(changes.parameters[0].from, 220.0);
+    }
+
+    #[test]
+    fn matches_modules_by_name_without_handles() {
+        let mut rack = Rack::default();
+        rack.add_panel();
+        rack.add_module_to::<Oscillator>(0);
+        let mut old = rack.export_patch();
+        let mut new = rack.export_patch();
+        for module in old
+            .block
+            .modules
+            .iter_mut()
+            .chain(new.block.modules.iter_mut())
+        {
+            module.handle = None;
+        }
+
+        assert!(diff(&old, &new).is_empty());
+    }
+}
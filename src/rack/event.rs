@@ -0,0 +1,20 @@
+use crate::{instance::instance::InstanceHandle, io::PortHandle};
+
+/// A change to a [`super::rack::Rack`], passed to the callbacks registered with `on_event`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RackEvent {
+    ModuleAdded(InstanceHandle),
+    ModuleRemoved(InstanceHandle),
+    /// A connection from an output to an input port.
+    Connected(PortHandle, PortHandle),
+    Disconnected(PortHandle, PortHandle),
+    ParameterChanged {
+        instance: InstanceHandle,
+        name: &'static str,
+        value: f32,
+    },
+}
+
+pub trait EventCallback: FnMut(&RackEvent) + 'static {}
+
+impl<F: FnMut(&RackEvent) + 'static> EventCallback for F {}
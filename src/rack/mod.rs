@@ -1,2 +1,10 @@
+pub mod block;
+pub mod check;
+pub mod diff;
+pub mod event;
+pub mod patch;
+#[allow(clippy::module_inception)]
 pub mod rack;
 pub mod response;
+pub mod timeline;
+pub mod vcv;
@@ -0,0 +1,173 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+use super::{block::Block, timeline::Timeline};
+use crate::tuning::Tuning;
+
+/// File extension of exported patches.
+#[cfg(not(target_arch = "wasm32"))]
+pub const EXTENSION: &str = "synthpatch";
+
+/// The modules of all panels of a rack with their connections, shared as a file or as text.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Patch {
+    /// The panel of each module of the block.
+    pub panels: Vec<usize>,
+    /// Widths of the panels that were resized by hand, `None` for panels following their contents.
+    #[serde(default)]
+    pub widths: Vec<Option<f32>>,
+    pub block: Block,
+    /// Base64 encoded contents of asset files by their path, so the patch works without them.
+    #[serde(default)]
+    pub embedded: Vec<(String, String)>,
+    #[serde(default)]
+    pub tuning: Tuning,
+    /// The arrangement, referring to modules by their index in the block.
+    #[serde(default)]
+    pub timeline: Option<Timeline<usize>>,
+}
+
+impl Patch {
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap_or_default()
+    }
+
+    pub fn from_ron(text: &str) -> Option<Self> {
+        ron::from_str(text).ok()
+    }
+
+    /// Encodes the patch as compressed text that can be pasted in a message.
+    pub fn to_text(&self) -> String {
+        let ron = ron::to_string(self).unwrap_or_default();
+        STANDARD.encode(miniz_oxide::deflate::compress_to_vec(ron.as_bytes(), 9))
+    }
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        let compressed = STANDARD.decode(text.trim()).ok()?;
+        let ron = miniz_oxide::inflate::decompress_to_vec(&compressed).ok()?;
+        Self::from_ron(std::str::from_utf8(&ron).ok()?)
+    }
+
+    /// The paths of the files the modules depend on, without duplicates.
+    pub fn assets(&self) -> Vec<String> {
+        let mut assets = Vec::<String>::new();
+        for asset in self
+            .block
+            .modules
+            .iter()
+            .flat_map(|module| module.asset_paths())
+        {
+            if !asset.is_empty() && !assets.contains(asset) {
+                assets.push(asset.clone())
+            }
+        }
+        assets
+    }
+
+    /// Includes the contents of the asset files in the patch.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn embed_assets(&mut self) {
+        for path in self.assets() {
+            if let Ok(bytes) = std::fs::read(&path) {
+                self.embedded.push((path, STANDARD.encode(bytes)))
+            }
+        }
+    }
+
+    /// Points the modules to existing files, extracting embedded assets that are missing to `dir`.
+    ///
+    /// Returns the paths of the assets that could not be found.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn resolve_assets(&mut self, dir: &Path) -> Vec<String> {
+        let mut missing = Vec::new();
+
+        for path in self.assets() {
+            if Path::new(&path).exists() {
+                continue;
+            }
+
+            let extracted = self
+                .embedded
+                .iter()
+                .find(|(embedded, _)| *embedded == path)
+                .and_then(|(_, data)| {
+                    let target = dir.join(Path::new(&path).file_name()?);
+                    std::fs::write(&target, STANDARD.decode(data).ok()?).ok()?;
+                    Some(target.to_string_lossy().to_string())
+                });
+
+            match extracted {
+                Some(extracted) => self.relink(&path, &extracted),
+                None => missing.push(path),
+            }
+        }
+
+        missing
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn relink(&mut self, from: &str, to: &str) {
+        for path in self
+            .block
+            .modules
+            .iter_mut()
+            .flat_map(|module| module.asset.iter_mut().chain(module.assets.iter_mut()))
+        {
+            if path == from {
+                *path = to.to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        module::Module,
+        modules::{
+            delay::{Delay, DelayInput},
+            oscillator::{FrameOutput, Oscillator},
+        },
+        rack::{diff::diff, rack::Rack},
+    };
+
+    #[test]
+    fn text_round_trip() {
+        let mut rack = Rack::default();
+        rack.add_panel();
+        let oscillator = rack.add_module_to::<Oscillator>(0);
+        let delay = rack.add_module_to::<Delay>(0);
+        rack.connect_typed::<FrameOutput, DelayInput>(oscillator, delay)
+            .unwrap();
+        rack.get_module_mut(oscillator).unwrap().freq = 220.0;
+        rack.get_module_mut(delay)
+            .unwrap()
+            .load_state("(time:(ms:120.0,division:Eighth,synced:true))");
+        let patch = rack.export_patch();
+
+        let decoded = Patch::from_text(&patch.to_text()).unwrap();
+        assert_eq!(decoded.to_ron(), patch.to_ron());
+
+        let mut imported = Rack::default();
+        imported.import_patch(&decoded);
+        assert!(diff(&patch, &imported.export_patch()).is_empty());
+
+        // the synced time is state, which the diff only compares as a whole
+        let state = rack.get_module(delay).unwrap().state();
+        assert!(state
+            .as_deref()
+            .is_some_and(|state| state.contains("Eighth")));
+        let imported_delay = imported.find_instances_of::<Delay>()[0];
+        assert_eq!(imported.get_module(imported_delay).unwrap().state(), state);
+    }
+
+    #[test]
+    fn refuses_invalid_text() {
+        assert!(Patch::from_text("not a patch").is_none());
+        assert!(Patch::from_text(&STANDARD.encode("not compressed")).is_none());
+    }
+}
@@ -1,36 +1,111 @@
-use std::{
-    any::Any,
-    sync::mpsc::{Receiver, Sender},
-};
+use std::sync::mpsc::{Receiver, Sender};
 
 use ahash::{HashMap, HashMapExt, HashSet};
 use eframe::{
     self,
-    egui::{self, Button, Context, Ui},
+    egui::{
+        self, text::LayoutJob, Align, Button, Context, CursorIcon, FontSelection, LayerId, Order,
+        RichText, Sense, Ui,
+    },
+    epaint::{Stroke, Vec2},
 };
 
-use super::response::RackResponse;
+use super::{
+    block::{Block, BlockConnection, BlockModule},
+    event::{EventCallback, RackEvent},
+    patch::Patch,
+    response::RackResponse,
+    timeline::Timeline,
+};
 #[cfg(not(target_arch = "wasm32"))]
-use crate::modules::file::File;
+use crate::modules::{
+    file::File, granular::Granular, pads::Pads, reverb::Reverb, sampler::Sampler, tracker::Tracker,
+};
 use crate::{
+    budget::Degradation,
     frame::Frame,
+    i18n::tr,
     instance::{
-        instance::{Instance, InstanceHandle, InstanceResponse, TypedInstanceHandle},
+        instance::{
+            Instance, InstanceHandle, InstanceResponse, TypedInstanceHandle, THUMBNAIL_DECIMATION,
+        },
         port::PortInstance,
     },
     io::{ConnectResult, ConnectResultWarn, Io, PortHandle},
-    module::{Input, Module, ModuleDescriptionDyn, Port, PortValueBoxed},
+    module::{
+        Input, Module, ModuleDescriptionDyn, Port, PortType, PortValueBoxed, VISUAL_DECIMATION,
+    },
     modules::{
-        audio::Audio, filter::Filter, keyboard::Keyboard, noise::Noise, ops::Operation,
-        oscillator::Oscillator, scope::Scope, value::Value,
+        additive::Additive,
+        audio::Audio,
+        autopan::AutoPan,
+        bus::{BusReceive, BusSend},
+        delay::{Delay, PingPong},
+        drums::{Hat, Kick, Snare},
+        envelope::Envelope,
+        filter::Filter,
+        flanger::Flanger,
+        function::Function,
+        generative::Generative,
+        impulse::Impulse,
+        keyboard::Keyboard,
+        lfo::Lfo,
+        looper::Looper,
+        macros::Macro,
+        metronome::Metronome,
+        mixer::Mixer,
+        multiband::MultibandComp,
+        noise::Noise,
+        octaver::Octaver,
+        ops::Operation,
+        oscillator::Oscillator,
+        pianoroll::PianoRoll,
+        pitch::PitchTrack,
+        reference::Reference,
+        scope::Scope,
+        sequencer::Sequencer,
+        shaper::Shaper,
+        shepard::Shepard,
+        speech::Speech,
+        stats::Stats,
+        trig::TrigTools,
+        unison::Unison,
+        value::Value,
+        vinyl::Vinyl,
+        visualizer::Visualizer,
+        width::Width,
+        xy::Xy,
     },
+    settings::{CableVisibility, Settings},
+    transport::Transport,
+    tuning::Tuning,
     types::{Type, TypeDefinitionDyn},
 };
 
+/// Scale of interactive elements in touch mode.
+const TOUCH_SCALE: f32 = 1.6;
+
+/// Amount of frames processed at once when rendering without an output.
+const RENDER_BLOCK: usize = 4096;
+
+/// Distance in points between lines of the background grid.
+pub const GRID_SPACING: f32 = 20.0;
+
+/// Amount of most used modules listed at the top of the add menu.
+const MOST_USED: usize = 5;
+
+/// Narrowest a panel can be made by dragging its edge.
+const MIN_PANEL_WIDTH: f32 = 100.0;
+
+/// Fewest instances a panel gets when tidying, smaller steps of the signal flow share a panel.
+const MIN_TIDY_INSTANCES: usize = 3;
+
 #[derive(Clone)]
 struct Panel {
     instances: Vec<InstanceHandle>,
     width: f32,
+    /// Width set by dragging the edge of the panel, instead of following its contents.
+    fixed: Option<f32>,
 }
 
 impl Panel {
@@ -38,6 +113,7 @@ impl Panel {
         Self {
             instances: Vec::new(),
             width: 0.0,
+            fixed: None,
         }
     }
 
@@ -57,34 +133,196 @@ impl Panel {
         ui: &mut Ui,
         responses: &mut HashMap<InstanceHandle, InstanceResponse>,
         sample_rate: u32,
+        settings: &mut Settings,
     ) {
-        ui.vertical(|ui| {
-            ui.set_min_width(100.0);
-            ui.set_max_width(self.width);
+        if settings.snap {
+            let offset = (ui.cursor().left() - ui.max_rect().left()) % GRID_SPACING;
+            if offset > 0.0 {
+                ui.add_space(GRID_SPACING - offset);
+            }
+        }
+
+        let response = ui.vertical(|ui| {
+            ui.set_min_width(MIN_PANEL_WIDTH);
+            if let Some(fixed) = self.fixed {
+                ui.set_min_width(fixed);
+            } else if settings.snap {
+                ui.set_min_width(self.width);
+            }
+            ui.set_max_width(self.fixed.unwrap_or(self.width));
 
             for handle in self.instances.iter() {
+                let dimmed = !rack.is_highlighted(*handle);
                 let instance = rack.instances.get_mut(handle).unwrap();
                 let mut ctx = ShowContext {
                     io: &mut rack.io,
                     instance: *handle,
-                    sample_rate,
+                    sample_rate: instance.description.process_rate(sample_rate),
+                    transport: rack.transport,
+                    touch: rack.touch,
+                    patching: rack.patching,
+                    settings,
+                };
+                let detached = instance.detached;
+                let title = format!("{} {}", instance.description.title(), handle);
+                let show = |ui: &mut Ui| {
+                    if dimmed {
+                        ui.set_opacity(0.3);
+                    }
+                    responses.insert(*handle, instance.show(&mut ctx, ui));
                 };
-                responses.insert(*handle, instance.show(&mut ctx, ui));
+
+                let revealed = rack.revealed == Some(*handle);
+                if detached {
+                    let mut open = true;
+                    egui::Window::new(title)
+                        .id(egui::Id::new(handle))
+                        .open(&mut open)
+                        .show(ui.ctx(), show);
+                    if !open {
+                        instance.detached = false;
+                    }
+                    if revealed {
+                        ui.ctx()
+                            .move_to_top(LayerId::new(Order::Middle, egui::Id::new(handle)));
+                    }
+                } else {
+                    let rect = ui.scope(show).response.rect;
+                    if revealed {
+                        ui.scroll_to_rect(rect, Some(Align::Center));
+                    }
+                }
+                if revealed {
+                    rack.revealed = None;
+                }
+
+                instance.update_ports(&mut rack.io);
             }
 
-            ui.menu_button("➕ Module", |ui| {
-                for definition in rack.modules.clone().iter() {
-                    if ui.button(&definition.name).clicked() {
-                        rack.add_module(definition, index);
+            ui.menu_button(tr("➕ Module"), |ui| {
+                let mut picked = None;
+
+                let favorites = settings.favorites.iter().map(String::as_str);
+                let common = favorites
+                    .chain(settings.most_used(MOST_USED))
+                    .filter_map(|name| {
+                        rack.modules
+                            .iter()
+                            .find(|definition| definition.matches_name(name))
+                    })
+                    .collect::<Vec<_>>();
+                for &definition in common.iter() {
+                    let star = if settings.is_favorite(&definition.name) {
+                        "★"
+                    } else {
+                        "🕘"
+                    };
+                    if ui
+                        .button(format!("{star} {}", definition.title()))
+                        .clicked()
+                    {
+                        picked = Some(definition.clone());
+                    }
+                }
+                if !common.is_empty() {
+                    ui.separator();
+                }
+
+                let mut modules = rack.modules.iter().collect::<Vec<_>>();
+                modules.sort_by_key(|definition| definition.category);
+                let mut category = None;
+                for definition in modules {
+                    if category != Some(definition.category) {
+                        category = Some(definition.category);
+                        ui.label(
+                            RichText::new(tr(definition.category.as_str()))
+                                .small()
+                                .color(definition.category.color()),
+                        );
+                    }
+                    ui.horizontal(|ui| {
+                        let favorite = settings.is_favorite(&definition.name);
+                        if ui
+                            .small_button(if favorite { "★" } else { "☆" })
+                            .on_hover_text_at_pointer(tr("star to list it at the top"))
+                            .clicked()
+                        {
+                            settings.toggle_favorite(&definition.name);
+                        }
+                        let text = RichText::new(format!("{} ", definition.icon))
+                            .color(definition.category.color());
+                        let mut job = LayoutJob::default();
+                        text.append_to(&mut job, ui.style(), FontSelection::Default, Align::Center);
+                        RichText::new(tr(&definition.name)).append_to(
+                            &mut job,
+                            ui.style(),
+                            FontSelection::Default,
+                            Align::Center,
+                        );
+                        if ui.button(job).clicked() {
+                            picked = Some(definition.clone());
+                        }
+                    });
+                }
+
+                if let Some(definition) = picked {
+                    settings.record_use(&definition.name);
+                    rack.add_module(&definition, index);
+                    ui.close_menu();
+                }
+
+                if !rack.blocks.is_empty() {
+                    ui.separator();
+                }
+
+                for block in rack.blocks.clone().iter() {
+                    if ui.button(format!("🧩 {}", block.name)).clicked() {
+                        rack.insert_block(block, index);
                         ui.close_menu();
                     }
                 }
             });
 
-            rack.panels.get_mut(index).unwrap().width = ui.min_rect().size().x;
+            let width = ui.min_rect().size().x;
+            rack.panels.get_mut(index).unwrap().width =
+                if settings.snap { snap(width) } else { width };
         });
 
-        ui.separator();
+        self.show_edge(rack, index, ui, response.response.rect.height(), settings);
+    }
+
+    /// Shows the separator after the panel, which can be dragged to set the width of the panel
+    /// and double clicked to let it follow the contents again.
+    fn show_edge(
+        &self,
+        rack: &mut Rack,
+        index: usize,
+        ui: &mut Ui,
+        height: f32,
+        settings: &Settings,
+    ) {
+        let (rect, response) =
+            ui.allocate_exact_size(Vec2::new(8.0, height), Sense::click_and_drag());
+        let response = response
+            .on_hover_cursor(CursorIcon::ResizeHorizontal)
+            .on_hover_text_at_pointer(tr("drag to resize the panel, double click to fit it"));
+
+        let stroke = if response.hovered() || response.dragged() {
+            ui.visuals().widgets.hovered.fg_stroke
+        } else {
+            ui.visuals().widgets.noninteractive.bg_stroke
+        };
+        ui.painter().vline(rect.center().x, rect.y_range(), stroke);
+
+        let panel = rack.panels.get_mut(index).unwrap();
+        if response.double_clicked() {
+            panel.fixed = None;
+        } else if response.dragged() {
+            let width = panel.fixed.unwrap_or(self.width) + response.drag_delta().x;
+            panel.fixed = Some(width.max(MIN_PANEL_WIDTH));
+        } else if response.drag_stopped() && settings.snap {
+            panel.fixed = panel.fixed.map(snap);
+        }
     }
 }
 
@@ -95,8 +333,41 @@ pub struct Rack {
     pub modules: Vec<ModuleDescriptionDyn>,
     types: Vec<TypeDefinitionDyn>,
     pub io: Io,
+    pub transport: Transport,
+    /// Master tune and transpose of the notes modules produce.
+    pub tuning: Tuning,
+    /// How far modules should reduce their quality to keep up.
+    pub degradation: Degradation,
+    /// Position within the decimation of visual modules, which are processed at 0.
+    visual_phase: usize,
+    /// Amount used when randomizing all instances.
+    pub mutation: f32,
+    /// Whether hovering a connection shows the values flowing through it.
+    pub probing: bool,
+    /// Which cables are drawn.
+    pub cables: CableVisibility,
+    /// Whether the first output of every instance is recorded for its header thumbnail.
+    pub thumbnails: bool,
+    /// Position within the decimation of the thumbnails, which are recorded at 0.
+    thumbnail_phase: usize,
+    /// Whether the ui is adapted for touch input, with larger hit areas.
+    touch: bool,
+    /// A port picked by clicking it, waiting for a second port to connect to.
+    patching: Option<PortHandle>,
+    selected: Option<InstanceHandle>,
+    /// An instance to scroll to the next time the panels are shown.
+    revealed: Option<InstanceHandle>,
+    /// The selected instance and everything up- and downstream of it.
+    highlighted: Option<HashSet<InstanceHandle>>,
     sender: Sender<Frame>,
     receiver: Receiver<Frame>,
+    /// Mixed output of the last [`Self::process_amount`], kept to reuse its allocation.
+    buffer: Vec<f32>,
+    callbacks: Vec<Box<dyn EventCallback>>,
+    /// Saved blocks that can be inserted from the add-module menu.
+    pub blocks: Vec<Block>,
+    /// Scenes and clips arranged over bars.
+    pub timeline: Timeline,
 }
 
 impl Default for Rack {
@@ -109,8 +380,26 @@ impl Default for Rack {
             modules: Vec::new(),
             types: Vec::new(),
             io: Io::default(),
+            transport: Transport::default(),
+            tuning: Tuning::default(),
+            degradation: Degradation::None,
+            visual_phase: 0,
+            mutation: 0.2,
+            probing: false,
+            cables: CableVisibility::default(),
+            thumbnails: false,
+            thumbnail_phase: 0,
+            touch: false,
+            patching: None,
+            selected: None,
+            revealed: None,
+            highlighted: None,
             sender,
             receiver,
+            buffer: Vec::new(),
+            callbacks: Vec::new(),
+            blocks: Vec::new(),
+            timeline: Timeline::default(),
         };
 
         new.init_type::<f32>();
@@ -118,15 +407,64 @@ impl Default for Rack {
         new.init_type::<Frame>();
 
         new.init_module::<Oscillator>();
+        new.init_module::<Additive>();
+        new.init_module::<Unison>();
         new.init_module::<Audio>();
         new.init_module::<Operation<f32>>();
         new.init_module::<Value<f32>>();
         new.init_module::<Scope>();
+        new.init_module::<Stats>();
+        new.init_module::<Visualizer>();
         new.init_module::<Keyboard>();
+        new.init_module::<Xy>();
         #[cfg(not(target_arch = "wasm32"))]
         new.init_module::<File>();
         new.init_module::<Filter>();
+        new.init_module::<Delay>();
+        new.init_module::<PingPong>();
+        new.init_module::<Flanger>();
+        new.init_module::<AutoPan>();
+        new.init_module::<Macro>();
+        new.init_module::<Octaver>();
+        new.init_module::<Vinyl>();
+        #[cfg(not(target_arch = "wasm32"))]
+        new.init_module::<Reverb>();
+        new.init_module::<MultibandComp>();
         new.init_module::<Noise>();
+        new.init_module::<Lfo>();
+        new.init_module::<Reference>();
+        new.init_module::<Shepard>();
+        new.init_module::<Width>();
+        new.init_module::<Mixer>();
+        new.init_module::<TrigTools>();
+        new.init_module::<Function>();
+        new.init_module::<Shaper>();
+        new.init_module::<Envelope>();
+        new.init_module::<Kick>();
+        new.init_module::<Snare>();
+        new.init_module::<Hat>();
+        new.init_module::<Impulse>();
+        #[cfg(not(target_arch = "wasm32"))]
+        new.init_module::<Pads>();
+        #[cfg(not(target_arch = "wasm32"))]
+        new.init_module::<Granular>();
+        #[cfg(not(target_arch = "wasm32"))]
+        new.init_module::<Sampler>();
+        #[cfg(not(target_arch = "wasm32"))]
+        new.init_module::<Tracker>();
+        new.init_module::<PianoRoll>();
+        new.init_module::<Sequencer>();
+        new.init_module::<Generative>();
+        new.init_module::<Speech>();
+        new.init_module::<Metronome>();
+        new.init_module::<Looper>();
+        new.init_module::<PitchTrack>();
+        new.init_module::<BusSend<f32>>();
+        new.init_module::<BusReceive<f32>>();
+        new.init_module::<BusSend<bool>>();
+        new.init_module::<BusReceive<bool>>();
+        new.init_module::<BusSend<Frame>>();
+        new.init_module::<BusReceive<Frame>>();
 
         new
     }
@@ -165,8 +503,10 @@ impl Rack {
         }
 
         let handle = instance.handle;
+        self.io.set_latency(handle, instance.description.latency);
         self.instances.insert(handle, instance);
         self.panels.get_mut(panel).unwrap().add_instance(handle);
+        self.emit(RackEvent::ModuleAdded(handle));
         handle
     }
 
@@ -174,9 +514,347 @@ impl Rack {
         self.panels.push(Panel::new())
     }
 
+    /// Adds an instance of a module to a panel.
+    pub fn add_module_to<T: Module>(&mut self, panel: usize) -> TypedInstanceHandle<T> {
+        self.add_module(&T::describe().into_dyn(), panel).as_typed()
+    }
+
+    /// Adds a new panel with the example patch of a module.
+    pub fn insert_example(&mut self, description: &ModuleDescriptionDyn) {
+        if let Some(example) = description.example {
+            self.add_panel();
+            example(self, self.panels.len() - 1);
+        }
+    }
+
+    /// Saves the selected instance and everything connected to it as a block.
+    pub fn save_block(&mut self, name: &str) {
+        let Some(group) = self.selected_group() else {
+            return;
+        };
+
+        let handles = self
+            .panels
+            .iter()
+            .flat_map(|panel| panel.instances.iter())
+            .filter(|handle| group.contains(handle))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let block = self.capture_block(name, &handles);
+        self.blocks.push(block)
+    }
+
+    /// Adds the modules of a block to a panel and restores their parameters and connections.
+    pub fn insert_block(&mut self, block: &Block, panel: usize) {
+        self.insert_block_with(block, |_| panel);
+    }
+
+    /// Captures the whole rack as a patch to share.
+    pub fn export_patch(&mut self) -> Patch {
+        let handles = self
+            .panels
+            .iter()
+            .flat_map(|panel| panel.instances.iter())
+            .copied()
+            .collect::<Vec<_>>();
+
+        let panels = self
+            .panels
+            .iter()
+            .enumerate()
+            .flat_map(|(i, panel)| panel.instances.iter().map(move |_| i))
+            .collect();
+
+        Patch {
+            panels,
+            widths: self.panels.iter().map(|panel| panel.fixed).collect(),
+            block: self.capture_block("patch", &handles),
+            embedded: Vec::new(),
+            tuning: self.tuning,
+            timeline: Some(
+                self.timeline
+                    .map(|handle| handles.iter().position(|other| other == handle)),
+            ),
+        }
+    }
+
+    /// Lays the instances out in panels by processing order, following the steps of the signal
+    /// flow with small steps merged into one panel, and unconnected instances in a last panel.
+    /// Panels keep the widest width set on the panels their instances came from.
+    pub fn arrange_tidy(&mut self) {
+        let current = self
+            .panels
+            .iter()
+            .flat_map(|panel| panel.instances.iter().map(|&handle| (handle, panel.fixed)))
+            .collect::<Vec<_>>();
+        let order = self.io.processing_order();
+
+        let panel = |instances: Vec<InstanceHandle>| Panel {
+            fixed: current
+                .iter()
+                .filter(|(handle, _)| instances.contains(handle))
+                .filter_map(|(_, fixed)| *fixed)
+                .reduce(f32::max),
+            instances,
+            ..Panel::new()
+        };
+
+        let mut panels = Vec::new();
+        let mut instances = Vec::new();
+        for step in order {
+            // keep the previous order within a step
+            instances.extend(
+                current
+                    .iter()
+                    .map(|(handle, _)| *handle)
+                    .filter(|handle| step.contains(handle)),
+            );
+            if instances.len() >= MIN_TIDY_INSTANCES {
+                panels.push(panel(std::mem::take(&mut instances)));
+            }
+        }
+        // the last steps join the panel before them when too small on their own
+        if !instances.is_empty() {
+            if let Some(last) = panels.pop() {
+                instances.splice(0..0, last.instances);
+            }
+            panels.push(panel(instances));
+        }
+
+        let unconnected = current
+            .iter()
+            .map(|(handle, _)| *handle)
+            .filter(|handle| !order.iter().flatten().any(|other| other == handle))
+            .collect::<Vec<_>>();
+        if !unconnected.is_empty() || panels.is_empty() {
+            panels.push(panel(unconnected));
+        }
+
+        self.panels = panels;
+    }
+
+    /// Removes all instances and panels.
+    pub fn clear(&mut self) {
+        for handle in self.instances.keys().copied().collect::<Vec<_>>() {
+            self.remove_instance(handle)
+        }
+        self.panels.clear();
+        self.timeline = Timeline::default();
+    }
+
+    /// Replaces everything in the rack with a patch.
+    pub fn import_patch(&mut self, patch: &Patch) {
+        self.clear();
+
+        let panel_count = patch.panels.iter().max().map_or(1, |&max| max + 1);
+        for _ in 0..panel_count {
+            self.add_panel()
+        }
+        for (panel, width) in self.panels.iter_mut().zip(patch.widths.iter()) {
+            panel.fixed = *width;
+        }
+        self.tuning = patch.tuning;
+
+        let handles = self.insert_block_with(&patch.block, |i| {
+            patch.panels.get(i).copied().unwrap_or_default()
+        });
+
+        if let Some(timeline) = &patch.timeline {
+            self.timeline = timeline.map(|&index| handles.get(index).copied().flatten());
+        }
+    }
+
+    /// Points every module depending on the file at `from` to the file at `to`.
+    pub fn relink_asset(&mut self, from: &str, to: &str) {
+        for instance in self.instances.values_mut() {
+            let assets = instance.module.assets();
+            if assets.iter().any(|path| path == from) {
+                let assets = assets
+                    .into_iter()
+                    .map(|path| if path == from { to.to_string() } else { path })
+                    .collect::<Vec<_>>();
+                instance.module.load_assets(&assets)
+            }
+        }
+    }
+
+    fn capture_block(&mut self, name: &str, handles: &[InstanceHandle]) -> Block {
+        let modules = handles
+            .iter()
+            .map(|handle| {
+                let instance = self.instances.get_mut(handle).unwrap();
+                BlockModule {
+                    name: instance.description.name.clone(),
+                    parameters: instance
+                        .module
+                        .parameters()
+                        .into_iter()
+                        .map(|parameter| (parameter.name.to_string(), *parameter.value))
+                        .collect(),
+                    inputs: instance
+                        .inputs
+                        .values()
+                        .filter(|port| {
+                            port.description.normalled.is_none()
+                                && self.io.input_connection(port.handle).is_none()
+                        })
+                        .filter_map(|port| {
+                            let value = self.io.get_input_dyn(port.handle)?;
+                            let any = (*value).as_any();
+                            let value = any.downcast_ref::<f32>().copied().or_else(|| {
+                                any.downcast_ref::<bool>().map(|&on| on as u8 as f32)
+                            })?;
+                            Some((port.description.name.to_string(), value))
+                        })
+                        .collect(),
+                    asset: None,
+                    assets: instance.module.assets(),
+                    state: instance.module.state(),
+                    tags: instance.tags.clone(),
+                    handle: Some(*handle),
+                }
+            })
+            .collect();
+
+        let connections = self
+            .connections()
+            .into_iter()
+            .filter_map(|(from, to)| {
+                Some(BlockConnection {
+                    from: handles.iter().position(|&handle| handle == from.instance)?,
+                    output: self.get_port(from)?.description.name.to_string(),
+                    to: handles.iter().position(|&handle| handle == to.instance)?,
+                    input: self.get_port(to)?.description.name.to_string(),
+                    gain: self.io.gain(from, to),
+                })
+            })
+            .collect();
+
+        Block {
+            name: name.to_string(),
+            modules,
+            connections,
+        }
+    }
+
+    /// Adds the modules of a block to the panels given by `panel` for the index of each module.
+    ///
+    /// Returns the handle of each module, `None` for modules that don't exist.
+    fn insert_block_with(
+        &mut self,
+        block: &Block,
+        panel: impl Fn(usize) -> usize,
+    ) -> Vec<Option<InstanceHandle>> {
+        let handles = block
+            .modules
+            .iter()
+            .enumerate()
+            .map(|(i, module)| {
+                let description = self
+                    .modules
+                    .iter()
+                    .find(|description| description.matches_name(&module.name))?
+                    .clone();
+                let handle = self.add_module(&description, panel(i));
+
+                let instance = self.instances.get_mut(&handle).unwrap();
+                instance.tags.clone_from(&module.tags);
+                // before the parameters, as it can change which parameters there are
+                if let Some(state) = &module.state {
+                    instance.module.load_state(state);
+                    instance.update_ports(&mut self.io);
+                }
+                for parameter in instance.module.parameters() {
+                    if let Some((_, value)) = module
+                        .parameters
+                        .iter()
+                        .find(|(name, _)| name == parameter.name)
+                    {
+                        *parameter.value = *value;
+                    }
+                }
+                let assets = module.asset_paths().cloned().collect::<Vec<_>>();
+                if !assets.is_empty() {
+                    instance.module.load_assets(&assets)
+                }
+                for (name, value) in module.inputs.iter() {
+                    let Some(port) = instance
+                        .inputs
+                        .values()
+                        .find(|port| port.description.name == name)
+                    else {
+                        continue;
+                    };
+                    let value: Box<dyn PortValueBoxed> = match port.description.type_name {
+                        "f32" => Box::new(*value),
+                        "bool" => Box::new(*value != 0.0),
+                        _ => continue,
+                    };
+                    self.io.set_input_dyn(port.handle, value);
+                }
+
+                Some(handle)
+            })
+            .collect::<Vec<_>>();
+
+        for connection in block.connections.iter() {
+            let (Some(from), Some(to)) = (
+                handles
+                    .get(connection.from)
+                    .copied()
+                    .flatten()
+                    .and_then(|handle| {
+                        self.find_port(handle, PortType::Output, &connection.output)
+                    }),
+                handles
+                    .get(connection.to)
+                    .copied()
+                    .flatten()
+                    .and_then(|handle| self.find_port(handle, PortType::Input, &connection.input)),
+            ) else {
+                continue;
+            };
+
+            if self.connect(from, to).is_ok() {
+                self.io.set_gain(from, to, connection.gain)
+            }
+        }
+
+        let map = |old: InstanceHandle| {
+            let index = block
+                .modules
+                .iter()
+                .position(|module| module.handle == Some(old))?;
+            handles.get(index).copied().flatten()
+        };
+        for handle in handles.iter().flatten() {
+            self.instances
+                .get_mut(handle)
+                .unwrap()
+                .module
+                .remap_instances(&map);
+        }
+
+        handles
+    }
+
+    /// Finds a port of an instance by its name.
+    pub(crate) fn find_port(
+        &self,
+        instance: InstanceHandle,
+        port_type: PortType,
+        name: &str,
+    ) -> Option<PortHandle> {
+        self.get_instance(instance)?
+            .port_descriptions()
+            .find(|description| description.name == name && description.port_type == port_type)
+            .map(|description| PortHandle::new(description.id, instance))
+    }
+
     #[allow(unused)]
     pub fn add_module_typed<T: Module>(&mut self) -> TypedInstanceHandle<T> {
-        if self.panels.get(0).is_none() {
+        if self.panels.is_empty() {
             self.panels.push(Panel::new())
         }
 
@@ -184,6 +862,10 @@ impl Rack {
     }
 
     pub fn remove_instance(&mut self, handle: InstanceHandle) {
+        if self.selected == Some(handle) {
+            self.selected = None;
+        }
+
         self.io.remove_instance(handle);
 
         for panel in self.panels.iter_mut() {
@@ -191,6 +873,124 @@ impl Rack {
         }
 
         self.instances.remove(&handle);
+        self.emit(RackEvent::ModuleRemoved(handle));
+    }
+
+    /// Registers a callback that is called for every change to the rack.
+    pub fn on_event(&mut self, callback: impl EventCallback) {
+        self.callbacks.push(Box::new(callback))
+    }
+
+    /// Passes pending connection changes and then `event` to the callbacks.
+    fn emit(&mut self, event: RackEvent) {
+        self.flush_events();
+        for callback in self.callbacks.iter_mut() {
+            callback(&event)
+        }
+    }
+
+    /// Passes connection changes made through [`Io`] to the callbacks.
+    fn flush_events(&mut self) {
+        for event in self.io.take_events() {
+            for callback in self.callbacks.iter_mut() {
+                callback(&event)
+            }
+        }
+    }
+
+    /// Sets the parameters other instances are targeted to by modules like [`Macro`].
+    fn apply_parameter_targets(&mut self) {
+        let targets = self
+            .instances
+            .values()
+            .flat_map(|instance| instance.module.parameter_targets())
+            .collect::<Vec<_>>();
+
+        for target in targets {
+            let Some(instance) = self.instances.get_mut(&target.instance) else {
+                continue;
+            };
+            for parameter in instance.module.parameters() {
+                if parameter.name == target.parameter {
+                    *parameter.value = target
+                        .value
+                        .clamp(*parameter.range.start(), *parameter.range.end());
+                }
+            }
+            instance.hold_locked();
+        }
+    }
+
+    /// Maps the first parameter moved by hand to the knob of a [`Macro`] that is learning.
+    fn learn_macros(&mut self, changes: &[RackEvent]) {
+        let Some(handle) = self.instances.values().find_map(|instance| {
+            let learning = instance.get_module::<Macro>()?.learning.is_some();
+            learning.then_some(instance.handle)
+        }) else {
+            return;
+        };
+
+        let targeted = self
+            .instances
+            .values()
+            .flat_map(|instance| instance.module.parameter_targets())
+            .collect::<Vec<_>>();
+        let change = changes.iter().find_map(|event| match *event {
+            RackEvent::ParameterChanged { instance, name, .. }
+                if instance != handle
+                    && !targeted
+                        .iter()
+                        .any(|target| target.instance == instance && target.parameter == name) =>
+            {
+                Some((instance, name))
+            }
+            _ => None,
+        });
+        let Some((instance, name)) = change else {
+            return;
+        };
+
+        let Some(range) = self.instances.get_mut(&instance).and_then(|instance| {
+            instance
+                .module
+                .parameters()
+                .into_iter()
+                .find(|parameter| parameter.name == name)
+                .map(|parameter| parameter.range)
+        }) else {
+            return;
+        };
+        if let Some(module) = self
+            .instances
+            .get_mut(&handle)
+            .and_then(|instance| instance.get_module_mut::<Macro>())
+        {
+            module.learn(instance, name, range);
+        }
+    }
+
+    /// Emits events for parameters edited in the ui since the last call.
+    fn emit_parameter_changes(&mut self) {
+        let changes = self
+            .instances
+            .values_mut()
+            .flat_map(|instance| {
+                let handle = instance.handle;
+                instance
+                    .take_parameter_changes()
+                    .into_iter()
+                    .map(move |(name, value)| RackEvent::ParameterChanged {
+                        instance: handle,
+                        name,
+                        value,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        self.learn_macros(&changes);
+        for event in changes {
+            self.emit(event)
+        }
     }
 
     pub fn connect(&mut self, from: PortHandle, to: PortHandle) -> Result<(), &'static str> {
@@ -202,20 +1002,67 @@ impl Rack {
                     self.disconnect(from, to)
                 }
 
-                self.io.connect(from, to);
+                let result = self.io.connect(from, to);
+                self.flush_events();
 
-                Ok(())
+                match result {
+                    ConnectResult::Err(err) => Err(err.as_str()),
+                    _ => Ok(()),
+                }
+            }
+            ConnectResult::Err(err) => {
+                tracing::warn!(
+                    "could not connect {} to {}: {}",
+                    from.instance,
+                    to.instance,
+                    err.as_str()
+                );
+                Err(err.as_str())
             }
-            ConnectResult::Err(err) => return Err(err.as_str()),
         }
     }
 
+    /// Connects output `O` of one instance to input `I` of another.
+    pub fn connect_typed<O: Port, I: Input>(
+        &mut self,
+        from: impl Into<InstanceHandle>,
+        to: impl Into<InstanceHandle>,
+    ) -> Result<(), &'static str> {
+        self.connect(PortHandle::new(O::id(), from), PortHandle::new(I::id(), to))
+    }
+
     pub fn can_connect(&self, from: PortHandle, to: PortHandle) -> ConnectResult {
         self.io.can_connect(from, to)
     }
 
     pub fn disconnect(&mut self, from: PortHandle, to: PortHandle) {
         self.io.disconnect(from, to);
+        self.flush_events();
+    }
+
+    /// Mutates the unlocked parameters of every instance.
+    pub fn randomize(&mut self) {
+        for instance in self.instances.values_mut() {
+            instance.randomize(self.mutation);
+        }
+    }
+
+    /// All connections as `(output, input)` pairs.
+    pub fn connections(&self) -> Vec<(PortHandle, PortHandle)> {
+        self.io
+            .connections()
+            .iter()
+            .flat_map(|(&from, connections)| connections.iter().map(move |&to| (from, to)))
+            .collect()
+    }
+
+    /// Handles to all instances of module `T`.
+    pub fn find_instances_of<T: Module>(&self) -> Vec<TypedInstanceHandle<T>> {
+        self.instances
+            .values()
+            .filter(|instance| instance.get_module::<T>().is_some())
+            .map(|instance| instance.handle.as_typed())
+            .collect()
     }
 
     pub fn get_instance(&self, handle: InstanceHandle) -> Option<&Instance> {
@@ -250,49 +1097,227 @@ impl Rack {
         instance.get_port_mut(handle)
     }
 
-    pub fn show(&mut self, ctx: &Context, sample_rate: u32) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            egui::ScrollArea::both()
-                .auto_shrink([false; 2])
-                .show(ui, |ui| {
-                    let mut responses = HashMap::new();
+    pub fn selected(&self) -> Option<InstanceHandle> {
+        self.selected
+    }
 
-                    ui.horizontal_centered(|ui| {
-                        for (i, panel) in self.panels.clone().into_iter().enumerate() {
-                            panel.show(self, i, ui, &mut responses, sample_rate);
-                        }
+    /// The selected instance and everything up- and downstream of it.
+    fn selected_group(&self) -> Option<HashSet<InstanceHandle>> {
+        self.selected.map(|selected| {
+            let mut group = self.io.upstream_instances(selected);
+            group.extend(self.io.downstream_instances(selected));
+            group.insert(selected);
+            group
+        })
+    }
 
-                        ui.vertical(|ui| {
-                            if ui.add(Button::new("➕ Panel").wrap(false)).clicked() {
-                                self.add_panel()
-                            }
-                        });
-                    });
+    /// Selects an instance and scrolls to it the next time the panels are shown.
+    pub fn reveal(&mut self, handle: InstanceHandle) {
+        self.selected = Some(handle);
+        self.revealed = Some(handle);
+    }
 
-                    let response = RackResponse::new(responses);
+    /// Instances whose module name, handle or one of the tags contains `query`, ignoring case.
+    pub fn search(&self, query: &str) -> Vec<InstanceHandle> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
 
-                    response.show_connections(self, ui);
-                    response.show_dragged(self, ui);
-                    response.process(self);
-                });
+        let matches = |text: &str| text.to_lowercase().contains(&query);
+        self.panels
+            .iter()
+            .flat_map(|panel| panel.instances.iter())
+            .filter(|handle| {
+                let instance = &self.instances[handle];
+                matches(&instance.description.name)
+                    || matches(tr(&instance.description.name))
+                    || matches(&handle.to_string())
+                    || instance.tags.iter().any(|tag| matches(tag))
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Selects an instance to highlight its signal flow, or deselects it when already selected.
+    pub fn toggle_selected(&mut self, handle: InstanceHandle) {
+        if self.selected == Some(handle) {
+            self.selected = None;
+        } else {
+            self.selected = Some(handle);
+        }
+    }
+
+    /// Whether an instance is part of the highlighted signal flow, always true without a selection.
+    pub fn is_highlighted(&self, handle: InstanceHandle) -> bool {
+        self.highlighted
+            .as_ref()
+            .is_none_or(|highlighted| highlighted.contains(&handle))
+    }
+
+    pub fn touch(&self) -> bool {
+        self.touch
+    }
+
+    /// Enables or disables touch mode, scaling up the interactive elements.
+    pub fn set_touch(&mut self, ctx: &Context, touch: bool) {
+        self.touch = touch;
+        ctx.style_mut(|style| {
+            let default = egui::style::Spacing::default();
+            let scale = if touch { TOUCH_SCALE } else { 1.0 };
+            style.spacing.interact_size = default.interact_size * scale;
+            style.spacing.button_padding = default.button_padding * scale;
+            style.spacing.icon_width = default.icon_width * scale;
         });
     }
 
-    pub fn process_amount(&mut self, sample_rate: u32, amount: usize) -> Vec<Vec<Frame>> {
+    pub fn patching(&self) -> Option<PortHandle> {
+        self.patching
+    }
+
+    /// Picks a port for patching, connecting it when another port was already picked.
+    pub fn patch(&mut self, handle: PortHandle) {
+        let Some(pending) = self.patching.take() else {
+            self.patching = Some(handle);
+            return;
+        };
+
+        let port_type = |handle| {
+            self.get_port(handle)
+                .map(|port: &PortInstance| port.description.port_type)
+        };
+
+        match (port_type(pending), port_type(handle)) {
+            (Some(PortType::Output), Some(PortType::Input)) => {
+                self.connect(pending, handle).ok();
+            }
+            (Some(PortType::Input), Some(PortType::Output)) => {
+                self.connect(handle, pending).ok();
+            }
+            _ if pending != handle => self.patching = Some(handle),
+            _ => {}
+        }
+    }
+
+    /// Shows the panels, `settings` is updated with the modules picked from the add menu.
+    pub fn show(&mut self, ctx: &Context, sample_rate: u32, settings: &mut Settings) {
+        egui::CentralPanel::default().show(ctx, |ui| self.show_embedded(ui, sample_rate, settings));
+    }
+
+    /// Shows the panels filling `ui`, so the rack can be part of the interface of another app.
+    ///
+    /// Modules only run when processed, by passing the amount of samples an [`crate::output::Output`]
+    /// can take to [`Rack::process_amount`] and pushing the result to it.
+    pub fn show_embedded(&mut self, ui: &mut Ui, sample_rate: u32, settings: &mut Settings) {
+        let ctx = ui.ctx().clone();
+        if ctx.input(|input| input.key_pressed(egui::Key::Escape)) {
+            self.patching = None;
+        }
+
+        if let Some(touch) = ctx.input(|input| input.multi_touch()) {
+            if touch.zoom_delta != 1.0 {
+                ctx.set_zoom_factor(ctx.zoom_factor() * touch.zoom_delta);
+            }
+        }
+
+        self.highlighted = self.selected_group();
+
+        egui::ScrollArea::both()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                if settings.grid {
+                    draw_grid(ui);
+                }
+
+                let mut responses = HashMap::new();
+
+                ui.horizontal_centered(|ui| {
+                    for (i, panel) in self.panels.clone().into_iter().enumerate() {
+                        panel.show(self, i, ui, &mut responses, sample_rate, settings);
+                    }
+
+                    ui.vertical(|ui| {
+                        if ui.add(Button::new(tr("➕ Panel")).wrap(false)).clicked() {
+                            self.add_panel()
+                        }
+                    });
+                });
+
+                let response = RackResponse::new(responses);
+
+                response.show_connections(self, ui, sample_rate, settings);
+                response.show_dragged(self, ui, settings);
+                response.show_patching(self, ui, settings);
+                response.process(self);
+            });
+
+        self.emit_parameter_changes();
+        self.flush_events();
+    }
+
+    /// Processes `amount` frames and returns the sinks mixed together as interleaved stereo samples.
+    ///
+    /// The returned buffer is owned by the rack and reused by the next call.
+    pub fn process_amount(&mut self, sample_rate: u32, amount: usize) -> &mut [f32] {
         puffin::profile_function!();
 
-        let mut frames = Vec::with_capacity(amount);
+        self.buffer.clear();
+        self.buffer.reserve(amount * 2);
+        self.apply_parameter_targets();
         let order = self.io.processing_order().clone();
 
+        // only what ends up in a sink matters, the rest is skipped
+        let audible = self.io.upstream_of(
+            self.instances
+                .values()
+                .filter(|instance| {
+                    instance.description.sink
+                        || instance.description.visual
+                        || instance.always_process
+                })
+                .map(|instance| instance.handle),
+        );
+        for instance in self.instances.values_mut() {
+            instance.processed = audible.contains(&instance.handle);
+        }
+
+        // instances without connections aren't part of the order, they can go last
+        let mut order = order;
+        let ordered = order.iter().flatten().copied().collect::<HashSet<_>>();
+        order.push(
+            audible
+                .iter()
+                .filter(|handle| !ordered.contains(handle))
+                .copied()
+                .collect(),
+        );
+
+        // visual modules only draw their inputs, so they can run at a lower rate after the rest
+        let (visual, audio): (Vec<_>, Vec<_>) = order
+            .iter()
+            .flatten()
+            .filter(|handle| audible.contains(handle))
+            .partition(|handle| self.instances[handle].description.visual);
+
         //to minimize hashmap lookups pointers are used
         //SAFETY: contents of the hashmap should not change and the every handle should be unique.
-        let pointers = {
-            order
-                .iter()
-                .flatten()
-                .map(|handle| self.instances.get_mut(handle).unwrap() as *mut _)
+        let mut pointers = |handles: Vec<&InstanceHandle>| {
+            handles
+                .into_iter()
+                .map(|handle| self.instances.get_mut(handle).unwrap() as *mut Instance)
                 .collect::<Vec<_>>()
         };
+        let audio = pointers(audio);
+        let visual = pointers(visual);
+
+        for pointer in audio.iter() {
+            let instance: &mut Instance = unsafe { &mut **pointer };
+            instance.prepare_smoothing(sample_rate);
+        }
+        for pointer in visual.iter() {
+            let instance: &mut Instance = unsafe { &mut **pointer };
+            instance.prepare_smoothing(sample_rate / VISUAL_DECIMATION as u32);
+        }
 
         {
             puffin::profile_scope!("frames");
@@ -301,21 +1326,127 @@ impl Rack {
                 sample_rate,
                 handle: InstanceHandle::new(),
                 io: &mut self.io,
+                transport: self.transport,
+                tuning: self.tuning,
+                degradation: self.degradation,
             };
 
             for _ in 0..amount {
-                for pointer in pointers.iter() {
+                if let Some(change) = self.timeline.update(&self.transport) {
+                    if let Some(scene) = change.scene {
+                        scene.apply(&mut self.instances);
+                    }
+                    for (handle, playing) in change.clips {
+                        if let Some(instance) = self.instances.get_mut(&handle) {
+                            instance.module.set_clip_playing(playing);
+                        }
+                    }
+                }
+                ctx.transport = self.transport;
+
+                for pointer in audio.iter() {
                     let instance: &mut Instance = unsafe { &mut **pointer };
                     ctx.handle = instance.handle;
 
-                    instance.module.process(&mut ctx)
+                    instance.guarded(|instance| {
+                        instance.smooth_parameters();
+                        instance.apply_normalled(ctx.io);
+                        instance.module.process(&mut ctx)
+                    });
+                }
+
+                if self.visual_phase == 0 {
+                    ctx.sample_rate = sample_rate / VISUAL_DECIMATION as u32;
+                    for pointer in visual.iter() {
+                        let instance: &mut Instance = unsafe { &mut **pointer };
+                        ctx.handle = instance.handle;
+
+                        instance.guarded(|instance| {
+                            instance.smooth_parameters();
+                            instance.apply_normalled(ctx.io);
+                            instance.module.process(&mut ctx)
+                        });
+                    }
+                    ctx.sample_rate = sample_rate;
+                }
+                self.visual_phase = (self.visual_phase + 1) % VISUAL_DECIMATION;
+
+                if self.thumbnails && self.thumbnail_phase == 0 {
+                    for pointer in audio.iter() {
+                        let instance: &mut Instance = unsafe { &mut **pointer };
+                        instance.record_thumbnail(ctx.io);
+                    }
+                }
+                self.thumbnail_phase = (self.thumbnail_phase + 1) % THUMBNAIL_DECIMATION;
+
+                self.transport.advance(sample_rate);
+
+                let mut mixed = Frame::ZERO;
+                for frame in self.receiver.try_iter() {
+                    mixed += frame;
                 }
 
-                frames.push(self.receiver.try_iter().collect::<Vec<_>>());
+                let (a, b) = mixed.as_f32_tuple();
+                self.buffer.push(a);
+                self.buffer.push(b);
             }
         }
 
-        frames
+        &mut self.buffer
+    }
+
+    /// Plays the timeline from the start without an output, returning interleaved stereo samples.
+    pub fn render_timeline(&mut self, sample_rate: u32) -> Vec<f32> {
+        let (transport, follow) = (self.transport, self.timeline.follow);
+        self.transport.stop();
+        self.transport.playing = true;
+        self.timeline.follow = true;
+
+        let beats = (self.timeline.bars() * self.transport.beats_per_bar as usize) as f32;
+        let mut remaining = (beats * self.transport.samples_per_beat(sample_rate)) as usize;
+        let mut rendered = Vec::with_capacity(remaining * 2);
+        while remaining > 0 {
+            let amount = remaining.min(RENDER_BLOCK);
+            rendered.extend_from_slice(self.process_amount(sample_rate, amount));
+            remaining -= amount;
+        }
+
+        self.transport = transport;
+        self.timeline.follow = follow;
+        rendered
+    }
+}
+
+/// Rounds a length up to a multiple of the grid spacing.
+fn snap(value: f32) -> f32 {
+    (value / GRID_SPACING).ceil() * GRID_SPACING
+}
+
+/// Draws grid lines behind the rack, anchored to its content so they scroll along.
+fn draw_grid(ui: &Ui) {
+    let origin = ui.min_rect().min;
+    let clip = ui.clip_rect();
+    let stroke = Stroke::new(
+        1.0f32,
+        ui.visuals()
+            .widgets
+            .noninteractive
+            .bg_stroke
+            .color
+            .gamma_multiply(0.5),
+    );
+    let painter = ui.painter();
+
+    let mut x = origin.x + ((clip.left() - origin.x) / GRID_SPACING).ceil() * GRID_SPACING;
+    while x < clip.right() {
+        painter.vline(x, clip.y_range(), stroke);
+        x += GRID_SPACING;
+    }
+
+    let mut y = origin.y + ((clip.top() - origin.y) / GRID_SPACING).ceil() * GRID_SPACING;
+    while y < clip.bottom() {
+        painter.hline(clip.x_range(), y, stroke);
+        y += GRID_SPACING;
     }
 }
 
@@ -323,6 +1454,9 @@ pub struct ProcessContext<'a> {
     sample_rate: u32,
     handle: InstanceHandle,
     io: &'a mut Io,
+    transport: Transport,
+    tuning: Tuning,
+    degradation: Degradation,
 }
 
 impl<'a> ProcessContext<'a> {
@@ -330,25 +1464,75 @@ impl<'a> ProcessContext<'a> {
         self.sample_rate
     }
 
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
+    /// How notes are turned into frequencies.
+    pub fn tuning(&self) -> &Tuning {
+        &self.tuning
+    }
+
+    /// How far to reduce quality to keep up, modules can do optional work less often.
+    pub fn degradation(&self) -> Degradation {
+        self.degradation
+    }
+
     pub fn get_input<I: Input>(&self) -> I::Type {
         self.io.get_input::<I>(self.handle)
     }
 
+    /// Whether a cable is connected to the input, for inputs that change behavior when used.
+    pub fn is_connected<I: Input>(&self) -> bool {
+        self.io
+            .input_connection(PortHandle::new(I::id(), self.handle))
+            .is_some()
+    }
+
+    /// Gets the value of one of several inputs of type `I`, added by [`Module::dynamic_ports`].
+    pub fn get_input_indexed<I: Input>(&self, index: usize) -> I::Type {
+        self.io.get_input_indexed::<I>(self.handle, index)
+    }
+
     pub fn set_output<P: Port>(&mut self, value: P::Type) {
         self.io.set_output::<P>(self.handle, value)
     }
+
+    /// Broadcasts a value to the bus with the given name.
+    pub fn send<T: PortValueBoxed>(&mut self, bus: &str, value: T) {
+        self.io.send_bus(bus, self.handle, Box::new(value))
+    }
+
+    /// Gets the value of a bus, `None` if nothing of this type was sent to it.
+    pub fn receive<T: PortValueBoxed + Clone>(&self, bus: &str) -> Option<T> {
+        let any = self.io.get_bus(bus)?.as_any();
+        any.downcast_ref::<T>().cloned()
+    }
+
+    /// Stops broadcasting to a bus this instance sent to.
+    pub fn remove_bus(&mut self, bus: &str) {
+        self.io.remove_bus(bus, self.handle)
+    }
 }
 
 pub struct ShowContext<'a> {
     io: &'a mut Io,
     pub instance: InstanceHandle,
     pub sample_rate: u32,
+    pub transport: Transport,
+    pub touch: bool,
+    pub patching: Option<PortHandle>,
+    pub settings: &'a Settings,
 }
 
 impl<'a> ShowContext<'a> {
+    pub fn bus_names(&self) -> Vec<String> {
+        self.io.bus_names()
+    }
+
     fn try_get_input<I: Input>(&self, handle: PortHandle) -> Option<I::Type> {
         let boxed = self.io.get_input_dyn(handle)?;
-        let any = &*boxed as &dyn Any;
+        let any = (*boxed).as_any();
         Some(any.downcast_ref::<I::Type>()?.clone())
     }
 
@@ -364,6 +1548,17 @@ impl<'a> ShowContext<'a> {
         }
     }
 
+    pub fn get_output_boxed(&self, handle: PortHandle) -> Option<Box<dyn PortValueBoxed>> {
+        self.io.get_output_dyn(handle)
+    }
+
+    /// Gets the last value the module wrote to one of its output ports.
+    pub fn get_output<P: Port>(&self, handle: PortHandle) -> Option<P::Type> {
+        let boxed = self.io.get_output_dyn(handle)?;
+        let any = (*boxed).as_any();
+        Some(any.downcast_ref::<P::Type>()?.clone())
+    }
+
     pub fn set_input<P: Port>(&mut self, handle: PortHandle, value: P::Type) {
         self.io.set_input_dyn(handle, Box::new(value))
     }
@@ -1,18 +1,27 @@
 use ahash::HashMap;
 use eframe::{
-    egui::{self, Id, LayerId, Order, Ui},
-    epaint::{Color32, Pos2, QuadraticBezierShape, Rgba, Shape, Stroke},
+    egui::{self, Align2, FontId, Id, LayerId, Order, RichText, Sense, Ui},
+    epaint::{Color32, Hsva, Pos2, Rect, Shape, Stroke, Vec2},
 };
+use egui_plot::{Line, Plot};
 
 use super::rack::Rack;
 use crate::{
+    i18n::tr,
     instance::{
         instance::{InstanceHandle, InstanceResponse},
         port::PortResponse,
     },
-    io::ConnectResult,
+    io::{ConnectResult, PortHandle, Probe},
+    module::PortValueBoxed,
+    settings::{CableStyle, CableVisibility, Pattern, Settings},
 };
 
+/// Maximum distance in points from a rope to probe it.
+const PROBE_DISTANCE: f32 = 6.0;
+const PROBE_POINTS: usize = 500;
+const ROPE_SEGMENTS: usize = 32;
+
 pub struct RackResponse {
     responses: HashMap<InstanceHandle, InstanceResponse>,
 }
@@ -41,37 +50,145 @@ impl RackResponse {
         self.get_port(|port| port.dragging)
     }
 
+    pub fn get_clicked_port(&self) -> Option<&PortResponse> {
+        self.get_port(|port| port.clicked)
+    }
+
     pub fn get_removed_instance(&self) -> Option<&InstanceResponse> {
         self.responses.values().find(|response| response.remove)
     }
 
+    pub fn get_selected_instance(&self) -> Option<&InstanceResponse> {
+        self.responses.values().find(|response| response.select)
+    }
+
+    pub fn get_example_instance(&self) -> Option<&InstanceResponse> {
+        self.responses.values().find(|response| response.example)
+    }
+
     pub fn get_response(&self, handle: InstanceHandle) -> Option<&InstanceResponse> {
         self.responses.get(&handle)
     }
 
-    pub fn show_connections(&self, rack: &Rack, ui: &mut Ui) {
-        for (&from, connections) in rack.io.connections().iter() {
+    pub fn show_connections(
+        &self,
+        rack: &mut Rack,
+        ui: &mut Ui,
+        sample_rate: u32,
+        settings: &Settings,
+    ) {
+        let pointer = ui.ctx().pointer_hover_pos();
+        let mut probed = None;
+
+        for (&from, connections) in rack.io.connections().clone().iter() {
             for &to in connections.iter() {
-                let from_response = self.get_response(from.instance).unwrap();
-                let to_response = self.get_response(to.instance).unwrap();
+                // instances in collapsed windows are not drawn and have no response
+                let (Some(from_port_response), Some(to_port_response)) = (
+                    self.get_response(from.instance)
+                        .and_then(|response| response.get_port_response(from)),
+                    self.get_response(to.instance)
+                        .and_then(|response| response.get_port_response(to)),
+                ) else {
+                    continue;
+                };
+
+                let visible = match rack.cables {
+                    CableVisibility::All => true,
+                    CableVisibility::Selected => rack.selected().is_some_and(|selected| {
+                        selected == from.instance || selected == to.instance
+                    }),
+                    CableVisibility::Hidden => false,
+                };
+                if !visible {
+                    continue;
+                }
 
-                let from_port_response = from_response.get_port_response(from).unwrap();
-                let to_port_response = to_response.get_port_response(to).unwrap();
+                let opacity = settings.cable_opacity(
+                    rack.selected().is_some(),
+                    rack.is_highlighted(from.instance) && rack.is_highlighted(to.instance),
+                );
 
-                let mut color = to_port_response.color;
-                color.a = 0.1;
+                let stroke = settings.cable_stroke(
+                    to_port_response.description.type_name,
+                    to_port_response.color,
+                    opacity,
+                );
 
                 draw_rope(
                     from_port_response.position,
                     to_port_response.position,
                     ui,
-                    Stroke::new(2.0, color),
+                    stroke,
+                    Pattern::Solid,
+                    settings,
+                );
+
+                let points = cable_points(
+                    from_port_response.position,
+                    to_port_response.position,
+                    settings,
                 );
+                // the gain scales the values leaving the output
+                if from_port_response.description.scalable {
+                    let middle = rope_middle(&points);
+                    show_gain(rack, from, to, middle, to_port_response.color, ui);
+                }
+
+                if let Some(pointer) = pointer.filter(|_| rack.probing) {
+                    if rope_distance(&points, pointer) < PROBE_DISTANCE {
+                        probed = Some((from, to));
+                    }
+                }
             }
         }
+
+        if let Some((from, to)) = probed {
+            if rack
+                .io
+                .probe()
+                .is_none_or(|probe| probe.from != from || probe.to != to)
+            {
+                rack.io
+                    .set_probe(Some(Probe::new(from, to, sample_rate as usize)));
+            }
+
+            if let Some(probe) = rack.io.probe() {
+                let output = rack.io.get_output_dyn(probe.from);
+                egui::containers::show_tooltip_at_pointer(ui.ctx(), Id::new("probe"), |ui| {
+                    show_probe(probe, output, ui)
+                });
+            }
+        } else if rack.io.probe().is_some() {
+            rack.io.set_probe(None);
+        }
+    }
+
+    /// Draws a rope from the port picked for patching to the pointer.
+    pub fn show_patching(&self, rack: &Rack, ui: &mut Ui, settings: &Settings) {
+        let Some(patching) = rack.patching() else {
+            return;
+        };
+
+        let Some(port) = self
+            .get_response(patching.instance)
+            .and_then(|response| response.get_port_response(patching))
+        else {
+            return;
+        };
+
+        if let Some(mouse_pos) = ui.ctx().pointer_hover_pos() {
+            draw_rope(
+                port.position,
+                mouse_pos,
+                ui,
+                Stroke::new(2.0f32, Color32::GOLD),
+                Pattern::Solid,
+                settings,
+            )
+        }
     }
 
-    pub fn show_dragged(&self, rack: &mut Rack, ui: &mut Ui) {
+    pub fn show_dragged(&self, rack: &mut Rack, ui: &mut Ui, settings: &Settings) {
         if let Some(dragged) = self.get_dragging_port() {
             let can_connect = if let Some(hovered) = self.get_hovered_port() {
                 let result = rack.can_connect(dragged.handle, hovered.handle);
@@ -81,21 +198,21 @@ impl RackResponse {
                         egui::containers::show_tooltip_at_pointer(
                             ui.ctx(),
                             Id::new(hovered.description.id),
-                            |ui| ui.label("✅connect"),
+                            |ui| ui.label(format!("✅{}", tr("connect"))),
                         );
                     }
                     ConnectResult::Warn(_) => {
                         egui::containers::show_tooltip_at_pointer(
                             ui.ctx(),
                             Id::new(hovered.description.id),
-                            |ui| ui.label(format!("⚠{}", result.as_str())),
+                            |ui| ui.label(format!("⚠{}", tr(result.as_str()))),
                         );
                     }
                     _ => {
                         egui::containers::show_tooltip_at_pointer(
                             ui.ctx(),
                             Id::new(hovered.description.id),
-                            |ui| ui.label(format!("❌{}", result.as_str())),
+                            |ui| ui.label(format!("❌{}", tr(result.as_str()))),
                         );
                     }
                 }
@@ -105,18 +222,10 @@ impl RackResponse {
                 None
             };
 
-            let stroke = if let Some(can_connect) = can_connect {
-                match can_connect {
-                    ConnectResult::Ok => Stroke::new(2.0, Color32::GREEN),
-                    ConnectResult::Warn(_) => Stroke::new(2.0, Color32::GOLD),
-                    _ => Stroke::new(2.0, Color32::RED),
-                }
-            } else {
-                Stroke::new(2.0, Rgba::WHITE)
-            };
+            let (stroke, pattern) = settings.validity_stroke(can_connect);
 
             if let Some(mouse_pos) = ui.ctx().pointer_interact_pos() {
-                draw_rope(dragged.position, mouse_pos, ui, stroke)
+                draw_rope(dragged.position, mouse_pos, ui, stroke, pattern, settings)
             }
         }
     }
@@ -129,6 +238,23 @@ impl RackResponse {
             }
         }
 
+        if let Some(selected) = self.get_selected_instance() {
+            rack.toggle_selected(selected.handle)
+        }
+
+        if let Some(clicked) = self.get_clicked_port() {
+            rack.patch(clicked.handle)
+        }
+
+        if let Some(example) = self.get_example_instance() {
+            if let Some(description) = rack
+                .get_instance(example.handle)
+                .map(|instance| instance.description.clone())
+            {
+                rack.insert_example(&description)
+            }
+        }
+
         //remove removed
         if let Some(removed) = self.get_removed_instance() {
             rack.remove_instance(removed.handle)
@@ -136,24 +262,201 @@ impl RackResponse {
     }
 }
 
-pub fn draw_rope(from: Pos2, to: Pos2, ui: &mut Ui, stroke: Stroke) {
+pub fn draw_rope(
+    from: Pos2,
+    to: Pos2,
+    ui: &mut Ui,
+    stroke: Stroke,
+    pattern: Pattern,
+    settings: &Settings,
+) {
     let layer = LayerId::new(Order::Middle, Id::from("dragged"));
     let mut painter = ui.ctx().layer_painter(layer);
-    let control = control_point(from, to);
-    let shape = Shape::QuadraticBezier(QuadraticBezierShape {
-        points: [from, control, to],
-        closed: false,
-        fill: Color32::TRANSPARENT,
-        stroke,
-    });
+    let points = cable_points(from, to, settings);
 
     painter.set_clip_rect(ui.clip_rect());
-    painter.add(shape);
+    match pattern {
+        Pattern::Solid => {
+            painter.add(Shape::line(points, stroke));
+        }
+        Pattern::Dashed => {
+            painter.extend(Shape::dashed_line(&points, stroke, 8.0, 4.0));
+        }
+        Pattern::Dotted => {
+            painter.extend(Shape::dotted_line(&points, stroke.color, 6.0, stroke.width));
+        }
+    }
+}
+
+/// Draws a handle in the middle of a connection that can be clicked to edit its gain.
+fn show_gain(
+    rack: &mut Rack,
+    from: PortHandle,
+    to: PortHandle,
+    at: Pos2,
+    color: Hsva,
+    ui: &mut Ui,
+) {
+    let id = Id::new(("gain", from, to));
+    let size = if rack.touch() { 24.0 } else { 10.0 };
+    let response = ui.interact(
+        Rect::from_center_size(at, Vec2::splat(size)),
+        id,
+        Sense::click(),
+    );
+    response.context_menu(|ui| {
+        if ui.button(tr("disconnect")).clicked() {
+            rack.disconnect(from, to);
+            ui.close_menu();
+        }
+    });
+    let gain = rack.io.gain(from, to);
+
+    let painter = ui
+        .ctx()
+        .layer_painter(LayerId::new(Order::Middle, Id::from("dragged")))
+        .with_clip_rect(ui.clip_rect());
+
+    let mut handle = color;
+    handle.a = if response.hovered() { 1.0 } else { 0.3 };
+    painter.circle_filled(at, 3.0, handle);
+
+    if gain != 1.0 {
+        painter.text(
+            at + Vec2::new(6.0, 0.0),
+            Align2::LEFT_CENTER,
+            format!("×{:.2}", gain),
+            FontId::monospace(10.0),
+            Color32::GRAY,
+        );
+    }
+
+    let popup = id.with("popup");
+    if response.clicked() {
+        ui.memory_mut(|memory| memory.toggle_popup(popup));
+    }
+
+    egui::popup_below_widget(ui, popup, &response, |ui| {
+        ui.set_min_width(120.0);
+        ui.horizontal(|ui| {
+            let mut gain = gain;
+            ui.label(tr("gain:"));
+            if ui
+                .add(
+                    egui::DragValue::new(&mut gain)
+                        .clamp_range(-2.0..=2.0)
+                        .speed(0.01),
+                )
+                .changed()
+            {
+                rack.io.set_gain(from, to, gain);
+            }
+            if ui.button(tr("reset")).clicked() {
+                rack.io.set_gain(from, to, 1.0);
+            }
+        });
+    });
+}
+
+/// Draws the recorded values of a probe with their statistics and the current output value before gain.
+fn show_probe(probe: &Probe, output: Option<Box<dyn PortValueBoxed>>, ui: &mut Ui) {
+    let step = (probe.values().len() / PROBE_POINTS).max(1);
+    let points = probe
+        .values()
+        .iter()
+        .enumerate()
+        .step_by(step)
+        .map(|(i, &value)| [i as f64, value as f64])
+        .collect::<Vec<_>>();
+
+    Plot::new("probe")
+        .width(200.0)
+        .height(80.0)
+        .show_axes(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .allow_drag(false)
+        .show(ui, |ui| {
+            ui.line(Line::new(points).color(Color32::LIGHT_GREEN))
+        });
+
+    if let Some((min, max, mean)) = probe.stats() {
+        ui.label(
+            RichText::new(format!(
+                "min: {:+.3}\nmax: {:+.3}\nmean: {:+.3}",
+                min, max, mean
+            ))
+            .monospace(),
+        );
+    }
+
+    if let Some(output) = output {
+        ui.label(RichText::new(format!("{}: {}", tr("output"), output.to_string())).monospace());
+    }
+}
+
+/// The points along a cable, depending on the cable style.
+fn cable_points(from: Pos2, to: Pos2, settings: &Settings) -> Vec<Pos2> {
+    match settings.cable_style {
+        CableStyle::Rope => {
+            let control = control_point(from, to, settings.rope_curvature);
+            (0..=ROPE_SEGMENTS)
+                .map(|i| {
+                    let t = i as f32 / ROPE_SEGMENTS as f32;
+                    (from.to_vec2() * (1.0 - t).powi(2)
+                        + control.to_vec2() * 2.0 * (1.0 - t) * t
+                        + to.to_vec2() * t.powi(2))
+                    .to_pos2()
+                })
+                .collect()
+        }
+        CableStyle::Straight => vec![from, to],
+        CableStyle::Orthogonal => {
+            let x = (from.x + to.x) / 2.0;
+            vec![from, Pos2::new(x, from.y), Pos2::new(x, to.y), to]
+        }
+    }
+}
+
+/// The shortest distance from a point to the segments of a cable.
+fn rope_distance(points: &[Pos2], point: Pos2) -> f32 {
+    points
+        .windows(2)
+        .map(|segment| {
+            let (a, b) = (segment[0], segment[1]);
+            let length = (b - a).length_sq();
+            let t = if length > 0.0 {
+                ((point - a).dot(b - a) / length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            (a + (b - a) * t).distance(point)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// The point halfway along a cable.
+fn rope_middle(points: &[Pos2]) -> Pos2 {
+    let total = points
+        .windows(2)
+        .map(|segment| segment[0].distance(segment[1]))
+        .sum::<f32>();
+    let mut remaining = total / 2.0;
+    for segment in points.windows(2) {
+        let length = segment[0].distance(segment[1]);
+        if length >= remaining && length > 0.0 {
+            return segment[0] + (segment[1] - segment[0]) * (remaining / length);
+        }
+        remaining -= length;
+    }
+    points.first().copied().unwrap_or_default()
 }
 
-fn control_point(a: Pos2, b: Pos2) -> Pos2 {
+/// The control point of a rope, sagging further down with `curvature`.
+fn control_point(a: Pos2, b: Pos2, curvature: f32) -> Pos2 {
     let mut middle = (b - a) / 2.0;
-    middle.y += a.distance(b) / 5.0;
-    middle.y += (b.y - a.y).max(0.0) / 3.0;
+    middle.y += a.distance(b) / 5.0 * curvature;
+    middle.y += (b.y - a.y).max(0.0) / 3.0 * curvature;
     a + middle
 }
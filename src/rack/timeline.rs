@@ -0,0 +1,475 @@
+use ahash::HashMap;
+use eframe::{
+    egui::{self, Ui},
+    epaint::{Color32, Vec2},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    instance::instance::{Instance, InstanceHandle},
+    transport::Transport,
+};
+
+/// Sample rate arrangements are rendered at.
+#[cfg(not(target_arch = "wasm32"))]
+pub const RENDER_SAMPLE_RATE: u32 = 48000;
+
+/// Parameter values of instances, applied together.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Scene<I> {
+    pub name: String,
+    values: Vec<(I, Vec<(String, f32)>)>,
+}
+
+impl Scene<InstanceHandle> {
+    /// Sets the stored parameters of the instances, matched by name.
+    pub fn apply(&self, instances: &mut HashMap<InstanceHandle, Instance>) {
+        for (handle, values) in self.values.iter() {
+            let Some(instance) = instances.get_mut(handle) else {
+                continue;
+            };
+            for parameter in instance.module.parameters() {
+                if let Some((_, value)) = values.iter().find(|(name, _)| name == parameter.name) {
+                    *parameter.value = *value;
+                }
+            }
+            instance.hold_locked();
+        }
+    }
+}
+
+/// Scenes and clips arranged over bars, played through by the transport.
+///
+/// Instances are referred to by `I`, their handle in a rack or their index in a patch.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timeline<I = InstanceHandle> {
+    /// Whether the transport plays through the arrangement.
+    pub follow: bool,
+    scenes: Vec<Scene<I>>,
+    /// The scene applied at the start of each bar, `None` keeps the current one.
+    scene_lane: Vec<Option<usize>>,
+    /// Instances with a clip and the bars in which they play it.
+    clip_lanes: Vec<(I, Vec<bool>)>,
+    /// The scene placed when clicking a bar.
+    #[serde(skip)]
+    brush: usize,
+    /// The bar the arrangement was last updated for.
+    #[serde(skip)]
+    last_bar: Option<u64>,
+    /// The scene launched to be applied at the next quantize boundary.
+    #[serde(skip)]
+    queued: Option<usize>,
+    /// Position of the transport in the last call to [`Timeline::update`].
+    #[serde(skip)]
+    previous: Option<f64>,
+}
+
+impl<I> Default for Timeline<I> {
+    fn default() -> Self {
+        Self {
+            follow: false,
+            scenes: Vec::new(),
+            scene_lane: vec![None; 8],
+            clip_lanes: Vec::new(),
+            brush: 0,
+            last_bar: None,
+            queued: None,
+            previous: None,
+        }
+    }
+}
+
+/// What changes at the start of a bar.
+pub struct BarChange<'a> {
+    pub scene: Option<&'a Scene<InstanceHandle>>,
+    /// Whether each instance in a clip lane should play its clip.
+    pub clips: Vec<(InstanceHandle, bool)>,
+}
+
+impl<I: Clone> Timeline<I> {
+    /// Length of the arrangement in bars.
+    pub fn bars(&self) -> usize {
+        self.scene_lane.len()
+    }
+
+    /// Converts the instance references, leaving out what `f` can't convert.
+    pub fn map<J>(&self, f: impl Fn(&I) -> Option<J>) -> Timeline<J> {
+        Timeline {
+            follow: self.follow,
+            scenes: self
+                .scenes
+                .iter()
+                .map(|scene| Scene {
+                    name: scene.name.clone(),
+                    values: scene
+                        .values
+                        .iter()
+                        .filter_map(|(instance, values)| Some((f(instance)?, values.clone())))
+                        .collect(),
+                })
+                .collect(),
+            scene_lane: self.scene_lane.clone(),
+            clip_lanes: self
+                .clip_lanes
+                .iter()
+                .filter_map(|(instance, bars)| Some((f(instance)?, bars.clone())))
+                .collect(),
+            brush: 0,
+            last_bar: None,
+            queued: None,
+            previous: None,
+        }
+    }
+}
+
+impl Timeline {
+    /// Returns what changes when the transport entered a new bar of the arrangement or reached
+    /// the boundary a launched scene waits for.
+    pub fn update(&mut self, transport: &Transport) -> Option<BarChange<'_>> {
+        let boundary = transport.playing && transport.at_boundary(self.previous);
+        self.previous = transport.playing.then(|| transport.position());
+        if boundary && self.queued.is_some() {
+            let scene = self.queued.take().and_then(|scene| self.scenes.get(scene));
+            return Some(BarChange {
+                scene,
+                clips: Vec::new(),
+            });
+        }
+
+        if !self.follow || !transport.playing {
+            self.last_bar = None;
+            return None;
+        }
+
+        let bar = transport.bar();
+        if self.last_bar == Some(bar) {
+            return None;
+        }
+        self.last_bar = Some(bar);
+
+        let index = bar as usize;
+        if index >= self.bars() {
+            return None;
+        }
+
+        Some(BarChange {
+            scene: self.scene_lane[index].and_then(|scene| self.scenes.get(scene)),
+            clips: self
+                .clip_lanes
+                .iter()
+                .map(|(instance, bars)| (*instance, bars[index]))
+                .collect(),
+        })
+    }
+
+    /// Applies a scene, or queues it for the next quantize boundary while the transport plays.
+    pub fn launch(
+        &mut self,
+        scene: usize,
+        instances: &mut HashMap<InstanceHandle, Instance>,
+        transport: &Transport,
+    ) {
+        if transport.playing {
+            self.queued = Some(scene);
+        } else if let Some(scene) = self.scenes.get(scene) {
+            scene.apply(instances);
+        }
+    }
+
+    pub fn scene_names(&self) -> Vec<&str> {
+        self.scenes
+            .iter()
+            .map(|scene| scene.name.as_str())
+            .collect()
+    }
+
+    /// Stores the parameters of all instances as a new scene.
+    fn capture(&mut self, instances: &mut HashMap<InstanceHandle, Instance>) {
+        let values = instances
+            .values_mut()
+            .map(|instance| {
+                let values = instance
+                    .module
+                    .parameters()
+                    .into_iter()
+                    .map(|parameter| (parameter.name.to_string(), *parameter.value))
+                    .collect();
+                (instance.handle, values)
+            })
+            .collect();
+
+        self.scenes.push(Scene {
+            name: format!("{} {}", tr("scene"), self.scenes.len() + 1),
+            values,
+        });
+        self.brush = self.scenes.len() - 1;
+    }
+
+    /// Forgets instances that were removed from the rack.
+    fn retain(&mut self, instances: &HashMap<InstanceHandle, Instance>) {
+        for scene in self.scenes.iter_mut() {
+            scene
+                .values
+                .retain(|(instance, _)| instances.contains_key(instance));
+        }
+        self.clip_lanes
+            .retain(|(instance, _)| instances.contains_key(instance));
+    }
+
+    /// Shows the scenes and the lanes, returns whether rendering the arrangement was requested.
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        instances: &mut HashMap<InstanceHandle, Instance>,
+        transport: &Transport,
+    ) -> bool {
+        self.retain(instances);
+        #[allow(unused_mut)]
+        let mut render = false;
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.follow, tr("follow"))
+                .on_hover_text_at_pointer(tr("let the transport play through the arrangement"));
+
+            let mut bars = self.bars();
+            ui.label(tr("length:"));
+            if ui
+                .add(
+                    egui::DragValue::new(&mut bars)
+                        .clamp_range(1..=256)
+                        .suffix(format!(" {}", tr("bars"))),
+                )
+                .changed()
+            {
+                self.scene_lane.resize(bars, None);
+                for (_, lane) in self.clip_lanes.iter_mut() {
+                    lane.resize(bars, false);
+                }
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .button(tr("render…"))
+                .on_hover_text_at_pointer(tr("play the arrangement from the start into a wav file"))
+                .clicked()
+            {
+                render = true;
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("scenes:"));
+            let mut removed = None;
+            let mut launched = None;
+            for (index, scene) in self.scenes.iter().enumerate() {
+                let response = ui
+                    .selectable_label(self.brush == index, &scene.name)
+                    .on_hover_text_at_pointer(tr("click to paint, right click to remove"));
+                if response.clicked() {
+                    self.brush = index;
+                }
+                if response.secondary_clicked() {
+                    removed = Some(index);
+                }
+
+                let queued = self.queued == Some(index);
+                if ui
+                    .selectable_label(queued, if queued { "⏳" } else { "▶" })
+                    .on_hover_text_at_pointer(tr(
+                        "launch the scene, at the next quantize boundary while the transport plays",
+                    ))
+                    .clicked()
+                {
+                    if queued {
+                        self.queued = None;
+                    } else {
+                        launched = Some(index);
+                    }
+                }
+            }
+
+            if ui
+                .button("➕")
+                .on_hover_text_at_pointer(tr("store the current parameters as a scene"))
+                .clicked()
+            {
+                self.capture(instances);
+            }
+
+            if let Some(launched) = launched {
+                self.launch(launched, instances, transport);
+            }
+
+            if let Some(removed) = removed {
+                self.scenes.remove(removed);
+                self.queued = None;
+                for scene in self.scene_lane.iter_mut() {
+                    *scene = match *scene {
+                        Some(scene) if scene == removed => None,
+                        Some(scene) if scene > removed => Some(scene - 1),
+                        scene => scene,
+                    };
+                }
+                self.brush = self.brush.min(self.scenes.len().saturating_sub(1));
+            }
+        });
+
+        let clips = instances
+            .values()
+            .filter(|instance| instance.module.has_clip())
+            .filter(|instance| {
+                !self
+                    .clip_lanes
+                    .iter()
+                    .any(|(handle, _)| *handle == instance.handle)
+            })
+            .map(|instance| {
+                (
+                    instance.handle,
+                    format!("{} {}", instance.description.title(), instance.handle),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        ui.add_enabled_ui(!clips.is_empty(), |ui| {
+            ui.menu_button(tr("➕ clip lane"), |ui| {
+                for (handle, name) in clips {
+                    if ui.button(name).clicked() {
+                        self.clip_lanes.push((handle, vec![false; self.bars()]));
+                        ui.close_menu();
+                    }
+                }
+            })
+        });
+
+        let playing = transport
+            .playing
+            .then(|| transport.bar() as usize)
+            .filter(|_| self.follow);
+
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            egui::Grid::new("timeline")
+                .spacing(Vec2::splat(2.0))
+                .show(ui, |ui| {
+                    ui.label("");
+                    for bar in 0..self.bars() {
+                        let text = egui::RichText::new(format!("{}", bar + 1)).monospace();
+                        if playing == Some(bar) {
+                            ui.label(text.color(Color32::GOLD));
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                    ui.end_row();
+
+                    ui.label(tr("scenes"));
+                    for scene in self.scene_lane.iter_mut() {
+                        let name = scene
+                            .and_then(|scene| self.scenes.get(scene))
+                            .map_or("·".to_string(), |scene| scene.name.clone());
+                        let mut button = egui::Button::new(name).min_size(Vec2::new(48.0, 24.0));
+                        if scene.is_some() {
+                            button = button.fill(Color32::DARK_BLUE);
+                        }
+                        let response = ui.add(button);
+                        if response.clicked() && !self.scenes.is_empty() {
+                            *scene = Some(self.brush);
+                        }
+                        if response.secondary_clicked() {
+                            *scene = None;
+                        }
+                    }
+                    ui.end_row();
+
+                    let mut removed = None;
+                    for (lane, (handle, bars)) in self.clip_lanes.iter_mut().enumerate() {
+                        let name = instances
+                            .get(handle)
+                            .map(|instance| instance.description.title())
+                            .unwrap_or_default();
+                        if ui
+                            .label(name)
+                            .on_hover_text_at_pointer(tr("right click to remove the lane"))
+                            .secondary_clicked()
+                        {
+                            removed = Some(lane);
+                        }
+                        for active in bars.iter_mut() {
+                            let mut button = egui::Button::new("").min_size(Vec2::new(48.0, 24.0));
+                            if *active {
+                                button = button.fill(Color32::DARK_GREEN);
+                            }
+                            if ui.add(button).clicked() {
+                                *active = !*active;
+                            }
+                        }
+                        ui.end_row();
+                    }
+
+                    if let Some(removed) = removed {
+                        self.clip_lanes.remove(removed);
+                    }
+                });
+        });
+
+        render
+    }
+}
+
+/// Encodes interleaved stereo samples as a 32 bit float wav file.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    const CHANNELS: u16 = 2;
+    const BYTES: u16 = 4;
+
+    let data = (samples.len() * BYTES as usize) as u32;
+    let mut wav = Vec::with_capacity(44 + data as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // 3 is IEEE float
+    wav.extend_from_slice(&3u16.to_le_bytes());
+    wav.extend_from_slice(&CHANNELS.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * (CHANNELS * BYTES) as u32).to_le_bytes());
+    wav.extend_from_slice(&(CHANNELS * BYTES).to_le_bytes());
+    wav.extend_from_slice(&(BYTES * 8).to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_header() {
+        let samples = [0.0, 0.5, -0.5, 1.0];
+        let wav = encode_wav(&samples, 48000);
+        let u16_at = |offset: usize| u16::from_le_bytes([wav[offset], wav[offset + 1]]);
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(wav[offset..offset + 4].try_into().unwrap());
+
+        assert_eq!(wav.len(), 44 + samples.len() * 4);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(4) as usize, wav.len() - 8);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32_at(16), 16);
+        assert_eq!(u16_at(20), 3);
+        assert_eq!(u16_at(22), 2);
+        assert_eq!(u32_at(24), 48000);
+        assert_eq!(u32_at(28), 48000 * 8);
+        assert_eq!(u16_at(32), 8);
+        assert_eq!(u16_at(34), 32);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(40) as usize, samples.len() * 4);
+        assert_eq!(f32::from_le_bytes(wav[48..52].try_into().unwrap()), 0.5);
+    }
+}
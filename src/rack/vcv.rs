@@ -0,0 +1,299 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use serde::Deserialize;
+
+use super::rack::Rack;
+use crate::{
+    instance::instance::InstanceHandle,
+    io::{ConnectResult, ConnectResultErr, PortHandle},
+    module::Port,
+    modules::{
+        audio::{Audio, AudioInput},
+        filter::{Filter, FilterInput, FilterOutput, FilterType},
+        missing::Missing,
+        ops::{InValueA, InValueB, Operation, Operator, OutValue},
+        oscillator::{FrameOutput, FrequencyInput, Oscillator, Wave},
+        scope::{Scope, ScopeInput},
+    },
+};
+
+/// File extension of VCV Rack patches.
+pub const EXTENSION: &str = "vcv";
+
+/// Magic number of zstd, used by VCV Rack 2 to compress patches.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Frequency of C4, which VCV Rack uses as the reference pitch.
+const C4: f32 = 261.6256;
+
+#[derive(Deserialize)]
+struct VcvPatch {
+    #[serde(default)]
+    modules: Vec<VcvModule>,
+    /// Called wires before VCV Rack 1.
+    #[serde(default, alias = "wires")]
+    cables: Vec<VcvCable>,
+}
+
+#[derive(Deserialize)]
+struct VcvModule {
+    /// Missing before VCV Rack 1, where cables refer to the index of the module.
+    id: Option<i64>,
+    #[serde(default)]
+    plugin: String,
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    params: Vec<VcvParam>,
+    #[serde(default)]
+    pos: [f32; 2],
+}
+
+impl VcvModule {
+    fn param(&self, id: i64) -> Option<f32> {
+        self.params
+            .iter()
+            .find(|param| param.id == Some(id))
+            .map(|param| param.value)
+    }
+}
+
+#[derive(Deserialize)]
+struct VcvParam {
+    #[serde(alias = "paramId")]
+    id: Option<i64>,
+    #[serde(default)]
+    value: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VcvCable {
+    output_module_id: i64,
+    output_id: i64,
+    input_module_id: i64,
+    input_id: i64,
+}
+
+/// The ports of an instance that correspond to the port ids of a VCV Rack module.
+struct Mapping {
+    inputs: Vec<(i64, PortHandle)>,
+    outputs: Vec<(i64, PortHandle)>,
+}
+
+/// Summary of an imported VCV Rack patch.
+pub struct VcvImport {
+    pub mapped: usize,
+    /// Names of the modules that were replaced by placeholders.
+    pub missing: Vec<String>,
+    /// Cables that were left out because they close a feedback loop, which can't be processed.
+    pub feedback: Vec<String>,
+}
+
+/// Replaces everything in the rack with the modules of a VCV Rack patch, as far as they have an equivalent.
+///
+/// Each row of the patch becomes a panel. Core modules are mapped onto similar modules, others are replaced by [`Missing`] placeholders.
+pub fn import(rack: &mut Rack, bytes: &[u8]) -> Result<VcvImport, &'static str> {
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        return Err("compressed VCV Rack 2 patches are not supported, extract patch.json first");
+    }
+
+    let patch: VcvPatch = serde_json::from_slice(bytes).map_err(|_| "not a VCV Rack patch")?;
+
+    let mut rows = patch
+        .modules
+        .iter()
+        .map(|module| module.pos[1] as i64)
+        .collect::<Vec<_>>();
+    rows.sort();
+    rows.dedup();
+
+    let mut modules = patch.modules.iter().enumerate().collect::<Vec<_>>();
+    modules.sort_by(|(_, a), (_, b)| a.pos[0].total_cmp(&b.pos[0]));
+
+    rack.clear();
+    for _ in rows.iter() {
+        rack.add_panel()
+    }
+
+    let mut result = VcvImport {
+        mapped: 0,
+        missing: Vec::new(),
+        feedback: Vec::new(),
+    };
+    let mut mappings = Vec::new();
+    let mut names = Vec::new();
+
+    for (i, module) in modules {
+        let id = module.id.unwrap_or(i as i64);
+        names.push((id, module.model.clone()));
+        let panel = rows
+            .iter()
+            .position(|&row| row == module.pos[1] as i64)
+            .unwrap_or_default();
+
+        let used = patch
+            .cables
+            .iter()
+            .filter(|cable| cable.output_module_id == id)
+            .map(|cable| cable.output_id)
+            .collect::<Vec<_>>();
+
+        match map_module(rack, module, panel, &used) {
+            Some(mapping) => {
+                result.mapped += 1;
+                mappings.push((id, mapping));
+            }
+            None => {
+                let name = format!("{} {}", module.plugin, module.model);
+                let missing = rack.add_module_to::<Missing>(panel);
+                if let Some(missing) = rack.get_module_mut(missing) {
+                    missing.name = name.clone();
+                }
+                result.missing.push(name);
+            }
+        }
+    }
+
+    for cable in patch.cables.iter() {
+        let from = mappings
+            .iter()
+            .find(|(id, _)| *id == cable.output_module_id)
+            .and_then(|(_, mapping)| find(&mapping.outputs, cable.output_id));
+        let to = mappings
+            .iter()
+            .find(|(id, _)| *id == cable.input_module_id)
+            .and_then(|(_, mapping)| find(&mapping.inputs, cable.input_id));
+
+        let Some((from, to)) = from.zip(to) else {
+            continue;
+        };
+        if let ConnectResult::Err(ConnectResultErr::Cycle) = rack.can_connect(from, to) {
+            let name = |id| {
+                names
+                    .iter()
+                    .find(|(other, _)| *other == id)
+                    .map(|(_, name)| name.as_str())
+                    .unwrap_or_default()
+            };
+            result.feedback.push(format!(
+                "{} → {}",
+                name(cable.output_module_id),
+                name(cable.input_module_id)
+            ));
+        } else {
+            rack.connect(from, to).ok();
+        }
+    }
+
+    Ok(result)
+}
+
+fn find(ports: &[(i64, PortHandle)], id: i64) -> Option<PortHandle> {
+    ports
+        .iter()
+        .find(|(port, _)| *port == id)
+        .map(|(_, handle)| *handle)
+}
+
+fn port<P: Port>(instance: InstanceHandle) -> PortHandle {
+    PortHandle::new(P::id(), instance)
+}
+
+/// Adds the equivalent of a VCV Rack module, `used` are the ids of its outputs that are connected.
+fn map_module(rack: &mut Rack, module: &VcvModule, panel: usize, used: &[i64]) -> Option<Mapping> {
+    match (module.plugin.as_str(), module.model.as_str()) {
+        ("Fundamental", "VCO" | "VCO-1") => {
+            let handle = rack.add_module_to::<Oscillator>(panel);
+            let instance = handle.as_untyped();
+
+            if let Some(oscillator) = rack.get_module_mut(handle) {
+                oscillator.wave = match used.first() {
+                    Some(1) => Wave::Triangle,
+                    Some(2) => Wave::Saw,
+                    Some(3) => Wave::Square,
+                    _ => Wave::Sine,
+                };
+
+                // the frequency knob is in semitones from C4
+                let semitones = module.param(2).unwrap_or_default();
+                oscillator.freq = C4 * 2f32.powf(semitones / 12.0);
+            }
+
+            Some(Mapping {
+                inputs: vec![(0, port::<FrequencyInput>(instance))],
+                outputs: (0..4)
+                    .map(|id| (id, port::<FrameOutput>(instance)))
+                    .collect(),
+            })
+        }
+        ("Fundamental", "VCF") => {
+            let handle = rack.add_module_to::<Filter>(panel);
+            let instance = handle.as_untyped();
+
+            if let Some(filter) = rack.get_module_mut(handle) {
+                if used.first() == Some(&1) {
+                    filter.filter_type = FilterType::HighPass;
+                }
+
+                // the frequency knob is from 0 to 1, covering 10 octaves around C4
+                if let Some(frequency) = module.param(0) {
+                    filter.cutoff = C4 * 2f32.powf(frequency * 10.0 - 5.0);
+                }
+            }
+
+            Some(Mapping {
+                inputs: vec![(3, port::<FilterInput>(instance))],
+                outputs: vec![
+                    (0, port::<FilterOutput>(instance)),
+                    (1, port::<FilterOutput>(instance)),
+                ],
+            })
+        }
+        ("Fundamental", "VCA-1") => Some(map_operation(rack, panel, Operator::Mul, &[1], &[0])),
+        ("Fundamental", "VCA") => Some(map_operation(rack, panel, Operator::Mul, &[2], &[0, 1])),
+        ("Fundamental", "VCMixer") => Some(map_operation(rack, panel, Operator::Add, &[1], &[2])),
+        ("Fundamental", "Scope") => {
+            let instance = rack.add_module_to::<Scope>(panel).as_untyped();
+
+            Some(Mapping {
+                inputs: vec![(0, port::<ScopeInput>(instance))],
+                outputs: Vec::new(),
+            })
+        }
+        ("Core", model) if model.starts_with("AudioInterface") => {
+            let instance = rack.add_module_to::<Audio>(panel).as_untyped();
+
+            Some(Mapping {
+                inputs: vec![(0, port::<AudioInput>(instance))],
+                outputs: Vec::new(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Maps a VCV Rack module with one output onto an operation, `a` and `b` are the input ids of its operands.
+fn map_operation(
+    rack: &mut Rack,
+    panel: usize,
+    operator: Operator,
+    a: &[i64],
+    b: &[i64],
+) -> Mapping {
+    let handle = rack.add_module_to::<Operation<f32>>(panel);
+    let instance = handle.as_untyped();
+
+    if let Some(operation) = rack.get_module_mut(handle) {
+        operation.operator = operator;
+    }
+
+    Mapping {
+        inputs: a
+            .iter()
+            .map(|&id| (id, port::<InValueA<f32>>(instance)))
+            .chain(b.iter().map(|&id| (id, port::<InValueB<f32>>(instance))))
+            .collect(),
+        outputs: vec![(0, port::<OutValue<f32>>(instance))],
+    }
+}
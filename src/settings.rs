@@ -0,0 +1,387 @@
+use std::collections::BTreeMap;
+
+use eframe::{
+    egui::{self, Context, Ui, Visuals},
+    epaint::{Color32, Hsva, Stroke},
+};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::{self, tr, Language},
+    io::ConnectResult,
+    util::EnumIter,
+};
+
+/// Key the settings are persisted under.
+pub const STORAGE_KEY: &str = "settings";
+
+/// Base visual theme of the app.
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+}
+
+/// How cables and ports are colored and shaped.
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum CableTheme {
+    Default,
+    HighContrast,
+    ColorblindSafe,
+}
+
+impl CableTheme {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CableTheme::Default => "default",
+            CableTheme::HighContrast => "high contrast",
+            CableTheme::ColorblindSafe => "colorblind safe",
+        }
+    }
+}
+
+/// How cables are routed between ports.
+#[derive(Clone, Copy, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum CableStyle {
+    /// A curve sagging like a rope.
+    Rope,
+    Straight,
+    /// Horizontal and vertical lines only.
+    Orthogonal,
+}
+
+impl CableStyle {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CableStyle::Rope => "rope",
+            CableStyle::Straight => "straight",
+            CableStyle::Orthogonal => "orthogonal",
+        }
+    }
+}
+
+/// Which cables are drawn, to see through dense patches.
+#[derive(Clone, Copy, PartialEq, Default, Sequence)]
+pub enum CableVisibility {
+    #[default]
+    All,
+    /// Only the cables of the selected instance.
+    Selected,
+    Hidden,
+}
+
+impl CableVisibility {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CableVisibility::All => "all cables",
+            CableVisibility::Selected => "cables of the selected module",
+            CableVisibility::Hidden => "no cables",
+        }
+    }
+}
+
+/// Line pattern of a cable, so information isn't conveyed by color alone.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Pattern {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+/// Colors from the Okabe-Ito palette, distinguishable with common color vision deficiencies.
+mod okabe_ito {
+    use eframe::epaint::Color32;
+
+    pub const ORANGE: Color32 = Color32::from_rgb(230, 159, 0);
+    pub const SKY_BLUE: Color32 = Color32::from_rgb(86, 180, 233);
+    pub const BLUISH_GREEN: Color32 = Color32::from_rgb(0, 158, 115);
+    pub const BLUE: Color32 = Color32::from_rgb(0, 114, 178);
+    pub const VERMILLION: Color32 = Color32::from_rgb(213, 94, 0);
+}
+
+/// A MIDI program change, in a bank selected beforehand, that launches a scene of the timeline.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramMapping {
+    pub bank: u16,
+    pub program: u8,
+    pub scene: usize,
+}
+
+/// App wide settings, persisted between runs.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub language: Language,
+    pub theme: Theme,
+    pub accent: Color32,
+    pub cable_theme: CableTheme,
+    /// Opacity of cables when nothing is selected.
+    pub cable_opacity: f32,
+    pub cable_style: CableStyle,
+    /// How far ropes sag, 0 being straight.
+    pub rope_curvature: f32,
+    /// Whether a grid is drawn behind the rack.
+    pub grid: bool,
+    /// Whether panels are aligned to the grid and their widths rounded up to it.
+    pub snap: bool,
+    /// Whether quality is reduced instead of muting when processing can't keep up.
+    pub cpu_guard: bool,
+    /// Whether instances show a small waveform of their first output in their header.
+    pub thumbnails: bool,
+    /// Names of the modules starred in the add menu.
+    pub favorites: Vec<String>,
+    /// How often each module was added from the add menu, by name.
+    pub usage: BTreeMap<String, u32>,
+    /// Name of the MIDI input port listened to.
+    pub midi_port: Option<String>,
+    /// Whether a virtual MIDI port is opened instead, where supported.
+    pub midi_virtual: bool,
+    pub program_changes: Vec<ProgramMapping>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::English,
+            theme: Theme::Dark,
+            accent: Visuals::dark().selection.bg_fill,
+            cable_theme: CableTheme::Default,
+            cable_opacity: 0.1,
+            cable_style: CableStyle::Rope,
+            rope_curvature: 1.0,
+            grid: false,
+            snap: false,
+            cpu_guard: true,
+            thumbnails: false,
+            favorites: Vec::new(),
+            usage: BTreeMap::new(),
+            midi_port: None,
+            midi_virtual: false,
+            program_changes: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Applies the language, theme and accent color.
+    pub fn apply(&self, ctx: &Context) {
+        i18n::set_language(self.language);
+
+        let mut visuals = match self.theme {
+            Theme::Dark => Visuals::dark(),
+            Theme::Light => Visuals::light(),
+        };
+        visuals.selection.bg_fill = self.accent;
+        visuals.hyperlink_color = self.accent;
+        ctx.set_visuals(visuals);
+    }
+
+    pub fn is_favorite(&self, module: &str) -> bool {
+        self.favorites.iter().any(|favorite| favorite == module)
+    }
+
+    pub fn toggle_favorite(&mut self, module: &str) {
+        if self.is_favorite(module) {
+            self.favorites.retain(|favorite| favorite != module);
+        } else {
+            self.favorites.push(module.to_string());
+        }
+    }
+
+    /// Counts a module being added from the add menu.
+    pub fn record_use(&mut self, module: &str) {
+        *self.usage.entry(module.to_string()).or_default() += 1;
+    }
+
+    /// The names of the `count` most added modules that aren't favorites, most used first.
+    pub fn most_used(&self, count: usize) -> Vec<&str> {
+        let mut used = self
+            .usage
+            .iter()
+            .filter(|(module, _)| !self.is_favorite(module))
+            .collect::<Vec<_>>();
+        used.sort_by(|a, b| b.1.cmp(a.1));
+        used.into_iter()
+            .take(count)
+            .map(|(module, _)| module.as_str())
+            .collect()
+    }
+
+    /// Opacity of a cable, depending on whether something is selected and it is part of it.
+    pub fn cable_opacity(&self, selection: bool, highlighted: bool) -> f32 {
+        match (selection, highlighted) {
+            (false, _) => self.cable_opacity,
+            (true, true) => (self.cable_opacity * 5.0).min(1.0),
+            (true, false) => self.cable_opacity * 0.2,
+        }
+    }
+
+    /// Whether ports and cables should be told apart by shape and pattern too.
+    pub fn use_shapes(&self) -> bool {
+        self.cable_theme != CableTheme::Default
+    }
+
+    /// The stroke of a connected cable, `port_color` being the random color of its input port.
+    pub fn cable_stroke(&self, type_name: &str, port_color: Hsva, opacity: f32) -> Stroke {
+        match self.cable_theme {
+            CableTheme::Default => {
+                let mut color = port_color;
+                color.a = opacity;
+                Stroke::new(2.0f32, color)
+            }
+            // these themes are more opaque by default for better contrast
+            CableTheme::HighContrast => Stroke::new(
+                3.0f32,
+                Color32::WHITE.gamma_multiply((opacity * 5.0).min(1.0)),
+            ),
+            CableTheme::ColorblindSafe => Stroke::new(
+                2.0f32,
+                Self::type_color(type_name).gamma_multiply((opacity * 5.0).min(1.0)),
+            ),
+        }
+    }
+
+    fn type_color(type_name: &str) -> Color32 {
+        match type_name {
+            "f32" => okabe_ito::SKY_BLUE,
+            "bool" => okabe_ito::ORANGE,
+            _ => okabe_ito::BLUISH_GREEN,
+        }
+    }
+
+    /// The stroke and pattern of a cable being dragged, showing whether it can connect.
+    pub fn validity_stroke(&self, result: Option<ConnectResult>) -> (Stroke, Pattern) {
+        let (ok, warn, err) = match self.cable_theme {
+            CableTheme::Default => (Color32::GREEN, Color32::GOLD, Color32::RED),
+            CableTheme::HighContrast => (Color32::WHITE, Color32::YELLOW, Color32::LIGHT_RED),
+            CableTheme::ColorblindSafe => {
+                (okabe_ito::BLUE, okabe_ito::ORANGE, okabe_ito::VERMILLION)
+            }
+        };
+
+        let width = if self.cable_theme == CableTheme::HighContrast {
+            3.0f32
+        } else {
+            2.0f32
+        };
+
+        let (color, pattern) = match result {
+            None => (Color32::WHITE, Pattern::Solid),
+            Some(ConnectResult::Ok) => (ok, Pattern::Solid),
+            Some(ConnectResult::Warn(_)) => (warn, Pattern::Dashed),
+            Some(ConnectResult::Err(_)) => (err, Pattern::Dotted),
+        };
+
+        let pattern = if self.use_shapes() {
+            pattern
+        } else {
+            Pattern::Solid
+        };
+
+        (Stroke::new(width, color), pattern)
+    }
+
+    /// Shows the settings, returns whether any changed.
+    pub fn show(&mut self, ui: &mut Ui) -> bool {
+        let old = self.clone();
+
+        egui::Grid::new("settings").show(ui, |ui| {
+            ui.label(tr("language:"));
+            egui::ComboBox::from_id_source("language")
+                .selected_text(self.language.as_str())
+                .show_ui(ui, |ui| {
+                    for language in Language::iter() {
+                        ui.selectable_value(&mut self.language, language, language.as_str());
+                    }
+                });
+            ui.end_row();
+
+            ui.label(tr("theme:"));
+            egui::ComboBox::from_id_source("theme")
+                .selected_text(tr(self.theme.as_str()))
+                .show_ui(ui, |ui| {
+                    for theme in Theme::iter() {
+                        ui.selectable_value(&mut self.theme, theme, tr(theme.as_str()));
+                    }
+                });
+            ui.end_row();
+
+            ui.label(tr("accent:"));
+            ui.color_edit_button_srgba(&mut self.accent);
+            ui.end_row();
+
+            ui.label(tr("cables:"));
+            egui::ComboBox::from_id_source("cable theme")
+                .selected_text(tr(self.cable_theme.as_str()))
+                .show_ui(ui, |ui| {
+                    for theme in CableTheme::iter() {
+                        ui.selectable_value(&mut self.cable_theme, theme, tr(theme.as_str()));
+                    }
+                })
+                .response
+                .on_hover_text_at_pointer(tr(
+                    "the high contrast and colorblind safe themes also use shapes for port types and line patterns for connection validity",
+                ));
+            ui.end_row();
+
+            ui.label(tr("cable opacity:"));
+            ui.add(egui::Slider::new(&mut self.cable_opacity, 0.02..=1.0));
+            ui.end_row();
+
+            ui.label(tr("cable style:"));
+            egui::ComboBox::from_id_source("cable style")
+                .selected_text(tr(self.cable_style.as_str()))
+                .show_ui(ui, |ui| {
+                    for style in CableStyle::iter() {
+                        ui.selectable_value(&mut self.cable_style, style, tr(style.as_str()));
+                    }
+                });
+            ui.end_row();
+
+            ui.label(tr("rope curvature:"));
+            ui.add_enabled(
+                self.cable_style == CableStyle::Rope,
+                egui::Slider::new(&mut self.rope_curvature, 0.0..=3.0),
+            );
+            ui.end_row();
+
+            ui.label(tr("background grid:"));
+            ui.checkbox(&mut self.grid, "");
+            ui.end_row();
+
+            ui.label(tr("snap to grid:"));
+            ui.checkbox(&mut self.snap, "");
+            ui.end_row();
+
+            ui.label(tr("cpu guard:"));
+            ui.checkbox(&mut self.cpu_guard, "").on_hover_text_at_pointer(tr(
+                "reduce quality instead of muting when processing can't keep up",
+            ));
+            ui.end_row();
+
+            ui.label(tr("waveform thumbnails:"));
+            ui.checkbox(&mut self.thumbnails, "").on_hover_text_at_pointer(tr(
+                "show a small waveform of the first output of every module in its header",
+            ));
+            ui.end_row();
+
+            ui.label("");
+            if ui.button(tr("reset")).clicked() {
+                *self = Self::default();
+            }
+            ui.end_row();
+        });
+
+        *self != old
+    }
+}
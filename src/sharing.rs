@@ -0,0 +1,379 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    path::PathBuf,
+    sync::mpsc::{Receiver, Sender},
+};
+
+use eframe::egui::{self, Context, Ui};
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::FileDialog;
+
+use crate::{
+    i18n::tr,
+    rack::{
+        diff::{self, PatchDiff},
+        patch::Patch,
+        rack::Rack,
+    },
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    rack::{
+        timeline::{encode_wav, RENDER_SAMPLE_RATE},
+        vcv::{self, VcvImport},
+    },
+    toast,
+};
+
+/// Results of file dialogs, which are shown on another thread.
+#[cfg(not(target_arch = "wasm32"))]
+enum Message {
+    Export(PathBuf),
+    Import(PathBuf),
+    ImportVcv(PathBuf),
+    Compare(PathBuf),
+    Render(PathBuf),
+    Located { asset: String, path: PathBuf },
+}
+
+/// Exports and imports patches as files or as compressed text.
+pub struct Sharing {
+    /// Whether exported files include the files the modules play.
+    #[cfg(not(target_arch = "wasm32"))]
+    embed: bool,
+    /// Shared text pasted by the user.
+    text: String,
+    error: Option<&'static str>,
+    /// Assets of the imported patch that could not be found.
+    missing: Vec<String>,
+    /// Summary of the last VCV Rack import, shown until closed.
+    #[cfg(not(target_arch = "wasm32"))]
+    vcv: Option<VcvImport>,
+    /// Changes from the rack to the patch it was last compared with, shown until closed.
+    diff: Option<PatchDiff>,
+    #[cfg(not(target_arch = "wasm32"))]
+    sender: Sender<Message>,
+    #[cfg(not(target_arch = "wasm32"))]
+    receiver: Receiver<Message>,
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(clippy::derivable_impls))]
+impl Default for Sharing {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            embed: true,
+            text: String::new(),
+            error: None,
+            missing: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            vcv: None,
+            diff: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            sender,
+            #[cfg(not(target_arch = "wasm32"))]
+            receiver,
+        }
+    }
+}
+
+impl Sharing {
+    pub fn show_menu(&mut self, rack: &mut Rack, ui: &mut Ui) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.checkbox(&mut self.embed, tr("embed sample files"));
+
+            if ui.button(tr("export to file…")).clicked() {
+                self.pick(
+                    FileDialog::new().add_filter(tr("patch"), &[crate::rack::patch::EXTENSION]),
+                    true,
+                    Message::Export,
+                );
+                ui.close_menu();
+            }
+
+            if ui.button(tr("import from file…")).clicked() {
+                self.pick(
+                    FileDialog::new().add_filter(tr("patch"), &[crate::rack::patch::EXTENSION]),
+                    false,
+                    Message::Import,
+                );
+                ui.close_menu();
+            }
+
+            if ui.button(tr("import VCV Rack patch…")).clicked() {
+                self.pick(
+                    FileDialog::new().add_filter("VCV Rack", &[vcv::EXTENSION, "json"]),
+                    false,
+                    Message::ImportVcv,
+                );
+                ui.close_menu();
+            }
+
+            if ui
+                .button(tr("compare with file…"))
+                .on_hover_text(tr("shows what changes from this rack to a patch file"))
+                .clicked()
+            {
+                self.pick(
+                    FileDialog::new().add_filter(tr("patch"), &[crate::rack::patch::EXTENSION]),
+                    false,
+                    Message::Compare,
+                );
+                ui.close_menu();
+            }
+
+            ui.separator();
+        }
+
+        if ui.button(tr("copy as text")).clicked() {
+            ui.output_mut(|output| output.copied_text = rack.export_patch().to_text());
+            ui.close_menu();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if ui
+            .button(tr("copy link"))
+            .on_hover_text(tr("a link to this page opening the patch"))
+            .clicked()
+        {
+            if let Some(link) = crate::web::link(&rack.export_patch()) {
+                ui.output_mut(|output| output.copied_text = link);
+            }
+            ui.close_menu();
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.text).hint_text(tr("paste a shared patch")),
+            );
+
+            if ui
+                .add_enabled(!self.text.is_empty(), egui::Button::new(tr("import")))
+                .clicked()
+            {
+                match Patch::from_text(&self.text) {
+                    Some(patch) => {
+                        self.import(rack, patch, None);
+                        self.text.clear();
+                    }
+                    None => self.error = Some("invalid patch"),
+                }
+            }
+
+            if ui
+                .add_enabled(!self.text.is_empty(), egui::Button::new(tr("compare")))
+                .on_hover_text(tr("shows what changes from this rack to the shared patch"))
+                .clicked()
+            {
+                match Patch::from_text(&self.text) {
+                    Some(patch) => {
+                        self.diff = Some(diff::diff(&rack.export_patch(), &patch));
+                        self.error = None;
+                    }
+                    None => self.error = Some("invalid patch"),
+                }
+            }
+        });
+
+        if let Some(error) = self.error {
+            ui.colored_label(ui.visuals().error_fg_color, tr(error));
+        }
+    }
+
+    /// Handles finished file dialogs and shows the assets that are missing after an import.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub fn show(&mut self, rack: &mut Rack, ctx: &Context) {
+        #[cfg(not(target_arch = "wasm32"))]
+        for message in self.receiver.try_iter().collect::<Vec<_>>() {
+            match message {
+                Message::Export(path) => {
+                    let mut patch = rack.export_patch();
+                    if self.embed {
+                        patch.embed_assets();
+                    }
+                    if std::fs::write(
+                        path.with_extension(crate::rack::patch::EXTENSION),
+                        patch.to_ron(),
+                    )
+                    .is_err()
+                    {
+                        toast::error(tr("could not write the file"));
+                    }
+                }
+                Message::Import(path) => {
+                    match std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|text| Patch::from_ron(&text))
+                    {
+                        Some(patch) => self.import(rack, patch, path.parent().map(PathBuf::from)),
+                        None => toast::error(tr("invalid patch")),
+                    }
+                }
+                Message::Compare(path) => {
+                    match std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|text| Patch::from_ron(&text))
+                    {
+                        Some(patch) => self.diff = Some(diff::diff(&rack.export_patch(), &patch)),
+                        None => toast::error(tr("invalid patch")),
+                    }
+                }
+                Message::ImportVcv(path) => {
+                    let result = std::fs::read(&path)
+                        .map_err(|_| "could not read the file")
+                        .and_then(|bytes| vcv::import(rack, &bytes));
+                    match result {
+                        Ok(import) => {
+                            self.vcv = Some(import);
+                            self.error = None;
+                        }
+                        Err(error) => toast::error(tr(error)),
+                    }
+                }
+                Message::Render(path) => {
+                    let samples = rack.render_timeline(RENDER_SAMPLE_RATE);
+                    if std::fs::write(
+                        path.with_extension("wav"),
+                        encode_wav(&samples, RENDER_SAMPLE_RATE),
+                    )
+                    .is_err()
+                    {
+                        toast::error(tr("could not write the file"));
+                    }
+                }
+                Message::Located { asset, path } => {
+                    rack.relink_asset(&asset, &path.to_string_lossy());
+                    self.missing.retain(|missing| *missing != asset);
+                }
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(import) = &self.vcv {
+            let mut open = true;
+            egui::Window::new(tr("VCV Rack import"))
+                .id(egui::Id::new("vcv import"))
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label(format!("{} {}", import.mapped, tr("modules were imported")));
+
+                    if !import.missing.is_empty() {
+                        ui.label(tr(
+                            "these modules have no equivalent and were replaced by placeholders:",
+                        ));
+                        for name in import.missing.iter() {
+                            ui.label(name);
+                        }
+                    }
+
+                    if !import.feedback.is_empty() {
+                        ui.label(tr(
+                            "these cables form a feedback loop, which isn't supported, and were left out:",
+                        ));
+                        for cable in import.feedback.iter() {
+                            ui.label(cable);
+                        }
+                    }
+                });
+
+            if !open {
+                self.vcv = None;
+            }
+        }
+
+        if let Some(diff) = &self.diff {
+            let mut open = true;
+            egui::Window::new(tr("patch changes"))
+                .id(egui::Id::new("patch diff"))
+                .open(&mut open)
+                .show(ctx, |ui| diff.show(ui));
+
+            if !open {
+                self.diff = None;
+            }
+        }
+
+        if self.missing.is_empty() {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new(tr("missing files"))
+            .id(egui::Id::new("missing files"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(tr("these files of the imported patch could not be found:"));
+
+                for asset in self.missing.clone() {
+                    ui.horizontal(|ui| {
+                        ui.label(&asset);
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if ui.button(tr("locate…")).clicked() {
+                            self.pick(FileDialog::new(), false, move |path| Message::Located {
+                                asset: asset.clone(),
+                                path,
+                            });
+                        }
+                    });
+                }
+            });
+
+        if !open {
+            self.missing.clear();
+        }
+    }
+
+    /// Asks where to render the timeline to, the rendering happens once a file is picked.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render(&self) {
+        self.pick(
+            FileDialog::new().add_filter("wav", &["wav"]),
+            true,
+            Message::Render,
+        );
+    }
+
+    #[allow(unused_mut, unused_variables)]
+    fn import(&mut self, rack: &mut Rack, mut patch: Patch, dir: Option<std::path::PathBuf>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let dir = dir
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_default();
+            self.missing = patch.resolve_assets(&dir);
+        }
+
+        rack.import_patch(&patch);
+        self.error = None;
+    }
+
+    /// Shows a file dialog on another thread and sends the picked path as a message.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pick(
+        &self,
+        dialog: FileDialog,
+        save: bool,
+        message: impl FnOnce(PathBuf) -> Message + Send + 'static,
+    ) {
+        std::thread::spawn({
+            let sender = self.sender.clone();
+            move || {
+                let path = if save {
+                    dialog.save_file()
+                } else {
+                    dialog.pick_file()
+                };
+
+                if let Some(path) = path {
+                    sender.send(message(path)).ok();
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,26 @@
+//! Vectorized math over sample buffers.
+//!
+//! Only whole buffers are handled here, like applying the output volume. Mixing the sinks and the
+//! filters stay scalar, as the rack processes every module one sample at a time and filters depend
+//! on their previous output.
+//!
+//! The loops work on fixed size chunks so the compiler can vectorize them.
+
+/// Amount of samples handled per chunk, fills one AVX register.
+const LANES: usize = 8;
+
+/// Multiplies every sample in `buffer` by `factor`.
+pub fn scale(buffer: &mut [f32], factor: f32) {
+    let mut chunks = buffer.chunks_exact_mut(LANES);
+
+    for chunk in &mut chunks {
+        let chunk: &mut [f32; LANES] = chunk.try_into().unwrap();
+        for sample in chunk.iter_mut() {
+            *sample *= factor;
+        }
+    }
+
+    for sample in chunks.into_remainder() {
+        *sample *= factor;
+    }
+}
@@ -0,0 +1,70 @@
+use std::{sync::Mutex, time::Duration};
+
+use eframe::{
+    egui::{self, Align2, Context, RichText, Sense},
+    epaint::Color32,
+};
+use wasm_timer::Instant;
+
+use crate::i18n::tr;
+
+/// How long a toast stays visible.
+const DURATION: Duration = Duration::from_secs(6);
+
+/// Errors waiting to be picked up by [`Toasts`].
+static PENDING: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Shows an error as a toast, can be called from any thread.
+pub fn error(message: impl Into<String>) {
+    PENDING.lock().unwrap().push(message.into());
+}
+
+/// Shows errors in the corner of the screen for a while.
+#[derive(Default)]
+pub struct Toasts {
+    toasts: Vec<(String, Instant)>,
+}
+
+impl Toasts {
+    pub fn show(&mut self, ctx: &Context) {
+        self.toasts.extend(
+            PENDING
+                .lock()
+                .unwrap()
+                .drain(..)
+                .map(|message| (message, Instant::now())),
+        );
+        self.toasts.retain(|(_, shown)| shown.elapsed() < DURATION);
+
+        let Some(oldest) = self.toasts.first() else {
+            return;
+        };
+        ctx.request_repaint_after(DURATION.saturating_sub(oldest.1.elapsed()));
+
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let mut dismissed = None;
+
+                for (i, (message, _)) in self.toasts.iter().enumerate() {
+                    let response = egui::Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            ui.set_max_width(300.0);
+                            ui.label(RichText::new(format!("⚠ {message}")).color(Color32::GOLD));
+                        })
+                        .response
+                        .interact(Sense::click())
+                        .on_hover_text_at_pointer(tr("click to dismiss"));
+
+                    if response.clicked() {
+                        dismissed = Some(i);
+                    }
+                }
+
+                if let Some(i) = dismissed {
+                    self.toasts.remove(i);
+                }
+            });
+    }
+}
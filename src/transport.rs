@@ -0,0 +1,253 @@
+use eframe::egui::{self, Response, RichText, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::tr, util::EnumIter};
+
+/// The boundary launched scenes and clips wait for while the transport plays.
+#[derive(Clone, Copy, PartialEq, Sequence)]
+pub enum Quantize {
+    Off,
+    Beat,
+    Bar,
+}
+
+impl Quantize {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Quantize::Off => "off",
+            Quantize::Beat => "beat",
+            Quantize::Bar => "bar",
+        }
+    }
+}
+
+/// The musical clock shared by all modules in a rack.
+#[derive(Clone, Copy)]
+pub struct Transport {
+    pub bpm: f32,
+    pub beats_per_bar: u32,
+    pub playing: bool,
+    pub quantize: Quantize,
+    /// Position in beats since the transport was started.
+    position: f64,
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self {
+            bpm: 120.0,
+            beats_per_bar: 4,
+            playing: false,
+            quantize: Quantize::Bar,
+            position: 0.0,
+        }
+    }
+}
+
+impl Transport {
+    /// Moves the transport forward by a single sample.
+    pub fn advance(&mut self, sample_rate: u32) {
+        if self.playing {
+            self.position += 1.0 / self.samples_per_beat(sample_rate) as f64;
+        }
+    }
+
+    pub fn samples_per_beat(&self, sample_rate: u32) -> f32 {
+        sample_rate as f32 * 60.0 / self.bpm.max(1.0)
+    }
+
+    /// Position in beats.
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    pub fn bar(&self) -> u64 {
+        (self.position / self.beats_per_bar as f64) as u64
+    }
+
+    /// The beat within the current bar, starting at 0.
+    pub fn beat(&self) -> u32 {
+        (self.position as u64 % self.beats_per_bar as u64) as u32
+    }
+
+    /// Whether a launch waiting for the [`Quantize`] boundary can happen, given the position
+    /// of the previous sample, `None` if the transport just started.
+    pub fn at_boundary(&self, previous: Option<f64>) -> bool {
+        let Some(previous) = previous else {
+            return true;
+        };
+        match self.quantize {
+            Quantize::Off => true,
+            Quantize::Beat => previous.floor() != self.position.floor(),
+            Quantize::Bar => {
+                let bar = |position: f64| (position / self.beats_per_bar as f64).floor();
+                bar(previous) != bar(self.position)
+            }
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.position = 0.0;
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        let icon = if self.playing { "⏸" } else { "▶" };
+        if ui.button(icon).clicked() {
+            self.playing = !self.playing;
+        }
+
+        if ui.button("⏹").clicked() {
+            self.stop();
+        }
+
+        ui.add(
+            egui::DragValue::new(&mut self.bpm)
+                .clamp_range(20.0..=400.0)
+                .speed(0.5)
+                .suffix(" bpm"),
+        );
+
+        ui.label(RichText::new(format!("{:>3}.{}", self.bar() + 1, self.beat() + 1)).monospace())
+            .on_hover_text_at_pointer(tr("bar.beat"));
+
+        egui::ComboBox::from_id_source("quantize")
+            .selected_text(format!("⏳ {}", tr(self.quantize.as_str())))
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for quantize in Quantize::iter() {
+                    ui.selectable_value(&mut self.quantize, quantize, tr(quantize.as_str()));
+                }
+            })
+            .response
+            .on_hover_text_at_pointer(tr(
+                "what launched scenes and clips wait for while the transport plays",
+            ));
+    }
+}
+
+/// A musical note length relative to the transport's tempo.
+#[derive(Clone, Copy, Default, PartialEq, Sequence, Serialize, Deserialize)]
+pub enum Division {
+    Whole,
+    Half,
+    #[default]
+    Quarter,
+    Eighth,
+    Sixteenth,
+    ThirtySecond,
+    HalfTriplet,
+    QuarterTriplet,
+    EighthTriplet,
+    SixteenthTriplet,
+    DottedHalf,
+    DottedQuarter,
+    DottedEighth,
+}
+
+impl Division {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Division::Whole => "1/1",
+            Division::Half => "1/2",
+            Division::Quarter => "1/4",
+            Division::Eighth => "1/8",
+            Division::Sixteenth => "1/16",
+            Division::ThirtySecond => "1/32",
+            Division::HalfTriplet => "1/2T",
+            Division::QuarterTriplet => "1/4T",
+            Division::EighthTriplet => "1/8T",
+            Division::SixteenthTriplet => "1/16T",
+            Division::DottedHalf => "1/2.",
+            Division::DottedQuarter => "1/4.",
+            Division::DottedEighth => "1/8.",
+        }
+    }
+
+    /// Length in beats, where a beat is a quarter note.
+    pub fn beats(&self) -> f32 {
+        match self {
+            Division::Whole => 4.0,
+            Division::Half => 2.0,
+            Division::Quarter => 1.0,
+            Division::Eighth => 0.5,
+            Division::Sixteenth => 0.25,
+            Division::ThirtySecond => 0.125,
+            Division::HalfTriplet => 4.0 / 3.0,
+            Division::QuarterTriplet => 2.0 / 3.0,
+            Division::EighthTriplet => 1.0 / 3.0,
+            Division::SixteenthTriplet => 1.0 / 6.0,
+            Division::DottedHalf => 3.0,
+            Division::DottedQuarter => 1.5,
+            Division::DottedEighth => 0.75,
+        }
+    }
+
+    pub fn ms(&self, bpm: f32) -> f32 {
+        self.beats() * 60000.0 / bpm.max(1.0)
+    }
+}
+
+/// A time parameter that is either set in milliseconds or synced to the transport's tempo.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SyncedTime {
+    pub ms: f32,
+    pub division: Division,
+    pub synced: bool,
+}
+
+impl SyncedTime {
+    pub fn new(ms: f32) -> Self {
+        Self {
+            ms,
+            division: Division::Quarter,
+            synced: false,
+        }
+    }
+
+    /// The current length in milliseconds, following the tempo when synced.
+    pub fn as_ms(&self, transport: &Transport) -> f32 {
+        if self.synced {
+            self.division.ms(transport.bpm)
+        } else {
+            self.ms
+        }
+    }
+
+    pub fn show(&mut self, id: impl std::hash::Hash, ui: &mut Ui) -> Response {
+        self.show_range(id, 0.0..=60000.0, ui)
+    }
+
+    /// Shows the time with the milliseconds limited to `range`, returns the response of the
+    /// milliseconds or the division so it can be labelled.
+    pub fn show_range(
+        &mut self,
+        id: impl std::hash::Hash,
+        range: std::ops::RangeInclusive<f32>,
+        ui: &mut Ui,
+    ) -> Response {
+        let response = if !self.synced {
+            ui.add(
+                egui::DragValue::new(&mut self.ms)
+                    .clamp_range(range)
+                    .speed(1.0)
+                    .suffix(" ms"),
+            )
+        } else {
+            egui::ComboBox::from_id_source(id)
+                .selected_text(self.division.as_str())
+                .width(50.0)
+                .show_ui(ui, |ui| {
+                    for division in Division::iter() {
+                        ui.selectable_value(&mut self.division, division, division.as_str());
+                    }
+                })
+                .response
+        };
+
+        ui.toggle_value(&mut self.synced, "♩")
+            .on_hover_text_at_pointer(tr("sync to tempo"));
+        response
+    }
+}
@@ -0,0 +1,60 @@
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+/// How notes are turned into frequencies by the modules of a rack.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    /// Frequency of A4 in Hz.
+    pub reference: f32,
+    /// Semitones every note is shifted by.
+    pub transpose: i32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            reference: 440.0,
+            transpose: 0,
+        }
+    }
+}
+
+impl Tuning {
+    /// Converts a MIDI note number to a frequency, with A4 (69) at the reference.
+    pub fn note_to_freq(&self, note: f32) -> f32 {
+        self.reference * 2f32.powf((note + self.transpose as f32 - 69.0) / 12.0)
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("A4:"));
+            ui.add(
+                egui::DragValue::new(&mut self.reference)
+                    .clamp_range(432.0..=446.0)
+                    .speed(0.1)
+                    .suffix(" Hz"),
+            )
+            .on_hover_text(tr("frequency all notes are tuned to"));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("transpose:"));
+            ui.add(
+                egui::DragValue::new(&mut self.transpose)
+                    .clamp_range(-24..=24)
+                    .suffix(" st"),
+            )
+            .on_hover_text(tr("semitones the notes of all modules are shifted by"));
+        });
+
+        if ui
+            .add_enabled(*self != Self::default(), egui::Button::new(tr("reset")))
+            .clicked()
+        {
+            *self = Self::default();
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::mpsc::Receiver;
+
+use eframe::egui::{self, Align2, Context, RichText};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::tr,
+    instance::instance::InstanceHandle,
+    module::Port,
+    modules::{
+        audio::AudioInput,
+        oscillator::{FrameOutput, Oscillator},
+    },
+    rack::{event::RackEvent, rack::Rack},
+};
+
+/// Key the tutorial progress is persisted under.
+pub const STORAGE_KEY: &str = "tutorial";
+
+/// A step of the onboarding tutorial, completed by changing the rack.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Step {
+    AddOscillator,
+    Connect,
+    // progress saved before the step changed the frequency continues there
+    #[serde(alias = "ChangeWave")]
+    ChangeFrequency,
+    Done,
+}
+
+impl Step {
+    const COUNT: usize = 3;
+
+    fn index(&self) -> usize {
+        match self {
+            Step::AddOscillator => 0,
+            Step::Connect => 1,
+            Step::ChangeFrequency => 2,
+            Step::Done => 3,
+        }
+    }
+
+    fn instructions(&self) -> &str {
+        match self {
+            Step::AddOscillator => "add an oscillator with the \"➕ Module\" button of a panel",
+            Step::Connect => {
+                "add an audio output and drag a cable from the sample output of the oscillator to it"
+            }
+            Step::ChangeFrequency => {
+                "change the frequency of the oscillator and listen to the difference"
+            }
+            Step::Done => "well done! explore the other modules, the \"?\" of a module explains it",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Step::AddOscillator => Step::Connect,
+            Step::Connect => Step::ChangeFrequency,
+            Step::ChangeFrequency | Step::Done => Step::Done,
+        }
+    }
+
+    fn is_completed_by(&self, event: &RackEvent, rack: &Rack) -> bool {
+        let is_oscillator = |handle: InstanceHandle| {
+            rack.get_instance(handle)
+                .is_some_and(|instance| instance.get_module::<Oscillator>().is_some())
+        };
+
+        match (self, event) {
+            (Step::AddOscillator, RackEvent::ModuleAdded(handle)) => is_oscillator(*handle),
+            (Step::Connect, RackEvent::Connected(from, to)) => {
+                from.id == FrameOutput::id()
+                    && is_oscillator(from.instance)
+                    && to.id == AudioInput::id()
+            }
+            (Step::ChangeFrequency, RackEvent::ParameterChanged { instance, name, .. }) => {
+                *name == "freq" && is_oscillator(*instance)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Guides first-run users through building a first patch.
+#[derive(Serialize, Deserialize)]
+pub struct Tutorial {
+    /// The current step, `None` when the tutorial is not running.
+    step: Option<Step>,
+    /// Changes to the rack the steps are completed by, see [`Tutorial::subscribe`].
+    #[serde(skip)]
+    receiver: Option<Receiver<RackEvent>>,
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self {
+            step: Some(Step::AddOscillator),
+            receiver: None,
+        }
+    }
+}
+
+impl Tutorial {
+    pub fn start(&mut self) {
+        self.step = Some(Step::AddOscillator);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.step.is_some()
+    }
+
+    /// Listens to the changes of `rack`, which complete the steps.
+    pub fn subscribe(&mut self, rack: &mut Rack) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        rack.on_event(move |event| {
+            sender.send(*event).ok();
+        });
+        self.receiver = Some(receiver);
+    }
+
+    /// Advances to the next step for each change to the rack that completes the current one.
+    pub fn update(&mut self, rack: &Rack) {
+        let Some(receiver) = &self.receiver else {
+            return;
+        };
+
+        for event in receiver.try_iter() {
+            if let Some(step) = self.step {
+                if step.is_completed_by(&event, rack) {
+                    self.step = Some(step.next());
+                }
+            }
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let Some(step) = self.step else {
+            return;
+        };
+
+        egui::Window::new(tr("tutorial"))
+            .id(egui::Id::new("tutorial"))
+            .anchor(Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(250.0);
+
+                if step != Step::Done {
+                    ui.label(RichText::new(format!("{}/{}", step.index() + 1, Step::COUNT)).weak());
+                }
+                ui.label(tr(step.instructions()));
+
+                ui.horizontal(|ui| {
+                    if step == Step::Done {
+                        if ui.button(tr("finish")).clicked() {
+                            self.step = None;
+                        }
+                    } else if ui.button(tr("skip tutorial")).clicked() {
+                        self.step = None;
+                    }
+                });
+            });
+    }
+}
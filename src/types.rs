@@ -11,12 +11,29 @@ pub trait Type: Clone + 'static {
     fn name() -> &'static str;
     fn to_string(&self) -> String;
     fn as_value(&self) -> f32;
+
+    /// Scales the value by a factor, types that can't be scaled are left as is.
+    #[allow(unused)]
+    fn scale(&self, factor: f32) -> Self {
+        self.clone()
+    }
+
+    /// Whether [`Type::scale`] changes values, only connections of these types have a gain.
+    fn scalable() -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
 }
 
 impl<T: Type> PortValueBoxed for T {
     fn name() -> &'static str {
         T::name()
     }
+    fn scalable() -> bool {
+        T::scalable()
+    }
     fn to_string(&self) -> String {
         self.to_string()
     }
@@ -24,6 +41,10 @@ impl<T: Type> PortValueBoxed for T {
     fn as_value(&self) -> f32 {
         self.as_value()
     }
+
+    fn scale(&self, factor: f32) -> Box<dyn PortValueBoxed> {
+        Box::new(Type::scale(self, factor))
+    }
 }
 
 pub struct TypeDefinitionDyn {
@@ -82,6 +103,14 @@ impl Type for f32 {
     fn as_value(&self) -> f32 {
         *self
     }
+
+    fn scale(&self, factor: f32) -> Self {
+        self * factor
+    }
+
+    fn scalable() -> bool {
+        true
+    }
 }
 
 impl Type for bool {
@@ -133,4 +162,12 @@ impl Type for Frame {
     fn as_value(&self) -> f32 {
         self.as_f32_mono()
     }
+
+    fn scale(&self, factor: f32) -> Self {
+        *self * factor
+    }
+
+    fn scalable() -> bool {
+        true
+    }
 }
@@ -19,3 +19,11 @@ pub fn random_color() -> Hsva {
         1.0,
     )
 }
+
+/// Name of a MIDI note number, like "C4".
+pub fn note_name(note: u8) -> String {
+    const NAMES: [&str; 12] = [
+        "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+    ];
+    format!("{}{}", NAMES[note as usize % 12], note as i32 / 12 - 1)
+}
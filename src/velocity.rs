@@ -0,0 +1,99 @@
+use eframe::egui::{self, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::tr, util::EnumIter};
+
+/// How the force a note is played with maps to its velocity.
+#[derive(Clone, Copy, PartialEq, Default, Sequence, Serialize, Deserialize)]
+pub enum VelocityCurve {
+    #[default]
+    Linear,
+    /// Soft notes get softer, for more control over quiet playing.
+    Exponential,
+    /// Every note has the same velocity.
+    Fixed,
+}
+
+impl VelocityCurve {
+    pub fn as_str(&self) -> &str {
+        match self {
+            VelocityCurve::Linear => "linear",
+            VelocityCurve::Exponential => "exp",
+            VelocityCurve::Fixed => "fixed",
+        }
+    }
+}
+
+/// Turns the force a note is played with into the velocity a module outputs, shared by the
+/// modules that play notes.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VelocityResponse {
+    pub curve: VelocityCurve,
+    /// Velocity of the softest and the hardest note, or of every note for [`VelocityCurve::Fixed`].
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for VelocityResponse {
+    fn default() -> Self {
+        Self {
+            curve: VelocityCurve::default(),
+            min: 0.0,
+            max: 1.0,
+        }
+    }
+}
+
+impl VelocityResponse {
+    /// The velocity for a note played with `force` from 0 to 1.
+    pub fn apply(&self, force: f32) -> f32 {
+        let force = force.clamp(0.0, 1.0);
+        let shaped = match self.curve {
+            VelocityCurve::Linear => force,
+            VelocityCurve::Exponential => force * force,
+            VelocityCurve::Fixed => return self.max,
+        };
+        self.min + (self.max - self.min) * shaped
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, id: impl std::hash::Hash) {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("velocity:"));
+            egui::ComboBox::from_id_source(id)
+                .selected_text(tr(self.curve.as_str()))
+                .width(60.0)
+                .show_ui(ui, |ui| {
+                    for curve in VelocityCurve::iter() {
+                        ui.selectable_value(&mut self.curve, curve, tr(curve.as_str()));
+                    }
+                })
+                .response
+                .on_hover_text_at_pointer(tr(
+                    "how the force notes are played with maps to their velocity",
+                ));
+
+            if self.curve != VelocityCurve::Fixed {
+                ui.add(
+                    egui::DragValue::new(&mut self.min)
+                        .clamp_range(0.0..=1.0)
+                        .speed(0.01),
+                )
+                .labelled_by(label.id)
+                .on_hover_text(tr("velocity of the softest note"));
+            }
+            ui.add(
+                egui::DragValue::new(&mut self.max)
+                    .clamp_range(0.0..=1.0)
+                    .speed(0.01),
+            )
+            .labelled_by(label.id)
+            .on_hover_text(tr(if self.curve == VelocityCurve::Fixed {
+                "velocity of every note"
+            } else {
+                "velocity of the hardest note"
+            }));
+        });
+    }
+}
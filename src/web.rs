@@ -0,0 +1,94 @@
+#![cfg(any(target_arch = "wasm32", test))]
+//! Sharing patches as links on the web.
+//!
+//! The url handling is also built for tests, which run natively.
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsValue;
+
+#[cfg(target_arch = "wasm32")]
+use crate::rack::patch::Patch;
+
+/// Key the patch of the rack is persisted under, to continue where the page was left.
+#[cfg(target_arch = "wasm32")]
+pub const STORAGE_KEY: &str = "patch";
+
+/// Name of the url parameter holding a patch, as in `#patch=...` or `?patch=...`.
+const PARAMETER: &str = "patch";
+
+/// Characters of base64 that have a meaning in urls, with their escaped form.
+const ESCAPES: [(&str, &str); 3] = [("+", "%2B"), ("/", "%2F"), ("=", "%3D")];
+
+fn escape(text: &str) -> String {
+    ESCAPES
+        .iter()
+        .fold(text.to_string(), |text, (from, to)| text.replace(from, to))
+}
+
+fn unescape(text: &str) -> String {
+    ESCAPES.iter().fold(text.to_string(), |text, (to, from)| {
+        text.replace(from, to).replace(&from.to_lowercase(), to)
+    })
+}
+
+/// The value of the patch parameter in the query or fragment of a url.
+fn parameter(part: &str) -> Option<String> {
+    part.split('&')
+        .find_map(|pair| pair.strip_prefix(PARAMETER)?.strip_prefix('='))
+        .map(unescape)
+}
+
+/// The patch shared in the url of the page.
+///
+/// The patch is removed from the url once read, so reloading the page continues with the persisted
+/// patch instead of starting over.
+#[cfg(target_arch = "wasm32")]
+pub fn url_patch() -> Option<Patch> {
+    let window = web_sys::window()?;
+    let location = window.location();
+
+    let hash = location.hash().unwrap_or_default();
+    let search = location.search().unwrap_or_default();
+    let text = parameter(hash.trim_start_matches('#'))
+        .or_else(|| parameter(search.trim_start_matches('?')))?;
+
+    if let (Ok(history), Ok(path)) = (window.history(), location.pathname()) {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&path));
+    }
+
+    Patch::from_text(&text)
+}
+
+/// A link to this page opening `patch`.
+#[cfg(target_arch = "wasm32")]
+pub fn link(patch: &Patch) -> Option<String> {
+    let location = web_sys::window()?.location();
+    Some(format!(
+        "{}{}#{PARAMETER}={}",
+        location.origin().ok()?,
+        location.pathname().ok()?,
+        escape(&patch.to_text())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_round_trip() {
+        let text = "ab+c/d==";
+        assert_eq!(escape(text), "ab%2Bc%2Fd%3D%3D");
+        assert_eq!(unescape(&escape(text)), text);
+        // browsers may lowercase the escapes
+        assert_eq!(unescape("ab%2bc%2fd%3d%3d"), text);
+    }
+
+    #[test]
+    fn finds_parameter() {
+        assert_eq!(parameter("patch=ab%2Bc").as_deref(), Some("ab+c"));
+        assert_eq!(parameter("a=1&patch=abc&b=2").as_deref(), Some("abc"));
+        assert_eq!(parameter("patches=abc"), None);
+        assert_eq!(parameter(""), None);
+    }
+}